@@ -13,12 +13,81 @@ const CONFIG_SECTION_NAME: &str = "client";
 const CONFIG_SERVER_PARAM: &str = "server";
 const CONFIG_PORT_PARAM: &str = "port";
 const CONFIG_USERNAME_PARAM: &str = "username";
+const CONFIG_REPORT_PAGE_SIZE_PARAM: &str = "report_page_size";
+const CONFIG_TRUSTED_SERVER_KEY_PATH_PARAM: &str = "trusted_server_key_path";
+
+/// Amounts of reports per page that can be selected in the settings
+/// (the last one is the maximum).
+pub const REPORT_PAGE_SIZES: [u64; 4] = [15, 25, 50, 100];
+
+const CONFIG_WINDOW_SECTION_NAME: &str = "window";
+const CONFIG_WINDOW_X_PARAM: &str = "x";
+const CONFIG_WINDOW_Y_PARAM: &str = "y";
+const CONFIG_WINDOW_WIDTH_PARAM: &str = "width";
+const CONFIG_WINDOW_HEIGHT_PARAM: &str = "height";
+
+/// Minimum size of the window that we restore.
+const MIN_WINDOW_WIDTH: f64 = 400.0;
+const MIN_WINDOW_HEIGHT: f64 = 300.0;
+
+/// Position and size of the main window.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WindowGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl WindowGeometry {
+    /// Creates geometry of a window with the specified size in the center of the display.
+    pub fn centered(width: f64, height: f64, display_width: f64, display_height: f64) -> Self {
+        Self {
+            x: display_width / 2.0 - width / 2.0,
+            y: display_height / 2.0 - height / 2.0,
+            width,
+            height,
+        }
+    }
+    /// Makes sure that the window is not bigger than the display and is fully visible,
+    /// otherwise moves the window to the center of the display.
+    ///
+    /// ## Arguments
+    /// * `display_width`: width of the display.
+    /// * `display_height`: height of the display.
+    pub fn fit_to_display(self, display_width: f64, display_height: f64) -> Self {
+        let width = self.width.max(MIN_WINDOW_WIDTH).min(display_width);
+        let height = self.height.max(MIN_WINDOW_HEIGHT).min(display_height);
+
+        // The display might have changed (for example, the window was on another monitor).
+        if self.x < 0.0
+            || self.y < 0.0
+            || self.x + width > display_width
+            || self.y + height > display_height
+        {
+            return WindowGeometry::centered(width, height, display_width, display_height);
+        }
+
+        Self {
+            x: self.x,
+            y: self.y,
+            width,
+            height,
+        }
+    }
+}
 
-#[derive(Default)]
 pub struct ConfigManager {
     pub server: String,
     pub port: String,
     pub username: String,
+    /// Amount of reports to display on one page.
+    pub report_page_size: u64,
+    /// Path to the public key of the server (`server_key.pub` file next to the server config)
+    /// that the server must have, if empty any server key is accepted.
+    pub trusted_server_key_path: String,
+    /// Window geometry when the client was closed last time.
+    pub window_geometry: Option<WindowGeometry>,
 }
 
 impl ConfigManager {
@@ -48,6 +117,31 @@ impl ConfigManager {
             CONFIG_USERNAME_PARAM,
             Some(&self.username),
         );
+        config_file.setstr(
+            CONFIG_SECTION_NAME,
+            CONFIG_REPORT_PAGE_SIZE_PARAM,
+            Some(&self.report_page_size.to_string()),
+        );
+        config_file.setstr(
+            CONFIG_SECTION_NAME,
+            CONFIG_TRUSTED_SERVER_KEY_PATH_PARAM,
+            Some(&self.trusted_server_key_path),
+        );
+
+        if let Some(geometry) = self.window_geometry {
+            for (param, value) in [
+                (CONFIG_WINDOW_X_PARAM, geometry.x),
+                (CONFIG_WINDOW_Y_PARAM, geometry.y),
+                (CONFIG_WINDOW_WIDTH_PARAM, geometry.width),
+                (CONFIG_WINDOW_HEIGHT_PARAM, geometry.height),
+            ] {
+                config_file.setstr(
+                    CONFIG_WINDOW_SECTION_NAME,
+                    param,
+                    Some(&value.round().to_string()),
+                );
+            }
+        }
 
         let config_path = ConfigManager::get_config_file_path();
         if let Err(e) = config_file.write(&config_path) {
@@ -104,5 +198,63 @@ impl ConfigManager {
         if let Some(username) = username {
             self.username = username;
         }
+
+        // Read report page size.
+        let report_page_size = config.get(CONFIG_SECTION_NAME, CONFIG_REPORT_PAGE_SIZE_PARAM);
+        if let Some(report_page_size) = report_page_size {
+            let max_page_size = REPORT_PAGE_SIZES[REPORT_PAGE_SIZES.len() - 1];
+            match report_page_size.parse::<u64>() {
+                Ok(value) if value > 0 && value <= max_page_size => {
+                    self.report_page_size = value;
+                }
+                _ => println!(
+                    "WARNING: the value \"{}\" of the config parameter \"{}\" should be \
+                    in range [1; {}], using the default value.",
+                    report_page_size, CONFIG_REPORT_PAGE_SIZE_PARAM, max_page_size
+                ),
+            }
+        }
+
+        // Read trusted server key path.
+        let trusted_server_key_path =
+            config.get(CONFIG_SECTION_NAME, CONFIG_TRUSTED_SERVER_KEY_PATH_PARAM);
+        if let Some(trusted_server_key_path) = trusted_server_key_path {
+            self.trusted_server_key_path = trusted_server_key_path;
+        }
+
+        // Read window geometry (only if all values are valid).
+        let read_window_value = |param: &str| -> Option<f64> {
+            config
+                .get(CONFIG_WINDOW_SECTION_NAME, param)?
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite())
+        };
+        if let (Some(x), Some(y), Some(width), Some(height)) = (
+            read_window_value(CONFIG_WINDOW_X_PARAM),
+            read_window_value(CONFIG_WINDOW_Y_PARAM),
+            read_window_value(CONFIG_WINDOW_WIDTH_PARAM),
+            read_window_value(CONFIG_WINDOW_HEIGHT_PARAM),
+        ) {
+            self.window_geometry = Some(WindowGeometry {
+                x,
+                y,
+                width,
+                height,
+            });
+        }
+    }
+}
+
+impl Default for ConfigManager {
+    fn default() -> Self {
+        Self {
+            server: String::new(),
+            port: String::new(),
+            username: String::new(),
+            report_page_size: REPORT_PAGE_SIZES[0],
+            trusted_server_key_path: String::new(),
+            window_geometry: None,
+        }
     }
 }
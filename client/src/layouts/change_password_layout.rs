@@ -1,11 +1,14 @@
 // External.
 use druid::widget::{prelude::*, SizedBox};
 use druid::widget::{Button, Flex, Label, LineBreaking, MainAxisAlignment, TextBox};
-use druid::{Lens, LensExt, TextAlignment, WidgetExt};
+use druid::{Key, Lens, LensExt, TextAlignment, WidgetExt};
 
 // Custom.
+use crate::layouts::connect_layout::ConnectLayout;
+use crate::misc::theme::LARGE_TEXT_SIZE;
 use crate::network::net_service::ConnectResult;
 use crate::{ApplicationState, Layout};
+use shared::misc::password_policy::PasswordPolicy;
 
 // Layout customization.
 const WIDTH_PADDING: f64 = 0.25;
@@ -16,13 +19,16 @@ const BOTTOM_PADDING: f64 = 0.75;
 const ROW_SPACING: f64 = 0.3;
 const BUTTONS_WIDTH_PADDING: f64 = 1.0;
 const BUTTON_HEIGHT: f64 = 0.3;
-const TEXT_SIZE: f64 = 20.0;
+const TEXT_SIZE: Key<f64> = LARGE_TEXT_SIZE;
 
 #[derive(Default, Clone, Data, Lens)]
 pub struct ChangePasswordLayout {
     pub old_password: String,
     pub new_password: String,
     pub new_password_repeat: String,
+    /// Requirements for the new password that the server sent.
+    #[data(same_fn = "PartialEq::eq")]
+    pub password_policy: Option<PasswordPolicy>,
     connect_error: String,
 }
 
@@ -36,6 +42,17 @@ impl ChangePasswordLayout {
             .must_fill_main_axis(true)
             .with_flex_child(SizedBox::empty().expand(), TOP_PADDING)
             .with_child(Label::new("Set New Password").with_text_size(TEXT_SIZE))
+            .with_default_spacer()
+            .with_child(
+                Label::new(|data: &ApplicationState, _env: &Env| {
+                    data.change_password_layout
+                        .password_policy
+                        .map(|policy| policy.describe())
+                        .unwrap_or_default()
+                })
+                .with_text_alignment(TextAlignment::Center)
+                .with_line_break_mode(LineBreaking::WordWrap),
+            )
             .with_flex_child(SizedBox::empty().expand(), ROW_SPACING)
             .with_flex_child(
                 Flex::row()
@@ -143,10 +160,18 @@ impl ChangePasswordLayout {
             return;
         }
 
+        if let Some(policy) = data.change_password_layout.password_policy {
+            if let Some(problem) = policy.check(&data.change_password_layout.new_password) {
+                data.change_password_layout.connect_error = problem;
+                return;
+            }
+        }
+
         // Try to connect.
+        let port = data.connect_layout.port.parse::<u16>().unwrap();
         let result = data.net_service.lock().unwrap().connect(
             data.connect_layout.server.clone(),
-            data.connect_layout.port.parse::<u16>().unwrap(),
+            port,
             data.connect_layout.username.clone(),
             data.change_password_layout.old_password.clone(),
             String::new(),
@@ -160,14 +185,25 @@ impl ChangePasswordLayout {
                     .lock()
                     .unwrap()
                     .log(&app_error.to_string());
-                data.change_password_layout.connect_error = app_error.to_string();
+                data.change_password_layout.connect_error =
+                    ConnectLayout::get_connect_error_message(
+                        &app_error,
+                        &data.connect_layout.server,
+                        port,
+                    );
             }
             ConnectResult::ConnectFailed(reason) => {
                 println!("{}", reason);
                 data.logger_service.lock().unwrap().log(&reason);
                 data.change_password_layout.connect_error = reason;
             }
-            ConnectResult::NeedFirstPassword => {
+            ConnectResult::WrongProtocol(server_protocol) => {
+                let message = ConnectLayout::get_protocol_mismatch_message(server_protocol, None);
+                println!("{}", message);
+                data.logger_service.lock().unwrap().log(&message);
+                data.change_password_layout.connect_error = message;
+            }
+            ConnectResult::NeedFirstPassword(_) => {
                 let message = "error: received \"NeedFirstPassword\" in Change Password mode.";
                 println!("{}", message);
                 data.logger_service.lock().unwrap().log(message);
@@ -195,8 +231,8 @@ impl ChangePasswordLayout {
 
                 data.current_layout = Layout::Otp;
             }
-            ConnectResult::Connected(is_admin) => {
-                data.main_layout.is_user_admin = is_admin;
+            ConnectResult::Connected(role, _) => {
+                data.main_layout.user_role = role;
 
                 data.change_password_layout.new_password_repeat = String::new();
                 data.change_password_layout.new_password = String::new();
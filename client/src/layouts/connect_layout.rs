@@ -1,11 +1,15 @@
 // External.
 use druid::widget::{prelude::*, SizedBox};
 use druid::widget::{Button, Flex, Label, LineBreaking, MainAxisAlignment, TextBox};
-use druid::{Lens, LensExt, TextAlignment, WidgetExt};
+use druid::{Key, Lens, LensExt, TextAlignment, WidgetExt};
+use native_dialog::{MessageDialog, MessageType};
 
 // Custom.
-use crate::{io::config_manager::ConfigManager, network::net_service::ConnectResult};
-use crate::{ApplicationState, Layout};
+use crate::misc::theme::LARGE_TEXT_SIZE;
+use crate::network::net_service::{ConnectResult, NetService};
+use crate::{io::config_manager::ConfigManager, ApplicationState, Layout};
+use shared::misc::error::{AppError, AppErrorKind};
+use shared::network::net_params::NETWORK_PROTOCOL_VERSION;
 
 // Layout customization.
 const WIDTH_PADDING: f64 = 0.25;
@@ -16,7 +20,7 @@ const BOTTOM_PADDING: f64 = 0.75;
 const ROW_SPACING: f64 = 0.2;
 const BUTTONS_WIDTH_PADDING: f64 = 1.0;
 const BUTTON_HEIGHT: f64 = 0.3;
-const TEXT_SIZE: f64 = 20.0;
+const TEXT_SIZE: Key<f64> = LARGE_TEXT_SIZE;
 
 #[derive(Clone, Data, Lens)]
 pub struct ConnectLayout {
@@ -178,6 +182,32 @@ impl ConnectLayout {
         }
         let port = port.unwrap();
 
+        // Check that the server speaks the same protocol before trying to log in.
+        match NetService::query_server_info(&data.connect_layout.server, port) {
+            Ok(server_info) => {
+                if server_info.net_protocol != NETWORK_PROTOCOL_VERSION {
+                    ConnectLayout::show_protocol_mismatch(
+                        data,
+                        server_info.net_protocol,
+                        Some(server_info.server_version),
+                    );
+                    return;
+                }
+                data.net_service
+                    .lock()
+                    .unwrap()
+                    .set_server_version(Some(server_info.server_version));
+            }
+            Err(app_error) => {
+                // Old servers don't support this request, the login will tell more.
+                data.logger_service
+                    .lock()
+                    .unwrap()
+                    .log(&format!("failed to query server info: {}", app_error));
+                data.net_service.lock().unwrap().set_server_version(None);
+            }
+        }
+
         // Try to connect.
         let result = data.net_service.lock().unwrap().connect(
             data.connect_layout.server.clone(),
@@ -194,14 +224,22 @@ impl ConnectLayout {
                     .lock()
                     .unwrap()
                     .log(&app_error.to_string());
-                data.connect_layout.connect_error = app_error.get_message();
+                data.connect_layout.connect_error = ConnectLayout::get_connect_error_message(
+                    &app_error,
+                    &data.connect_layout.server,
+                    port,
+                );
             }
             ConnectResult::ConnectFailed(reason) => {
                 println!("{}", reason);
                 data.logger_service.lock().unwrap().log(&reason);
                 data.connect_layout.connect_error = reason;
             }
-            ConnectResult::NeedFirstPassword => {
+            ConnectResult::WrongProtocol(server_protocol) => {
+                ConnectLayout::show_protocol_mismatch(data, server_protocol, None);
+            }
+            ConnectResult::NeedFirstPassword(password_policy) => {
+                data.change_password_layout.password_policy = Some(password_policy);
                 data.current_layout = Layout::ChangePassword;
             }
             ConnectResult::SetupOTP(qr_code) => {
@@ -211,13 +249,89 @@ impl ConnectLayout {
             ConnectResult::NeedOTP => {
                 data.current_layout = Layout::Otp;
             }
-            ConnectResult::Connected(is_admin) => {
-                data.main_layout.is_user_admin = is_admin;
+            ConnectResult::Connected(role, _) => {
+                data.main_layout.user_role = role;
                 data.connect_layout.password = String::new();
                 data.current_layout = Layout::Main;
             }
         }
     }
+    /// Returns a message that explains why the connection to the server failed.
+    ///
+    /// ## Arguments
+    /// * `app_error`: error returned by `NetService::connect`.
+    /// * `server`: server name or address.
+    /// * `port`: server port.
+    pub fn get_connect_error_message(app_error: &AppError, server: &str, port: u16) -> String {
+        match app_error.get_kind() {
+            AppErrorKind::AddressNotResolved => format!(
+                "Could not find the server \"{}\", please, check the server name.",
+                server
+            ),
+            AppErrorKind::ConnectionRefused => format!(
+                "The server \"{}\" refused the connection on port {}, please, check the port \
+                and make sure that the server is running.",
+                server, port
+            ),
+            AppErrorKind::Timeout => format!(
+                "The server \"{}\" did not respond in time, please, check the server name, \
+                the port and your network connection.",
+                server
+            ),
+            AppErrorKind::ConnectionClosed => format!(
+                "The server closed the connection: {}.",
+                app_error.get_message()
+            ),
+            AppErrorKind::Protocol | AppErrorKind::Internal => app_error.get_message(),
+        }
+    }
+    /// Returns a message that tells which application needs an update
+    /// because the client and the server use different network protocols.
+    ///
+    /// ## Arguments
+    /// * `server_protocol`: network protocol version of the server.
+    /// * `server_version`: application version of the server (if known).
+    pub fn get_protocol_mismatch_message(
+        server_protocol: u16,
+        server_version: Option<String>,
+    ) -> String {
+        let server_name = match server_version {
+            Some(version) => format!("The server (v{})", version),
+            None => String::from("The server"),
+        };
+
+        format!(
+            "{} uses network protocol version {} while this application (v{}) \
+            uses version {}.\nPlease update {} to connect.",
+            server_name,
+            server_protocol,
+            env!("CARGO_PKG_VERSION"),
+            NETWORK_PROTOCOL_VERSION,
+            if server_protocol > NETWORK_PROTOCOL_VERSION {
+                "the client"
+            } else {
+                "the server"
+            }
+        )
+    }
+    /// Shows a dialog that tells that the client and the server are incompatible.
+    fn show_protocol_mismatch(
+        data: &mut ApplicationState,
+        server_protocol: u16,
+        server_version: Option<String>,
+    ) {
+        let message = ConnectLayout::get_protocol_mismatch_message(server_protocol, server_version);
+        println!("{}", message);
+        data.logger_service.lock().unwrap().log(&message);
+
+        let _ = MessageDialog::new()
+            .set_type(MessageType::Error)
+            .set_title("Update Required")
+            .set_text(&message)
+            .show_alert();
+
+        data.connect_layout.connect_error = message;
+    }
     fn on_settings_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
         data.current_layout = Layout::Settings;
     }
@@ -3,19 +3,31 @@ use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 // External.
-use druid::widget::{prelude::*, SizedBox, ViewSwitcher};
+use druid::widget::{prelude::*, Scroll, SizedBox, ViewSwitcher};
 use druid::widget::{Button, Flex, Label, MainAxisAlignment, Padding};
-use druid::{Color, WidgetExt};
+use druid::{Color, Key, WidgetExt};
+use native_dialog::{FileDialog, MessageDialog, MessageType};
 
 // Custom.
-use crate::network::net_service::ServerDiskUsage;
+use crate::io::config_manager::ConfigManager;
+use crate::layouts::{
+    change_password_layout::ChangePasswordLayout,
+    otp_layout::OtpLayout,
+    report_layout::ReportLayout,
+    stats_layout::{StatsLayout, STATS_DAY_COUNT},
+};
+use crate::misc::connection_status_controller::ConnectionStatusController;
+use crate::misc::theme::NORMAL_TEXT_SIZE;
+use crate::network::net_service::{ServerDiskUsage, RECONNECT_MAX_ATTEMPTS};
 use crate::widgets::report::ReportWidget;
 use crate::{ApplicationState, Layout};
+use shared::misc::error::{AppError, AppErrorKind};
 use shared::misc::report::ReportSummary;
+use shared::misc::user::UserRole;
 
 // Layout customization.
-const TEXT_SIZE: f64 = 18.0;
-pub const REPORT_COUNT_PER_PAGE: u64 = 15;
+const TEXT_SIZE: Key<f64> = NORMAL_TEXT_SIZE;
+const CSV_EXPORT_REPORTS_PER_REQUEST: u64 = 100;
 
 #[derive(Clone, Data)]
 pub struct MainLayout {
@@ -23,11 +35,24 @@ pub struct MainLayout {
 
     pub repaint_ui: bool,
 
+    pub sort_by_priority: bool,
+
+    pub unread_only: bool,
+
+    /// Amount of reports to display on one page.
+    pub report_page_size: u64,
+
+    /// Whether the server closed the connection (for example, due to our inactivity).
+    pub connection_lost: bool,
+
+    /// Number of failed attempts to restore the lost connection.
+    pub reconnect_attempts: u32,
+
     #[data(ignore)]
     pub reports: Rc<RefCell<Vec<ReportSummary>>>, // using Rc because Data requires Clone
 
     #[data(ignore)]
-    pub is_user_admin: bool,
+    pub user_role: UserRole,
 
     #[data(ignore)]
     pub total_reports: Cell<u64>,
@@ -43,6 +68,58 @@ impl MainLayout {
             |data: &ApplicationState, _env| data.main_layout.repaint_ui,
             |_selector, data, _env| Box::new(MainLayout::build_ui_internal(data)),
         )
+        .controller(ConnectionStatusController::new())
+    }
+    fn build_connection_status_ui() -> impl Widget<ApplicationState> {
+        ViewSwitcher::new(
+            |data: &ApplicationState, _env| {
+                (
+                    data.main_layout.connection_lost,
+                    data.main_layout.reconnect_attempts,
+                )
+            },
+            |(connection_lost, reconnect_attempts), data, _env| {
+                let net_service = data.net_service.lock().unwrap();
+                let server_version = match net_service.get_server_version() {
+                    Some(version) => format!("v{}", version),
+                    None => String::from("unknown version"),
+                };
+
+                if *connection_lost {
+                    Box::new(
+                        Flex::row()
+                            .with_child(
+                                Label::new(format!(
+                                    "Connection to {} (server {}) was lost, \
+                                    reconnecting (attempt {} out of {})...",
+                                    net_service.get_server_address(),
+                                    server_version,
+                                    reconnect_attempts + 1,
+                                    RECONNECT_MAX_ATTEMPTS
+                                ))
+                                .with_text_size(TEXT_SIZE)
+                                .with_text_color(Color::YELLOW),
+                            )
+                            .with_default_spacer()
+                            .with_child(
+                                Button::from_label(
+                                    Label::new("Login Again").with_text_size(TEXT_SIZE),
+                                )
+                                .on_click(MainLayout::on_login_again_clicked),
+                            ),
+                    )
+                } else {
+                    Box::new(
+                        Label::new(format!(
+                            "Connected to {} (server {}).",
+                            net_service.get_server_address(),
+                            server_version
+                        ))
+                        .with_text_size(TEXT_SIZE),
+                    )
+                }
+            },
+        )
     }
     fn build_ui_internal(data: &ApplicationState) -> impl Widget<ApplicationState> {
         if data.main_layout.reports.borrow_mut().is_empty() {
@@ -58,8 +135,12 @@ impl MainLayout {
             .with_default_spacer();
 
         if data.main_layout.reports.borrow().len() == 0 && data.main_layout.current_page == 1 {
-            reports_column
-                .add_child(Label::new("No reports were received yet!").with_text_size(TEXT_SIZE))
+            let message = if data.main_layout.unread_only {
+                "There are no unread reports!"
+            } else {
+                "No reports were received yet!"
+            };
+            reports_column.add_child(Label::new(message).with_text_size(TEXT_SIZE))
         } else {
             for report in data.main_layout.reports.borrow().iter() {
                 reports_column.add_child(
@@ -69,6 +150,9 @@ impl MainLayout {
                         report.game.clone(),
                         report.date.clone(),
                         report.time.clone(),
+                        report.priority,
+                        report.occurrence_count,
+                        report.is_unread,
                     )
                     .build_ui(),
                 );
@@ -85,6 +169,16 @@ impl MainLayout {
             Flex::column()
                 .main_axis_alignment(MainAxisAlignment::Start)
                 .must_fill_main_axis(true)
+                .with_child(
+                    Flex::row()
+                        .must_fill_main_axis(true)
+                        .with_flex_child(MainLayout::build_connection_status_ui().align_left(), 1.0)
+                        .with_child(
+                            Button::from_label(Label::new("Logout").with_text_size(TEXT_SIZE))
+                                .on_click(MainLayout::on_logout_clicked),
+                        ),
+                )
+                .with_default_spacer()
                 .with_flex_child(
                     Flex::row()
                         .must_fill_main_axis(true)
@@ -94,9 +188,20 @@ impl MainLayout {
                             )
                             .on_click(MainLayout::on_refresh_report_list_clicked)
                             .align_left(),
-                            0.25,
+                            0.2,
+                        )
+                        .with_flex_child(
+                            Button::from_label(Label::new("Export CSV").with_text_size(TEXT_SIZE))
+                                .on_click(MainLayout::on_export_csv_clicked)
+                                .align_left(),
+                            0.15,
+                        )
+                        .with_flex_child(
+                            Button::from_label(Label::new("Stats").with_text_size(TEXT_SIZE))
+                                .on_click(MainLayout::on_stats_clicked)
+                                .align_left(),
+                            0.1,
                         )
-                        .with_flex_child(SizedBox::empty().expand(), 0.05)
                         .with_flex_child(
                             Label::new(|data: &ApplicationState, _env: &_| {
                                 let disk = data.main_layout.get_server_disk_usage(data);
@@ -120,14 +225,43 @@ impl MainLayout {
                                     Color::WHITE
                                 },
                             ),
-                            0.4,
+                            0.3,
                         )
                         .with_flex_child(SizedBox::empty().expand(), 0.05)
-                        .with_flex_child(SizedBox::empty().expand(), 0.25),
+                        .with_flex_child(
+                            Button::from_label(
+                                Label::new(|data: &ApplicationState, _env: &_| {
+                                    if data.main_layout.unread_only {
+                                        String::from("Show: Unread")
+                                    } else {
+                                        String::from("Show: All")
+                                    }
+                                })
+                                .with_text_size(TEXT_SIZE),
+                            )
+                            .on_click(MainLayout::on_unread_only_clicked)
+                            .align_right(),
+                            0.2,
+                        )
+                        .with_flex_child(
+                            Button::from_label(
+                                Label::new(|data: &ApplicationState, _env: &_| {
+                                    if data.main_layout.sort_by_priority {
+                                        String::from("Sort by: Priority")
+                                    } else {
+                                        String::from("Sort by: Date")
+                                    }
+                                })
+                                .with_text_size(TEXT_SIZE),
+                            )
+                            .on_click(MainLayout::on_sort_order_clicked)
+                            .align_right(),
+                            0.2,
+                        ),
                     0.1,
                 )
                 .with_default_spacer()
-                .with_flex_child(reports_column, 1.0)
+                .with_flex_child(Scroll::new(reports_column).vertical(), 1.0)
                 .with_default_spacer()
                 .with_child(
                     Flex::row()
@@ -170,21 +304,14 @@ impl MainLayout {
                                             )
                                             .disabled_if(|data: &ApplicationState, _env| {
                                                 data.main_layout.current_page
-                                                    >= MainLayout::calculate_last_page(
-                                                        data.main_layout.total_reports.get(),
-                                                    )
+                                                    >= data.main_layout.calculate_last_page()
                                             })
                                             .on_click(MainLayout::on_next_page_clicked),
                                         ),
                                 )
                                 .with_child(
                                     Label::new(|data: &ApplicationState, _env: &_| {
-                                        format!(
-                                            "out of {}",
-                                            MainLayout::calculate_last_page(
-                                                data.main_layout.total_reports.get()
-                                            )
-                                        )
+                                        format!("out of {}", data.main_layout.calculate_last_page())
                                     })
                                     .with_text_size(TEXT_SIZE),
                                 ),
@@ -196,9 +323,7 @@ impl MainLayout {
                             )
                             .disabled_if(|data: &ApplicationState, _env| {
                                 data.main_layout.current_page
-                                    >= MainLayout::calculate_last_page(
-                                        data.main_layout.total_reports.get(),
-                                    )
+                                    >= data.main_layout.calculate_last_page()
                             })
                             .on_click(MainLayout::on_open_last_page_clicked)
                             .align_right(),
@@ -211,11 +336,12 @@ impl MainLayout {
         data.net_service.lock().unwrap().get_server_disk_usage()
     }
     fn query_reports(&self, data: &ApplicationState) -> Result<(Vec<ReportSummary>, u64), ()> {
-        let result = data
-            .net_service
-            .lock()
-            .unwrap()
-            .query_reports(self.current_page, REPORT_COUNT_PER_PAGE);
+        let result = data.net_service.lock().unwrap().query_reports(
+            self.current_page,
+            self.report_page_size,
+            self.sort_by_priority,
+            self.unread_only,
+        );
 
         if let Err(app_error) = result {
             data.logger_service
@@ -228,28 +354,17 @@ impl MainLayout {
         Ok(result.unwrap())
     }
     fn on_open_last_page_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
-        let last_page = MainLayout::calculate_last_page(data.main_layout.total_reports.get());
+        let last_page = data.main_layout.calculate_last_page();
 
-        let result = data
-            .net_service
-            .lock()
-            .unwrap()
-            .query_reports(last_page, REPORT_COUNT_PER_PAGE);
+        let result = data.net_service.lock().unwrap().query_reports(
+            last_page,
+            data.main_layout.report_page_size,
+            data.main_layout.sort_by_priority,
+            data.main_layout.unread_only,
+        );
 
         if let Err(app_error) = result {
-            if app_error.get_message().contains("FIN") {
-                data.current_layout = Layout::Connect;
-                data.connect_layout.connect_error = format!(
-                    "{}\nMaybe the server \
-                    closed the connection due to your inactivity.",
-                    app_error.get_message()
-                );
-            } else {
-                data.logger_service
-                    .lock()
-                    .unwrap()
-                    .log(&app_error.to_string());
-            }
+            MainLayout::on_request_failed(data, app_error);
             return;
         }
 
@@ -260,6 +375,55 @@ impl MainLayout {
         data.main_layout.current_page = last_page;
         data.main_layout.repaint_ui = !data.main_layout.repaint_ui;
     }
+    /// Logs the error of a request and shows a warning if the connection was lost.
+    fn on_request_failed(data: &mut ApplicationState, app_error: AppError) {
+        data.logger_service
+            .lock()
+            .unwrap()
+            .log(&app_error.to_string());
+
+        if app_error.get_kind() == AppErrorKind::ConnectionClosed {
+            data.main_layout.connection_lost = true;
+        }
+    }
+    fn on_login_again_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
+        MainLayout::logout(data);
+        data.connect_layout.connect_error = String::from(
+            "Maybe the server closed the connection due to your inactivity, \
+            please, connect again.",
+        );
+    }
+    /// Shows the connect layout with the specified message after all attempts
+    /// to restore the lost connection failed.
+    pub fn on_connection_not_restored(data: &mut ApplicationState, message: &str) {
+        MainLayout::logout(data);
+        data.connect_layout.connect_error = format!(
+            "The connection to the server was lost and could not be restored: {}",
+            message
+        );
+    }
+    fn on_logout_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
+        MainLayout::logout(data);
+        data.logger_service.lock().unwrap().log("logged out");
+    }
+    /// Closes the connection, removes passwords and reports from the memory
+    /// and shows the connect layout.
+    ///
+    /// ## Remarks
+    /// Server address, port and username are kept to connect again.
+    fn logout(data: &mut ApplicationState) {
+        data.net_service.lock().unwrap().disconnect();
+
+        data.connect_layout.password = String::new();
+        data.connect_layout.connect_error = String::new();
+        data.otp_layout = OtpLayout::new();
+        data.change_password_layout = ChangePasswordLayout::new();
+        data.report_layout = ReportLayout::new();
+        data.stats_layout = StatsLayout::new();
+        data.main_layout = MainLayout::new();
+
+        data.current_layout = Layout::Connect;
+    }
     fn on_refresh_report_list_clicked(
         _ctx: &mut EventCtx,
         data: &mut ApplicationState,
@@ -268,27 +432,135 @@ impl MainLayout {
         data.main_layout.reports.borrow_mut().clear();
         data.main_layout.repaint_ui = !data.main_layout.repaint_ui;
     }
-    fn on_open_first_page_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
+    fn on_stats_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
         let result = data
             .net_service
             .lock()
             .unwrap()
-            .query_reports(1, REPORT_COUNT_PER_PAGE);
-
+            .query_stats(STATS_DAY_COUNT);
         if let Err(app_error) = result {
-            if app_error.get_message().contains("FIN") {
-                data.current_layout = Layout::Connect;
-                data.connect_layout.connect_error = format!(
-                    "{}\nMaybe the server \
-                    closed the connection due to your inactivity.",
-                    app_error.get_message()
-                );
-            } else {
-                data.logger_service
-                    .lock()
-                    .unwrap()
-                    .log(&app_error.to_string());
+            MainLayout::on_request_failed(data, app_error);
+            return;
+        }
+
+        data.stats_layout.stats = Rc::new(result.unwrap());
+        data.current_layout = Layout::Stats;
+    }
+    fn on_export_csv_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
+        let path = FileDialog::new()
+            .add_filter("CSV file", &["csv"])
+            .set_filename("reports.csv")
+            .show_save_single_file()
+            .unwrap();
+        if path.is_none() {
+            return;
+        }
+        let path = path.unwrap();
+
+        // Query all reports page by page.
+        let mut reports: Vec<ReportSummary> = Vec::new();
+        let mut page = 1;
+        loop {
+            let result = data.net_service.lock().unwrap().query_reports(
+                page,
+                CSV_EXPORT_REPORTS_PER_REQUEST,
+                false,
+                false,
+            );
+
+            if let Err(app_error) = result {
+                MainLayout::on_request_failed(data, app_error);
+                return;
+            }
+
+            let (page_reports, total_count) = result.unwrap();
+            let is_last_page =
+                page_reports.is_empty() || page * CSV_EXPORT_REPORTS_PER_REQUEST >= total_count;
+            reports.extend(page_reports);
+            if is_last_page {
+                break;
+            }
+            page += 1;
+        }
+
+        let mut csv = String::from("id,title,game,date,time\r\n");
+        for report in reports.iter() {
+            csv += &format!(
+                "{},{},{},{},{}\r\n",
+                report.id,
+                MainLayout::escape_csv_field(&report.title),
+                MainLayout::escape_csv_field(&report.game),
+                MainLayout::escape_csv_field(&report.date),
+                MainLayout::escape_csv_field(&report.time)
+            );
+        }
+
+        let (message_type, text) = match std::fs::write(&path, csv) {
+            Ok(()) => (
+                MessageType::Info,
+                format!("Exported {} report(-s).", reports.len()),
+            ),
+            Err(e) => {
+                data.logger_service.lock().unwrap().log(&format!(
+                    "failed to write CSV file \"{}\" (error: {})",
+                    path.display(),
+                    e
+                ));
+                (
+                    MessageType::Error,
+                    format!("Failed to save the file, error: {}", e),
+                )
             }
+        };
+        let _ = MessageDialog::new()
+            .set_type(message_type)
+            .set_title("Export CSV")
+            .set_text(&text)
+            .show_alert();
+    }
+    /// Returns a value that can be safely used as a CSV field.
+    ///
+    /// Fields with commas, quotes or new lines are quoted, values that spreadsheet
+    /// applications would treat as formulas are prefixed with an apostrophe
+    /// (titles and game names are written by players).
+    fn escape_csv_field(value: &str) -> String {
+        let mut value = value.to_string();
+        if value.starts_with(['=', '+', '-', '@']) {
+            value.insert(0, '\'');
+        }
+
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value
+        }
+    }
+    fn on_sort_order_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
+        data.main_layout.sort_by_priority = !data.main_layout.sort_by_priority;
+
+        // Show the first page using the new sort order.
+        data.main_layout.current_page = 1;
+        data.main_layout.reports.borrow_mut().clear(); // will refresh reports list
+        data.main_layout.repaint_ui = !data.main_layout.repaint_ui;
+    }
+    fn on_unread_only_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
+        data.main_layout.unread_only = !data.main_layout.unread_only;
+
+        // Show the first page using the new filter.
+        data.main_layout.current_page = 1;
+        data.main_layout.reports.borrow_mut().clear(); // will refresh reports list
+        data.main_layout.repaint_ui = !data.main_layout.repaint_ui;
+    }
+    fn on_open_first_page_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
+        let result = data.net_service.lock().unwrap().query_reports(
+            1,
+            data.main_layout.report_page_size,
+            data.main_layout.sort_by_priority,
+            data.main_layout.unread_only,
+        );
+
+        if let Err(app_error) = result {
+            MainLayout::on_request_failed(data, app_error);
             return;
         }
 
@@ -314,26 +586,15 @@ impl MainLayout {
         let page_diff: i64 = if is_next { 1 } else { -1 };
         let new_page = (data.main_layout.current_page as i64 + page_diff) as u64;
 
-        let result = data
-            .net_service
-            .lock()
-            .unwrap()
-            .query_reports(new_page, REPORT_COUNT_PER_PAGE);
+        let result = data.net_service.lock().unwrap().query_reports(
+            new_page,
+            data.main_layout.report_page_size,
+            data.main_layout.sort_by_priority,
+            data.main_layout.unread_only,
+        );
 
         if let Err(app_error) = result {
-            if app_error.get_message().contains("FIN") {
-                data.current_layout = Layout::Connect;
-                data.connect_layout.connect_error = format!(
-                    "{}\nMaybe the server \
-                    closed the connection due to your inactivity.",
-                    app_error.get_message()
-                );
-            } else {
-                data.logger_service
-                    .lock()
-                    .unwrap()
-                    .log(&app_error.to_string());
-            }
+            MainLayout::on_request_failed(data, app_error);
             return;
         }
 
@@ -344,14 +605,16 @@ impl MainLayout {
         data.main_layout.current_page = new_page;
         data.main_layout.repaint_ui = !data.main_layout.repaint_ui;
     }
-    fn calculate_last_page(total_reports: u64) -> u64 {
-        if total_reports <= REPORT_COUNT_PER_PAGE {
+    fn calculate_last_page(&self) -> u64 {
+        let total_reports = self.total_reports.get();
+
+        if total_reports <= self.report_page_size {
             return 1;
         }
 
-        let mut last_page = total_reports / REPORT_COUNT_PER_PAGE;
+        let mut last_page = total_reports / self.report_page_size;
 
-        if total_reports % REPORT_COUNT_PER_PAGE != 0 {
+        if total_reports % self.report_page_size != 0 {
             last_page += 1;
         }
 
@@ -365,8 +628,13 @@ impl Default for MainLayout {
             current_page: 1,
             reports: Rc::new(RefCell::new(Vec::new())),
             total_reports: Cell::new(0),
-            is_user_admin: false,
+            user_role: UserRole::default(),
             repaint_ui: false,
+            sort_by_priority: false,
+            unread_only: false,
+            report_page_size: ConfigManager::new().report_page_size,
+            connection_lost: false,
+            reconnect_attempts: 0,
         }
     }
 }
@@ -4,3 +4,4 @@ pub mod main_layout;
 pub mod otp_layout;
 pub mod report_layout;
 pub mod settings_layout;
+pub mod stats_layout;
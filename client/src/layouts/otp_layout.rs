@@ -1,15 +1,17 @@
 // External.
 use base64::{engine::general_purpose, Engine as _};
 use druid::widget::{prelude::*, LineBreaking, SizedBox};
-use druid::widget::{Button, Flex, Label, MainAxisAlignment, TextBox};
+use druid::widget::{Button, Either, Flex, Label, MainAxisAlignment, TextBox};
 use druid::{
     piet::{ImageBuf, ImageFormat, InterpolationMode},
     widget::{FillStrat, Image},
 };
-use druid::{Lens, LensExt, TextAlignment, WidgetExt};
+use druid::{Key, Lens, LensExt, TextAlignment, WidgetExt};
 use image::DynamicImage::ImageRgba8;
 
 // Custom.
+use crate::layouts::connect_layout::ConnectLayout;
+use crate::misc::theme::LARGE_TEXT_SIZE;
 use crate::network::net_service::ConnectResult;
 use crate::{ApplicationState, Layout};
 
@@ -19,7 +21,7 @@ const TOP_PADDING: f64 = 0.1;
 const BOTTOM_PADDING: f64 = 0.1;
 const BUTTONS_WIDTH_PADDING: f64 = 1.0;
 const BUTTON_HEIGHT: f64 = 0.14;
-const TEXT_SIZE: f64 = 20.0;
+const TEXT_SIZE: Key<f64> = LARGE_TEXT_SIZE;
 const ROW_SPACING: f64 = 0.2;
 
 #[derive(Default, Clone, Data, Lens)]
@@ -27,6 +29,7 @@ pub struct OtpLayout {
     pub qr_code: Option<String>,
     otp: String,
     connect_error: String,
+    otp_backup_codes: String, // not empty once OTP setup is finished
 }
 
 impl OtpLayout {
@@ -34,6 +37,13 @@ impl OtpLayout {
         Self::default()
     }
     pub fn build_ui(&self) -> impl Widget<ApplicationState> {
+        Either::new(
+            |data: &ApplicationState, _env| data.otp_layout.otp_backup_codes.is_empty(),
+            self.build_otp_ui(),
+            OtpLayout::build_backup_codes_ui(),
+        )
+    }
+    fn build_otp_ui(&self) -> impl Widget<ApplicationState> {
         let mut qr_code_item = Flex::column();
         if self.qr_code.is_some() {
             // Decode base64.
@@ -85,6 +95,13 @@ impl OtpLayout {
                 .with_child(image_widget);
         }
 
+        // Backup codes are only accepted once OTP setup is finished.
+        let otp_label_text = if self.qr_code.is_some() {
+            "Enter your OTP:"
+        } else {
+            "Enter your OTP (or an OTP backup code):"
+        };
+
         Flex::column()
             .main_axis_alignment(MainAxisAlignment::Center)
             .must_fill_main_axis(true)
@@ -97,7 +114,7 @@ impl OtpLayout {
                     .must_fill_main_axis(true)
                     .with_flex_child(SizedBox::empty().expand(), BUTTONS_WIDTH_PADDING)
                     .with_flex_child(
-                        Label::new("Enter your OTP:")
+                        Label::new(otp_label_text)
                             .with_text_size(TEXT_SIZE)
                             .expand(),
                         1.0,
@@ -146,6 +163,61 @@ impl OtpLayout {
             )
             .with_flex_child(SizedBox::empty().expand(), BOTTOM_PADDING)
     }
+    fn build_backup_codes_ui() -> impl Widget<ApplicationState> {
+        Flex::column()
+            .main_axis_alignment(MainAxisAlignment::Center)
+            .must_fill_main_axis(true)
+            .with_flex_child(SizedBox::empty().expand(), TOP_PADDING)
+            .with_flex_child(
+                Flex::row()
+                    .with_flex_child(SizedBox::empty().expand(), WIDTH_PADDING)
+                    .with_flex_child(
+                        Label::new(
+                            "OTP setup is finished. Below are your OTP backup codes, \
+                            save them somewhere safe. If you will lose access to your \
+                            authenticator app you can enter one of these codes instead \
+                            of an OTP (each code can only be used once). \
+                            These codes will not be shown again.",
+                        )
+                        .with_text_size(TEXT_SIZE)
+                        .with_text_alignment(TextAlignment::Center)
+                        .with_line_break_mode(LineBreaking::WordWrap)
+                        .expand(),
+                        1.0,
+                    )
+                    .with_flex_child(SizedBox::empty().expand(), WIDTH_PADDING),
+                1.0,
+            )
+            .with_default_spacer()
+            .with_child(
+                Label::new(|data: &ApplicationState, _env: &Env| {
+                    data.otp_layout.otp_backup_codes.clone()
+                })
+                .with_text_size(TEXT_SIZE)
+                .with_text_alignment(TextAlignment::Center),
+            )
+            .with_flex_child(SizedBox::empty().expand(), ROW_SPACING)
+            .with_flex_child(
+                Flex::row()
+                    .main_axis_alignment(MainAxisAlignment::Center)
+                    .must_fill_main_axis(true)
+                    .with_flex_child(SizedBox::empty().expand(), BUTTONS_WIDTH_PADDING)
+                    .with_flex_child(
+                        Button::from_label(Label::new("Continue").with_text_size(TEXT_SIZE))
+                            .on_click(OtpLayout::on_continue_clicked)
+                            .expand(),
+                        1.0,
+                    )
+                    .with_flex_child(SizedBox::empty().expand(), BUTTONS_WIDTH_PADDING),
+                BUTTON_HEIGHT,
+            )
+            .with_flex_child(SizedBox::empty().expand(), BOTTOM_PADDING)
+    }
+    fn on_continue_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
+        // Don't keep backup codes in memory.
+        data.otp_layout.otp_backup_codes = String::new();
+        data.current_layout = Layout::Main;
+    }
     fn on_connect_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
         // Check if all essential fields are filled.
         if data.otp_layout.otp.is_empty() {
@@ -181,19 +253,36 @@ impl OtpLayout {
                     .lock()
                     .unwrap()
                     .log(&app_error.to_string());
-                data.otp_layout.connect_error = app_error.to_string();
+                data.otp_layout.connect_error = ConnectLayout::get_connect_error_message(
+                    &app_error,
+                    &data.connect_layout.server,
+                    port,
+                );
             }
             ConnectResult::ConnectFailed(reason) => {
                 println!("{}", reason);
                 data.logger_service.lock().unwrap().log(&reason);
                 data.otp_layout.connect_error = reason;
             }
-            ConnectResult::Connected(is_admin) => {
-                data.main_layout.is_user_admin = is_admin;
+            ConnectResult::WrongProtocol(server_protocol) => {
+                let message = ConnectLayout::get_protocol_mismatch_message(server_protocol, None);
+                println!("{}", message);
+                data.logger_service.lock().unwrap().log(&message);
+                data.otp_layout.connect_error = message;
+            }
+            ConnectResult::Connected(role, otp_backup_codes) => {
+                data.main_layout.user_role = role;
                 data.connect_layout.password = String::new();
-                data.current_layout = Layout::Main;
+                data.otp_layout.otp = String::new();
+
+                if otp_backup_codes.is_empty() {
+                    data.current_layout = Layout::Main;
+                } else {
+                    // Show backup codes before going to the main layout.
+                    data.otp_layout.otp_backup_codes = otp_backup_codes.join("\n");
+                }
             }
-            ConnectResult::NeedFirstPassword => {
+            ConnectResult::NeedFirstPassword(_) => {
                 let message = "error: received \"NeedFirstPassword\" in OTP mode.";
                 println!("{}", message);
                 data.logger_service.lock().unwrap().log(message);
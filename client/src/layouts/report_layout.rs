@@ -1,26 +1,45 @@
 // Std.
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Write;
 use std::rc::Rc;
 
 // External.
-use druid::widget::{prelude::*, Scroll, SizedBox};
-use druid::widget::{Button, Flex, Label, Padding};
-use druid::{TextAlignment, WidgetExt};
+use druid::piet::{ImageBuf, ImageFormat, InterpolationMode};
+use druid::text::RichText;
+use druid::widget::{prelude::*, Either, Scroll, SizedBox};
+use druid::widget::{
+    Button, CrossAxisAlignment, FillStrat, Flex, Image, Label, LineBreaking, Padding, ProgressBar,
+    RawLabel, TextBox,
+};
+use druid::{Application, Key, Lens, LensExt, TextAlignment, WidgetExt};
 use native_dialog::{FileDialog, MessageDialog, MessageType};
 
 // Custom.
-use super::main_layout::REPORT_COUNT_PER_PAGE;
+use crate::misc::markdown::markdown_to_rich_text;
 use crate::misc::report_attachment_button::*;
+use crate::misc::theme::NORMAL_TEXT_SIZE;
 use crate::{ApplicationState, Layout};
-use shared::misc::report::ReportData;
+use shared::misc::error::AppErrorKind;
+use shared::misc::report::{ReportComment, ReportData, ReportPriority, REPORT_COMMENT_MAX_LENGTH};
 
 // Layout customization.
-const TEXT_SIZE: f64 = 18.0;
+const TEXT_SIZE: Key<f64> = NORMAL_TEXT_SIZE;
+const THUMBNAIL_SIZE: f64 = 128.0;
+const THUMBNAILS_PER_ROW: usize = 4;
 
-#[derive(Clone)]
+#[derive(Clone, Data, Lens)]
 pub struct ReportLayout {
-    pub report: Rc<ReportData>, // using Rc to implement Clone
+    pub report: Rc<ReportData>, // using Rc to implement Clone (changes are detected by pointer)
+    pub report_text: RichText,  // report text (rendered from Markdown if it's Markdown)
+    pub comments: Rc<Vec<ReportComment>>,
+    pub new_comment: String,
+    pub copy_status: String, // tells what was copied to the clipboard
+    pub show_raw_report: bool,
+    pub raw_report: String,             // report as JSON (for debugging)
+    pub download_progress: Option<f64>, // `Some` while an attachment is being downloaded
+    pub download_file_name: String,
+    pub download_size_in_bytes: usize,
 }
 
 impl ReportLayout {
@@ -28,9 +47,26 @@ impl ReportLayout {
         Self::default()
     }
 
+    /// Sets the report to display.
+    ///
+    /// ## Arguments
+    /// * `report`: report to display.
+    /// * `text_size`: size of the report text (see `ApplicationTheme::get_normal_text_size`).
+    pub fn set_report(&mut self, report: ReportData, text_size: f64) {
+        self.report_text = if report.is_markdown {
+            markdown_to_rich_text(&report.text, text_size)
+        } else {
+            RichText::new(report.text.as_str().into())
+        };
+        self.raw_report = ReportLayout::report_to_json(&report);
+        self.report = Rc::new(report);
+        self.copy_status = String::new();
+        self.show_raw_report = false;
+    }
+
     pub fn build_ui(data: &ApplicationState) -> impl Widget<ApplicationState> {
         let mut delete_report_section: Flex<ApplicationState> = Flex::row();
-        if data.main_layout.is_user_admin {
+        if data.main_layout.user_role.can_delete_reports() {
             delete_report_section = delete_report_section
                 .with_flex_child(SizedBox::empty().expand_width(), 0.5)
                 .with_child(
@@ -43,6 +79,33 @@ impl ReportLayout {
                 delete_report_section.with_flex_child(SizedBox::empty().expand_width(), 1.0)
         }
 
+        // Setup priority section.
+        let mut priority_section: Flex<ApplicationState> = Flex::row().with_child(
+            Label::new(|data: &ApplicationState, _env: &_| {
+                format!("Priority: {}", data.report_layout.report.priority)
+            })
+            .with_text_size(TEXT_SIZE),
+        );
+        if data.main_layout.user_role.can_change_reports() {
+            priority_section.add_default_spacer();
+            for priority in [
+                ReportPriority::Low,
+                ReportPriority::Normal,
+                ReportPriority::High,
+                ReportPriority::Critical,
+            ] {
+                priority_section.add_child(
+                    Button::from_label(Label::new(priority.to_string()).with_text_size(TEXT_SIZE))
+                        .disabled_if(move |data: &ApplicationState, _env| {
+                            data.report_layout.report.priority == priority
+                        })
+                        .on_click(move |_ctx, data: &mut ApplicationState, _env| {
+                            ReportLayout::on_priority_clicked(data, priority)
+                        }),
+                );
+            }
+        }
+
         // Setup attachment column.
         let mut attachment_column = Flex::column();
 
@@ -54,6 +117,20 @@ impl ReportLayout {
             );
             attachment_column.add_default_spacer();
             for attachment in data.report_layout.report.attachments.iter() {
+                if attachment.is_expired {
+                    // The data was removed, only show the name.
+                    attachment_column.add_child(
+                        Label::new(format!(
+                            "{} (expired, {})",
+                            attachment.file_name,
+                            ReportLayout::format_size(attachment.size_in_bytes)
+                        ))
+                        .with_text_size(TEXT_SIZE)
+                        .align_left(),
+                    );
+                    continue;
+                }
+
                 attachment_column.add_child(
                     Flex::row()
                         .with_child(
@@ -66,20 +143,133 @@ impl ReportLayout {
                                 ReportAttachmentButtonController::new(ReportAttachmentButtonData {
                                     attachment_id: attachment.id,
                                     attachment_file_name: attachment.file_name.clone(),
+                                    attachment_size_in_bytes: attachment.size_in_bytes,
                                 }),
                             ),
                         )
                         .with_child(
-                            Label::new(match attachment.size_in_bytes {
-                                0..=1023 => format!("{} bytes", attachment.size_in_bytes),
-                                1024..=1048575 => format!("{} KB", attachment.size_in_bytes / 1024),
-                                _ => format!("{} MB", attachment.size_in_bytes / 1024 / 1024),
-                            })
+                            Label::new(format!(
+                                "{} ({})",
+                                ReportLayout::format_size(attachment.size_in_bytes),
+                                attachment.mime_type
+                            ))
                             .with_text_size(TEXT_SIZE),
                         )
                         .align_left(),
                 );
             }
+
+            // Show previews of images in a grid.
+            let thumbnails: Vec<Image> = data
+                .report_layout
+                .report
+                .attachments
+                .iter()
+                .filter_map(|attachment| {
+                    ReportLayout::thumbnail_to_image(attachment.thumbnail.as_ref()?)
+                })
+                .collect();
+            if !thumbnails.is_empty() {
+                attachment_column.add_default_spacer();
+
+                let mut thumbnail_row = Flex::row();
+                for (i, thumbnail) in thumbnails.into_iter().enumerate() {
+                    if i != 0 && i % THUMBNAILS_PER_ROW == 0 {
+                        attachment_column.add_child(thumbnail_row.align_left());
+                        attachment_column.add_default_spacer();
+                        thumbnail_row = Flex::row();
+                    }
+                    thumbnail_row.add_child(thumbnail.fix_size(THUMBNAIL_SIZE, THUMBNAIL_SIZE));
+                    thumbnail_row.add_default_spacer();
+                }
+                attachment_column.add_child(thumbnail_row.align_left());
+            }
+
+            attachment_column.add_child(ReportLayout::build_download_progress_ui().align_left());
+        }
+
+        // Setup metadata table (keys in the left column, values in the right column).
+        let mut metadata_column = Flex::column();
+
+        if !data.report_layout.report.metadata.is_empty() {
+            metadata_column.add_child(
+                Label::new("Report metadata:")
+                    .with_text_size(TEXT_SIZE)
+                    .align_left(),
+            );
+            metadata_column.add_default_spacer();
+
+            let mut key_column = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            let mut value_column = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            for (key, value) in data.report_layout.report.metadata.iter() {
+                key_column.add_child(Label::new(format!("{}:", key)).with_text_size(TEXT_SIZE));
+                value_column.add_child(Label::new(value.clone()).with_text_size(TEXT_SIZE));
+            }
+
+            metadata_column.add_child(
+                Flex::row()
+                    .cross_axis_alignment(CrossAxisAlignment::Start)
+                    .with_child(key_column)
+                    .with_default_spacer()
+                    .with_child(value_column)
+                    .align_left(),
+            );
+        }
+
+        // Setup comment section.
+        let mut comment_section = Flex::column()
+            .with_child(
+                Label::new(|data: &ApplicationState, _env: &_| {
+                    format!("Comments ({}):", data.report_layout.comments.len())
+                })
+                .with_text_size(TEXT_SIZE)
+                .align_left(),
+            )
+            .with_default_spacer()
+            .with_flex_child(
+                Scroll::new(
+                    Label::new(|data: &ApplicationState, _env: &_| {
+                        data.report_layout
+                            .comments
+                            .iter()
+                            .map(|comment| {
+                                format!(
+                                    "{} ({}, {}):\n{}",
+                                    comment.author, comment.date, comment.time, comment.text
+                                )
+                            })
+                            .collect::<Vec<String>>()
+                            .join("\n\n")
+                    })
+                    .with_line_break_mode(LineBreaking::WordWrap)
+                    .with_text_size(TEXT_SIZE)
+                    .align_left(),
+                )
+                .vertical(),
+                1.0,
+            );
+        if data.main_layout.user_role.can_change_reports() {
+            comment_section.add_default_spacer();
+            comment_section.add_child(
+                Flex::row()
+                    .with_flex_child(
+                        TextBox::multiline()
+                            .with_text_size(TEXT_SIZE)
+                            .with_placeholder("Your comment...")
+                            .lens(ApplicationState::report_layout.then(ReportLayout::new_comment))
+                            .expand_width(),
+                        1.0,
+                    )
+                    .with_default_spacer()
+                    .with_child(
+                        Button::from_label(Label::new("Add Comment").with_text_size(TEXT_SIZE))
+                            .disabled_if(|data: &ApplicationState, _env| {
+                                let length = data.report_layout.new_comment.trim().chars().count();
+                                length == 0 || length > REPORT_COMMENT_MAX_LENGTH
+                            })
+                            .on_click(ReportLayout::on_add_comment_clicked),
+                    ),
+            );
         }
 
         Padding::new(
@@ -158,24 +348,70 @@ impl ReportLayout {
                         )
                         .align_left(),
                 )
+                .with_child(
+                    Flex::row()
+                        .with_child(
+                            Label::new(|data: &ApplicationState, _env: &_| {
+                                format!(
+                                    "Reporter: {}",
+                                    ReportLayout::get_reporter_info(&data.report_layout.report)
+                                )
+                            })
+                            .with_text_size(TEXT_SIZE),
+                        )
+                        .align_left(),
+                )
+                .with_child(
+                    Flex::row()
+                        .with_child(
+                            Label::new(|data: &ApplicationState, _env: &_| {
+                                format!(
+                                    "Session: {}",
+                                    ReportLayout::get_session_info(&data.report_layout.report)
+                                )
+                            })
+                            .with_text_size(TEXT_SIZE),
+                        )
+                        .align_left(),
+                )
+                .with_child(priority_section.align_left())
                 .with_default_spacer()
                 .with_default_spacer()
                 .with_flex_child(
-                    Scroll::new(
-                        Label::new(|data: &ApplicationState, _env: &_| {
-                            data.report_layout.report.text.clone()
-                        })
-                        .with_text_size(TEXT_SIZE)
-                        .align_left(),
-                    )
-                    .vertical(),
+                    Either::new(
+                        |data: &ApplicationState, _env| data.report_layout.show_raw_report,
+                        Scroll::new(
+                            Label::new(|data: &ApplicationState, _env: &_| {
+                                data.report_layout.raw_report.clone()
+                            })
+                            .with_text_size(TEXT_SIZE)
+                            .with_line_break_mode(LineBreaking::WordWrap)
+                            .expand_width(),
+                        )
+                        .vertical(),
+                        Scroll::new(
+                            RawLabel::new()
+                                .with_text_size(TEXT_SIZE)
+                                .with_line_break_mode(LineBreaking::WordWrap)
+                                .lens(
+                                    ApplicationState::report_layout.then(ReportLayout::report_text),
+                                )
+                                .expand_width(),
+                        )
+                        .vertical(),
+                    ),
                     1.0,
                 )
                 .with_default_spacer()
                 .with_default_spacer()
+                .with_child(metadata_column)
+                .with_default_spacer()
                 .with_child(attachment_column)
                 .with_default_spacer()
                 .with_default_spacer()
+                .with_flex_child(comment_section, 0.6)
+                .with_default_spacer()
+                .with_default_spacer()
                 .with_flex_child(
                     Flex::row()
                         .with_child(
@@ -183,6 +419,39 @@ impl ReportLayout {
                                 .on_click(ReportLayout::on_return_clicked),
                         )
                         .with_flex_child(delete_report_section, 1.0)
+                        .with_child(
+                            Label::new(|data: &ApplicationState, _env: &_| {
+                                data.report_layout.copy_status.clone()
+                            })
+                            .with_text_size(TEXT_SIZE),
+                        )
+                        .with_default_spacer()
+                        .with_child(
+                            Button::from_label(
+                                Label::new(|data: &ApplicationState, _env: &_| {
+                                    if data.report_layout.show_raw_report {
+                                        String::from("View Text")
+                                    } else {
+                                        String::from("View Raw")
+                                    }
+                                })
+                                .with_text_size(TEXT_SIZE),
+                            )
+                            .on_click(
+                                |_ctx, data: &mut ApplicationState, _env| {
+                                    data.report_layout.show_raw_report =
+                                        !data.report_layout.show_raw_report;
+                                },
+                            ),
+                        )
+                        .with_child(
+                            Button::from_label(Label::new("Copy ID").with_text_size(TEXT_SIZE))
+                                .on_click(ReportLayout::on_copy_id_clicked),
+                        )
+                        .with_child(
+                            Button::from_label(Label::new("Copy Text").with_text_size(TEXT_SIZE))
+                                .on_click(ReportLayout::on_copy_text_clicked),
+                        )
                         .with_child(
                             Button::from_label(
                                 Label::new("Save Text to File").with_text_size(TEXT_SIZE),
@@ -192,18 +461,128 @@ impl ReportLayout {
                     0.2,
                 ),
         )
+        // Other requests will wait for the download to finish.
+        .disabled_if(|data: &ApplicationState, _env| data.report_layout.download_progress.is_some())
+    }
+    /// Shows the progress of the attachment download (if downloading).
+    fn build_download_progress_ui() -> impl Widget<ApplicationState> {
+        Either::new(
+            |data: &ApplicationState, _env| data.report_layout.download_progress.is_some(),
+            Flex::row()
+                .with_child(
+                    Label::new(|data: &ApplicationState, _env: &_| {
+                        let progress = data.report_layout.download_progress.unwrap_or_default();
+                        format!(
+                            "Downloading \"{}\": {} / {}",
+                            data.report_layout.download_file_name,
+                            ReportLayout::format_size(
+                                (data.report_layout.download_size_in_bytes as f64 * progress)
+                                    as usize
+                            ),
+                            ReportLayout::format_size(data.report_layout.download_size_in_bytes)
+                        )
+                    })
+                    .with_text_size(TEXT_SIZE),
+                )
+                .with_default_spacer()
+                .with_child(ProgressBar::new().lens(druid::lens::Map::new(
+                    |data: &ApplicationState| {
+                        data.report_layout.download_progress.unwrap_or_default()
+                    },
+                    |_data: &mut ApplicationState, _progress: f64| {},
+                ))),
+            SizedBox::empty(),
+        )
+    }
+    /// Returns the report as pretty-printed JSON (without thumbnails of attachments).
+    fn report_to_json(report: &ReportData) -> String {
+        let mut value = match serde_json::to_value(report) {
+            Ok(value) => value,
+            Err(e) => return format!("failed to convert the report to JSON: {}", e),
+        };
+
+        if let Some(attachments) = value["attachments"].as_array_mut() {
+            for attachment in attachments.iter_mut() {
+                if let Some(attachment) = attachment.as_object_mut() {
+                    attachment.remove("thumbnail");
+                }
+            }
+        }
+
+        serde_json::to_string_pretty(&value)
+            .unwrap_or_else(|e| format!("failed to convert the report to JSON: {}", e))
+    }
+    /// Returns human-readable size of an attachment.
+    pub fn format_size(size_in_bytes: usize) -> String {
+        match size_in_bytes {
+            0..=1023 => format!("{} bytes", size_in_bytes),
+            1024..=1048575 => format!("{} KB", size_in_bytes / 1024),
+            _ => format!("{} MB", size_in_bytes / 1024 / 1024),
+        }
+    }
+    /// Returns version and network protocol of the reporter that sent the report
+    /// (reports saved by older server versions don't have this information).
+    fn get_reporter_info(report: &ReportData) -> String {
+        if report.reporter_net_protocol == 0 {
+            return String::from("unknown");
+        }
+
+        format!(
+            "v{} (network protocol {})",
+            report.reporter_version, report.reporter_net_protocol
+        )
+    }
+    /// Returns ID of the game session and playtime (both are optional).
+    fn get_session_info(report: &ReportData) -> String {
+        let playtime = format!(
+            "{}:{:02}:{:02}",
+            report.playtime_seconds / 3600,
+            report.playtime_seconds % 3600 / 60,
+            report.playtime_seconds % 60
+        );
+
+        match (report.session_id.is_empty(), report.playtime_seconds == 0) {
+            (true, true) => String::from("no information provided"),
+            (true, false) => format!("unknown ID (playtime: {})", playtime),
+            (false, true) => report.session_id.clone(),
+            (false, false) => format!("{} (playtime: {})", report.session_id, playtime),
+        }
+    }
+    /// Converts a PNG thumbnail received from the server to an image widget.
+    ///
+    /// ## Return
+    /// `None` if the thumbnail could not be decoded.
+    fn thumbnail_to_image(thumbnail: &[u8]) -> Option<Image> {
+        let image = image::load_from_memory(thumbnail).ok()?.to_rgba8();
+
+        let image_width = image.width() as usize;
+        let image_height = image.height() as usize;
+
+        let image_data = ImageBuf::from_raw(
+            image.into_raw(),
+            ImageFormat::RgbaSeparate,
+            image_width,
+            image_height,
+        );
+
+        Some(
+            Image::new(image_data)
+                .fill_mode(FillStrat::Contain)
+                .interpolation_mode(InterpolationMode::Bilinear),
+        )
     }
     fn on_return_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
         // Do this here because query_reports from MainLayout
         // does not have mut Data.
-        let result = data
-            .net_service
-            .lock()
-            .unwrap()
-            .query_reports(data.main_layout.current_page, REPORT_COUNT_PER_PAGE);
+        let result = data.net_service.lock().unwrap().query_reports(
+            data.main_layout.current_page,
+            data.main_layout.report_page_size,
+            data.main_layout.sort_by_priority,
+            data.main_layout.unread_only,
+        );
 
         if let Err(app_error) = result {
-            if app_error.get_message().contains("FIN") {
+            if app_error.get_kind() == AppErrorKind::ConnectionClosed {
                 data.current_layout = Layout::Connect;
                 data.connect_layout.connect_error = format!(
                     "{}\nMaybe the server \
@@ -244,7 +623,7 @@ impl ReportLayout {
             .unwrap()
             .delete_report(data.report_layout.report.id);
         if let Err(app_error) = result {
-            if app_error.get_message().contains("FIN") {
+            if app_error.get_kind() == AppErrorKind::ConnectionClosed {
                 data.current_layout = Layout::Connect;
                 data.connect_layout.connect_error = format!(
                     "{}\nMaybe the server \
@@ -269,6 +648,96 @@ impl ReportLayout {
             data.current_layout = Layout::Main;
         }
     }
+    fn on_priority_clicked(data: &mut ApplicationState, priority: ReportPriority) {
+        let report_id = data.report_layout.report.id;
+
+        // Change priority.
+        let result = data
+            .net_service
+            .lock()
+            .unwrap()
+            .set_report_priority(report_id, priority);
+        if let Err(app_error) = result {
+            if app_error.get_kind() == AppErrorKind::ConnectionClosed {
+                data.current_layout = Layout::Connect;
+                data.connect_layout.connect_error = format!(
+                    "{}\nMaybe the server \
+                    closed the connection due to your inactivity.",
+                    app_error.get_message()
+                );
+            } else {
+                println!("ERROR: {}", app_error);
+            }
+
+            return;
+        }
+        if !result.unwrap() {
+            println!("ERROR: a report with id {} was not found", report_id);
+            return;
+        }
+
+        // Query updated report.
+        let result = data.net_service.lock().unwrap().query_report(report_id);
+        if let Err(app_error) = result {
+            println!("ERROR: {}", app_error);
+            return;
+        }
+        data.report_layout
+            .set_report(result.unwrap(), data.theme.get_normal_text_size());
+    }
+    fn on_add_comment_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
+        let report_id = data.report_layout.report.id;
+
+        // Add comment.
+        let result = data
+            .net_service
+            .lock()
+            .unwrap()
+            .add_report_comment(report_id, data.report_layout.new_comment.trim().to_string());
+        if let Err(app_error) = result {
+            if app_error.get_kind() == AppErrorKind::ConnectionClosed {
+                data.current_layout = Layout::Connect;
+                data.connect_layout.connect_error = format!(
+                    "{}\nMaybe the server \
+                    closed the connection due to your inactivity.",
+                    app_error.get_message()
+                );
+            } else {
+                println!("ERROR: {}", app_error);
+            }
+
+            return;
+        }
+        if !result.unwrap() {
+            println!("ERROR: a report with id {} was not found", report_id);
+            return;
+        }
+        data.report_layout.new_comment = String::new();
+
+        // Query updated comments.
+        let result = data
+            .net_service
+            .lock()
+            .unwrap()
+            .query_report_comments(report_id);
+        if let Err(app_error) = result {
+            println!("ERROR: {}", app_error);
+            return;
+        }
+        data.report_layout.comments = Rc::new(result.unwrap());
+    }
+    fn on_copy_id_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
+        Application::global()
+            .clipboard()
+            .put_string(data.report_layout.report.id.to_string());
+        data.report_layout.copy_status = String::from("ID copied.");
+    }
+    fn on_copy_text_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
+        Application::global()
+            .clipboard()
+            .put_string(&data.report_layout.report.text);
+        data.report_layout.copy_status = String::from("Text copied.");
+    }
     fn on_save_to_file_clicked(_ctx: &mut EventCtx, data: &mut ApplicationState, _env: &Env) {
         let path = FileDialog::new()
             .add_filter("Text file", &["txt"])
@@ -316,6 +785,27 @@ impl ReportLayout {
         )
         .unwrap();
         writeln!(&mut file, "os_info: {}", data.report_layout.report.os_info).unwrap();
+        writeln!(
+            &mut file,
+            "reporter: {}",
+            ReportLayout::get_reporter_info(&data.report_layout.report)
+        )
+        .unwrap();
+        writeln!(
+            &mut file,
+            "session: {}",
+            ReportLayout::get_session_info(&data.report_layout.report)
+        )
+        .unwrap();
+        writeln!(
+            &mut file,
+            "priority: {}",
+            data.report_layout.report.priority
+        )
+        .unwrap();
+        for (key, value) in data.report_layout.report.metadata.iter() {
+            writeln!(&mut file, "metadata.{}: {}", key, value).unwrap();
+        }
         writeln!(&mut file, "text:\n{}", data.report_layout.report.text).unwrap();
     }
 }
@@ -334,8 +824,24 @@ impl Default for ReportLayout {
                 sender_name: String::new(),
                 sender_email: String::new(),
                 os_info: String::new(),
+                priority: ReportPriority::default(),
                 attachments: Vec::new(),
+                metadata: BTreeMap::new(),
+                reporter_net_protocol: 0,
+                reporter_version: String::new(),
+                is_markdown: false,
+                session_id: String::new(),
+                playtime_seconds: 0,
             }),
+            report_text: RichText::new("".into()),
+            comments: Rc::new(Vec::new()),
+            new_comment: String::new(),
+            copy_status: String::new(),
+            show_raw_report: false,
+            raw_report: String::new(),
+            download_progress: None,
+            download_file_name: String::new(),
+            download_size_in_bytes: 0,
         }
     }
 }
@@ -1,12 +1,15 @@
 // External.
 use druid::widget::{prelude::*, Button, Padding};
 use druid::widget::{CrossAxisAlignment, Flex, Label, LineBreaking, SizedBox};
+use druid::Key;
 
 // Custom.
+use crate::io::config_manager::{ConfigManager, REPORT_PAGE_SIZES};
 use crate::io::log_manager::LogManager;
+use crate::misc::theme::{ThemePreset, LARGE_TEXT_SIZE, UI_SCALE_STEP};
 use crate::{ApplicationState, Layout};
 
-const TEXT_SIZE: f64 = 20.0;
+const TEXT_SIZE: Key<f64> = LARGE_TEXT_SIZE;
 
 #[derive(Default, Clone, Data)]
 pub struct SettingsLayout {}
@@ -15,6 +18,34 @@ impl SettingsLayout {
     pub fn new() -> Self {
         Self::default()
     }
+    fn build_report_page_size_ui() -> impl Widget<ApplicationState> {
+        let mut row = Flex::row()
+            .with_child(Label::new("Reports per page:").with_text_size(TEXT_SIZE))
+            .with_default_spacer();
+
+        for page_size in REPORT_PAGE_SIZES {
+            row.add_child(
+                Button::from_label(Label::new(page_size.to_string()).with_text_size(TEXT_SIZE))
+                    .disabled_if(move |data: &ApplicationState, _env| {
+                        data.main_layout.report_page_size == page_size
+                    })
+                    .on_click(move |_ctx, data: &mut ApplicationState, _env| {
+                        SettingsLayout::on_report_page_size_clicked(data, page_size)
+                    }),
+            );
+        }
+
+        row
+    }
+    fn on_report_page_size_clicked(data: &mut ApplicationState, page_size: u64) {
+        data.main_layout.report_page_size = page_size;
+        data.main_layout.current_page = 1;
+        data.main_layout.reports.borrow_mut().clear(); // will refresh reports list
+
+        let mut config = ConfigManager::new();
+        config.report_page_size = page_size;
+        config.write_config_to_file();
+    }
     pub fn build_ui() -> impl Widget<ApplicationState> {
         Padding::new(
             10.0,
@@ -58,6 +89,63 @@ impl SettingsLayout {
                     1.0,
                 )
                 .with_default_spacer()
+                .with_flex_child(
+                    Flex::row()
+                        .with_child(Label::new("Theme:").with_text_size(TEXT_SIZE))
+                        .with_default_spacer()
+                        .with_child(
+                            Button::from_label(Label::new("Default").with_text_size(TEXT_SIZE))
+                                .on_click(|_ctx, data: &mut ApplicationState, _env| {
+                                    data.theme.set_preset(ThemePreset::Default);
+                                }),
+                        )
+                        .with_default_spacer()
+                        .with_child(
+                            Button::from_label(
+                                Label::new("High Contrast").with_text_size(TEXT_SIZE),
+                            )
+                            .on_click(
+                                |_ctx, data: &mut ApplicationState, _env| {
+                                    data.theme.set_preset(ThemePreset::HighContrast);
+                                },
+                            ),
+                        ),
+                    1.0,
+                )
+                .with_default_spacer()
+                .with_flex_child(SettingsLayout::build_report_page_size_ui(), 1.0)
+                .with_default_spacer()
+                .with_flex_child(
+                    Flex::row()
+                        .with_child(Label::new("UI scale:").with_text_size(TEXT_SIZE))
+                        .with_default_spacer()
+                        .with_child(
+                            Button::from_label(Label::new("-").with_text_size(TEXT_SIZE)).on_click(
+                                |_ctx, data: &mut ApplicationState, _env| {
+                                    let ui_scale = data.theme.ui_scale - UI_SCALE_STEP;
+                                    data.theme.set_ui_scale(ui_scale);
+                                },
+                            ),
+                        )
+                        .with_default_spacer()
+                        .with_child(
+                            Label::dynamic(|data: &ApplicationState, _env| {
+                                format!("{:.0}%", data.theme.ui_scale * 100.0)
+                            })
+                            .with_text_size(TEXT_SIZE),
+                        )
+                        .with_default_spacer()
+                        .with_child(
+                            Button::from_label(Label::new("+").with_text_size(TEXT_SIZE)).on_click(
+                                |_ctx, data: &mut ApplicationState, _env| {
+                                    let ui_scale = data.theme.ui_scale + UI_SCALE_STEP;
+                                    data.theme.set_ui_scale(ui_scale);
+                                },
+                            ),
+                        ),
+                    1.0,
+                )
+                .with_default_spacer()
                 .with_flex_child(
                     Label::new(
                         "Theme can be customized by copy-pasting \
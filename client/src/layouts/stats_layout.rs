@@ -0,0 +1,139 @@
+// Std.
+use std::rc::Rc;
+
+// External.
+use druid::widget::{prelude::*, Scroll};
+use druid::widget::{Button, CrossAxisAlignment, Flex, Label, Padding};
+use druid::{Key, WidgetExt};
+
+// Custom.
+use crate::misc::theme::NORMAL_TEXT_SIZE;
+use crate::{ApplicationState, Layout};
+use shared::misc::report::ReportStats;
+
+// Layout customization.
+const TEXT_SIZE: Key<f64> = NORMAL_TEXT_SIZE;
+const SPARKLINE_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+const GAME_BAR_MAX_LENGTH: usize = 30; // in characters
+
+/// Number of last days to show report counts for.
+pub const STATS_DAY_COUNT: u64 = 30;
+
+#[derive(Clone, Data, Default)]
+pub struct StatsLayout {
+    pub stats: Rc<ReportStats>, // using Rc to implement Clone (changes are detected by pointer)
+}
+
+impl StatsLayout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn build_ui(data: &ApplicationState) -> impl Widget<ApplicationState> {
+        let stats = &data.stats_layout.stats;
+
+        let day_counts: Vec<u64> = stats.per_day.iter().map(|(_, count)| *count).collect();
+        let total_for_period: u64 = day_counts.iter().sum();
+
+        let mut column = Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(
+                Label::new(format!(
+                    "Reports received during the last {} day(-s): {}",
+                    stats.per_day.len(),
+                    total_for_period
+                ))
+                .with_text_size(TEXT_SIZE),
+            )
+            .with_default_spacer()
+            .with_child(
+                Label::new(StatsLayout::build_sparkline(&day_counts)).with_text_size(TEXT_SIZE),
+            );
+
+        if let (Some((first_day, _)), Some((last_day, _))) =
+            (stats.per_day.first(), stats.per_day.last())
+        {
+            column.add_child(
+                Label::new(format!("{} - {}", first_day, last_day)).with_text_size(TEXT_SIZE),
+            );
+        }
+
+        if let Some((busiest_day, count)) = stats
+            .per_day
+            .iter()
+            .filter(|(_, count)| *count != 0)
+            .max_by_key(|(_, count)| *count)
+        {
+            column.add_child(
+                Label::new(format!(
+                    "Busiest day: {} ({} report(-s))",
+                    busiest_day, count
+                ))
+                .with_text_size(TEXT_SIZE),
+            );
+        }
+
+        column.add_default_spacer();
+        column.add_default_spacer();
+        column.add_child(Label::new("Reports per game (all time):").with_text_size(TEXT_SIZE));
+        column.add_default_spacer();
+
+        if stats.per_game.is_empty() {
+            column.add_child(Label::new("No reports were received yet!").with_text_size(TEXT_SIZE));
+        }
+
+        let max_game_count = stats
+            .per_game
+            .iter()
+            .map(|(_, count)| *count)
+            .max()
+            .unwrap_or(0);
+        for (game_name, count) in stats.per_game.iter() {
+            let bar_length = (*count as f64 / max_game_count as f64 * GAME_BAR_MAX_LENGTH as f64)
+                .ceil() as usize;
+
+            column.add_child(
+                Label::new(format!(
+                    "{} {} - {}",
+                    SPARKLINE_CHARS[SPARKLINE_CHARS.len() - 1]
+                        .to_string()
+                        .repeat(bar_length),
+                    count,
+                    game_name
+                ))
+                .with_text_size(TEXT_SIZE),
+            );
+        }
+
+        Padding::new(
+            10.0,
+            Flex::column()
+                .must_fill_main_axis(true)
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(
+                    Button::from_label(Label::new("Return").with_text_size(TEXT_SIZE)).on_click(
+                        |_ctx, data: &mut ApplicationState, _env| {
+                            data.current_layout = Layout::Main;
+                        },
+                    ),
+                )
+                .with_default_spacer()
+                .with_flex_child(Scroll::new(column).vertical().expand(), 1.0),
+        )
+    }
+    /// Returns a line of characters with heights that represent the specified values.
+    fn build_sparkline(values: &[u64]) -> String {
+        let max_value = values.iter().copied().max().unwrap_or(0);
+
+        values
+            .iter()
+            .map(|value| {
+                if max_value == 0 {
+                    return SPARKLINE_CHARS[0];
+                }
+                let index = (*value as f64 / max_value as f64 * (SPARKLINE_CHARS.len() - 1) as f64)
+                    .round() as usize;
+                SPARKLINE_CHARS[index]
+            })
+            .collect()
+    }
+}
@@ -7,25 +7,32 @@ use std::sync::{Arc, Mutex};
 
 // External.
 use druid::widget::prelude::*;
-use druid::widget::ViewSwitcher;
+use druid::widget::{EnvScope, ViewSwitcher};
 use druid::{
-    AppDelegate, AppLauncher, Command, Data, DelegateCtx, Env, Handled, Lens, Target, WindowDesc,
+    AppDelegate, AppLauncher, Command, Data, DelegateCtx, Env, FontWeight, Handled, Lens, Target,
+    WidgetExt, WindowDesc,
 };
 use native_dialog::{FileDialog, MessageDialog, MessageType};
 use rdev::display_size;
 
 // Custom.
+use io::config_manager::{ConfigManager, WindowGeometry};
 use io::log_manager::LogManager;
 use layouts::{
     change_password_layout::ChangePasswordLayout, connect_layout::ConnectLayout,
     main_layout::MainLayout, otp_layout::OtpLayout, report_layout::ReportLayout,
-    settings_layout::SettingsLayout,
+    settings_layout::SettingsLayout, stats_layout::StatsLayout,
+};
+use misc::report_attachment_button::{
+    AttachmentDownloadResult, ATTACHMENT_DOWNLOAD_FINISHED, ATTACHMENT_DOWNLOAD_PROGRESS,
+    REPORT_ATTACHMENT_BUTTON_CLICKED,
 };
-use misc::report_attachment_button::REPORT_ATTACHMENT_BUTTON_CLICKED;
 use misc::report_id_button::REPORT_ID_BUTTON_CLICKED;
 use misc::theme::*;
+use misc::window_controller::WindowController;
 use network::net_service::NetService;
-use shared::misc::error::AppError;
+use shared::misc::error::{AppError, AppErrorKind};
+use shared::network::messaging::MAX_MESSAGE_LEN;
 
 mod io;
 mod layouts;
@@ -41,6 +48,7 @@ pub enum Layout {
     ChangePassword,
     Otp,
     Report,
+    Stats,
 }
 
 #[derive(Clone, Data, Lens)] // Clone is required by `AppDelegate`.
@@ -53,8 +61,8 @@ pub struct ApplicationState {
     settings_layout: SettingsLayout,
     change_password_layout: ChangePasswordLayout,
     otp_layout: OtpLayout,
-    #[data(ignore)]
     report_layout: ReportLayout,
+    stats_layout: StatsLayout,
 
     // services
     #[data(ignore)]
@@ -73,15 +81,22 @@ pub fn main() {
     };
 
     let (w, h) = display_size().unwrap();
+    let (w, h) = (w as f64, h as f64);
+
+    // Restore the window from the last session (if it fits on the display).
+    let window_geometry = match ConfigManager::new().window_geometry {
+        Some(geometry) => geometry.fit_to_display(w, h),
+        None => WindowGeometry::centered(window_size.width, window_size.height, w, h),
+    };
 
     // Describe the main window.
-    let main_window = WindowDesc::new(build_root_widget())
+    // Apply the theme again when it's changed in the settings.
+    let root_widget = EnvScope::new(apply_theme, build_root_widget()).controller(WindowController);
+
+    let main_window = WindowDesc::new(root_widget)
         .title("FBugReporter - Client")
-        .window_size(window_size)
-        .set_position((
-            w as f64 / 2.0 - window_size.width / 2.0,
-            h as f64 / 2.0 - window_size.height / 2.0,
-        ));
+        .window_size((window_geometry.width, window_geometry.height))
+        .set_position((window_geometry.x, window_geometry.y));
 
     // Create the initial app state.
     let initial_state = ApplicationState {
@@ -92,6 +107,7 @@ pub fn main() {
         change_password_layout: ChangePasswordLayout::new(),
         otp_layout: OtpLayout::new(),
         report_layout: ReportLayout::new(),
+        stats_layout: StatsLayout::new(),
         net_service: Arc::new(Mutex::new(NetService::new())),
         logger_service: Arc::new(Mutex::new(LogManager::new())),
         theme: ApplicationTheme::new(),
@@ -107,6 +123,7 @@ pub fn main() {
 }
 
 fn apply_theme(env: &mut Env, data: &ApplicationState) {
+    env.set(druid::theme::TEXT_COLOR, data.theme.text_color);
     env.set(
         druid::theme::WINDOW_BACKGROUND_COLOR,
         data.theme.background_color,
@@ -132,6 +149,39 @@ fn apply_theme(env: &mut Env, data: &ApplicationState) {
     env.set(druid::theme::PRIMARY_LIGHT, data.theme.active_border_color);
     env.set(druid::theme::BUTTON_DARK, data.theme.button_dark_color);
     env.set(druid::theme::BUTTON_LIGHT, data.theme.button_light_color);
+
+    // Use druid's default border widths unless the high-contrast preset is used.
+    let (textbox_border_width, button_border_width) =
+        if data.theme.preset == ThemePreset::HighContrast {
+            (HIGH_CONTRAST_BORDER_WIDTH, HIGH_CONTRAST_BORDER_WIDTH)
+        } else {
+            (1.0, 2.0)
+        };
+    env.set(druid::theme::TEXTBOX_BORDER_WIDTH, textbox_border_width);
+    env.set(druid::theme::BUTTON_BORDER_WIDTH, button_border_width);
+
+    // Scale text (druid's default text size is 15).
+    let ui_scale = data.theme.ui_scale;
+    env.set(druid::theme::TEXT_SIZE_NORMAL, 15.0 * ui_scale);
+    env.set(druid::theme::TEXT_SIZE_LARGE, 24.0 * ui_scale);
+    env.set(
+        druid::theme::UI_FONT,
+        create_font(15.0 * ui_scale, FontWeight::REGULAR),
+    );
+    env.set(
+        druid::theme::UI_FONT_BOLD,
+        create_font(15.0 * ui_scale, FontWeight::BOLD),
+    );
+    env.set(LARGE_TEXT_SIZE, data.theme.get_large_text_size());
+    env.set(NORMAL_TEXT_SIZE, data.theme.get_normal_text_size());
+    env.set(
+        NORMAL_FONT,
+        create_font(data.theme.get_normal_text_size(), FontWeight::REGULAR),
+    );
+    env.set(
+        NORMAL_BOLD_FONT,
+        create_font(data.theme.get_normal_text_size(), FontWeight::BOLD),
+    );
 }
 
 fn build_root_widget() -> impl Widget<ApplicationState> {
@@ -144,6 +194,7 @@ fn build_root_widget() -> impl Widget<ApplicationState> {
             Layout::ChangePassword => Box::new(ChangePasswordLayout::build_ui()),
             Layout::Otp => Box::new(OtpLayout::build_ui(&data.otp_layout)),
             Layout::Report => Box::new(ReportLayout::build_ui(data)),
+            Layout::Stats => Box::new(StatsLayout::build_ui(data)),
         },
     )
 }
@@ -153,7 +204,7 @@ struct MyDelegate;
 impl AppDelegate<ApplicationState> for MyDelegate {
     fn command(
         &mut self,
-        _ctx: &mut DelegateCtx,
+        ctx: &mut DelegateCtx,
         _target: Target,
         cmd: &Command,
         data: &mut ApplicationState,
@@ -166,7 +217,7 @@ impl AppDelegate<ApplicationState> for MyDelegate {
                 .unwrap()
                 .query_report(button_data.report_id);
             if let Err(app_error) = report {
-                if app_error.get_message().contains("FIN") {
+                if app_error.get_kind() == AppErrorKind::ConnectionClosed {
                     data.current_layout = Layout::Connect;
                     data.connect_layout.connect_error = format!(
                         "{}\nMost likely the server \
@@ -181,11 +232,40 @@ impl AppDelegate<ApplicationState> for MyDelegate {
             }
             let report = report.unwrap();
 
-            data.report_layout.report = std::rc::Rc::new(report);
+            let comments = data
+                .net_service
+                .lock()
+                .unwrap()
+                .query_report_comments(button_data.report_id);
+            if let Err(app_error) = comments {
+                println!("ERROR: {}", app_error);
+                return Handled::Yes;
+            }
+
+            data.report_layout
+                .set_report(report, data.theme.get_normal_text_size());
+            data.report_layout.comments = std::rc::Rc::new(comments.unwrap());
+            data.report_layout.new_comment = String::new();
             data.current_layout = Layout::Report;
 
             Handled::Yes
         } else if let Some(button_data) = cmd.get(REPORT_ATTACHMENT_BUTTON_CLICKED) {
+            // The attachment is sent in one network message (that also has other fields).
+            if button_data.attachment_size_in_bytes >= MAX_MESSAGE_LEN {
+                let _ = MessageDialog::new()
+                    .set_type(MessageType::Error)
+                    .set_title("Attachment")
+                    .set_text(&format!(
+                        "Attachment \"{}\" ({}) can't be downloaded because it exceeds \
+                        the maximum size of a network message ({}).",
+                        button_data.attachment_file_name,
+                        ReportLayout::format_size(button_data.attachment_size_in_bytes),
+                        ReportLayout::format_size(MAX_MESSAGE_LEN)
+                    ))
+                    .show_alert();
+                return Handled::Yes;
+            }
+
             // Ask where to save the file.
             let path_to_save_attachment = FileDialog::new()
                 .set_filename(&button_data.attachment_file_name)
@@ -196,31 +276,55 @@ impl AppDelegate<ApplicationState> for MyDelegate {
             }
             let path_to_save_attachment = path_to_save_attachment.unwrap();
 
-            {
-                // TODO: show modal window instead when https://github.com/linebender/druid/issues/429 is done
-                if let Err(e) = MessageDialog::new()
-                    .set_type(MessageType::Info)
-                    .set_title("Attachment")
-                    .set_text(&format!(
-                        "Attachment \"{}\" is queued for download.\n\
-                        You will be notified when the attachment will be downloaded.",
-                        button_data.attachment_file_name,
-                    ))
-                    .show_alert()
-                {
-                    let message = AppError::new(&e.to_string()).to_string();
-                    data.logger_service.lock().unwrap().log(&message);
-                    println!("{}", message);
-                }
-            }
+            data.report_layout.download_file_name = button_data.attachment_file_name.clone();
+            data.report_layout.download_size_in_bytes = button_data.attachment_size_in_bytes;
+            data.report_layout.download_progress = Some(0.0);
 
-            let result = data
-                .net_service
-                .lock()
-                .unwrap()
-                .download_attachment(button_data.attachment_id, path_to_save_attachment.as_path());
-            if let Err(app_error) = result {
-                if app_error.get_message().contains("FIN") {
+            // Download in another thread to show the progress.
+            let net_service = data.net_service.clone();
+            let event_sink = ctx.get_external_handle();
+            let attachment_id = button_data.attachment_id;
+            let attachment_file_name = button_data.attachment_file_name.clone();
+            std::thread::spawn(move || {
+                let mut last_percent = 0;
+                let result = net_service.lock().unwrap().download_attachment(
+                    attachment_id,
+                    path_to_save_attachment.as_path(),
+                    &mut |received, total| {
+                        // Don't flood the UI with updates.
+                        let percent = received * 100 / total.max(1);
+                        if percent != last_percent {
+                            last_percent = percent;
+                            let _ = event_sink.submit_command(
+                                ATTACHMENT_DOWNLOAD_PROGRESS,
+                                (received, total),
+                                Target::Auto,
+                            );
+                        }
+                    },
+                );
+
+                let _ = event_sink.submit_command(
+                    ATTACHMENT_DOWNLOAD_FINISHED,
+                    AttachmentDownloadResult {
+                        attachment_file_name,
+                        path: path_to_save_attachment,
+                        result,
+                    },
+                    Target::Auto,
+                );
+            });
+
+            Handled::Yes
+        } else if let Some((received, total)) = cmd.get(ATTACHMENT_DOWNLOAD_PROGRESS) {
+            data.report_layout.download_progress = Some(*received as f64 / *total as f64);
+
+            Handled::Yes
+        } else if let Some(download) = cmd.get(ATTACHMENT_DOWNLOAD_FINISHED) {
+            data.report_layout.download_progress = None;
+
+            if let Err(app_error) = &download.result {
+                if app_error.get_kind() == AppErrorKind::ConnectionClosed {
                     data.current_layout = Layout::Connect;
                     data.connect_layout.connect_error = format!(
                         "{}\nMost likely the server \
@@ -233,7 +337,7 @@ impl AppDelegate<ApplicationState> for MyDelegate {
 
                 return Handled::Yes;
             }
-            let is_found = result.unwrap();
+            let is_found = *download.result.as_ref().unwrap();
 
             if is_found {
                 if let Err(e) = MessageDialog::new()
@@ -241,8 +345,8 @@ impl AppDelegate<ApplicationState> for MyDelegate {
                     .set_title("Attachment")
                     .set_text(&format!(
                         "Attachment \"{}\" was successfully downloaded and saved at \"{}\".",
-                        button_data.attachment_file_name,
-                        path_to_save_attachment.to_str().unwrap()
+                        download.attachment_file_name,
+                        download.path.to_string_lossy()
                     ))
                     .show_alert()
                 {
@@ -256,7 +360,7 @@ impl AppDelegate<ApplicationState> for MyDelegate {
                 .set_text(&format!(
                     "Attachment \"{}\" was not found on the server \
                         (maybe this report was just deleted by an administrator).",
-                    button_data.attachment_file_name
+                    download.attachment_file_name
                 ))
                 .show_alert()
             {
@@ -0,0 +1,166 @@
+// Std.
+use std::time::{Duration, Instant};
+
+// External.
+use druid::widget::prelude::*;
+use druid::widget::Controller;
+use druid::TimerToken;
+
+// Custom.
+use crate::layouts::main_layout::MainLayout;
+use crate::network::net_service::{ConnectResult, NetService, RECONNECT_MAX_ATTEMPTS};
+use crate::ApplicationState;
+
+/// How often to check that the server did not close the connection.
+const CONNECTION_CHECK_INTERVAL_SEC: u64 = 5;
+
+/// How often to tell the server that the client is still open.
+const KEEP_ALIVE_INTERVAL_SEC: u64 = 60;
+
+/// Periodically checks the connection to the server and updates the connection
+/// status of the main layout.
+///
+/// ## Remarks
+/// Once the connection is lost tries to restore it with exponential backoff and only
+/// shows the connect layout if all attempts failed.
+pub struct ConnectionStatusController {
+    timer: TimerToken,
+    /// Time when the last keep-alive message was sent.
+    time_of_last_keep_alive: Instant,
+}
+
+impl ConnectionStatusController {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Default for ConnectionStatusController {
+    fn default() -> Self {
+        Self {
+            timer: TimerToken::INVALID,
+            time_of_last_keep_alive: Instant::now(),
+        }
+    }
+}
+
+impl ConnectionStatusController {
+    /// Checks if the connection is still alive and sends keep-alive messages.
+    ///
+    /// ## Return
+    /// Time to wait before the next check.
+    fn check_connection(&mut self, data: &mut ApplicationState) -> Option<Duration> {
+        if data.net_service.lock().unwrap().check_connection() {
+            if self.time_of_last_keep_alive.elapsed()
+                >= Duration::from_secs(KEEP_ALIVE_INTERVAL_SEC)
+            {
+                self.time_of_last_keep_alive = Instant::now();
+                if let Err(app_error) = data.net_service.lock().unwrap().send_keep_alive() {
+                    data.logger_service
+                        .lock()
+                        .unwrap()
+                        .log(&app_error.to_string());
+                }
+            }
+
+            return Some(Duration::from_secs(CONNECTION_CHECK_INTERVAL_SEC));
+        }
+
+        data.logger_service
+            .lock()
+            .unwrap()
+            .log("the server closed the connection");
+        data.main_layout.connection_lost = true;
+        data.main_layout.reconnect_attempts = 0;
+
+        Some(NetService::get_reconnect_delay(0))
+    }
+    /// Makes an attempt to restore the lost connection.
+    ///
+    /// ## Return
+    /// Time to wait before the next check, `None` if the connect layout was shown.
+    fn try_reconnect(&mut self, data: &mut ApplicationState) -> Option<Duration> {
+        let result = data.net_service.lock().unwrap().reconnect();
+
+        let message = match result {
+            ConnectResult::Connected(_, _) => {
+                data.logger_service
+                    .lock()
+                    .unwrap()
+                    .log("the connection was restored");
+                data.main_layout.connection_lost = false;
+                data.main_layout.reconnect_attempts = 0;
+                return Some(Duration::from_secs(CONNECTION_CHECK_INTERVAL_SEC));
+            }
+            ConnectResult::InternalError(app_error) => {
+                data.logger_service
+                    .lock()
+                    .unwrap()
+                    .log(&format!("failed to restore the connection: {}", app_error));
+
+                data.main_layout.reconnect_attempts += 1;
+                if data.main_layout.reconnect_attempts < RECONNECT_MAX_ATTEMPTS {
+                    return Some(NetService::get_reconnect_delay(
+                        data.main_layout.reconnect_attempts,
+                    ));
+                }
+
+                app_error.get_message()
+            }
+            ConnectResult::ConnectFailed(reason) => reason,
+            ConnectResult::WrongProtocol(_) => {
+                String::from("the server now uses a different network protocol")
+            }
+            // Need OTP (it can't be reused) or a new password, ask the user.
+            _ => String::from("please, login again"),
+        };
+
+        MainLayout::on_connection_not_restored(data, &message);
+
+        None
+    }
+}
+
+impl<W: Widget<ApplicationState>> Controller<ApplicationState, W> for ConnectionStatusController {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut ApplicationState,
+        env: &Env,
+    ) {
+        if let Event::Timer(token) = event {
+            if *token == self.timer {
+                let next_check = if data.main_layout.connection_lost {
+                    self.try_reconnect(data)
+                } else {
+                    self.check_connection(data)
+                };
+
+                if let Some(next_check) = next_check {
+                    self.timer = ctx.request_timer(next_check);
+                }
+                return;
+            }
+        }
+
+        child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &ApplicationState,
+        env: &Env,
+    ) {
+        if let LifeCycle::WidgetAdded = event {
+            // Check right away to show the actual status.
+            self.timer = ctx.request_timer(Duration::ZERO);
+        }
+
+        child.lifecycle(ctx, event, data, env)
+    }
+}
@@ -0,0 +1,183 @@
+// External.
+use druid::text::{AttributesAdder, RichText, RichTextBuilder};
+use druid::{Color, FontFamily, FontStyle, FontWeight};
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag};
+
+const QUOTE_AND_CODE_COLOR: Color = Color::grey8(0xAA);
+const LINK_COLOR: Color = Color::rgb8(0x6A, 0x9F, 0xE0);
+
+/// Converts Markdown text into a text that can be displayed by `RawLabel`.
+///
+/// Supports headings, bold/italic/strikethrough text, lists, quotes, links
+/// and code (inline and blocks), other elements are displayed as plain text.
+///
+/// ## Arguments
+/// * `text`: Markdown text.
+/// * `text_size`: size of the normal text (headings are bigger).
+pub fn markdown_to_rich_text(text: &str, text_size: f64) -> RichText {
+    let mut writer = TextWriter::new();
+
+    // Pairs of "start position" - "tag" for the tags that were not closed yet.
+    let mut tag_stack: Vec<(usize, Tag)> = Vec::new();
+
+    // Number of the next item for each (nested) list, `None` for unordered lists.
+    let mut list_stack: Vec<Option<u64>> = Vec::new();
+
+    let parser = Parser::new_ext(text, Options::ENABLE_STRIKETHROUGH);
+    for event in parser {
+        match event {
+            Event::Start(tag) => {
+                match &tag {
+                    Tag::List(first_number) => {
+                        writer.start_new_line();
+                        list_stack.push(*first_number);
+                    }
+                    Tag::Item => {
+                        let indent = "    ".repeat(list_stack.len().saturating_sub(1));
+                        let marker = match list_stack.last_mut() {
+                            Some(Some(number)) => {
+                                *number += 1;
+                                format!("{}{}. ", indent, *number - 1)
+                            }
+                            _ => format!("{}• ", indent),
+                        };
+                        writer.push(&marker);
+                    }
+                    _ => {}
+                }
+                tag_stack.push((writer.position, tag));
+            }
+            Event::End(_) => {
+                let (start_position, tag) = tag_stack
+                    .pop()
+                    .expect("parser does not return unbalanced tags");
+
+                add_attributes_for_tag(
+                    &tag,
+                    writer
+                        .builder
+                        .add_attributes_for_range(start_position..writer.position),
+                    text_size,
+                );
+
+                match tag {
+                    Tag::Paragraph | Tag::Heading(..) | Tag::CodeBlock(_) => {
+                        writer.start_new_line();
+                        // Items of "tight" lists don't have paragraphs so keep list items compact.
+                        if list_stack.is_empty() {
+                            writer.push("\n");
+                        }
+                    }
+                    Tag::List(_) => {
+                        list_stack.pop();
+                        if list_stack.is_empty() {
+                            writer.push("\n");
+                        }
+                    }
+                    Tag::Item => writer.start_new_line(),
+                    _ => {}
+                }
+            }
+            Event::Text(text) => {
+                writer.push(&text);
+            }
+            Event::Code(text) | Event::Html(text) => {
+                writer
+                    .push(&text)
+                    .font_family(FontFamily::MONOSPACE)
+                    .text_color(QUOTE_AND_CODE_COLOR);
+            }
+            Event::SoftBreak | Event::HardBreak => {
+                // Players don't expect new lines to be ignored.
+                writer.push("\n");
+            }
+            Event::Rule => {
+                writer.start_new_line();
+                writer.push("――――――――――\n\n");
+            }
+            Event::TaskListMarker(is_checked) => {
+                writer.push(if is_checked { "[x] " } else { "[ ] " });
+            }
+            Event::FootnoteReference(text) => {
+                writer.push(&format!("[{}]", text));
+            }
+        }
+    }
+
+    writer.builder.build()
+}
+
+/// Appends text to `RichTextBuilder` while remembering the current position.
+struct TextWriter {
+    builder: RichTextBuilder,
+    /// Length of the text (in bytes) that was pushed so far.
+    position: usize,
+    /// Whether the pushed text is empty or ends with a new line.
+    is_line_start: bool,
+}
+
+impl TextWriter {
+    fn new() -> Self {
+        Self {
+            builder: RichTextBuilder::new(),
+            position: 0,
+            is_line_start: true,
+        }
+    }
+    /// Appends the text, returned value can be used to style the appended text.
+    fn push(&mut self, text: &str) -> AttributesAdder<'_> {
+        if !text.is_empty() {
+            self.position += text.len();
+            self.is_line_start = text.ends_with('\n');
+        }
+        self.builder.push(text)
+    }
+    /// Appends a new line if the current line is not empty.
+    fn start_new_line(&mut self) {
+        if !self.is_line_start {
+            self.push("\n");
+        }
+    }
+}
+
+/// Adds text attributes that display the specified tag.
+fn add_attributes_for_tag(tag: &Tag, mut attributes: AttributesAdder, text_size: f64) {
+    match tag {
+        Tag::Heading(level, _, _) => {
+            let multiplier = match level {
+                HeadingLevel::H1 => 1.6,
+                HeadingLevel::H2 => 1.4,
+                HeadingLevel::H3 => 1.2,
+                _ => 1.0,
+            };
+            attributes
+                .size(text_size * multiplier)
+                .weight(FontWeight::BOLD);
+        }
+        Tag::BlockQuote => {
+            attributes
+                .style(FontStyle::Italic)
+                .text_color(QUOTE_AND_CODE_COLOR);
+        }
+        Tag::CodeBlock(_) => {
+            attributes
+                .font_family(FontFamily::MONOSPACE)
+                .text_color(QUOTE_AND_CODE_COLOR);
+        }
+        Tag::Emphasis => {
+            attributes.style(FontStyle::Italic);
+        }
+        Tag::Strong => {
+            attributes.weight(FontWeight::BOLD);
+        }
+        Tag::Strikethrough => {
+            attributes.strikethrough(true);
+        }
+        Tag::Link(..) => {
+            // Links are not clickable because reports are written by players
+            // and we don't want to open unknown URLs.
+            attributes.underline(true).text_color(LINK_COLOR);
+        }
+        _ => {}
+    }
+}
@@ -1,3 +1,6 @@
+pub mod connection_status_controller;
+pub mod markdown;
 pub mod report_attachment_button;
 pub mod report_id_button;
 pub mod theme;
+pub mod window_controller;
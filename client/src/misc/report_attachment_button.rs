@@ -1,16 +1,36 @@
+use std::path::PathBuf;
+
 use druid::widget::prelude::*;
 use druid::widget::{Button, Controller};
 use druid::{Selector, Target};
 
 use crate::ApplicationState;
+use shared::misc::error::AppError;
 
 pub const REPORT_ATTACHMENT_BUTTON_CLICKED: Selector<ReportAttachmentButtonData> =
     Selector::new("report_attachment_button_clicked");
 
+/// Sent while an attachment is being downloaded (contains the amount of received bytes
+/// and the total amount of bytes to receive).
+pub const ATTACHMENT_DOWNLOAD_PROGRESS: Selector<(usize, usize)> =
+    Selector::new("attachment_download_progress");
+
+/// Sent when an attachment download is finished.
+pub const ATTACHMENT_DOWNLOAD_FINISHED: Selector<AttachmentDownloadResult> =
+    Selector::new("attachment_download_finished");
+
+/// Result of an attachment download (see `NetService::download_attachment`).
+pub struct AttachmentDownloadResult {
+    pub attachment_file_name: String,
+    pub path: PathBuf,
+    pub result: Result<bool, AppError>,
+}
+
 #[derive(Clone)]
 pub struct ReportAttachmentButtonData {
     pub attachment_id: usize,
     pub attachment_file_name: String,
+    pub attachment_size_in_bytes: usize,
 }
 
 pub struct ReportAttachmentButtonController {
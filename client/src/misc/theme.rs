@@ -5,7 +5,7 @@ use std::path::PathBuf;
 // External.
 use configparser::ini::Ini;
 use druid::widget::prelude::*;
-use druid::{Color, Lens};
+use druid::{Color, FontDescriptor, FontFamily, FontWeight, Key, Lens};
 use platform_dirs::AppDirs;
 
 // Custom.
@@ -23,9 +23,63 @@ const CONFIG_THEME_INACTIVE_BORDER_COLOR_PARAM: &str = "inactive_border_color";
 const CONFIG_THEME_BUTTON_DARK_COLOR_PARAM: &str = "button_dark_color";
 const CONFIG_THEME_BUTTON_LIGHT_COLOR_PARAM: &str = "button_light_color";
 const CONFIG_THEME_BORDER_RADIUS_PARAM: &str = "border_radius";
+const CONFIG_THEME_UI_SCALE_PARAM: &str = "ui_scale";
+const CONFIG_THEME_TEXT_COLOR_PARAM: &str = "text_color";
+const CONFIG_THEME_PRESET_PARAM: &str = "preset";
+
+/// Width of textbox and button borders in the high-contrast preset.
+pub const HIGH_CONTRAST_BORDER_WIDTH: f64 = 3.0;
+
+/// Range and step of the UI scale that can be set in the settings.
+pub const MIN_UI_SCALE: f64 = 0.5;
+pub const MAX_UI_SCALE: f64 = 3.0;
+pub const UI_SCALE_STEP: f64 = 0.1;
+
+/// Text size (before scaling) in layouts with few widgets.
+const DEFAULT_LARGE_TEXT_SIZE: f64 = 20.0;
+/// Text size (before scaling) in layouts with lots of information.
+const DEFAULT_NORMAL_TEXT_SIZE: f64 = 18.0;
+
+/// Scaled text size for layouts with few widgets.
+pub const LARGE_TEXT_SIZE: Key<f64> = Key::new("fbugreporter.theme.large_text_size");
+/// Scaled text size for layouts with lots of information.
+pub const NORMAL_TEXT_SIZE: Key<f64> = Key::new("fbugreporter.theme.normal_text_size");
+/// Font with `NORMAL_TEXT_SIZE`.
+pub const NORMAL_FONT: Key<FontDescriptor> = Key::new("fbugreporter.theme.normal_font");
+/// Bold font with `NORMAL_TEXT_SIZE`.
+pub const NORMAL_BOLD_FONT: Key<FontDescriptor> = Key::new("fbugreporter.theme.normal_bold_font");
+
+/// Set of theme values that can be selected in the settings.
+#[derive(Clone, Copy, Data, PartialEq, Eq, Debug)]
+pub enum ThemePreset {
+    Default,
+    /// Maximal contrast between text and background, bold borders and no grays.
+    HighContrast,
+}
+
+impl ThemePreset {
+    /// Returns the name of the preset that is stored in the theme file.
+    fn to_config_value(self) -> &'static str {
+        match self {
+            ThemePreset::Default => "default",
+            ThemePreset::HighContrast => "high_contrast",
+        }
+    }
+    /// Returns the preset by its name from the theme file.
+    fn from_config_value(value: &str) -> Option<Self> {
+        match value {
+            "default" => Some(ThemePreset::Default),
+            "high_contrast" => Some(ThemePreset::HighContrast),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Clone, Data, Lens, Debug)]
 pub struct ApplicationTheme {
+    /// Preset that the theme values were taken from (values can be customized afterwards).
+    pub preset: ThemePreset,
+    pub text_color: Color,
     pub background_color: Color,
     pub placeholder_color: Color,
     pub textbox_background_color: Color,
@@ -35,6 +89,8 @@ pub struct ApplicationTheme {
     pub button_dark_color: Color,
     pub button_light_color: Color,
     pub border_radius: f64,
+    /// Multiplier for text sizes (`1.0` means no scaling).
+    pub ui_scale: f64,
 }
 
 impl ApplicationTheme {
@@ -61,6 +117,23 @@ impl ApplicationTheme {
         }
 
         let mut some_values_were_empty = false;
+        match config.get(CONFIG_THEME_SECTION_NAME, CONFIG_THEME_PRESET_PARAM) {
+            Some(value) => match ThemePreset::from_config_value(&value) {
+                Some(preset) => theme.preset = preset,
+                None => println!(
+                    "ERROR: unknown value '{}' of theme parameter '{}'.",
+                    value, CONFIG_THEME_PRESET_PARAM
+                ),
+            },
+            None => some_values_were_empty = true,
+        }
+        if read_theme_color_hex(
+            CONFIG_THEME_TEXT_COLOR_PARAM,
+            &mut theme.text_color,
+            &config,
+        ) {
+            some_values_were_empty = true;
+        }
         if read_theme_color_hex(
             CONFIG_THEME_BACKGROUND_COLOR_PARAM,
             &mut theme.background_color,
@@ -124,6 +197,19 @@ impl ApplicationTheme {
         ) {
             some_values_were_empty = true;
         }
+        if read_theme_param_float(CONFIG_THEME_UI_SCALE_PARAM, &mut theme.ui_scale, &config) {
+            some_values_were_empty = true;
+        }
+        if !(MIN_UI_SCALE..=MAX_UI_SCALE).contains(&theme.ui_scale) {
+            println!(
+                "ERROR: theme parameter '{}' should be in range [{}; {}], using value {}.",
+                CONFIG_THEME_UI_SCALE_PARAM,
+                MIN_UI_SCALE,
+                MAX_UI_SCALE,
+                ApplicationTheme::default().ui_scale
+            );
+            theme.ui_scale = ApplicationTheme::default().ui_scale;
+        }
 
         if some_values_were_empty {
             // Create a new file with all values filled.
@@ -135,9 +221,80 @@ impl ApplicationTheme {
 
         theme
     }
+    /// Sets the UI scale (clamped to the allowed range) and saves the theme.
+    ///
+    /// ## Arguments
+    /// * `ui_scale`: multiplier for text sizes.
+    pub fn set_ui_scale(&mut self, ui_scale: f64) {
+        // Round to avoid values like 1.2000000000000002.
+        let ui_scale = (ui_scale / UI_SCALE_STEP).round() * UI_SCALE_STEP;
+        self.ui_scale = ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+
+        if let Err(e) = self.save_theme() {
+            // Non-critical error.
+            println!("WARNING: {}", e);
+        }
+    }
+    /// Replaces all colors with the colors of the specified preset and saves the theme.
+    ///
+    /// ## Arguments
+    /// * `preset`: preset to use.
+    pub fn set_preset(&mut self, preset: ThemePreset) {
+        let ui_scale = self.ui_scale;
+
+        *self = match preset {
+            ThemePreset::Default => ApplicationTheme::default(),
+            ThemePreset::HighContrast => ApplicationTheme::high_contrast(),
+        };
+        self.ui_scale = ui_scale;
+
+        if let Err(e) = self.save_theme() {
+            // Non-critical error.
+            println!("WARNING: {}", e);
+        }
+    }
+    /// Returns theme values of the high-contrast preset.
+    ///
+    /// ## Remarks
+    /// Placeholder text (cyan) and inactive borders (yellow) use different colors
+    /// so that empty textboxes are still readable.
+    pub fn high_contrast() -> Self {
+        ApplicationTheme {
+            preset: ThemePreset::HighContrast,
+            text_color: Color::WHITE,
+            background_color: Color::BLACK,
+            placeholder_color: Color::rgb8(0, 255, 255),
+            textbox_background_color: Color::BLACK,
+            inactive_border_color: Color::rgb8(255, 255, 0),
+            active_border_color: Color::WHITE,
+            text_selection_color: Color::rgb8(0, 0, 255),
+            button_dark_color: Color::BLACK,
+            button_light_color: Color::BLACK,
+            border_radius: 0.0,
+            ui_scale: 1.0,
+        }
+    }
+    /// Returns scaled text size for layouts with lots of information.
+    pub fn get_normal_text_size(&self) -> f64 {
+        DEFAULT_NORMAL_TEXT_SIZE * self.ui_scale
+    }
+    /// Returns scaled text size for layouts with few widgets.
+    pub fn get_large_text_size(&self) -> f64 {
+        DEFAULT_LARGE_TEXT_SIZE * self.ui_scale
+    }
     fn save_theme(&self) -> Result<(), AppError> {
         let mut config = Ini::new();
 
+        config.setstr(
+            CONFIG_THEME_SECTION_NAME,
+            CONFIG_THEME_PRESET_PARAM,
+            Some(self.preset.to_config_value()),
+        );
+        config.setstr(
+            CONFIG_THEME_SECTION_NAME,
+            CONFIG_THEME_TEXT_COLOR_PARAM,
+            Some(&format!("{:?}", self.text_color)[1..]),
+        );
         config.setstr(
             CONFIG_THEME_SECTION_NAME,
             CONFIG_THEME_BACKGROUND_COLOR_PARAM,
@@ -183,6 +340,11 @@ impl ApplicationTheme {
             CONFIG_THEME_BORDER_RADIUS_PARAM,
             Some(&self.border_radius.to_string()),
         );
+        config.setstr(
+            CONFIG_THEME_SECTION_NAME,
+            CONFIG_THEME_UI_SCALE_PARAM,
+            Some(&format!("{:.1}", self.ui_scale)),
+        );
 
         if let Err(e) = config.write(Self::get_theme_config_file_path()) {
             return Err(AppError::new(&e.to_string()));
@@ -223,6 +385,8 @@ impl ApplicationTheme {
 impl Default for ApplicationTheme {
     fn default() -> Self {
         ApplicationTheme {
+            preset: ThemePreset::Default,
+            text_color: Color::rgb8(240, 240, 234),
             background_color: Color::rgb8(30, 26, 22),
             placeholder_color: Color::rgb8(65, 60, 55),
             textbox_background_color: Color::rgb8(35, 30, 25),
@@ -232,10 +396,18 @@ impl Default for ApplicationTheme {
             button_dark_color: Color::rgb8(181, 98, 2),
             button_light_color: Color::rgb8(181, 98, 2),
             border_radius: 10.0,
+            ui_scale: 1.0,
         }
     }
 }
 
+/// Creates a font of the system UI with the specified size and weight.
+pub fn create_font(size: f64, weight: FontWeight) -> FontDescriptor {
+    FontDescriptor::new(FontFamily::SYSTEM_UI)
+        .with_size(size)
+        .with_weight(weight)
+}
+
 /// Read color parameter from hex string.
 ///
 /// Returns `true` if the value was empty, `false` if it was set.
@@ -0,0 +1,35 @@
+use druid::widget::prelude::*;
+use druid::widget::Controller;
+
+use crate::io::config_manager::{ConfigManager, WindowGeometry};
+use crate::ApplicationState;
+
+/// Saves position and size of the main window when it's closed.
+pub struct WindowController;
+
+impl<W: Widget<ApplicationState>> Controller<ApplicationState, W> for WindowController {
+    fn event(
+        &mut self,
+        child: &mut W,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut ApplicationState,
+        env: &Env,
+    ) {
+        if let Event::WindowCloseRequested = event {
+            let position = ctx.window().get_position();
+            let size = ctx.window().get_size();
+
+            let mut config = ConfigManager::new();
+            config.window_geometry = Some(WindowGeometry {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+            });
+            config.write_config_to_file();
+        }
+
+        child.event(ctx, event, data, env)
+    }
+}
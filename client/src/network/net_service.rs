@@ -1,5 +1,6 @@
 // Std.
 use std::fs;
+use std::io;
 use std::net::*;
 use std::path::Path;
 use std::time::Duration;
@@ -9,12 +10,18 @@ use sha2::{Digest, Sha512};
 
 // Custom.
 use crate::io::config_manager::ConfigManager;
-use shared::misc::error::AppError;
+use shared::misc::error::{AppError, AppErrorKind};
+use shared::misc::password_policy::PasswordPolicy;
+use shared::misc::report::ReportComment;
 use shared::misc::report::ReportData;
+use shared::misc::report::ReportPriority;
+use shared::misc::report::ReportStats;
 use shared::misc::report::ReportSummary;
+use shared::misc::user::UserRole;
 use shared::network::client_messages::*;
 use shared::network::messaging::*;
 use shared::network::net_params::*;
+use shared::network::server_identity::ServerPublicKey;
 
 #[derive(Clone, Copy)]
 pub struct ServerDiskUsage {
@@ -22,20 +29,55 @@ pub struct ServerDiskUsage {
     pub used_disk_space_mb: u64,
 }
 
+/// Versions that the server reported.
+pub struct ServerInfo {
+    pub net_protocol: u16,
+    pub server_version: String,
+}
+
 pub enum ConnectResult {
-    Connected(bool),
+    Connected(UserRole, Vec<String>), // user role, OTP backup codes (only once OTP setup is finished)
     ConnectFailed(String),
-    NeedFirstPassword,
+    /// The server uses a different network protocol version (contains the server's version).
+    WrongProtocol(u16),
+    NeedFirstPassword(PasswordPolicy), // requirements for the new password
     SetupOTP(String),
     NeedOTP,
     InternalError(AppError),
 }
 
+/// Maximum number of attempts to restore a dropped connection before giving up.
+pub const RECONNECT_MAX_ATTEMPTS: u32 = 5;
+
+/// Time to wait before the first attempt to restore a dropped connection
+/// (doubles after each failed attempt).
+const RECONNECT_BASE_DELAY_SEC: u64 = 1;
+
+/// Maximum time to wait between attempts to restore a dropped connection.
+const RECONNECT_MAX_DELAY_SEC: u64 = 16;
+
+/// Data of the last successful login that is used to restore a dropped connection.
+#[derive(Clone)]
+struct LoginData {
+    server: String,
+    port: u16,
+    username: String,
+    password_hash: Vec<u8>,
+}
+
 pub struct NetService {
     socket: Option<TcpStream>,
     secret_key: [u8; SECRET_KEY_SIZE],
     is_connected: bool,
     current_server_disk_usage: ServerDiskUsage,
+    /// Address of the server in the form "server:port".
+    server_address: String,
+    /// Application version of the server (if known).
+    server_version: Option<String>,
+    /// Data of the last successful login (if logged in).
+    login_data: Option<LoginData>,
+    /// Token that the server gave us to resume the session (if logged in).
+    session_token: Option<String>,
 }
 
 impl NetService {
@@ -59,82 +101,157 @@ impl NetService {
         otp: String,
         new_password: Option<String>,
     ) -> ConnectResult {
-        let addrs = format!("{}:{}", server, port).to_socket_addrs();
-        if let Err(e) = addrs {
-            return ConnectResult::InternalError(AppError::new(&e.to_string()));
-        }
-        let addrs = addrs.unwrap();
+        let password_hash = NetService::hash_password(&password);
+        let new_password_hash = new_password.map(|password| NetService::hash_password(&password));
 
-        let mut tcp_socket: Option<TcpStream> = None;
-        for addr in addrs {
-            let result = TcpStream::connect_timeout(&addr, Duration::from_secs(2));
-            if let Ok(socket) = result {
-                tcp_socket = Some(socket);
-                break;
+        self.login(
+            server,
+            port,
+            username,
+            password_hash,
+            otp,
+            new_password_hash,
+        )
+    }
+
+    /// Tries to restore a dropped connection by resuming the session (using the session
+    /// token that the server gave us) or by logging in again with the data of the last
+    /// successful login if the token is expired.
+    ///
+    /// ## Remarks
+    /// If the session token is expired and the user has OTP enabled the server will
+    /// answer with `ConnectResult::NeedOTP` since the previous OTP is no longer valid,
+    /// in this case the user needs to login again.
+    pub fn reconnect(&mut self) -> ConnectResult {
+        let login_data = match self.login_data.clone() {
+            Some(login_data) => login_data,
+            None => {
+                return ConnectResult::InternalError(AppError::new(
+                    "there is no previous login to restore the connection",
+                ))
             }
-        }
+        };
 
-        if tcp_socket.is_none() {
-            return ConnectResult::InternalError(AppError::new(
-                "could not connect to the \
-                        server, make sure that server name and port are correct and the \
-                        server is running",
-            ));
+        if let Some(socket) = self.socket.take() {
+            // The socket is most likely already closed by the server.
+            let _ = socket.shutdown(Shutdown::Both);
         }
+        self.is_connected = false;
 
-        let tcp_socket = tcp_socket.unwrap();
-
-        // Configure socket.
-        if let Err(e) = tcp_socket.set_nodelay(true) {
-            return ConnectResult::InternalError(AppError::new(&e.to_string()));
-        }
-        if let Err(e) = tcp_socket.set_nonblocking(true) {
-            return ConnectResult::InternalError(AppError::new(&e.to_string()));
+        if let Some(session_token) = self.session_token.clone() {
+            let result = self.send_login_request(
+                &login_data.server,
+                login_data.port,
+                ClientRequest::ResumeSession {
+                    client_net_protocol: NETWORK_PROTOCOL_VERSION,
+                    session_token,
+                },
+            );
+            match result {
+                ConnectResult::Connected(_, _)
+                | ConnectResult::InternalError(_)
+                | ConnectResult::WrongProtocol(_) => return result,
+                _ => {
+                    // The token is expired, login using password.
+                    self.session_token = None;
+                }
+            }
         }
 
-        self.socket = Some(tcp_socket);
-
-        // Establish secure connection.
-        let secret_key = accept_secure_connection_establishment(self.socket.as_mut().unwrap());
-        if let Err(app_error) = secret_key {
-            return ConnectResult::InternalError(app_error);
-        }
-        let result = secret_key.unwrap().try_into();
-        if result.is_err() {
-            return ConnectResult::InternalError(AppError::new(
-                "failed to convert Vec<u8> to generic array",
-            ));
-        }
-        self.secret_key = result.unwrap();
+        self.login(
+            login_data.server,
+            login_data.port,
+            login_data.username,
+            login_data.password_hash,
+            String::new(),
+            None,
+        )
+    }
 
-        // Generate password hash.
-        let mut hasher = Sha512::new();
-        hasher.update(password.as_bytes());
-        let password = hasher.finalize().to_vec();
+    /// Returns time to wait before the specified attempt to restore a dropped connection.
+    ///
+    /// ## Arguments
+    /// * `attempt`: number of the attempt (starting from 0).
+    pub fn get_reconnect_delay(attempt: u32) -> Duration {
+        let delay = RECONNECT_BASE_DELAY_SEC.saturating_mul(2u64.saturating_pow(attempt));
+        Duration::from_secs(delay.min(RECONNECT_MAX_DELAY_SEC))
+    }
 
+    /// Establishes a secure connection and sends login request.
+    ///
+    /// See `connect` for the description of the arguments (except that
+    /// passwords are already hashed).
+    fn login(
+        &mut self,
+        server: String,
+        port: u16,
+        username: String,
+        password_hash: Vec<u8>,
+        otp: String,
+        new_password_hash: Option<Vec<u8>>,
+    ) -> ConnectResult {
         // Prepare packet to send.
         let mut packet = ClientRequest::Login {
             client_net_protocol: NETWORK_PROTOCOL_VERSION,
             username: username.clone(),
-            password: password.clone(),
+            password: password_hash.clone(),
             otp,
         };
 
-        if let Some(new_password) = new_password {
-            // Generate new password hash.
-            hasher = Sha512::new();
-            hasher.update(new_password.as_bytes());
-            let new_password = hasher.finalize().to_vec();
-
-            // Update packet to send.
+        if let Some(new_password_hash) = new_password_hash.clone() {
             packet = ClientRequest::SetFirstPassword {
                 client_net_protocol: NETWORK_PROTOCOL_VERSION,
                 username: username.clone(),
-                old_password: password,
-                new_password,
+                old_password: password_hash.clone(),
+                new_password: new_password_hash,
             }
         }
 
+        let result = self.send_login_request(&server, port, packet);
+        if !matches!(result, ConnectResult::Connected(_, _)) {
+            return result;
+        }
+
+        // Connected.
+        let mut config = ConfigManager::new();
+        config.server = server.clone();
+        config.port = port.to_string();
+        config.username = username.clone();
+        config.write_config_to_file();
+
+        self.login_data = Some(LoginData {
+            server,
+            port,
+            username,
+            password_hash: new_password_hash.unwrap_or(password_hash),
+        });
+
+        result
+    }
+
+    /// Establishes a secure connection, sends login (or resume session) request
+    /// and receives the answer.
+    ///
+    /// ## Arguments
+    /// * `server`: server name or address.
+    /// * `port`: server port.
+    /// * `packet`: request to send.
+    fn send_login_request(
+        &mut self,
+        server: &str,
+        port: u16,
+        packet: ClientRequest,
+    ) -> ConnectResult {
+        let result = NetService::establish_secure_connection(server, port);
+        if let Err(app_error) = result {
+            return ConnectResult::InternalError(app_error);
+        }
+        let (tcp_socket, secret_key) = result.unwrap();
+
+        self.socket = Some(tcp_socket);
+        self.secret_key = secret_key;
+        self.server_address = format!("{}:{}", server, port);
+
         if let Some(app_error) =
             send_message(self.socket.as_mut().unwrap(), &self.secret_key, packet)
         {
@@ -151,7 +268,10 @@ impl NetService {
             &mut is_fin,
         );
         if is_fin {
-            return ConnectResult::InternalError(AppError::new("unexpected FIN received"));
+            return ConnectResult::InternalError(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
         if let Err(app_error) = packet {
             return ConnectResult::InternalError(app_error);
@@ -161,28 +281,29 @@ impl NetService {
         // Deserialize.
         let packet = bincode::deserialize::<ClientAnswer>(&packet);
         if let Err(e) = packet {
-            return ConnectResult::InternalError(AppError::new(&e.to_string()));
+            return ConnectResult::InternalError(AppError::with_kind(
+                AppErrorKind::Protocol,
+                &e.to_string(),
+            ));
         }
         let packet = packet.unwrap();
 
-        let mut _is_admin = false;
+        let mut _role = UserRole::default();
+        let mut _otp_backup_codes = Vec::new();
         match packet {
             ClientAnswer::LoginAnswer {
                 is_ok,
-                is_admin,
                 fail_reason,
+                otp_backup_codes,
+                role,
+                session_token,
+                ..
             } => {
                 if !is_ok {
                     let mut _message = String::new();
                     match fail_reason.unwrap() {
                         ClientLoginFailReason::WrongProtocol { server_protocol } => {
-                            _message = format!(
-                                "Failed to connect to the server \
-                            due to incompatible application version.\n\
-                            Your application uses network protocol version {}, \
-                            while the server supports version {}.",
-                                NETWORK_PROTOCOL_VERSION, server_protocol
-                            );
+                            return ConnectResult::WrongProtocol(server_protocol);
                         }
                         ClientLoginFailReason::WrongCredentials { result } => match result {
                             ClientLoginFailResult::FailedAttempt {
@@ -211,53 +332,241 @@ impl NetService {
                             return ConnectResult::SetupOTP(qr_code);
                         }
                         ClientLoginFailReason::NeedOTP => return ConnectResult::NeedOTP,
-                        ClientLoginFailReason::NeedFirstPassword => {
-                            return ConnectResult::NeedFirstPassword;
+                        ClientLoginFailReason::NeedFirstPassword { password_policy } => {
+                            return ConnectResult::NeedFirstPassword(password_policy);
+                        }
+                        ClientLoginFailReason::WeakPassword { reason } => {
+                            _message = reason;
+                        }
+                        ClientLoginFailReason::SessionExpired => {
+                            _message = String::from("The session expired, please, login again.");
+                        }
+                        ClientLoginFailReason::ServerBusy => {
+                            _message = String::from("The server is busy, please, try again later.");
                         }
                     }
                     return ConnectResult::ConnectFailed(_message);
                 } else {
-                    _is_admin = is_admin;
+                    _role = role;
+                    _otp_backup_codes = otp_backup_codes;
+                    self.session_token = Some(session_token);
                 }
             }
             _ => {
-                return ConnectResult::InternalError(AppError::new("unexpected packet received"));
+                return ConnectResult::InternalError(AppError::with_kind(
+                    AppErrorKind::Protocol,
+                    "unexpected packet received",
+                ));
             }
         }
 
-        // Connected.
-        let mut config = ConfigManager::new();
-        config.server = server;
-        config.port = port.to_string();
-        config.username = username;
-        config.write_config_to_file();
-
         self.is_connected = true;
 
         // Return control here, don't drop the connection,
         // wait for further commands from the user.
-        ConnectResult::Connected(_is_admin)
+        ConnectResult::Connected(_role, _otp_backup_codes)
+    }
+
+    /// Closes the connection to the server (if connected) and forgets the session.
+    ///
+    /// ## Remarks
+    /// The server ends the session right away and our session token
+    /// can no longer be used.
+    pub fn disconnect(&mut self) {
+        if self.is_connected {
+            // Ignore errors since the server might have already closed the connection.
+            let _ = send_message(
+                self.socket.as_mut().unwrap(),
+                &self.secret_key,
+                ClientRequest::Logout,
+            );
+        }
+
+        if let Some(socket) = self.socket.take() {
+            // The socket might be already closed by the server.
+            let _ = socket.shutdown(Shutdown::Both);
+        }
+
+        self.secret_key = [0; SECRET_KEY_SIZE];
+        self.is_connected = false;
+        self.current_server_disk_usage = ServerDiskUsage {
+            total_disk_space_mb: 0,
+            used_disk_space_mb: 0,
+        };
+        self.server_address = String::new();
+        self.server_version = None;
+        self.login_data = None;
+        self.session_token = None;
+    }
+
+    /// Asks the server for its network protocol version and application version
+    /// (uses a separate connection that is closed after the answer is received).
+    ///
+    /// ## Remarks
+    /// Old servers do not support this request and close the connection, in this case
+    /// an error is returned.
+    ///
+    /// ## Arguments
+    /// * `server`: server name or address.
+    /// * `port`: server port.
+    pub fn query_server_info(server: &str, port: u16) -> Result<ServerInfo, AppError> {
+        let (mut tcp_socket, secret_key) = NetService::establish_secure_connection(server, port)?;
+
+        if let Some(app_error) =
+            send_message(&mut tcp_socket, &secret_key, ClientRequest::QueryServerInfo)
+        {
+            return Err(app_error);
+        }
+
+        // Receive answer.
+        let mut is_fin = false;
+        let packet = receive_message(
+            &mut tcp_socket,
+            &secret_key,
+            None,
+            std::usize::MAX,
+            &mut is_fin,
+        );
+        if is_fin {
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
+        }
+        if let Err(app_error) = packet {
+            return Err(app_error);
+        }
+        let packet = packet.unwrap();
+
+        // Deserialize.
+        let packet = bincode::deserialize::<ClientAnswer>(&packet);
+        if let Err(e) = packet {
+            return Err(AppError::with_kind(AppErrorKind::Protocol, &e.to_string()));
+        }
+
+        match packet.unwrap() {
+            ClientAnswer::ServerInfo {
+                net_protocol,
+                server_version,
+            } => Ok(ServerInfo {
+                net_protocol,
+                server_version,
+            }),
+            _ => Err(AppError::with_kind(
+                AppErrorKind::Protocol,
+                "unexpected packet received",
+            )),
+        }
     }
 
     pub fn get_server_disk_usage(&self) -> ServerDiskUsage {
         self.current_server_disk_usage
     }
 
+    /// Returns address of the server in the form "server:port".
+    pub fn get_server_address(&self) -> &str {
+        &self.server_address
+    }
+
+    /// Returns application version of the server (if known).
+    pub fn get_server_version(&self) -> Option<&str> {
+        self.server_version.as_deref()
+    }
+
+    /// Remembers application version of the server (see `query_server_info`)
+    /// to display it after the connection is established.
+    pub fn set_server_version(&mut self, server_version: Option<String>) {
+        self.server_version = server_version;
+    }
+
+    /// Checks if the server closed the connection (for example, due to our inactivity).
+    ///
+    /// ## Remarks
+    /// Does not send anything to the server.
+    ///
+    /// ## Return
+    /// `true` if the connection is still alive, `false` otherwise.
+    pub fn check_connection(&mut self) -> bool {
+        if !self.is_connected {
+            return false;
+        }
+        let socket = self.socket.as_mut().unwrap();
+
+        if socket.set_nonblocking(true).is_err() {
+            // Can't check without blocking, consider the connection alive.
+            return true;
+        }
+
+        let mut buffer = [0u8; 1];
+        let result = socket.peek(&mut buffer);
+
+        if socket.set_nonblocking(false).is_err() {
+            // Other requests expect a blocking socket.
+            self.is_connected = false;
+            return false;
+        }
+
+        self.is_connected = match result {
+            Ok(0) => false, // FIN
+            Ok(_) => true,
+            Err(e) => e.kind() == io::ErrorKind::WouldBlock,
+        };
+
+        self.is_connected
+    }
+
+    /// Tells the server that the client is still open so that the server does not
+    /// disconnect us due to inactivity (if the server allows that).
+    ///
+    /// ## Remarks
+    /// Does nothing if not connected.
+    pub fn send_keep_alive(&mut self) -> Result<(), AppError> {
+        if !self.is_connected {
+            return Ok(());
+        }
+
+        if let Some(app_error) = send_message(
+            self.socket.as_mut().unwrap(),
+            &self.secret_key,
+            ClientRequest::KeepAlive,
+        ) {
+            return Err(app_error);
+        }
+
+        Ok(())
+    }
+
     /// Queries a page of report from the server.
     ///
+    /// ## Arguments
+    /// * `page`: page of reports to query.
+    /// * `amount`: amount of reports per page.
+    /// * `sort_by_priority`: whether to sort reports by priority (highest first) or by ID.
+    /// * `unread_only`: whether to only query reports that this user did not open yet.
+    ///
     /// ## Return
-    /// A page of reports and a total number of reports in the database.
+    /// A page of reports and a total number of (unread) reports in the database.
     pub fn query_reports(
         &mut self,
         page: u64,
         amount: u64,
+        sort_by_priority: bool,
+        unread_only: bool,
     ) -> Result<(Vec<ReportSummary>, u64), AppError> {
         if !self.is_connected {
-            return Err(AppError::new("not connected"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "not connected",
+            ));
         }
 
         // Prepare packet to send.
-        let packet = ClientRequest::QueryReportsSummary { page, amount };
+        let packet = ClientRequest::QueryReportsSummary {
+            page,
+            amount,
+            sort_by_priority,
+            unread_only,
+        };
 
         if let Some(app_error) =
             send_message(self.socket.as_mut().unwrap(), &self.secret_key, packet)
@@ -274,17 +583,20 @@ impl NetService {
             &mut is_fin,
         );
         if is_fin {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
-        if let Err(e) = result {
-            return Err(AppError::new(&e.to_string()));
+        if let Err(app_error) = result {
+            return Err(app_error);
         }
         let serialized_packet = result.unwrap();
 
         // Deserialize.
         let packet = bincode::deserialize::<ClientAnswer>(&serialized_packet);
         if let Err(e) = packet {
-            return Err(AppError::new(&e.to_string()));
+            return Err(AppError::with_kind(AppErrorKind::Protocol, &e.to_string()));
         }
         let packet = packet.unwrap();
 
@@ -301,12 +613,21 @@ impl NetService {
                 };
                 Ok((reports, total_reports))
             }
-            _ => Err(AppError::new("unexpected packet received")),
+            _ => Err(AppError::with_kind(
+                AppErrorKind::Protocol,
+                "unexpected packet received",
+            )),
         }
     }
 
     /// Downloads and saves an attachment from the server.
     ///
+    /// ## Arguments
+    /// * `attachment_id`: ID of the attachment to download.
+    /// * `path_to_save`: path to the file to save the attachment to.
+    /// * `on_progress`: called while the attachment is being received with the amount
+    /// of received bytes and the total amount of bytes to receive.
+    ///
     /// ## Return
     /// `Err(AppError)` if something went wrong, otherwise
     /// `Ok(true)` if attachment is found and saved,
@@ -315,9 +636,13 @@ impl NetService {
         &mut self,
         attachment_id: usize,
         path_to_save: &Path,
+        on_progress: &mut dyn FnMut(usize, usize),
     ) -> Result<bool, AppError> {
         if !self.is_connected {
-            return Err(AppError::new("not connected"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "not connected",
+            ));
         }
 
         // Prepare packet to send.
@@ -332,21 +657,25 @@ impl NetService {
 
         // Wait for answer.
         let mut is_fin = false;
-        let serialized_packet = receive_message(
+        let serialized_packet = receive_message_with_progress(
             self.socket.as_mut().unwrap(),
             &self.secret_key,
             None,
             std::usize::MAX,
             &mut is_fin,
+            on_progress,
         )?;
         if is_fin {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
 
         // Deserialize.
         let message = bincode::deserialize::<ClientAnswer>(&serialized_packet);
         if let Err(e) = message {
-            return Err(AppError::new(&e.to_string()));
+            return Err(AppError::with_kind(AppErrorKind::Protocol, &e.to_string()));
         }
         let message = message.unwrap();
 
@@ -361,12 +690,18 @@ impl NetService {
                     Ok(true)
                 }
             }
-            _ => Err(AppError::new("unexpected message received")),
+            _ => Err(AppError::with_kind(
+                AppErrorKind::Protocol,
+                "unexpected message received",
+            )),
         }
     }
     pub fn query_report(&mut self, report_id: u64) -> Result<ReportData, AppError> {
         if !self.is_connected {
-            return Err(AppError::new("not connected"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "not connected",
+            ));
         }
 
         // Prepare packet to send.
@@ -389,13 +724,16 @@ impl NetService {
             &mut is_fin,
         )?;
         if is_fin {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
 
         // Deserialize.
         let message = bincode::deserialize::<ClientAnswer>(&serialized_packet);
         if let Err(e) = message {
-            return Err(AppError::new(&e.to_string()));
+            return Err(AppError::with_kind(AppErrorKind::Protocol, &e.to_string()));
         }
         let message = message.unwrap();
 
@@ -411,7 +749,14 @@ impl NetService {
                 sender_name,
                 sender_email,
                 os_info,
+                priority,
                 attachments,
+                metadata,
+                reporter_net_protocol,
+                reporter_version,
+                is_markdown,
+                session_id,
+                playtime_seconds,
             } => Ok(ReportData {
                 id,
                 title,
@@ -423,14 +768,27 @@ impl NetService {
                 sender_name,
                 sender_email,
                 os_info,
+                priority,
                 attachments,
+                metadata,
+                reporter_net_protocol,
+                reporter_version,
+                is_markdown,
+                session_id,
+                playtime_seconds,
             }),
-            _ => Err(AppError::new("unexpected message received")),
+            _ => Err(AppError::with_kind(
+                AppErrorKind::Protocol,
+                "unexpected message received",
+            )),
         }
     }
     pub fn delete_report(&mut self, report_id: u64) -> Result<bool, AppError> {
         if !self.is_connected {
-            return Err(AppError::new("not connected"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "not connected",
+            ));
         }
 
         // Prepare packet to send.
@@ -451,17 +809,20 @@ impl NetService {
             &mut is_fin,
         );
         if is_fin {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
-        if let Err(e) = result {
-            return Err(AppError::new(&e.to_string()));
+        if let Err(app_error) = result {
+            return Err(app_error);
         }
         let serialized_packet = result.unwrap();
 
         // Deserialize.
         let packet = bincode::deserialize::<ClientAnswer>(&serialized_packet);
         if let Err(e) = packet {
-            return Err(AppError::new(&e.to_string()));
+            return Err(AppError::with_kind(AppErrorKind::Protocol, &e.to_string()));
         }
         let packet = packet.unwrap();
 
@@ -469,8 +830,328 @@ impl NetService {
             ClientAnswer::DeleteReportResult {
                 is_found_and_removed,
             } => Ok(is_found_and_removed),
-            _ => Err(AppError::new("unexpected packet received")),
+            _ => Err(AppError::with_kind(
+                AppErrorKind::Protocol,
+                "unexpected packet received",
+            )),
+        }
+    }
+    /// Changes priority of a report (requires admin privileges).
+    ///
+    /// ## Return
+    /// `Ok(true)` if the report was found and changed,
+    /// `Ok(false)` if the report was not found.
+    pub fn set_report_priority(
+        &mut self,
+        report_id: u64,
+        priority: ReportPriority,
+    ) -> Result<bool, AppError> {
+        if !self.is_connected {
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "not connected",
+            ));
+        }
+
+        // Prepare packet to send.
+        let packet = ClientRequest::SetReportPriority {
+            report_id,
+            priority,
+        };
+
+        if let Some(app_error) =
+            send_message(self.socket.as_mut().unwrap(), &self.secret_key, packet)
+        {
+            return Err(app_error);
+        }
+
+        let mut is_fin = false;
+        let serialized_packet = receive_message(
+            self.socket.as_mut().unwrap(),
+            &self.secret_key,
+            None,
+            std::usize::MAX,
+            &mut is_fin,
+        )?;
+        if is_fin {
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
+        }
+
+        // Deserialize.
+        let packet = bincode::deserialize::<ClientAnswer>(&serialized_packet);
+        if let Err(e) = packet {
+            return Err(AppError::with_kind(AppErrorKind::Protocol, &e.to_string()));
+        }
+        let packet = packet.unwrap();
+
+        match packet {
+            ClientAnswer::SetReportPriorityResult {
+                is_found_and_changed,
+            } => Ok(is_found_and_changed),
+            _ => Err(AppError::with_kind(
+                AppErrorKind::Protocol,
+                "unexpected packet received",
+            )),
+        }
+    }
+    /// Queries comments of a report (oldest first).
+    pub fn query_report_comments(
+        &mut self,
+        report_id: u64,
+    ) -> Result<Vec<ReportComment>, AppError> {
+        if !self.is_connected {
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "not connected",
+            ));
+        }
+
+        // Prepare packet to send.
+        let packet = ClientRequest::QueryReportComments { report_id };
+
+        if let Some(app_error) =
+            send_message(self.socket.as_mut().unwrap(), &self.secret_key, packet)
+        {
+            return Err(app_error);
+        }
+
+        let mut is_fin = false;
+        let serialized_packet = receive_message(
+            self.socket.as_mut().unwrap(),
+            &self.secret_key,
+            None,
+            std::usize::MAX,
+            &mut is_fin,
+        )?;
+        if is_fin {
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
+        }
+
+        // Deserialize.
+        let packet = bincode::deserialize::<ClientAnswer>(&serialized_packet);
+        if let Err(e) = packet {
+            return Err(AppError::with_kind(AppErrorKind::Protocol, &e.to_string()));
+        }
+        let packet = packet.unwrap();
+
+        match packet {
+            ClientAnswer::ReportComments { comments } => Ok(comments),
+            _ => Err(AppError::with_kind(
+                AppErrorKind::Protocol,
+                "unexpected packet received",
+            )),
+        }
+    }
+    /// Queries the number of reports per game and per day.
+    ///
+    /// ## Arguments
+    /// * `day_count`: number of last days (including today) to return counts for.
+    pub fn query_stats(&mut self, day_count: u64) -> Result<ReportStats, AppError> {
+        if !self.is_connected {
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "not connected",
+            ));
+        }
+
+        // Prepare packet to send.
+        let packet = ClientRequest::QueryStats { day_count };
+
+        if let Some(app_error) =
+            send_message(self.socket.as_mut().unwrap(), &self.secret_key, packet)
+        {
+            return Err(app_error);
+        }
+
+        let mut is_fin = false;
+        let serialized_packet = receive_message(
+            self.socket.as_mut().unwrap(),
+            &self.secret_key,
+            None,
+            std::usize::MAX,
+            &mut is_fin,
+        )?;
+        if is_fin {
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
+        }
+
+        // Deserialize.
+        let packet = bincode::deserialize::<ClientAnswer>(&serialized_packet);
+        if let Err(e) = packet {
+            return Err(AppError::with_kind(AppErrorKind::Protocol, &e.to_string()));
+        }
+        let packet = packet.unwrap();
+
+        match packet {
+            ClientAnswer::Stats { stats } => Ok(stats),
+            _ => Err(AppError::with_kind(
+                AppErrorKind::Protocol,
+                "unexpected packet received",
+            )),
+        }
+    }
+    /// Adds a comment to a report (requires permission to change reports).
+    ///
+    /// ## Return
+    /// `Ok(true)` if the report was found and the comment was added,
+    /// `Ok(false)` if the report was not found.
+    pub fn add_report_comment(&mut self, report_id: u64, text: String) -> Result<bool, AppError> {
+        if !self.is_connected {
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "not connected",
+            ));
+        }
+
+        // Prepare packet to send.
+        let packet = ClientRequest::AddReportComment { report_id, text };
+
+        if let Some(app_error) =
+            send_message(self.socket.as_mut().unwrap(), &self.secret_key, packet)
+        {
+            return Err(app_error);
+        }
+
+        let mut is_fin = false;
+        let serialized_packet = receive_message(
+            self.socket.as_mut().unwrap(),
+            &self.secret_key,
+            None,
+            std::usize::MAX,
+            &mut is_fin,
+        )?;
+        if is_fin {
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
+        }
+
+        // Deserialize.
+        let packet = bincode::deserialize::<ClientAnswer>(&serialized_packet);
+        if let Err(e) = packet {
+            return Err(AppError::with_kind(AppErrorKind::Protocol, &e.to_string()));
         }
+        let packet = packet.unwrap();
+
+        match packet {
+            ClientAnswer::AddReportCommentResult { is_found_and_added } => Ok(is_found_and_added),
+            _ => Err(AppError::with_kind(
+                AppErrorKind::Protocol,
+                "unexpected packet received",
+            )),
+        }
+    }
+    /// Returns SHA-512 hash of the password (the server never receives plain passwords).
+    fn hash_password(password: &str) -> Vec<u8> {
+        let mut hasher = Sha512::new();
+        hasher.update(password.as_bytes());
+        hasher.finalize().to_vec()
+    }
+    /// Connects to the server and establishes a secure connection.
+    ///
+    /// ## Return
+    /// Connected socket and the secret key to encrypt messages.
+    fn establish_secure_connection(
+        server: &str,
+        port: u16,
+    ) -> Result<(TcpStream, [u8; SECRET_KEY_SIZE]), AppError> {
+        let addrs: Vec<SocketAddr> = match format!("{}:{}", server, port).to_socket_addrs() {
+            Ok(addrs) => addrs.collect(),
+            Err(e) => {
+                return Err(AppError::with_kind(
+                    AppErrorKind::AddressNotResolved,
+                    &format!(
+                        "failed to resolve server address \"{}\" (error: {})",
+                        server, e
+                    ),
+                ))
+            }
+        };
+        if addrs.is_empty() {
+            return Err(AppError::with_kind(
+                AppErrorKind::AddressNotResolved,
+                &format!(
+                    "server address \"{}\" was not resolved to any IP address",
+                    server
+                ),
+            ));
+        }
+
+        let mut tcp_socket: Option<TcpStream> = None;
+        let mut last_error: Option<io::Error> = None;
+        for addr in addrs {
+            match TcpStream::connect_timeout(&addr, Duration::from_secs(2)) {
+                Ok(socket) => {
+                    tcp_socket = Some(socket);
+                    break;
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        if tcp_socket.is_none() {
+            let error = last_error.unwrap();
+            let kind = match error.kind() {
+                io::ErrorKind::ConnectionRefused => AppErrorKind::ConnectionRefused,
+                io::ErrorKind::TimedOut => AppErrorKind::Timeout,
+                _ => AppErrorKind::Internal,
+            };
+            return Err(AppError::with_kind(
+                kind,
+                &format!(
+                    "could not connect to the server, make sure that server name and port \
+                    are correct and the server is running (error: {})",
+                    error
+                ),
+            ));
+        }
+
+        let mut tcp_socket = tcp_socket.unwrap();
+
+        // Configure socket.
+        if let Err(e) = tcp_socket.set_nodelay(true) {
+            return Err(AppError::new(&e.to_string()));
+        }
+        if let Err(e) = tcp_socket.set_nonblocking(true) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        // Read the server key that we trust (if specified).
+        let trusted_server_key_path = ConfigManager::new().trusted_server_key_path;
+        let trusted_server_key = if trusted_server_key_path.is_empty() {
+            None
+        } else {
+            match ServerPublicKey::read_from_file(Path::new(&trusted_server_key_path)) {
+                Ok(key) => Some(key),
+                Err(app_error) => {
+                    return Err(AppError::new(&format!(
+                        "{}, refusing to connect (check the \"trusted_server_key_path\" \
+                        parameter in the config)",
+                        app_error.get_message()
+                    )));
+                }
+            }
+        };
+
+        // Establish secure connection.
+        let secret_key =
+            accept_secure_connection_establishment(&mut tcp_socket, trusted_server_key.as_ref())?;
+        let result = secret_key.try_into();
+        if result.is_err() {
+            return Err(AppError::new("failed to convert Vec<u8> to generic array"));
+        }
+
+        Ok((tcp_socket, result.unwrap()))
     }
 }
 
@@ -484,6 +1165,10 @@ impl Default for NetService {
                 total_disk_space_mb: 0,
                 used_disk_space_mb: 0,
             },
+            server_address: String::new(),
+            server_version: None,
+            login_data: None,
+            session_token: None,
         }
     }
 }
@@ -1,18 +1,22 @@
 // External.
 use druid::widget::prelude::*;
 use druid::widget::{Button, Flex, Label, MainAxisAlignment};
-use druid::{Lens, TextAlignment, WidgetExt};
+use druid::{Color, FontDescriptor, Key, Lens, TextAlignment, WidgetExt};
 
 // Custom.
 use crate::misc::report_id_button::*;
+use crate::misc::theme::{NORMAL_BOLD_FONT, NORMAL_FONT, NORMAL_TEXT_SIZE};
 use crate::ApplicationState;
+use shared::misc::report::ReportPriority;
 
 // Layout customization.
-const TITLE_WIDTH: f64 = 0.4;
-const GAME_WIDTH: f64 = 0.2;
-const DATE_WIDTH: f64 = 0.2;
-const TIME_WIDTH: f64 = 0.2;
-const TEXT_SIZE: f64 = 18.0;
+const TITLE_WIDTH: f64 = 0.3;
+const PRIORITY_WIDTH: f64 = 0.15;
+const GAME_WIDTH: f64 = 0.15;
+const OCCURRENCES_WIDTH: f64 = 0.1;
+const DATE_WIDTH: f64 = 0.15;
+const TIME_WIDTH: f64 = 0.15;
+const TEXT_SIZE: Key<f64> = NORMAL_TEXT_SIZE;
 const TITLE_MAX_CHARS_TO_DISPLAY: usize = 20;
 
 #[derive(Clone, Data, Lens)]
@@ -22,11 +26,24 @@ pub struct ReportWidget {
     game: String,
     date: String,
     time: String,
+    #[data(same_fn = "PartialEq::eq")]
+    priority: ReportPriority,
+    occurrence_count: u64,
+    is_unread: bool,
     is_hovered: bool,
 }
 
 impl ReportWidget {
-    pub fn new(id: u64, title: String, game: String, date: String, time: String) -> Self {
+    pub fn new(
+        id: u64,
+        title: String,
+        game: String,
+        date: String,
+        time: String,
+        priority: ReportPriority,
+        occurrence_count: u64,
+        is_unread: bool,
+    ) -> Self {
         let mut cut_title = title.chars().take(TITLE_MAX_CHARS_TO_DISPLAY).collect();
 
         if title.chars().count() > TITLE_MAX_CHARS_TO_DISPLAY {
@@ -39,9 +56,29 @@ impl ReportWidget {
             game,
             date,
             time,
+            priority,
+            occurrence_count,
+            is_unread,
             is_hovered: false,
         }
     }
+    /// Returns color that is used to display the specified report priority.
+    pub fn get_priority_color(priority: ReportPriority) -> Color {
+        match priority {
+            ReportPriority::Low => Color::GRAY,
+            ReportPriority::Normal => Color::WHITE,
+            ReportPriority::High => Color::YELLOW,
+            ReportPriority::Critical => Color::RED,
+        }
+    }
+    /// Returns font for the report's row (unread reports are displayed in bold).
+    fn get_font(&self) -> Key<FontDescriptor> {
+        if self.is_unread {
+            NORMAL_BOLD_FONT
+        } else {
+            NORMAL_FONT
+        }
+    }
     pub fn build_title_ui() -> impl Widget<ApplicationState> {
         Flex::row()
             .main_axis_alignment(MainAxisAlignment::Start)
@@ -53,6 +90,13 @@ impl ReportWidget {
                     .expand_width(),
                 TITLE_WIDTH,
             )
+            .with_flex_child(
+                Label::new("Priority")
+                    .with_text_alignment(TextAlignment::Start)
+                    .with_text_size(TEXT_SIZE)
+                    .expand_width(),
+                PRIORITY_WIDTH,
+            )
             .with_flex_child(
                 Label::new("Game")
                     .with_text_alignment(TextAlignment::Start)
@@ -60,6 +104,13 @@ impl ReportWidget {
                     .expand_width(),
                 GAME_WIDTH,
             )
+            .with_flex_child(
+                Label::new("Occurrences")
+                    .with_text_alignment(TextAlignment::Start)
+                    .with_text_size(TEXT_SIZE)
+                    .expand_width(),
+                OCCURRENCES_WIDTH,
+            )
             .with_flex_child(
                 Label::new("Date")
                     .with_text_alignment(TextAlignment::Start)
@@ -83,7 +134,7 @@ impl ReportWidget {
                 Button::from_label(
                     Label::new(self.title.clone())
                         .with_text_alignment(TextAlignment::Start)
-                        .with_text_size(TEXT_SIZE),
+                        .with_font(self.get_font()),
                 )
                 .controller(ReportIdButtonController::new(ReportIdButtonData {
                     report_id: self.id,
@@ -91,24 +142,39 @@ impl ReportWidget {
                 .expand_width(),
                 TITLE_WIDTH,
             )
+            .with_flex_child(
+                Label::new(self.priority.to_string())
+                    .with_text_alignment(TextAlignment::Start)
+                    .with_font(self.get_font())
+                    .with_text_color(ReportWidget::get_priority_color(self.priority))
+                    .expand_width(),
+                PRIORITY_WIDTH,
+            )
             .with_flex_child(
                 Label::new(self.game.clone())
                     .with_text_alignment(TextAlignment::Start)
-                    .with_text_size(TEXT_SIZE)
+                    .with_font(self.get_font())
                     .expand_width(),
                 GAME_WIDTH,
             )
+            .with_flex_child(
+                Label::new(self.occurrence_count.to_string())
+                    .with_text_alignment(TextAlignment::Start)
+                    .with_font(self.get_font())
+                    .expand_width(),
+                OCCURRENCES_WIDTH,
+            )
             .with_flex_child(
                 Label::new(self.date.clone())
                     .with_text_alignment(TextAlignment::Start)
-                    .with_text_size(TEXT_SIZE)
+                    .with_font(self.get_font())
                     .expand_width(),
                 DATE_WIDTH,
             )
             .with_flex_child(
                 Label::new(self.time.clone())
                     .with_text_alignment(TextAlignment::Start)
-                    .with_text_size(TEXT_SIZE)
+                    .with_font(self.get_font())
                     .expand_width(),
                 TIME_WIDTH,
             )
@@ -21,6 +21,13 @@ var initial_report_text: String = ""; # stores initial contents of the "report"
 func _ready():
 	reporter.setup_game(game_name, game_version);
 	reporter.setup_report_receiver("Server", "127.0.0.1:50123", "");
+
+	# save a report if the game crashes and send reports from previous crashes
+	reporter.enable_crash_handler();
+	if reporter.get_crash_report_count() > 0:
+		var crash_result_code: int = reporter.send_crash_reports();
+		if crash_result_code != 0:
+			print("failed to send crash reports (result code " + str(crash_result_code) + "), will try again on the next launch")
 	
 	initial_report_text = report_text_node.text;
 
@@ -46,6 +53,8 @@ func send_report(
 	reporter.set_sender_name(sender_name);
 	reporter.set_sender_email(sender_email);
 	reporter.set_report_attachments(report_attachments);
+	reporter.set_report_priority(1); # optional: 0 - low, 1 - normal (default), 2 - high, 3 - critical
+	reporter.set_report_metadata("scene", get_tree().current_scene.name); # optional: custom key/value pairs
 
 	# Take a screenshot.
 	if take_screenshot:
@@ -73,6 +82,12 @@ func send_report(
 		# Remove previously saved screenshot (if there was one).
 		reporter.set_clear_screenshot();
 
+	# Optionally check the report without sending it (`validate_report` does not use the network).
+	var validation: Dictionary = reporter.validate_report();
+	if validation["result"] == 4:
+		result_text_node.text = "The attachment \"" + validation["path"] + "\" does not exist.";
+		return;
+
 	# Send report.
 	var result_code: int = reporter.send_report();
 	var error_message: String = ""
@@ -84,8 +99,9 @@ func send_report(
 			# you forgot to initialize the reporter
 			error_message = "Remote address / report receiver type is not set.";
 		elif result_code == 2:
-			# invalid input (some input string is too long)
-			error_message = "The field \"" + reporter.get_last_error() + "\" is too long!";
+			# invalid input (some input string is too long, checked by the reporter and the server)
+			# (the report text can also be too short if a minimum length is required)
+			error_message = "The field \"" + reporter.get_last_error() + "\" is too long or too short!";
 		elif result_code == 3:
 			# could not connect to the server
 			error_message = "Could not connect to the server.";
@@ -106,6 +122,25 @@ func send_report(
 		elif result_code == 6:
 			# other error, use `get_last_error` for description
 			error_message = "An error occurred: " + str(reporter.get_last_error()) + "."
+		elif result_code == 7:
+			# the server does not accept reports from this game version (see `setup_game`)
+			result_text_node.text = "Reports from this version of the game are no longer accepted, please, update your game.";
+			return;
+		elif result_code == 8:
+			# the server rejected the report, use `get_last_error` for the reason
+			error_message = "The server rejected the report: " + str(reporter.get_last_error()) + "."
+		elif result_code == 9:
+			# the server did not accept the proof of work (should not happen, try sending again)
+			error_message = "The server did not accept the proof of work, please, try again."
+		elif result_code == 10:
+			# the same sender email was used for a report recently, use `get_last_error` for details
+			error_message = "Too many reports: " + str(reporter.get_last_error()) + "."
+		elif result_code == 11:
+			# the server failed to save the report (internal server error)
+			error_message = "The server failed to process the report, please, try again later."
+		elif result_code == 12:
+			# the connection was lost or timed out while sending the report
+			error_message = "The connection to the server was lost, please, try again later."
 		else:
 			# adding this just in case
 			error_message = "The reporter returned unknown error code \"" + str(result_code) + "\".";
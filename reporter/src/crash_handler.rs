@@ -0,0 +1,502 @@
+// Std.
+use std::fs;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, Once};
+use std::time::SystemTime;
+
+// External.
+use backtrace::Backtrace;
+
+// Custom.
+use shared::misc::{error::AppError, report::*};
+
+/// Extension of files with spooled crash reports.
+const CRASH_REPORT_FILE_EXTENSION: &str = "json";
+/// Name of the file that exists while the game is running (see `CrashHandler`).
+const SESSION_MARKER_FILE_NAME: &str = "session.pending";
+
+/// State of the crash handler that is shared by all `CrashHandler` objects
+/// (`None` while there are no handlers, the panic hook does nothing in this case).
+static CRASH_HANDLER_STATE: Mutex<Option<CrashHandlerState>> = Mutex::new(None);
+/// Installs the panic hook (the hook is never removed, it only checks the state).
+static PANIC_HOOK: Once = Once::new();
+
+/// Process-wide state of the crash handler.
+struct CrashHandlerState {
+    /// Number of existing `CrashHandler` objects, the session marker is removed
+    /// when the last one is dropped.
+    handler_count: usize,
+    /// Report with information about the game (name, version, metadata, etc.),
+    /// the crash info is added to a copy of this report.
+    report_template: GameReport,
+    spool_directory: PathBuf,
+}
+
+/// Writes a report to the spool directory when the process panics so that the report
+/// can be sent on the next launch (see `read_spooled_reports`).
+///
+/// ## Remarks
+/// The report is not sent from the panic hook because the crashing process might be
+/// terminated at any moment (and network I/O can take seconds).
+///
+/// The panic hook only sees panics of the Rust code in this library, to also detect crashes
+/// of the game/engine a session marker file is created when the handler is installed
+/// and removed when the handler is dropped, if the marker still exists on the next launch
+/// the previous launch did not end properly and a report without backtrace is spooled.
+///
+/// Handlers share one panic hook and one session marker: crash reports use the last
+/// report template and the marker is removed when the last handler is dropped.
+pub struct CrashHandler {
+    spool_directory: PathBuf,
+}
+
+impl CrashHandler {
+    /// Enables spooling of crash reports (the panic hook is installed on the first call,
+    /// the previous hook is still called).
+    ///
+    /// ## Arguments
+    /// * `report_template`: report with information about the game (report name and
+    ///   report text are replaced with the crash info).
+    /// * `spool_directory`: directory to store crash reports in (created if it does not exist).
+    ///
+    /// ## Return
+    /// Error if the directory or the session marker could not be created or if another
+    /// handler uses a different directory.
+    pub fn install(
+        report_template: GameReport,
+        spool_directory: PathBuf,
+    ) -> Result<Self, AppError> {
+        if let Err(e) = fs::create_dir_all(&spool_directory) {
+            return Err(AppError::new(&format!(
+                "failed to create the directory for crash reports \"{}\" (error: {})",
+                spool_directory.display(),
+                e
+            )));
+        }
+
+        let marker_path = spool_directory.join(SESSION_MARKER_FILE_NAME);
+        let mut state_guard = lock_state();
+        match state_guard.as_mut() {
+            Some(state) => {
+                if state.spool_directory != spool_directory {
+                    return Err(AppError::new(&format!(
+                        "the crash handler already uses another directory \"{}\"",
+                        state.spool_directory.display()
+                    )));
+                }
+
+                // The session marker was created by this launch, only update it.
+                write_session_marker(&report_template, &marker_path)?;
+                state.report_template = report_template;
+                state.handler_count += 1;
+            }
+            None => {
+                spool_unfinished_launch_report(&spool_directory)?;
+
+                write_session_marker(&report_template, &marker_path)?;
+                *state_guard = Some(CrashHandlerState {
+                    handler_count: 1,
+                    report_template,
+                    spool_directory: spool_directory.clone(),
+                });
+            }
+        }
+        drop(state_guard);
+
+        PANIC_HOOK.call_once(|| {
+            let previous_hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                spool_panic_report(info);
+                previous_hook(info);
+            }));
+        });
+
+        Ok(Self { spool_directory })
+    }
+    /// Replaces the report that is used as a template for crash reports
+    /// (for example, when game info or metadata was changed).
+    pub fn set_report_template(&self, report_template: GameReport) -> Result<(), AppError> {
+        let mut state_guard = lock_state();
+        if let Some(state) = state_guard.as_mut() {
+            write_session_marker(
+                &report_template,
+                &state.spool_directory.join(SESSION_MARKER_FILE_NAME),
+            )?;
+            state.report_template = report_template;
+        }
+
+        Ok(())
+    }
+    /// Returns the directory where crash reports are stored.
+    pub fn get_spool_directory(&self) -> &Path {
+        &self.spool_directory
+    }
+}
+
+impl Drop for CrashHandler {
+    /// Marks the launch as properly ended if this is the last handler.
+    fn drop(&mut self) {
+        let mut state_guard = lock_state();
+        let is_last_handler = match state_guard.as_mut() {
+            Some(state) => {
+                state.handler_count -= 1;
+                state.handler_count == 0
+            }
+            None => false,
+        };
+
+        if is_last_handler {
+            *state_guard = None;
+            let _ = fs::remove_file(self.spool_directory.join(SESSION_MARKER_FILE_NAME));
+        }
+    }
+}
+
+/// Locks the process-wide state of the crash handler.
+fn lock_state() -> MutexGuard<'static, Option<CrashHandlerState>> {
+    // The state is always valid (nothing panics while it's locked).
+    CRASH_HANDLER_STATE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Called by the panic hook, spools a report about the panic if a handler exists.
+///
+/// ## Remarks
+/// The session marker is not removed because the game might continue to run
+/// (godot-rust catches panics in functions that are called by the engine).
+fn spool_panic_report(info: &PanicHookInfo) {
+    // Don't panic here (a panic inside of the panic hook aborts the process).
+    let state_guard = lock_state();
+    if let Some(state) = state_guard.as_ref() {
+        let report = create_crash_report(&state.report_template, info);
+        let _ = spool_report(&report, &state.spool_directory);
+    }
+}
+
+/// Spools a report if the session marker of the previous launch still exists
+/// (the previous launch did not end properly).
+fn spool_unfinished_launch_report(spool_directory: &Path) -> Result<(), AppError> {
+    let marker_path = spool_directory.join(SESSION_MARKER_FILE_NAME);
+    let report = fs::read_to_string(marker_path)
+        .ok()
+        .and_then(|json| serde_json::from_str::<GameReport>(&json).ok());
+    if let Some(mut report) = report {
+        report.report_name = String::from("Crash: the game stopped unexpectedly");
+        report.report_text = String::from(
+            "The previous launch of the game did not end properly (the game crashed, \
+            was killed or the computer was turned off), no backtrace is available \
+            (panics of the reporter are saved as separate reports).",
+        );
+        report.priority = ReportPriority::Critical;
+        spool_report(&report, spool_directory)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the report template to the session marker file (used to create a report
+/// if the game does not stop properly).
+fn write_session_marker(report_template: &GameReport, marker_path: &Path) -> Result<(), AppError> {
+    let json = serde_json::to_string(report_template);
+    if let Err(e) = json {
+        return Err(AppError::new(&e.to_string()));
+    }
+
+    if let Err(e) = fs::write(marker_path, json.unwrap()) {
+        return Err(AppError::new(&format!(
+            "failed to create the session marker \"{}\" (error: {})",
+            marker_path.display(),
+            e
+        )));
+    }
+
+    Ok(())
+}
+
+/// Creates a report that describes a panic.
+///
+/// ## Arguments
+/// * `report_template`: report with information about the game.
+/// * `info`: information about the panic.
+fn create_crash_report(report_template: &GameReport, info: &PanicHookInfo) -> GameReport {
+    let message = if let Some(message) = info.payload().downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = info.payload().downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown error")
+    };
+
+    let location = match info.location() {
+        Some(location) => format!("{}:{}", location.file(), location.line()),
+        None => String::from("unknown location"),
+    };
+
+    let mut report = report_template.clone();
+    report.priority = ReportPriority::Critical;
+    // The message might contain personal data (like paths) so it's only stored in the report
+    // text (redaction patterns are applied to the text).
+    report.report_name = truncate(
+        &format!("Crash at {}", location),
+        ReportLimits::ReportName.max_length(),
+    );
+    report.report_text = truncate(
+        &format!(
+            "The game crashed at {}: {}\n\nBacktrace:\n{:?}",
+            location,
+            message,
+            Backtrace::new()
+        ),
+        ReportLimits::ReportText.max_length(),
+    );
+
+    report
+}
+
+/// Saves the report to a new file in the specified directory.
+///
+/// ## Return
+/// Path to the new file.
+pub fn spool_report(report: &GameReport, spool_directory: &Path) -> Result<PathBuf, AppError> {
+    let json = serde_json::to_string(report);
+    if let Err(e) = json {
+        return Err(AppError::new(&e.to_string()));
+    }
+
+    let timestamp_in_ms = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let file_name = format!("crash_{}_{:08x}", timestamp_in_ms, rand::random::<u32>());
+
+    // Write to a temporary file first so that a partially written report is never read.
+    let temp_path = spool_directory.join(format!("{}.tmp", file_name));
+    let path = spool_directory.join(format!("{}.{}", file_name, CRASH_REPORT_FILE_EXTENSION));
+    if let Err(e) = fs::write(&temp_path, json.unwrap()) {
+        return Err(AppError::new(&format!(
+            "failed to write the crash report to \"{}\" (error: {})",
+            temp_path.display(),
+            e
+        )));
+    }
+    if let Err(e) = fs::rename(&temp_path, &path) {
+        return Err(AppError::new(&format!(
+            "failed to write the crash report to \"{}\" (error: {})",
+            path.display(),
+            e
+        )));
+    }
+
+    Ok(path)
+}
+
+/// Reads reports that were spooled by the crash handler (oldest first).
+///
+/// ## Return
+/// Paths to report files and reports, files that could not be parsed are removed.
+pub fn read_spooled_reports(spool_directory: &Path) -> Vec<(PathBuf, GameReport)> {
+    let entries = match fs::read_dir(spool_directory) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(), // nothing was spooled yet
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == CRASH_REPORT_FILE_EXTENSION)
+        })
+        .collect();
+    paths.sort();
+
+    let mut reports = Vec::new();
+    for path in paths {
+        let report = fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<GameReport>(&json).ok());
+        match report {
+            Some(report) => reports.push((path, report)),
+            None => {
+                // Corrupted report, don't try to send it on each launch.
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+
+    reports
+}
+
+/// Returns the first `max_length` characters of the text.
+fn truncate(text: &str, max_length: usize) -> String {
+    text.chars().take(max_length).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    // Std.
+    use std::sync::Arc;
+
+    use super::*;
+
+    /// Tests that change the panic hook or the crash handler state should not run
+    /// at the same time.
+    static PANIC_HOOK_LOCK: Mutex<()> = Mutex::new(());
+
+    fn create_test_report() -> GameReport {
+        GameReport {
+            report_name: String::new(),
+            report_text: String::new(),
+            sender_name: String::new(),
+            sender_email: String::new(),
+            game_name: String::from("game"),
+            game_version: String::from("1.0.0"),
+            client_os_info: os_info::Info::unknown(),
+            priority: ReportPriority::default(),
+            metadata: Default::default(),
+            reporter_version: String::from(env!("CARGO_PKG_VERSION")),
+            is_markdown: false,
+            session_id: String::new(),
+            playtime_seconds: 0,
+        }
+    }
+
+    #[test]
+    fn spooled_reports_are_read_back() {
+        let directory = create_test_directory();
+        fs::create_dir_all(&directory).unwrap();
+
+        let mut report = create_test_report();
+        report.report_name = String::from("Crash: test");
+        let path = spool_report(&report, &directory).unwrap();
+
+        // Corrupted reports are removed.
+        let corrupted_path = directory.join(format!("corrupted.{}", CRASH_REPORT_FILE_EXTENSION));
+        fs::write(&corrupted_path, "{").unwrap();
+
+        let reports = read_spooled_reports(&directory);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].0, path);
+        assert_eq!(reports[0].1.report_name, "Crash: test");
+        assert_eq!(reports[0].1.game_name, "game");
+        assert!(!corrupted_path.exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    fn create_test_directory() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "fbugreporter_crash_test_{:08x}",
+            rand::random::<u32>()
+        ))
+    }
+
+    #[test]
+    fn unfinished_launch_is_spooled_on_next_install() {
+        let _guard = PANIC_HOOK_LOCK.lock().unwrap();
+        let directory = create_test_directory();
+
+        // Simulate a launch that did not end properly.
+        fs::create_dir_all(&directory).unwrap();
+        write_session_marker(
+            &create_test_report(),
+            &directory.join(SESSION_MARKER_FILE_NAME),
+        )
+        .unwrap();
+
+        let handler = CrashHandler::install(create_test_report(), directory.clone()).unwrap();
+        let reports = read_spooled_reports(&directory);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].1.game_name, "game");
+        assert_eq!(reports[0].1.priority, ReportPriority::Critical);
+        fs::remove_file(&reports[0].0).unwrap();
+
+        // The launch ended properly.
+        drop(handler);
+        let handler = CrashHandler::install(create_test_report(), directory.clone()).unwrap();
+        assert!(read_spooled_reports(&directory).is_empty());
+
+        drop(handler);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn panic_after_drop_is_not_spooled() {
+        let _guard = PANIC_HOOK_LOCK.lock().unwrap();
+        let directory = create_test_directory();
+
+        let handler = CrashHandler::install(create_test_report(), directory.clone()).unwrap();
+        drop(handler);
+        assert!(!directory.join(SESSION_MARKER_FILE_NAME).exists());
+
+        let _ = std::panic::catch_unwind(|| panic!("test panic"));
+        assert!(read_spooled_reports(&directory).is_empty());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn two_handlers_share_hook_and_marker() {
+        let _guard = PANIC_HOOK_LOCK.lock().unwrap();
+        let directory = create_test_directory();
+
+        let first_handler = CrashHandler::install(create_test_report(), directory.clone()).unwrap();
+        let mut report_template = create_test_report();
+        report_template.game_version = String::from("2.0.0");
+        let second_handler = CrashHandler::install(report_template, directory.clone()).unwrap();
+
+        // The marker of this launch is not reported as an unfinished launch.
+        assert!(read_spooled_reports(&directory).is_empty());
+
+        // Only one report per panic (using the last template), the marker is kept
+        // because the game might continue to run.
+        let _ = std::panic::catch_unwind(|| panic!("test panic"));
+        let reports = read_spooled_reports(&directory);
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].1.game_version, "2.0.0");
+        assert!(reports[0].1.report_text.contains("test panic"));
+        assert!(directory.join(SESSION_MARKER_FILE_NAME).exists());
+        fs::remove_file(&reports[0].0).unwrap();
+
+        // The marker is removed after the last handler is dropped.
+        drop(first_handler);
+        assert!(directory.join(SESSION_MARKER_FILE_NAME).exists());
+        drop(second_handler);
+        assert!(!directory.join(SESSION_MARKER_FILE_NAME).exists());
+
+        // Another directory can't be used while a handler exists.
+        let handler = CrashHandler::install(create_test_report(), directory.clone()).unwrap();
+        let other_directory = create_test_directory();
+        assert!(CrashHandler::install(create_test_report(), other_directory.clone()).is_err());
+
+        drop(handler);
+        fs::remove_dir_all(&directory).unwrap();
+        fs::remove_dir_all(&other_directory).unwrap();
+    }
+
+    #[test]
+    fn crash_report_fits_field_limits() {
+        let _guard = PANIC_HOOK_LOCK.lock().unwrap();
+        let message = "x".repeat(10000);
+
+        // Use a hook to get `PanicHookInfo`.
+        let report = Arc::new(Mutex::new(None));
+        let hook_report = report.clone();
+        let previous_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            *hook_report.lock().unwrap() = Some(create_crash_report(&create_test_report(), info));
+        }));
+        let _ = std::panic::catch_unwind(|| {
+            std::panic::panic_any(message.clone());
+        });
+        std::panic::set_hook(previous_hook);
+
+        let report = report.lock().unwrap().take().unwrap();
+        assert!(report.report_name.starts_with("Crash at "));
+        assert!(report.report_name.chars().count() <= ReportLimits::ReportName.max_length());
+        assert!(report.report_text.contains("xxxxx"));
+        assert!(report.report_text.chars().count() <= ReportLimits::ReportText.max_length());
+        assert_eq!(report.priority, ReportPriority::Critical);
+    }
+}
@@ -0,0 +1,329 @@
+// Std.
+use std::fs::{metadata, File};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// Custom.
+use shared::misc::error::AppError;
+
+/// Makes sure that files can be created in the specified directory
+/// (creates the directory if it does not exist).
+pub fn check_directory_is_writable(directory: &Path) -> Result<(), AppError> {
+    if let Err(e) = std::fs::create_dir_all(directory) {
+        return Err(AppError::new(&format!(
+            "failed to create the directory (error: {})",
+            e
+        )));
+    }
+
+    let test_file_path = directory.join(".fbugreporter_write_test");
+    if let Err(e) = std::fs::write(&test_file_path, []) {
+        return Err(AppError::new(&format!(
+            "the directory is not writable (error: {})",
+            e
+        )));
+    }
+
+    if let Err(e) = std::fs::remove_file(&test_file_path) {
+        return Err(AppError::new(&format!(
+            "failed to remove the test file (error: {})",
+            e
+        )));
+    }
+
+    Ok(())
+}
+
+/// Returns the amount of bytes that the file takes as a report attachment
+/// (the file name and the file contents).
+///
+/// ## Return
+/// Error if failed to get the file's metadata (for example, if the file does not exist).
+pub fn get_attachment_size_in_bytes(path: &str) -> Result<u64, std::io::Error> {
+    let file_size = metadata(path)?.len();
+
+    let file_name_length = match Path::new(path).file_name() {
+        Some(file_name) => file_name.len() as u64,
+        None => 0,
+    };
+
+    Ok(file_name_length + file_size)
+}
+
+/// Returns files from the specified directory sorted by path.
+///
+/// ## Arguments
+/// * `directory`: directory to look for files in.
+/// * `recursive`: whether to also look for files in subdirectories or not.
+///
+/// ## Return
+/// Error if failed to read the directory, otherwise found files and errors
+/// that occurred while reading directory entries, subdirectories or files
+/// (such entries are skipped).
+pub fn get_files_in_directory(
+    directory: &Path,
+    recursive: bool,
+) -> Result<(Vec<PathBuf>, Vec<AppError>), AppError> {
+    let entries = std::fs::read_dir(directory);
+    if let Err(e) = entries {
+        return Err(AppError::new(&format!(
+            "failed to read the directory \"{}\" (error: {})",
+            directory.display(),
+            e
+        )));
+    }
+
+    let mut files: Vec<PathBuf> = Vec::new();
+    let mut errors: Vec<AppError> = Vec::new();
+
+    for entry in entries.unwrap() {
+        if let Err(e) = entry {
+            errors.push(AppError::new(&e.to_string()));
+            continue;
+        }
+        let entry = entry.unwrap();
+
+        // Get type of the entry.
+        let entry_type = entry.file_type();
+        if let Err(e) = entry_type {
+            errors.push(AppError::new(&e.to_string()));
+            continue;
+        }
+        let entry_type = entry_type.unwrap();
+
+        if entry_type.is_dir() {
+            if !recursive {
+                continue;
+            }
+
+            match get_files_in_directory(&entry.path(), true) {
+                Ok((mut subdirectory_files, mut subdirectory_errors)) => {
+                    files.append(&mut subdirectory_files);
+                    errors.append(&mut subdirectory_errors);
+                }
+                Err(app_error) => errors.push(app_error),
+            }
+            continue;
+        }
+
+        if !entry_type.is_file() {
+            continue;
+        }
+
+        // Make sure the file can be read.
+        if let Err(e) = File::open(entry.path()) {
+            errors.push(AppError::new(&format!(
+                "skipping the file \"{}\" because it can't be read (error: {})",
+                entry.path().display(),
+                e
+            )));
+            continue;
+        }
+
+        files.push(entry.path());
+    }
+
+    files.sort();
+
+    Ok((files, errors))
+}
+
+/// Returns files from the specified directory sorted from the most recently modified
+/// file to the oldest one (files with the same modification time are sorted by path).
+///
+/// ## Return
+/// Error if failed to read the directory, otherwise sorted files and errors
+/// that occurred while reading directory entries (such entries are skipped).
+/// Files with unknown modification time are considered the oldest.
+pub fn get_files_sorted_by_modification_time(
+    directory: &Path,
+) -> Result<(Vec<PathBuf>, Vec<AppError>), AppError> {
+    let entries = std::fs::read_dir(directory);
+    if let Err(e) = entries {
+        return Err(AppError::new(&e.to_string()));
+    }
+
+    let mut files: Vec<(PathBuf, SystemTime)> = Vec::new();
+    let mut errors: Vec<AppError> = Vec::new();
+
+    // Read files modification date.
+    for entry in entries.unwrap() {
+        if let Err(e) = entry {
+            errors.push(AppError::new(&e.to_string()));
+            continue;
+        }
+        let entry = entry.unwrap();
+
+        // Get type of the entry.
+        let entry_type = entry.file_type();
+        if let Err(e) = entry_type {
+            errors.push(AppError::new(&e.to_string()));
+            continue;
+        }
+
+        // Look only for files.
+        if !entry_type.unwrap().is_file() {
+            continue;
+        }
+
+        // Get modification time.
+        let last_modified = metadata(entry.path()).and_then(|metadata| metadata.modified());
+        let last_modified = match last_modified {
+            Ok(time) => time,
+            Err(e) => {
+                errors.push(AppError::new(&e.to_string()));
+                SystemTime::UNIX_EPOCH
+            }
+        };
+
+        files.push((entry.path(), last_modified));
+    }
+
+    // Most recent first.
+    files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    Ok((files.into_iter().map(|(path, _)| path).collect(), errors))
+}
+
+#[cfg(test)]
+mod tests {
+    // Std.
+    use std::env;
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Creates an empty directory for a test in the temp directory.
+    fn create_test_directory(name: &str) -> PathBuf {
+        let mut path = env::temp_dir();
+        path.push("FBugReporter");
+        path.push("tests");
+        path.push(name);
+
+        if path.exists() {
+            std::fs::remove_dir_all(&path).unwrap();
+        }
+        std::fs::create_dir_all(&path).unwrap();
+
+        path
+    }
+
+    /// Creates a file with the specified modification time (in seconds since UNIX epoch).
+    fn create_file(directory: &Path, name: &str, modified_at_sec: u64) -> PathBuf {
+        let path = directory.join(name);
+        let file = File::create(&path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(modified_at_sec))
+            .unwrap();
+
+        path
+    }
+
+    #[test]
+    fn files_sorted_from_most_recent() {
+        let directory = create_test_directory("files_sorted_from_most_recent");
+
+        let oldest = create_file(&directory, "oldest.log", 1_000);
+        let newest = create_file(&directory, "newest.log", 3_000);
+        let middle = create_file(&directory, "middle.log", 2_000);
+        std::fs::create_dir(directory.join("subdirectory")).unwrap();
+
+        let (files, errors) = get_files_sorted_by_modification_time(&directory).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(files, vec![newest, middle, oldest]);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn files_with_same_modification_time_sorted_by_path() {
+        let directory = create_test_directory("files_with_same_modification_time_sorted_by_path");
+
+        let b = create_file(&directory, "b.log", 2_000);
+        let a = create_file(&directory, "a.log", 2_000);
+        let old = create_file(&directory, "c.log", 1_000);
+
+        let (files, _) = get_files_sorted_by_modification_time(&directory).unwrap();
+        assert_eq!(files, vec![a, b, old]);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn missing_directory_is_error() {
+        let directory = create_test_directory("missing_directory_is_error");
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert!(get_files_sorted_by_modification_time(&directory).is_err());
+    }
+
+    #[test]
+    fn directory_is_writable_creates_directory() {
+        let directory = create_test_directory("directory_is_writable_creates_directory");
+        let temp_directory = directory.join("temp");
+
+        check_directory_is_writable(&temp_directory).unwrap();
+        assert!(temp_directory.is_dir());
+        assert_eq!(std::fs::read_dir(&temp_directory).unwrap().count(), 0);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn file_is_not_writable_directory() {
+        let directory = create_test_directory("file_is_not_writable_directory");
+        let file = create_file(&directory, "file.txt", 1_000);
+
+        assert!(check_directory_is_writable(&file).is_err());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn files_in_directory_not_recursive() {
+        let directory = create_test_directory("files_in_directory_not_recursive");
+
+        let b = create_file(&directory, "b.log", 1_000);
+        let a = create_file(&directory, "a.log", 2_000);
+        std::fs::create_dir(directory.join("subdirectory")).unwrap();
+        create_file(&directory.join("subdirectory"), "c.log", 1_000);
+
+        let (files, errors) = get_files_in_directory(&directory, false).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(files, vec![a, b]);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn files_in_directory_recursive() {
+        let directory = create_test_directory("files_in_directory_recursive");
+
+        let a = create_file(&directory, "a.log", 1_000);
+        let subdirectory = directory.join("subdirectory");
+        std::fs::create_dir_all(subdirectory.join("nested")).unwrap();
+        let b = create_file(&subdirectory, "b.log", 1_000);
+        let c = create_file(&subdirectory.join("nested"), "c.log", 1_000);
+
+        let (files, errors) = get_files_in_directory(&directory, true).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(files, vec![a, b, c]);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn attachment_size_includes_file_name() {
+        let directory = create_test_directory("attachment_size_includes_file_name");
+
+        let path = directory.join("file.txt");
+        std::fs::write(&path, [0u8; 10]).unwrap();
+
+        assert_eq!(
+            get_attachment_size_in_bytes(path.to_str().unwrap()).unwrap(),
+            "file.txt".len() as u64 + 10
+        );
+        assert!(get_attachment_size_in_bytes(directory.join("missing").to_str().unwrap()).is_err());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,1268 @@
+// Std.
+use std::io::Read;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+use std::{env, fs::File};
+
+// External.
+use godot::engine::image::Format;
+use godot::engine::Image;
+use godot::engine::ProjectSettings;
+use godot::prelude::*;
+use image::{DynamicImage, ImageBuffer, RgbImage, RgbaImage};
+
+// Custom.
+use crate::crash_handler::*;
+use crate::files::*;
+use crate::localization::*;
+use crate::log_manager::*;
+use crate::report_builder::ReportBuilder;
+use crate::report_receiver::*;
+use shared::misc::{error::AppError, report::*};
+use shared::network::net_params::NETWORK_PROTOCOL_VERSION;
+
+/// Directory for crash reports (see `enable_crash_handler`), `user://` is separate
+/// for each project.
+const CRASH_REPORT_DIRECTORY: &str = "user://fbugreporter_crash_reports";
+
+struct FBugReporterExtension;
+
+#[gdextension]
+unsafe impl ExtensionLibrary for FBugReporterExtension {}
+
+#[derive(GodotClass)]
+#[class(base=Node)]
+struct FBugReporter {
+    /// Report information that was set (report construction and validation).
+    report_builder: ReportBuilder,
+    attachments: Vec<String>,
+    remote_address: Option<String>,
+    /// Maximum size of attachments (in total) that was last received from the report receiver.
+    max_attachments_size_in_mb: Option<usize>,
+    screenshot_path: Option<String>,
+    /// Directory for temporary files (like the screenshot), `None` to use the default one.
+    temp_directory: Option<PathBuf>,
+    last_report: Option<GameReport>,
+    auth_token: String,
+    connect_timeout_in_sec: u64,
+    last_error: String,
+    /// Translations of `last_error` messages.
+    localization: Localization,
+    report_receiver: Option<Box<dyn ReportReceiver>>,
+    /// Spools crash reports, `None` if `enable_crash_handler` was not called.
+    crash_handler: Option<CrashHandler>,
+
+    #[base]
+    base: Base<Node>,
+}
+
+#[godot_api]
+impl NodeVirtual for FBugReporter {
+    fn init(base: Base<Node>) -> Self {
+        Self {
+            report_builder: ReportBuilder::new(),
+            attachments: Vec::new(),
+            remote_address: None,
+            max_attachments_size_in_mb: None,
+            auth_token: String::new(),
+            connect_timeout_in_sec: DEFAULT_CONNECT_TIMEOUT_IN_SEC,
+            screenshot_path: None,
+            temp_directory: None,
+            last_report: None,
+            last_error: String::new(),
+            localization: Localization::new(),
+            base,
+            report_receiver: None,
+            crash_handler: None,
+        }
+    }
+}
+
+#[godot_api]
+impl FBugReporter {
+    /// Sets game essential information that will be used in all sent reports.
+    #[func]
+    fn setup_game(&mut self, game_name: GodotString, game_version: GodotString) {
+        self.report_builder.game_name = game_name.into();
+        self.report_builder.game_version = game_version.into();
+    }
+
+    /// Tells reporter what remote entity we are targeting.
+    ///
+    /// ## Remarks
+    /// See files in `src/report_receiver` for all available report receivers.
+    ///
+    /// ## Arguments
+    /// * `receiver_type` name of the remote entity type, for example "Server" for
+    /// FBugReporter server, see `src/report_receiver/mod.rs` enum `ReportReceiverType` for
+    /// all options.
+    /// * `remote_address` string that describes remote entity's address (depends on the report
+    /// receiver), this can be a domain name, IPv4 address, IPv6 address or something else.
+    /// * `auth_token` optional authentication token that some report receivers require.
+    #[func]
+    fn setup_report_receiver(
+        &mut self,
+        receiver_type: GodotString,
+        remote_address: GodotString,
+        auth_token: GodotString,
+    ) {
+        // Create receiver.
+        let receiver_type = Into::<String>::into(receiver_type);
+        let result = create_report_receiver(&receiver_type);
+
+        // Check for errors.
+        if result.is_none() {
+            godot_error!(
+                "failed to find a report receiver for the specified type \"{}\"",
+                receiver_type
+            );
+            return;
+        }
+
+        // Save info.
+        let mut report_receiver = result.unwrap();
+        report_receiver.set_connect_timeout(Duration::from_secs(self.connect_timeout_in_sec));
+        self.report_receiver = Some(report_receiver);
+        self.remote_address = Some(Into::<String>::into(remote_address));
+        self.max_attachments_size_in_mb = None;
+        self.auth_token = Into::<String>::into(auth_token);
+    }
+
+    /// Sets the maximum time to wait for a connection to the remote entity
+    /// (see `setup_report_receiver`) when sending a report.
+    ///
+    /// ## Arguments
+    /// * `timeout_in_sec`: connect timeout in seconds (5 by default).
+    #[func]
+    fn set_connect_timeout(&mut self, timeout_in_sec: i64) {
+        if timeout_in_sec <= 0 {
+            godot_error!(
+                "connect timeout should be positive (received \"{}\")",
+                timeout_in_sec
+            );
+            return;
+        }
+
+        self.connect_timeout_in_sec = timeout_in_sec as u64;
+
+        if let Some(report_receiver) = self.report_receiver.as_mut() {
+            report_receiver.set_connect_timeout(Duration::from_secs(self.connect_timeout_in_sec));
+        }
+    }
+
+    /// Sets the minimum amount of characters required in the report text
+    /// (the server might require a different minimum).
+    ///
+    /// ## Arguments
+    /// * `length`: minimum length of the report text (0 by default).
+    #[func]
+    fn set_min_report_text_length(&mut self, length: i64) {
+        if length < 0 {
+            godot_error!(
+                "minimum report text length should not be negative (received \"{}\")",
+                length
+            );
+            return;
+        }
+
+        self.report_builder.min_report_text_length = length as usize;
+    }
+
+    /// Adds a regular expression, matches of which will be replaced with "[redacted]"
+    /// in the report text (and optionally in the sender name, see `set_redact_sender_name`)
+    /// when the report is sent.
+    ///
+    /// ## Arguments
+    /// * `pattern`: regular expression (for example, to hide e-mails or file paths).
+    #[func]
+    fn add_redaction_pattern(&mut self, pattern: GodotString) {
+        if let Err(app_error) = self
+            .report_builder
+            .add_redaction_pattern(&Into::<String>::into(pattern))
+        {
+            godot_error!("{}", app_error.get_message());
+        }
+    }
+
+    /// Removes all patterns that were added using `add_redaction_pattern`.
+    #[func]
+    fn clear_redaction_patterns(&mut self) {
+        self.report_builder.clear_redaction_patterns();
+    }
+
+    /// Sets whether redaction patterns should also be applied to the sender name or not.
+    ///
+    /// ## Arguments
+    /// * `enable`: `true` to redact the sender name (`false` by default).
+    #[func]
+    fn set_redact_sender_name(&mut self, enable: bool) {
+        self.report_builder.redact_sender_name = enable;
+    }
+
+    /// Enables or disables anonymous reports.
+    ///
+    /// ## Remarks
+    /// In anonymous mode sender name and sender e-mail are not sent (even if they were set)
+    /// and OS info is not collected. Report name, report text, game info, priority, metadata
+    /// and attachments (including the screenshot) are still sent.
+    ///
+    /// ## Arguments
+    /// * `enable`: `true` to send anonymous reports (`false` by default).
+    #[func]
+    fn set_anonymous(&mut self, enable: bool) {
+        self.report_builder.anonymous = enable;
+    }
+
+    #[func]
+    fn set_report_name(&mut self, report_name: GodotString) {
+        self.report_builder.report_name = report_name.into();
+    }
+
+    #[func]
+    fn set_report_text(&mut self, report_text: GodotString) {
+        self.report_builder.report_text = report_text.into();
+    }
+
+    /// Sets whether the report text is written in Markdown or not (clients only render
+    /// Markdown in reports that were marked as Markdown).
+    ///
+    /// ## Arguments
+    /// * `is_markdown`: `true` if the report text is Markdown (`false` by default).
+    #[func]
+    fn set_report_is_markdown(&mut self, is_markdown: bool) {
+        self.report_builder.is_markdown = is_markdown;
+    }
+
+    /// Sets the ID of the current game session so that reports can be matched with
+    /// your own logs or analytics (not sent by default).
+    ///
+    /// ## Arguments
+    /// * `session_id`: ID of the session, an empty string clears it.
+    #[func]
+    fn set_report_session_id(&mut self, session_id: GodotString) {
+        self.report_builder.session_id = session_id.into();
+    }
+
+    /// Sets how long the player played before sending the report (not sent by default).
+    ///
+    /// ## Arguments
+    /// * `playtime_in_sec`: playtime in seconds, `0` clears it.
+    #[func]
+    fn set_report_playtime_seconds(&mut self, playtime_in_sec: i64) {
+        if playtime_in_sec < 0 {
+            godot_error!(
+                "playtime should not be negative (received \"{}\")",
+                playtime_in_sec
+            );
+            return;
+        }
+
+        self.report_builder.playtime_seconds = playtime_in_sec as u64;
+    }
+
+    /// Sets the template of the report text, if the report text is empty (or is still
+    /// equal to the previous template) it's replaced with the template.
+    ///
+    /// ## Arguments
+    /// * `text`: template (for example, "Steps to reproduce:\n\nExpected:\n\nActual:\n").
+    #[func]
+    fn set_report_template(&mut self, text: GodotString) {
+        self.report_builder.set_report_template(text.into());
+    }
+
+    /// Returns the template that was set using `set_report_template` (empty by default).
+    #[func]
+    fn get_report_template(&self) -> GodotString {
+        self.report_builder.get_report_template().into()
+    }
+
+    /// Tells if the report text differs from the template (whitespace at the beginning
+    /// and at the end is ignored).
+    #[func]
+    fn is_report_text_modified(&self) -> bool {
+        self.report_builder.is_report_text_modified()
+    }
+
+    /// Sets whether the report text that is equal to the template should be treated
+    /// as empty or not (such reports are not sent, the "invalid input" result is returned).
+    ///
+    /// ## Arguments
+    /// * `enable`: `true` to reject unmodified templates (`false` by default).
+    #[func]
+    fn set_reject_unmodified_template(&mut self, enable: bool) {
+        self.report_builder.reject_unmodified_template = enable;
+    }
+
+    #[func]
+    fn set_sender_name(&mut self, sender_name: GodotString) {
+        self.report_builder.sender_name = sender_name.into();
+    }
+
+    #[func]
+    fn set_sender_email(&mut self, sender_email: GodotString) {
+        self.report_builder.sender_email = sender_email.into();
+    }
+
+    /// Sets priority of the report.
+    ///
+    /// ## Arguments
+    /// * `priority`: `0` - low, `1` - normal (default), `2` - high, `3` - critical.
+    #[func]
+    fn set_report_priority(&mut self, priority: i32) {
+        match ReportPriority::from_value(priority) {
+            Some(priority) => self.report_builder.report_priority = priority,
+            None => {
+                godot_error!("the specified report priority \"{}\" is unknown", priority);
+            }
+        }
+    }
+
+    /// Adds a custom key/value pair to the report (for example: current level,
+    /// player position or graphics settings), setting an existing key replaces its value.
+    ///
+    /// ## Arguments
+    /// * `key`: name of the value (see `MetadataKey` field limit).
+    /// * `value`: value to store, an empty value removes the key (see `MetadataValue` field limit).
+    ///
+    /// ## Remarks
+    /// Lengths are checked in `send_report`, the number of keys is limited
+    /// (an error is printed if the limit is reached).
+    #[func]
+    fn set_report_metadata(&mut self, key: GodotString, value: GodotString) {
+        if let Err(app_error) = self.report_builder.set_metadata(key.into(), value.into()) {
+            godot_error!("{}", app_error.get_message());
+        }
+    }
+
+    /// Removes all custom key/value pairs that were set using `set_report_metadata`.
+    #[func]
+    fn clear_report_metadata(&mut self) {
+        self.report_builder.report_metadata.clear();
+    }
+
+    /// Replaces all report attachments with the specified files.
+    #[func]
+    fn set_report_attachments(&mut self, attachments: Array<GodotString>) {
+        self.attachments.clear();
+
+        for path in attachments.iter_shared() {
+            self.attachments.push(path.into());
+        }
+    }
+
+    /// Adds a file to report attachments (does nothing if the file is already attached).
+    ///
+    /// ## Remarks
+    /// The file is checked when the report is sent (see `validate_report`).
+    #[func]
+    fn add_report_attachment(&mut self, path: GodotString) {
+        let path: String = path.into();
+
+        if !self.attachments.contains(&path) {
+            self.attachments.push(path);
+        }
+    }
+
+    /// Removes all report attachments (the screenshot is not removed,
+    /// see `set_clear_screenshot`).
+    #[func]
+    fn clear_report_attachments(&mut self) {
+        self.attachments.clear();
+    }
+
+    /// Adds files from the specified directory to report attachments.
+    ///
+    /// ## Remarks
+    /// Subdirectories (if not recursive) and files that can't be read are skipped
+    /// (a warning is logged). If the total size of attachments (including previously
+    /// added ones) exceeds the last received attachment size limit no files are added.
+    ///
+    /// ## Arguments
+    /// * `path`: path to the directory.
+    /// * `recursive`: whether to also add files from subdirectories or not.
+    ///
+    /// ## Return
+    /// Result code: `0` if the files were added, `4` if the directory does not exist,
+    /// `5` if the files are too big (see `get_last_error`).
+    #[func]
+    fn add_attachment_directory(&mut self, path: GodotString, recursive: bool) -> i32 {
+        let path: String = path.into();
+
+        let result = get_files_in_directory(Path::new(&path), recursive);
+        if let Err(e) = result {
+            godot_warn!("{}", e);
+            self.last_error = path;
+            return ReportResult::AttachmentDoesNotExist.value();
+        }
+        let (files, errors) = result.unwrap();
+
+        for error in errors {
+            godot_warn!("{}", error);
+        }
+
+        // Skip files that are already attached or can't be converted to string.
+        let mut new_paths: Vec<String> = Vec::new();
+        for file in files {
+            match file.to_str() {
+                Some(file_path) => {
+                    let file_path = file_path.to_string();
+                    if !self.attachments.contains(&file_path) {
+                        new_paths.push(file_path);
+                    }
+                }
+                None => {
+                    godot_warn!("{}", AppError::new("unable to convert path to string"));
+                }
+            }
+        }
+
+        // Check total size (without reading the files).
+        if let Some(max_size_in_bytes) = self
+            .max_attachments_size_in_mb
+            .and_then(|size_in_mb| size_in_mb.checked_mul(1024 * 1024))
+        {
+            let mut total_attachment_size_in_bytes: u64 = 0;
+            for attachment_path in self.attachments.iter().chain(new_paths.iter()) {
+                // Missing files are reported when the report is sent.
+                total_attachment_size_in_bytes +=
+                    get_attachment_size_in_bytes(attachment_path).unwrap_or(0);
+            }
+
+            if total_attachment_size_in_bytes > max_size_in_bytes as u64 {
+                self.last_error = self.localization.get_message(
+                    MessageKey::AttachmentDirectoryTooBig,
+                    &[
+                        &path,
+                        &self
+                            .max_attachments_size_in_mb
+                            .unwrap_or_default()
+                            .to_string(),
+                    ],
+                );
+                godot_warn!("{}", self.last_error);
+                return ReportResult::AttachmentTooBig.value();
+            }
+        }
+
+        self.attachments.append(&mut new_paths);
+
+        ReportResult::Ok.value()
+    }
+
+    /// Sets the directory where temporary files (like the screenshot) are stored.
+    ///
+    /// ## Remarks
+    /// By default a directory in the system's temp directory is used, on some platforms
+    /// it's not writable or can be cleared while the game is running.
+    ///
+    /// ## Arguments
+    /// * `path`: path to the directory (Godot paths like "user://reporter" are supported),
+    /// the directory is created if it does not exist, if the directory is not writable
+    /// an error is printed and the previous directory is used.
+    #[func]
+    fn set_temp_directory(&mut self, path: GodotString) {
+        let path = PathBuf::from(Into::<String>::into(
+            ProjectSettings::singleton().globalize_path(path),
+        ));
+
+        if let Err(app_error) = check_directory_is_writable(&path) {
+            godot_error!(
+                "the directory \"{}\" can't be used for temporary files: {}",
+                path.display(),
+                app_error
+            );
+            return;
+        }
+
+        self.temp_directory = Some(path);
+    }
+
+    #[func]
+    fn set_screenshot(&mut self, viewport_image: Gd<Image>) {
+        // Prepare screenshot path.
+        let mut screenshot_path_buf = self.get_temp_directory();
+
+        if let Err(e) = std::fs::create_dir_all(screenshot_path_buf.as_path()) {
+            godot_warn!(
+                "{}",
+                AppError::new(&format!(
+                    "failed to create the directory for the screenshot \"{}\" (error: {}), \
+                    use `set_temp_directory` to specify a writable directory",
+                    screenshot_path_buf.display(),
+                    e
+                ))
+            );
+            return;
+        }
+
+        // Use unique names so that a new screenshot does not overwrite the previous one
+        // while it's used.
+        let timestamp_in_ms = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        screenshot_path_buf.push(format!(
+            "screenshot_{}_{:08x}.jpg",
+            timestamp_in_ms,
+            rand::random::<u32>()
+        ));
+
+        let img = Self::convert_to_rgba_image(&viewport_image);
+
+        // Save image.
+        if let Err(e) = img.save(screenshot_path_buf.as_path()) {
+            godot_warn!(
+                "{}",
+                AppError::new(&format!(
+                    "failed to save the screenshot to \"{}\" (error: {}), \
+                    use `set_temp_directory` to specify a writable directory",
+                    screenshot_path_buf.display(),
+                    e
+                ))
+            );
+        } else {
+            let screenshot_path = screenshot_path_buf.as_path().to_str();
+            match screenshot_path {
+                Some(screenshot_path) => {
+                    self.replace_screenshot(Some(String::from(screenshot_path)));
+                }
+                None => {
+                    godot_warn!(
+                        "{}",
+                        AppError::new("unable to convert screenshot path to string")
+                    );
+                }
+            }
+        }
+    }
+
+    #[func]
+    fn set_clear_screenshot(&mut self) {
+        self.replace_screenshot(None);
+    }
+
+    /// Sends the report.
+    ///
+    /// ## Return
+    /// Value of `ReportResult` enum, zero if successful, otherwise error
+    /// (use `get_last_error` to get error description if needed).
+    #[func]
+    fn send_report(&mut self) -> i32 {
+        if self.remote_address.is_none() || self.report_receiver.is_none() {
+            return ReportResult::ServerNotSet.value();
+        }
+
+        // Construct report object (without sensitive data).
+        let (report, redaction_count) = self.report_builder.create_redacted_report();
+
+        // Check input length.
+        let invalid_field = self.report_builder.validate(&report);
+        if let Some(report_limit_error) = invalid_field {
+            self.last_error = report_limit_error.to_string();
+            return ReportResult::InvalidInput.value();
+        }
+
+        // Prepare logging.
+        let mut logger = LogManager::new();
+        logger.log(&format!(
+            "FBugReporter (reporter) (v{})",
+            env!("CARGO_PKG_VERSION"),
+        ));
+        logger.log(&format!(
+            "Redacted {} match(-es) of {} redaction pattern(-s).",
+            redaction_count,
+            self.report_builder.get_redaction_pattern_count()
+        ));
+        logger.log(&format!("Received a report: {:?}", report));
+
+        // Add screenshot as an attachment.
+        if let Some(screenshot_path) = &self.screenshot_path {
+            if !Path::new(&screenshot_path).exists() {
+                godot_warn!(
+                    "{}",
+                    AppError::new("previously saved screenshot no longer exists")
+                );
+            } else if !self.attachments.iter().any(|path| path == screenshot_path) {
+                self.attachments.push(screenshot_path.clone());
+            }
+        } else {
+            logger.log("No screenshot provided.");
+        }
+
+        // Process other attachments.
+        let mut report_attachments: Vec<ReportAttachment> = Vec::new();
+        if !self.attachments.is_empty() {
+            // Check that the specified paths exist.
+            for path in self.attachments.iter() {
+                if !Path::new(&path).exists() {
+                    return ReportResult::AttachmentDoesNotExist.value();
+                }
+            }
+
+            // Request max attachment size (in total) in MB.
+            let mut max_attachments_size_in_mb = std::usize::MAX;
+
+            let result = self
+                .report_receiver
+                .as_mut()
+                .unwrap()
+                .request_max_attachment_size_in_mb(
+                    self.remote_address.as_ref().unwrap().clone(),
+                    &self.report_builder.game_name,
+                    &mut logger,
+                );
+            if let Some(max_size_mb) = result {
+                max_attachments_size_in_mb = max_size_mb;
+                self.max_attachments_size_in_mb = Some(max_size_mb);
+
+                logger.log(&format!(
+                    "Received maximum allowed attachment size of {} MB.",
+                    max_attachments_size_in_mb
+                ));
+            }
+
+            // Generate attachments from paths.
+            let result = Self::generate_attachments_from_paths(
+                self.attachments.clone(),
+                max_attachments_size_in_mb,
+                &mut logger,
+            );
+            if let Err(msg) = result {
+                logger.log(&msg);
+                self.last_error = msg;
+                return ReportResult::Other(String::new()).value();
+            }
+            report_attachments = result.unwrap();
+
+            // Check if exceeded maximum size.
+            if report_attachments.is_empty() {
+                return ReportResult::AttachmentTooBig.value();
+            }
+        }
+
+        // Send report.
+        let result = self.report_receiver.as_mut().unwrap().send_report(
+            self.remote_address.as_ref().unwrap().clone(),
+            self.auth_token.clone(),
+            report.clone(),
+            &mut logger,
+            report_attachments,
+        );
+
+        // Delete the screenshot (if we took one).
+        if let Some(screenshot_path) = self.screenshot_path.take() {
+            if Path::new(&screenshot_path).exists() {
+                if let Err(e) = std::fs::remove_file(&screenshot_path) {
+                    logger.log(&format!(
+                        "failed to delete screenshot from \"{}\" (error: {})",
+                        &screenshot_path, e
+                    ));
+                }
+            }
+        }
+
+        // Clear the previous error (if existed).
+        self.last_error = String::new();
+
+        // Process result.
+        match result {
+            SendReportResult::Ok => {
+                self.last_report = Some(report);
+                logger.log("Successfully sent the report.");
+
+                ReportResult::Ok.value()
+            }
+            SendReportResult::CouldNotConnect => {
+                logger.log("Failed to connect to the server.");
+
+                ReportResult::CouldNotConnect.value()
+            }
+            SendReportResult::VersionRejected => {
+                logger.log(&format!(
+                    "The server does not accept reports from the game version \"{}\".",
+                    report.game_version
+                ));
+                self.last_error = self
+                    .localization
+                    .get_message(MessageKey::VersionRejected, &[]);
+
+                ReportResult::VersionRejected.value()
+            }
+            SendReportResult::Rejected(reason) => {
+                logger.log(&format!("The server rejected the report: {}.", reason));
+                self.last_error = reason;
+
+                ReportResult::ServerRejected.value()
+            }
+            SendReportResult::InvalidProofOfWork => {
+                logger.log("The server did not accept the proof of work.");
+
+                ReportResult::InvalidProofOfWork.value()
+            }
+            SendReportResult::RateLimited(retry_after_in_sec) => {
+                logger.log(&format!(
+                    "The server does not accept new reports from this sender for {} second(-s).",
+                    retry_after_in_sec
+                ));
+                self.last_error = self
+                    .localization
+                    .get_message(MessageKey::RateLimited, &[&retry_after_in_sec.to_string()]);
+
+                ReportResult::RateLimited.value()
+            }
+            SendReportResult::FieldTooLong(field) => {
+                logger.log(&format!(
+                    "The server says that the field \"{}\" is too long.",
+                    field
+                ));
+                self.last_error = field;
+
+                ReportResult::InvalidInput.value()
+            }
+            SendReportResult::FieldTooShort(field, min_length) => {
+                logger.log(&format!(
+                    "The server says that the field \"{}\" is too short (at least {} characters \
+                    are required).",
+                    field, min_length
+                ));
+                self.last_error = field;
+
+                ReportResult::InvalidInput.value()
+            }
+            SendReportResult::ServerError => {
+                logger.log("The server failed to process the report.");
+                self.last_error = self.localization.get_message(MessageKey::ServerError, &[]);
+
+                ReportResult::ServerError.value()
+            }
+            SendReportResult::ConnectionLost(message) => {
+                logger.log(&message);
+                self.last_error = message;
+
+                ReportResult::ConnectionLost.value()
+            }
+            SendReportResult::Other(message) => {
+                logger.log(&message);
+                self.last_error = message;
+
+                ReportResult::Other(String::new()).value()
+            }
+        }
+    }
+
+    /// Installs a crash handler that saves a report when the reporter's Rust code panics
+    /// (with a backtrace) or when the game does not stop properly (engine crash, killed
+    /// process, etc.), saved reports are sent using `send_crash_reports` on the next launch.
+    ///
+    /// ## Remarks
+    /// Crash reports use the game info, sender info, priority, session info and metadata
+    /// that were set during the last call of this function (on any reporter, the crash
+    /// handler is shared), call it again to update them. Crash reports don't have attachments.
+    #[func]
+    fn enable_crash_handler(&mut self) {
+        let report_template = self.report_builder.create_report();
+
+        if let Some(crash_handler) = &self.crash_handler {
+            if let Err(app_error) = crash_handler.set_report_template(report_template) {
+                godot_error!("{}", app_error.get_message());
+            }
+            return;
+        }
+
+        let spool_directory = PathBuf::from(Into::<String>::into(
+            ProjectSettings::singleton().globalize_path(CRASH_REPORT_DIRECTORY.into()),
+        ));
+        match CrashHandler::install(report_template, spool_directory) {
+            Ok(crash_handler) => self.crash_handler = Some(crash_handler),
+            Err(app_error) => {
+                godot_error!("{}", app_error.get_message());
+            }
+        }
+    }
+
+    /// Returns the number of crash reports that were saved by the crash handler
+    /// and were not sent yet (see `enable_crash_handler`).
+    #[func]
+    fn get_crash_report_count(&self) -> i32 {
+        match &self.crash_handler {
+            Some(crash_handler) => {
+                read_spooled_reports(crash_handler.get_spool_directory()).len() as i32
+            }
+            None => 0,
+        }
+    }
+
+    /// Sends crash reports that were saved by the crash handler (see `enable_crash_handler`).
+    ///
+    /// ## Remarks
+    /// Reports that could not be sent because of connection/server problems are kept
+    /// to be sent later, reports that were rejected (for example, because of the game
+    /// version) are removed.
+    ///
+    /// ## Return
+    /// Value of `ReportResult` enum, zero if all reports were sent (or there are no reports),
+    /// otherwise error (use `get_last_error` to get error description if needed).
+    #[func]
+    fn send_crash_reports(&mut self) -> i32 {
+        if self.remote_address.is_none() || self.report_receiver.is_none() {
+            return ReportResult::ServerNotSet.value();
+        }
+
+        let reports = match &self.crash_handler {
+            Some(crash_handler) => read_spooled_reports(crash_handler.get_spool_directory()),
+            None => return ReportResult::Ok.value(),
+        };
+        if reports.is_empty() {
+            return ReportResult::Ok.value();
+        }
+
+        let mut logger = LogManager::new();
+        logger.log(&format!(
+            "FBugReporter (reporter) (v{})",
+            env!("CARGO_PKG_VERSION"),
+        ));
+        logger.log(&format!("Sending {} crash report(-s).", reports.len()));
+
+        self.last_error = String::new();
+
+        for (path, report) in reports {
+            let (report, redaction_count) = self.report_builder.redact_report(report);
+            logger.log(&format!(
+                "Redacted {} match(-es) in the crash report \"{}\".",
+                redaction_count,
+                path.display()
+            ));
+
+            let result = self.report_receiver.as_mut().unwrap().send_report(
+                self.remote_address.as_ref().unwrap().clone(),
+                self.auth_token.clone(),
+                report,
+                &mut logger,
+                Vec::new(),
+            );
+
+            // Keep the report if it can be sent later.
+            let retry_result = match result {
+                SendReportResult::Ok => {
+                    logger.log("Successfully sent the crash report.");
+                    None
+                }
+                SendReportResult::CouldNotConnect => Some(ReportResult::CouldNotConnect),
+                SendReportResult::RateLimited(_) => Some(ReportResult::RateLimited),
+                SendReportResult::ServerError => Some(ReportResult::ServerError),
+                SendReportResult::InvalidProofOfWork => Some(ReportResult::InvalidProofOfWork),
+                SendReportResult::ConnectionLost(message) => {
+                    self.last_error = message;
+                    Some(ReportResult::ConnectionLost)
+                }
+                SendReportResult::VersionRejected
+                | SendReportResult::Rejected(_)
+                | SendReportResult::FieldTooLong(_)
+                | SendReportResult::FieldTooShort(_, _)
+                | SendReportResult::Other(_) => {
+                    logger.log("The crash report was not accepted, removing it.");
+                    None
+                }
+            };
+
+            if let Some(retry_result) = retry_result {
+                logger.log("Failed to send the crash report, it will be sent later.");
+                return retry_result.value();
+            }
+
+            if let Err(e) = std::fs::remove_file(&path) {
+                logger.log(&format!(
+                    "failed to remove the crash report \"{}\" (error: {})",
+                    path.display(),
+                    e
+                ));
+            }
+        }
+
+        ReportResult::Ok.value()
+    }
+
+    #[func]
+    fn get_log_file_path(&self) -> GodotString {
+        LogManager::get_log_file_path()
+            .to_str()
+            .unwrap()
+            .to_owned()
+            .into()
+    }
+
+    /// Checks the report and its attachments without sending anything
+    /// (useful to show validation errors in the report form).
+    ///
+    /// ## Return
+    /// Dictionary with the key `result` that contains a value of `ReportResult` enum
+    /// (zero if the report can be sent). If a field is too long, also contains `field` (name
+    /// of the field) and `limit` (maximum amount of characters). If an attachment does not exist
+    /// or attachments are too big, also contains `path` (path to the attachment), for big
+    /// attachments `limit` contains the maximum allowed size (in total) in megabytes.
+    ///
+    /// ## Remarks
+    /// The size of attachments is only checked if the maximum allowed size is known
+    /// from a previously sent report (the size limit is never requested here).
+    #[func]
+    fn validate_report(&self) -> Dictionary {
+        let mut result = Dictionary::new();
+
+        if self.remote_address.is_none() || self.report_receiver.is_none() {
+            result.insert("result", ReportResult::ServerNotSet.value());
+            return result;
+        }
+
+        // Check input length.
+        if let Some(invalid_field) = self
+            .report_builder
+            .validate(&self.report_builder.create_report())
+        {
+            result.insert("result", ReportResult::InvalidInput.value());
+            result.insert("field", invalid_field.to_string());
+            result.insert("limit", invalid_field.max_length() as i64);
+            result.insert(
+                "min_length",
+                self.report_builder.get_min_length(&invalid_field) as i64,
+            );
+            return result;
+        }
+
+        // Collect attachments (the screenshot is attached when the report is sent).
+        let mut paths = self.attachments.clone();
+        if let Some(screenshot_path) = &self.screenshot_path {
+            if Path::new(&screenshot_path).exists() && !paths.contains(screenshot_path) {
+                paths.push(screenshot_path.clone());
+            }
+        }
+
+        // Check attachments.
+        let max_attachments_size_in_bytes = self
+            .max_attachments_size_in_mb
+            .and_then(|size_in_mb| size_in_mb.checked_mul(1024 * 1024));
+        let mut total_attachment_size_in_bytes: u64 = 0;
+        for path in paths {
+            let attachment_size = get_attachment_size_in_bytes(&path);
+            if attachment_size.is_err() {
+                result.insert("result", ReportResult::AttachmentDoesNotExist.value());
+                result.insert("path", path);
+                return result;
+            }
+            total_attachment_size_in_bytes += attachment_size.unwrap();
+
+            if let Some(max_size_in_bytes) = max_attachments_size_in_bytes {
+                if total_attachment_size_in_bytes > max_size_in_bytes as u64 {
+                    result.insert("result", ReportResult::AttachmentTooBig.value());
+                    result.insert("path", path);
+                    result.insert(
+                        "limit",
+                        self.max_attachments_size_in_mb.unwrap_or_default() as i64,
+                    );
+                    return result;
+                }
+            }
+        }
+
+        result.insert("result", ReportResult::Ok.value());
+        result
+    }
+
+    /// Asks the report receiver for its network protocol version and application version
+    /// to check (before sending reports) if this reporter is compatible with it.
+    ///
+    /// ## Return
+    /// Dictionary with `result` code (`0` if the versions were received, `1` if the report
+    /// receiver is not set, `6` if the request failed, see `get_last_error`), if the versions
+    /// were received the dictionary also contains `net_protocol`, `server_version`
+    /// and `is_compatible` (`false` if the reporter or the server needs an update).
+    #[func]
+    fn query_server_info(&mut self) -> Dictionary {
+        let mut result = Dictionary::new();
+
+        if self.remote_address.is_none() || self.report_receiver.is_none() {
+            result.insert("result", ReportResult::ServerNotSet.value());
+            return result;
+        }
+
+        let mut logger = LogManager::new();
+        let server_info = self
+            .report_receiver
+            .as_mut()
+            .unwrap()
+            .request_server_info(self.remote_address.as_ref().unwrap().clone(), &mut logger);
+        if server_info.is_none() {
+            self.last_error = self
+                .localization
+                .get_message(MessageKey::ServerInfoFailed, &[]);
+            result.insert("result", ReportResult::Other(String::new()).value());
+            return result;
+        }
+        let server_info = server_info.unwrap();
+
+        result.insert("result", ReportResult::Ok.value());
+        result.insert("net_protocol", server_info.net_protocol as i64);
+        result.insert("server_version", server_info.server_version);
+        result.insert(
+            "is_compatible",
+            server_info.net_protocol == NETWORK_PROTOCOL_VERSION,
+        );
+        result
+    }
+
+    /// Returns description of the last error (see `set_locale`).
+    #[func]
+    fn get_last_error(&self) -> GodotString {
+        self.last_error.clone().into()
+    }
+
+    /// Sets the language of the messages returned by `get_last_error`
+    /// (result codes don't depend on the locale).
+    ///
+    /// ## Remarks
+    /// Messages without a translation (see `add_translation`) are returned in English.
+    ///
+    /// ## Arguments
+    /// * `locale`: locale code, for example: "de" or "pt_BR" (if there is no translation
+    /// for "pt_BR" the translation for "pt" is used).
+    #[func]
+    fn set_locale(&mut self, locale: GodotString) {
+        self.localization.set_locale(&Into::<String>::into(locale));
+    }
+
+    /// Adds a translation of a message returned by `get_last_error`.
+    ///
+    /// ## Arguments
+    /// * `locale`: locale of the translation (see `set_locale`).
+    /// * `key`: name of the message, see `src/localization.rs` enum `MessageKey`
+    /// for all messages.
+    /// * `text`: translated text, arguments of the message (like `{0}`) are replaced
+    /// in the same way as in the English text.
+    #[func]
+    fn add_translation(&mut self, locale: GodotString, key: GodotString, text: GodotString) {
+        let key = Into::<String>::into(key);
+
+        match MessageKey::from_str(&key) {
+            Ok(message_key) => self.localization.add_translation(
+                &Into::<String>::into(locale),
+                message_key,
+                &Into::<String>::into(text),
+            ),
+            Err(_) => {
+                godot_error!("unknown message key \"{}\"", key);
+            }
+        }
+    }
+
+    /// Returns the maximum allowed length of a report field.
+    #[func]
+    fn get_field_limit(&self, field_name: GodotString) -> i32 {
+        let name: String = field_name.into();
+
+        let result = ReportLimits::from_string(name.as_str());
+        if result.is_none() {
+            godot_error!("the specified report field name \"{}\" is unknown", name);
+            return 0;
+        }
+
+        result.unwrap().max_length() as i32
+    }
+
+    /// Returns N most recently modified files from the specified directory
+    /// (sorted from the most recently modified file to the oldest one).
+    ///
+    /// ## Remarks
+    /// Subdirectories are ignored. If the directory has less than `file_count` files
+    /// all files are returned, if `file_count` is not positive an empty array is returned.
+    #[func]
+    fn get_last_modified_files(&self, path: GodotString, file_count: i32) -> Array<GodotString> {
+        let result = get_files_sorted_by_modification_time(Path::new(&Into::<String>::into(path)));
+        if let Err(ref e) = result {
+            godot_warn!("{}", e);
+            return Array::new();
+        }
+        let (files, errors) = result.unwrap();
+
+        for error in errors {
+            godot_warn!("{}", error);
+        }
+
+        // Collect the output array.
+        let mut out_paths: Array<GodotString> = Array::new();
+        for file in files.iter().take(file_count.max(0) as usize) {
+            match file.to_str() {
+                Some(path) => out_paths.push(path.into()),
+                None => {
+                    godot_warn!("{}", AppError::new("unable to convert path to string"));
+                }
+            }
+        }
+
+        out_paths
+    }
+
+    /// Converts Godot image to an RGBA image.
+    ///
+    /// ## Remarks
+    /// 8-bit RGB/RGBA images (viewport images use one of these formats) are converted
+    /// by copying all pixel data at once, other formats are converted pixel by pixel.
+    fn convert_to_rgba_image(viewport_image: &Gd<Image>) -> RgbaImage {
+        let width = viewport_image.get_width() as u32;
+        let height = viewport_image.get_height() as u32;
+        let format = viewport_image.get_format();
+
+        if format == Format::FORMAT_RGBA8 || format == Format::FORMAT_RGB8 {
+            let data = viewport_image.get_data().to_vec();
+
+            let img = if format == Format::FORMAT_RGBA8 {
+                RgbaImage::from_raw(width, height, data)
+            } else {
+                RgbImage::from_raw(width, height, data)
+                    .map(|rgb_image| DynamicImage::ImageRgb8(rgb_image).into_rgba8())
+            };
+
+            // `None` if the buffer is smaller than expected (should not happen).
+            if let Some(img) = img {
+                return img;
+            }
+        }
+
+        let mut img: RgbaImage = ImageBuffer::new(width, height);
+
+        // Write pixels from viewport image.
+        for row in 0..viewport_image.get_height() {
+            for column in 0..viewport_image.get_width() {
+                let color: Color = viewport_image.get_pixel(column, row);
+                let new_pixel = image::Rgba([
+                    (color.r * 255.0) as u8,
+                    (color.g * 255.0) as u8,
+                    (color.b * 255.0) as u8,
+                    (color.a * 255.0) as u8,
+                ]);
+
+                img.put_pixel(column as u32, row as u32, new_pixel);
+            }
+        }
+
+        img
+    }
+
+    /// Returns the directory for temporary files (see `set_temp_directory`).
+    fn get_temp_directory(&self) -> PathBuf {
+        if let Some(temp_directory) = &self.temp_directory {
+            return temp_directory.clone();
+        }
+
+        let mut temp_directory = env::temp_dir();
+        temp_directory.push("FBugReporter");
+        temp_directory.push("reporter");
+
+        temp_directory
+    }
+
+    /// Sets the new screenshot and removes the previous one (if existed).
+    fn replace_screenshot(&mut self, screenshot_path: Option<String>) {
+        let previous_path = std::mem::replace(&mut self.screenshot_path, screenshot_path);
+        if let Some(previous_path) = previous_path {
+            // The screenshot is added to attachments when the report is sent.
+            self.attachments.retain(|path| path != &previous_path);
+
+            // The file might be already removed.
+            let _ = std::fs::remove_file(previous_path);
+        }
+    }
+
+    /// Converts paths to files to report attachments.
+    /// Expects file paths to be valid and exist.
+    ///
+    /// ## Return
+    /// `Ok` with empty array if attachments in total exceed the specified size,
+    /// otherwise array with processed attachments.
+    /// `Err` with error message if an internal error occurred.
+    fn generate_attachments_from_paths(
+        paths: Vec<String>,
+        max_attachments_size_in_mb: usize,
+        logger: &mut LogManager,
+    ) -> Result<Vec<ReportAttachment>, String> {
+        // Calculate maximum attachment size in bytes.
+        let mut max_attachments_size_in_bytes = std::usize::MAX;
+        let result = max_attachments_size_in_mb.checked_mul(1024 * 1024);
+        if let Some(size) = result {
+            max_attachments_size_in_bytes = size;
+        }
+
+        // Check the size before reading the files to not load huge files into memory.
+        let mut expected_size_in_bytes: u64 = 0;
+        for path in paths.iter() {
+            let attachment_size = get_attachment_size_in_bytes(path);
+            if let Err(e) = attachment_size {
+                return Err(format!(
+                    "failed to get the file size (error: {}), path: {}",
+                    e, path
+                ));
+            }
+            expected_size_in_bytes += attachment_size.unwrap();
+
+            if expected_size_in_bytes > max_attachments_size_in_bytes as u64 {
+                return Ok(Vec::new());
+            }
+        }
+
+        let mut attachments: Vec<ReportAttachment> = Vec::new();
+        let mut total_attachment_size_in_bytes: usize = 0;
+        for path in paths {
+            let file_path = Path::new(&path);
+
+            // Check file name.
+            let file_name = file_path.file_name();
+            if file_name.is_none() {
+                return Err(format!("file name is empty, path: {}", path));
+            }
+            let file_name = file_name.unwrap().to_str();
+            if file_name.is_none() {
+                return Err(format!("failed to get file name, path: {}", path));
+            }
+            let file_name = String::from(file_name.unwrap());
+            total_attachment_size_in_bytes += file_name.len();
+
+            logger.log(&format!("Processing report attachment {}...", file_name,));
+
+            // Read file into vec.
+            let mut data: Vec<u8> = Vec::new();
+
+            let file = File::open(path.clone());
+            if let Err(e) = file {
+                return Err(format!(
+                    "failed to open the file (error: {}), path: {}",
+                    e, path
+                ));
+            }
+            let mut file = file.unwrap();
+            let result = file.read_to_end(&mut data);
+            if let Err(e) = result {
+                return Err(format!(
+                    "failed to read the file (error: {}), path: {}",
+                    e, path
+                ));
+            }
+            let file_size = result.unwrap();
+            total_attachment_size_in_bytes += file_size;
+
+            logger.log(&format!(
+                "Processed report attachment {} of size {} bytes.",
+                file_name, file_size
+            ));
+
+            let attachment = ReportAttachment { file_name, data };
+            attachments.push(attachment);
+        }
+
+        // Files could have changed since the check above.
+        if total_attachment_size_in_bytes > max_attachments_size_in_bytes {
+            return Ok(Vec::new());
+        }
+
+        Ok(attachments)
+    }
+}
@@ -0,0 +1,159 @@
+// Std.
+use std::collections::HashMap;
+
+// External.
+use strum_macros::{Display, EnumString};
+
+/// Locale of the built-in messages.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Human-readable messages that can be returned by `get_last_error`.
+///
+/// ## Remarks
+/// Names of the entries are used by games to register translations so they never change.
+/// Messages can have arguments: `{0}`, `{1}` and so on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumString, Display)]
+pub enum MessageKey {
+    /// The server does not accept reports from this game version.
+    VersionRejected,
+    /// The sender sent a report recently, `{0}` is the number of seconds to wait.
+    RateLimited,
+    /// The server failed to process the report.
+    ServerError,
+    /// Failed to receive the network protocol version and the application version
+    /// of the report receiver.
+    ServerInfoFailed,
+    /// Files from a directory exceed the attachment size limit, `{0}` is the directory
+    /// and `{1}` is the limit in megabytes.
+    AttachmentDirectoryTooBig,
+}
+
+impl MessageKey {
+    /// Returns the English text of the message.
+    pub fn default_text(&self) -> &'static str {
+        match self {
+            MessageKey::VersionRejected => {
+                "reports from this game version are no longer accepted, please, update your game"
+            }
+            MessageKey::RateLimited => {
+                "you have recently sent a report, please, try again in {0} second(-s)"
+            }
+            MessageKey::ServerError => {
+                "the server failed to process the report, please, try again later"
+            }
+            MessageKey::ServerInfoFailed => {
+                "failed to query server info (old servers do not support this request), \
+                see the log file for details"
+            }
+            MessageKey::AttachmentDirectoryTooBig => {
+                "files from the directory \"{0}\" exceed the attachment size limit ({1} MB)"
+            }
+        }
+    }
+}
+
+/// Stores translations of messages that are returned by `get_last_error`.
+pub struct Localization {
+    /// Locale of the messages (for example: "de" or "pt_BR").
+    locale: String,
+    /// Translations registered by the game (locale - key - text).
+    translations: HashMap<String, HashMap<MessageKey, String>>,
+}
+
+impl Localization {
+    /// Creates localization that uses English messages.
+    pub fn new() -> Self {
+        Self {
+            locale: String::from(DEFAULT_LOCALE),
+            translations: HashMap::new(),
+        }
+    }
+    /// Sets the locale of the messages.
+    ///
+    /// ## Remarks
+    /// If there is no translation for "LANGUAGE_REGION" locale (for example: "pt_BR")
+    /// the translation for "LANGUAGE" (for example: "pt") is used, then English.
+    pub fn set_locale(&mut self, locale: &str) {
+        self.locale = locale.to_string();
+    }
+    /// Adds (or replaces) a translation of the message.
+    ///
+    /// ## Arguments
+    /// * `locale`: locale of the translation.
+    /// * `key`: message to translate.
+    /// * `text`: translated text (can use the same arguments as the English text).
+    pub fn add_translation(&mut self, locale: &str, key: MessageKey, text: &str) {
+        self.translations
+            .entry(locale.to_string())
+            .or_default()
+            .insert(key, text.to_string());
+    }
+    /// Returns the message in the current locale (English if there is no translation).
+    ///
+    /// ## Arguments
+    /// * `key`: message to return.
+    /// * `args`: values that replace `{0}`, `{1}` and so on.
+    pub fn get_message(&self, key: MessageKey, args: &[&str]) -> String {
+        let language = self.locale.split(['_', '-']).next().unwrap_or(&self.locale);
+
+        let mut text = [self.locale.as_str(), language]
+            .iter()
+            .find_map(|locale| self.translations.get(*locale)?.get(&key))
+            .map(|text| text.as_str())
+            .unwrap_or_else(|| key.default_text())
+            .to_string();
+
+        for (i, arg) in args.iter().enumerate() {
+            text = text.replace(&format!("{{{}}}", i), arg);
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_by_default() {
+        let localization = Localization::new();
+
+        assert_eq!(
+            localization.get_message(MessageKey::RateLimited, &["30"]),
+            "you have recently sent a report, please, try again in 30 second(-s)"
+        );
+    }
+
+    #[test]
+    fn uses_translation_of_current_locale() {
+        let mut localization = Localization::new();
+        localization.add_translation(
+            "de",
+            MessageKey::RateLimited,
+            "bitte versuchen Sie es in {0} Sekunden erneut",
+        );
+        localization.set_locale("de");
+
+        assert_eq!(
+            localization.get_message(MessageKey::RateLimited, &["30"]),
+            "bitte versuchen Sie es in 30 Sekunden erneut"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_language_then_english() {
+        let mut localization = Localization::new();
+        localization.add_translation("pt", MessageKey::ServerError, "erro do servidor");
+        localization.set_locale("pt_BR");
+
+        assert_eq!(
+            localization.get_message(MessageKey::ServerError, &[]),
+            "erro do servidor"
+        );
+        assert_eq!(
+            localization.get_message(MessageKey::VersionRejected, &[]),
+            MessageKey::VersionRejected.default_text()
+        );
+    }
+}
@@ -1,26 +1,43 @@
 // Std.
+#[cfg(not(target_arch = "wasm32"))]
 use std::fs::{File, *};
+#[cfg(not(target_arch = "wasm32"))]
 use std::io::prelude::*;
+#[cfg(not(target_arch = "wasm32"))]
 use std::path::PathBuf;
 
 // External.
 use chrono::Local;
+#[cfg(not(target_arch = "wasm32"))]
 use platform_dirs::UserDirs;
 
+#[cfg(not(target_arch = "wasm32"))]
 const LOG_FILE_NAME: &str = "reporter.log";
+#[cfg(not(target_arch = "wasm32"))]
 const LOG_FILE_DIR: &str = "FBugReporter";
 
+/// Writes logs to a file (on wasm32 logs are written to the browser console).
 pub struct LogManager {
+    #[cfg(not(target_arch = "wasm32"))]
     log_file_path: PathBuf,
 }
 
 impl LogManager {
     /// Removes old log file and creates an empty one.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[allow(clippy::new_without_default)] // creating a logger recreates the log file
     pub fn new() -> Self {
         Self {
             log_file_path: LogManager::recreate_log_file(),
         }
     }
+    /// Creates a logger that writes to the browser console.
+    #[cfg(target_arch = "wasm32")]
+    #[allow(clippy::new_without_default)] // same API as on other platforms
+    pub fn new() -> Self {
+        Self {}
+    }
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn get_log_file_path() -> PathBuf {
         #[cfg(any(windows, unix))]
         {
@@ -51,6 +68,7 @@ impl LogManager {
             compile_error!("Reporter is not implemented for this OS.");
         }
     }
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn log(&self, text: &str) {
         let mut log_file = self.open_log_file();
 
@@ -60,6 +78,15 @@ impl LogManager {
             panic!("An error occurred at [{}, {}]: {:?}", file!(), line!(), e);
         }
     }
+    #[cfg(target_arch = "wasm32")]
+    pub fn log(&self, text: &str) {
+        let datetime = Local::now();
+
+        web_sys::console::log_1(
+            &format!("[{}]: FBugReporter: {}", datetime.naive_local(), text).into(),
+        );
+    }
+    #[cfg(not(target_arch = "wasm32"))]
     fn open_log_file(&self) -> std::fs::File {
         let log_file = OpenOptions::new()
             .write(true)
@@ -72,6 +99,7 @@ impl LogManager {
         log_file.unwrap()
     }
     /// Returns new log file path.
+    #[cfg(not(target_arch = "wasm32"))]
     fn recreate_log_file() -> PathBuf {
         let log_path = LogManager::get_log_file_path();
 
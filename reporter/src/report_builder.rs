@@ -0,0 +1,376 @@
+// Std.
+use std::collections::BTreeMap;
+
+// External.
+use regex::{NoExpand, Regex};
+
+// Custom.
+use shared::misc::{error::AppError, report::*};
+
+/// Text that replaces matches of redaction patterns.
+const REDACTED_TEXT: &str = "[redacted]";
+
+/// Collects report information and creates reports from it.
+///
+/// ## Remarks
+/// Does not use Godot or the file system so it can also be used in web builds.
+pub struct ReportBuilder {
+    pub report_name: String,
+    pub report_text: String,
+    pub sender_name: String,
+    pub sender_email: String,
+    pub game_name: String,
+    pub game_version: String,
+    pub report_priority: ReportPriority,
+    /// Use `set_metadata` to add entries (it checks the number of entries).
+    pub report_metadata: BTreeMap<String, String>,
+    pub min_report_text_length: usize,
+    /// Whether the report text is written in Markdown or not.
+    pub is_markdown: bool,
+    /// ID of the game session (empty if not specified).
+    pub session_id: String,
+    /// How long the player played before sending the report (`0` if not specified).
+    pub playtime_seconds: u64,
+    /// Whether to treat report text that is equal to the template as empty or not.
+    pub reject_unmodified_template: bool,
+    /// Whether to apply redaction patterns to the sender name or not.
+    pub redact_sender_name: bool,
+    /// Whether to send reports without sender name, sender e-mail and OS info or not.
+    pub anonymous: bool,
+    /// Matches of these patterns are removed from the report before it's sent.
+    redaction_patterns: Vec<Regex>,
+    /// Text that is used as the initial report text (use `set_report_template` to change).
+    report_template: String,
+}
+
+impl Default for ReportBuilder {
+    fn default() -> Self {
+        Self {
+            report_name: String::new(),
+            report_text: String::new(),
+            sender_name: String::new(),
+            sender_email: String::new(),
+            game_name: String::new(),
+            game_version: String::new(),
+            report_priority: ReportPriority::default(),
+            report_metadata: BTreeMap::new(),
+            min_report_text_length: ReportLimits::ReportText.default_min_length(),
+            is_markdown: false,
+            session_id: String::new(),
+            playtime_seconds: 0,
+            reject_unmodified_template: false,
+            redact_sender_name: false,
+            anonymous: false,
+            redaction_patterns: Vec::new(),
+            report_template: String::new(),
+        }
+    }
+}
+
+impl ReportBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sets a custom key/value pair, setting an existing key replaces its value.
+    ///
+    /// ## Arguments
+    /// * `key`: name of the value.
+    /// * `value`: value to store, an empty value removes the key.
+    ///
+    /// ## Return
+    /// Error if the report already has the maximum number of metadata entries.
+    pub fn set_metadata(&mut self, key: String, value: String) -> Result<(), AppError> {
+        if value.is_empty() {
+            self.report_metadata.remove(&key);
+            return Ok(());
+        }
+
+        if !self.report_metadata.contains_key(&key)
+            && self.report_metadata.len() >= REPORT_METADATA_MAX_ENTRY_COUNT
+        {
+            return Err(AppError::new(&format!(
+                "unable to add metadata \"{}\" because the report already has the maximum \
+                number of metadata entries ({})",
+                key, REPORT_METADATA_MAX_ENTRY_COUNT
+            )));
+        }
+
+        self.report_metadata.insert(key, value);
+
+        Ok(())
+    }
+    /// Adds a regular expression, matches of which will be replaced with "[redacted]"
+    /// in the report text (and optionally in the sender name).
+    ///
+    /// ## Return
+    /// Error if the pattern is not a valid regular expression.
+    pub fn add_redaction_pattern(&mut self, pattern: &str) -> Result<(), AppError> {
+        match Regex::new(pattern) {
+            Ok(regex) => {
+                self.redaction_patterns.push(regex);
+                Ok(())
+            }
+            Err(e) => Err(AppError::new(&format!(
+                "invalid redaction pattern \"{}\": {}",
+                pattern, e
+            ))),
+        }
+    }
+    /// Removes all patterns that were added using `add_redaction_pattern`.
+    pub fn clear_redaction_patterns(&mut self) {
+        self.redaction_patterns.clear();
+    }
+    /// Returns the number of patterns that were added using `add_redaction_pattern`.
+    pub fn get_redaction_pattern_count(&self) -> usize {
+        self.redaction_patterns.len()
+    }
+    /// Sets the template of the report text (for example, "Steps to reproduce:\n\nExpected:\n").
+    ///
+    /// ## Remarks
+    /// If the report text is empty (or is still equal to the previous template)
+    /// the report text is replaced with the new template.
+    pub fn set_report_template(&mut self, template: String) {
+        if self.report_text.is_empty() || !self.is_report_text_modified() {
+            self.report_text = template.clone();
+        }
+
+        self.report_template = template;
+    }
+    /// Returns the template that was set using `set_report_template`.
+    pub fn get_report_template(&self) -> &str {
+        &self.report_template
+    }
+    /// Tells if the report text differs from the template
+    /// (whitespace at the beginning and at the end is ignored).
+    pub fn is_report_text_modified(&self) -> bool {
+        self.report_text.trim() != self.report_template.trim()
+    }
+    /// Creates a report from the information that was set.
+    pub fn create_report(&self) -> GameReport {
+        // Don't collect personal data in anonymous mode.
+        let (sender_name, sender_email, client_os_info) = if self.anonymous {
+            (String::new(), String::new(), os_info::Info::unknown())
+        } else {
+            (
+                self.sender_name.clone(),
+                self.sender_email.clone(),
+                os_info::get(),
+            )
+        };
+
+        GameReport {
+            report_name: self.report_name.clone(),
+            report_text: self.report_text.clone(),
+            sender_name,
+            sender_email,
+            game_name: self.game_name.clone(),
+            game_version: self.game_version.clone(),
+            client_os_info,
+            priority: self.report_priority,
+            metadata: self.report_metadata.clone(),
+            reporter_version: String::from(env!("CARGO_PKG_VERSION")),
+            is_markdown: self.is_markdown,
+            session_id: self.session_id.clone(),
+            playtime_seconds: self.playtime_seconds,
+        }
+    }
+    /// Creates a report from the information that was set and removes matches
+    /// of redaction patterns from it.
+    ///
+    /// ## Return
+    /// Report to send and the number of replaced matches.
+    pub fn create_redacted_report(&self) -> (GameReport, usize) {
+        self.redact_report(self.create_report())
+    }
+    /// Removes matches of redaction patterns from the report
+    /// (for example, from a report that was created by the crash handler).
+    ///
+    /// ## Return
+    /// Report without matches and the number of replaced matches.
+    pub fn redact_report(&self, mut report: GameReport) -> (GameReport, usize) {
+        let (report_text, mut redaction_count) =
+            redact_text(&report.report_text, &self.redaction_patterns);
+        report.report_text = report_text;
+        if self.redact_sender_name {
+            let (sender_name, count) = redact_text(&report.sender_name, &self.redaction_patterns);
+            report.sender_name = sender_name;
+            redaction_count += count;
+        }
+
+        (report, redaction_count)
+    }
+    /// Returns the minimum amount of characters required for the field.
+    pub fn get_min_length(&self, field: &ReportLimits) -> usize {
+        match field {
+            ReportLimits::ReportText => self.min_report_text_length,
+            _ => field.default_min_length(),
+        }
+    }
+    /// Checks lengths of the report fields (and that the report text is not an unmodified
+    /// template if `reject_unmodified_template` is enabled).
+    ///
+    /// ## Return
+    /// `None` if the report is valid, otherwise the id of the invalid field.
+    pub fn validate(&self, report: &GameReport) -> Option<ReportLimits> {
+        if report.report_name.chars().count() > ReportLimits::ReportName.max_length() {
+            return Some(ReportLimits::ReportName);
+        }
+
+        if report.report_text.chars().count() > ReportLimits::ReportText.max_length()
+            || report.report_text.chars().count() < self.min_report_text_length
+        {
+            return Some(ReportLimits::ReportText);
+        }
+
+        // The user did not describe the problem.
+        if self.reject_unmodified_template
+            && !self.report_template.trim().is_empty()
+            && report.report_text.trim() == self.report_template.trim()
+        {
+            return Some(ReportLimits::ReportText);
+        }
+
+        if report.sender_name.chars().count() > ReportLimits::SenderName.max_length() {
+            return Some(ReportLimits::SenderName);
+        }
+
+        if report.sender_email.chars().count() > ReportLimits::SenderEmail.max_length() {
+            return Some(ReportLimits::SenderEmail);
+        }
+
+        if report.game_name.chars().count() > ReportLimits::GameName.max_length() {
+            return Some(ReportLimits::GameName);
+        }
+
+        if report.game_version.chars().count() > ReportLimits::GameVersion.max_length() {
+            return Some(ReportLimits::GameVersion);
+        }
+
+        for (key, value) in report.metadata.iter() {
+            if key.chars().count() > ReportLimits::MetadataKey.max_length() {
+                return Some(ReportLimits::MetadataKey);
+            }
+
+            if value.chars().count() > ReportLimits::MetadataValue.max_length() {
+                return Some(ReportLimits::MetadataValue);
+            }
+        }
+
+        if report.session_id.chars().count() > ReportLimits::SessionId.max_length() {
+            return Some(ReportLimits::SessionId);
+        }
+
+        None
+    }
+}
+
+/// Replaces all matches of the specified patterns with `REDACTED_TEXT`.
+///
+/// ## Return
+/// Text without matches and the number of replaced matches.
+fn redact_text(text: &str, patterns: &[Regex]) -> (String, usize) {
+    let mut text = text.to_string();
+    let mut redaction_count = 0;
+
+    for pattern in patterns {
+        let match_count = pattern.find_iter(&text).count();
+        if match_count == 0 {
+            continue;
+        }
+
+        redaction_count += match_count;
+        text = pattern
+            .replace_all(&text, NoExpand(REDACTED_TEXT))
+            .into_owned();
+    }
+
+    (text, redaction_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redact_text_replaces_all_matches() {
+        let patterns = vec![
+            Regex::new(r"[\w.]+@[\w.]+").unwrap(),
+            Regex::new(r"C:\\Users\\\w+").unwrap(),
+        ];
+
+        let (text, count) = redact_text(
+            "mail me at a@b.com or c@d.org, saves are in C:\\Users\\john\\saves",
+            &patterns,
+        );
+        assert_eq!(
+            text,
+            "mail me at [redacted] or [redacted], saves are in [redacted]\\saves"
+        );
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn template_is_only_used_for_empty_or_unmodified_text() {
+        let mut builder = ReportBuilder::new();
+
+        builder.set_report_template(String::from("Steps to reproduce:\n"));
+        assert_eq!(builder.report_text, "Steps to reproduce:\n");
+        assert!(!builder.is_report_text_modified());
+
+        // Unmodified text is replaced with the new template.
+        builder.set_report_template(String::from("Expected:\n"));
+        assert_eq!(builder.report_text, "Expected:\n");
+
+        builder.report_text = String::from("Expected: no crash");
+        assert!(builder.is_report_text_modified());
+
+        builder.set_report_template(String::from("Actual:\n"));
+        assert_eq!(builder.report_text, "Expected: no crash");
+        assert_eq!(builder.get_report_template(), "Actual:\n");
+    }
+
+    #[test]
+    fn unmodified_template_is_rejected_only_if_enabled() {
+        let mut builder = ReportBuilder::new();
+        builder.set_report_template(String::from("Steps to reproduce:\n"));
+        builder.report_text = String::from(" Steps to reproduce: ");
+
+        assert!(builder.validate(&builder.create_report()).is_none());
+
+        builder.reject_unmodified_template = true;
+        assert!(matches!(
+            builder.validate(&builder.create_report()),
+            Some(ReportLimits::ReportText)
+        ));
+
+        builder.report_text = String::from("Steps to reproduce: open the map");
+        assert!(builder.validate(&builder.create_report()).is_none());
+    }
+
+    #[test]
+    fn session_info_is_sent_and_validated() {
+        let mut builder = ReportBuilder::new();
+        builder.session_id = String::from("a1b2c3");
+        builder.playtime_seconds = 4000;
+
+        let report = builder.create_report();
+        assert_eq!(report.session_id, "a1b2c3");
+        assert_eq!(report.playtime_seconds, 4000);
+        assert!(builder.validate(&report).is_none());
+
+        builder.session_id = "a".repeat(ReportLimits::SessionId.max_length() + 1);
+        assert!(matches!(
+            builder.validate(&builder.create_report()),
+            Some(ReportLimits::SessionId)
+        ));
+    }
+
+    #[test]
+    fn redact_text_without_matches() {
+        let patterns = vec![Regex::new(r"\d{4}-\d{4}").unwrap()];
+
+        let (text, count) = redact_text("nothing to hide ($1)", &patterns);
+        assert_eq!(text, "nothing to hide ($1)");
+        assert_eq!(count, 0);
+    }
+}
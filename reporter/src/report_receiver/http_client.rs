@@ -0,0 +1,305 @@
+// Std.
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
+// External.
+use serde_json::Value;
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::{JsCast, JsValue};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_futures::JsFuture;
+
+// Custom.
+use super::SendReportResult;
+#[cfg(not(target_arch = "wasm32"))]
+use super::{ReportReceiver, ServerInfo, DEFAULT_CONNECT_TIMEOUT_IN_SEC};
+use crate::log_manager::LogManager;
+use shared::misc::error::AppError;
+use shared::misc::report::*;
+
+/// POST request that an HTTP report receiver sends.
+pub struct HttpRequest {
+    /// What is sent (used in logs, should not contain secrets like tokens).
+    pub description: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// Reason why an HTTP request failed.
+pub enum HttpError {
+    /// Failed to connect to the remote entity, contains error description.
+    CouldNotConnect(String),
+    /// The remote entity answered with a status code that is not 2XX,
+    /// contains the status code and the body of the answer.
+    Status(u16, String),
+    /// The request failed for another reason (for example, a timeout), contains error description.
+    Other(String),
+}
+
+/// Report receiver that sends reports using HTTP requests.
+///
+/// ## Remarks
+/// Receivers only describe requests so that the same code is used with `ureq`
+/// on desktop platforms and with `fetch` in web builds (see `send_report_over_http`).
+pub trait HttpReportReceiver {
+    /// Returns maximum allowed size of attachments (in total) in megabytes
+    /// (`None` if not limited or attachments are not sent).
+    fn get_max_attachment_size_in_mb(&self) -> Option<usize>;
+
+    /// Returns maximum time to wait for an answer (after the connection was established).
+    ///
+    /// ## Remarks
+    /// Not used in web builds, the browser decides when to stop waiting.
+    fn get_request_timeout_in_sec(&self) -> u64;
+
+    /// Creates requests that send the report.
+    ///
+    /// ## Arguments
+    /// * `remote_address` string that describes remote entity's address (depends on the receiver).
+    /// * `report` report to send.
+    /// * `attachments` report attachments.
+    ///
+    /// ## Return
+    /// Requests to send one after another (sending stops at the first failed request)
+    /// or an error if the remote address is invalid.
+    fn create_requests(
+        &self,
+        remote_address: &str,
+        report: &GameReport,
+        attachments: &[ReportAttachment],
+    ) -> Result<Vec<HttpRequest>, AppError>;
+
+    /// Converts a failed request to the result of `send_report`.
+    fn handle_error(&self, error: HttpError, logger: &mut LogManager) -> SendReportResult;
+}
+
+impl HttpRequest {
+    /// Creates a request with a JSON body.
+    pub fn json(description: &str, url: String, body: &Value) -> Self {
+        Self {
+            description: description.to_string(),
+            url,
+            headers: vec![(
+                String::from("Content-Type"),
+                String::from("application/json"),
+            )],
+            body: body.to_string().into_bytes(),
+        }
+    }
+}
+
+/// Implements `ReportReceiver` for an HTTP report receiver (requests are sent using `ureq`).
+#[cfg(not(target_arch = "wasm32"))]
+pub struct HttpReportReceiverAdapter<T: HttpReportReceiver> {
+    receiver: T,
+    /// Time to wait for a connection to the remote entity.
+    connect_timeout: Duration,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: HttpReportReceiver> HttpReportReceiverAdapter<T> {
+    pub fn new(receiver: T) -> Self {
+        Self {
+            receiver,
+            connect_timeout: Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_IN_SEC),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<T: HttpReportReceiver> ReportReceiver for HttpReportReceiverAdapter<T> {
+    fn set_connect_timeout(&mut self, timeout: Duration) {
+        self.connect_timeout = timeout;
+    }
+
+    fn request_max_attachment_size_in_mb(
+        &mut self,
+        _remote_address: String,
+        _game_name: &str,
+        _logger: &mut LogManager,
+    ) -> Option<usize> {
+        self.receiver.get_max_attachment_size_in_mb()
+    }
+
+    fn request_server_info(
+        &mut self,
+        _remote_address: String,
+        _logger: &mut LogManager,
+    ) -> Option<ServerInfo> {
+        None
+    }
+
+    fn send_report(
+        &mut self,
+        remote_address: String,
+        _auth_token: String,
+        report: GameReport,
+        logger: &mut LogManager,
+        attachments: Vec<ReportAttachment>,
+    ) -> SendReportResult {
+        send_report_over_http(
+            &self.receiver,
+            &remote_address,
+            &report,
+            &attachments,
+            self.connect_timeout,
+            logger,
+        )
+    }
+}
+
+/// Creates and sends requests of an HTTP report receiver.
+///
+/// ## Arguments
+/// * `receiver` receiver that describes requests.
+/// * `remote_address` string that describes remote entity's address (depends on the receiver).
+/// * `report` report to send.
+/// * `attachments` report attachments.
+/// * `connect_timeout` time to wait for a connection.
+/// * `logger` logger that will be used to write to logs.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn send_report_over_http(
+    receiver: &dyn HttpReportReceiver,
+    remote_address: &str,
+    report: &GameReport,
+    attachments: &[ReportAttachment],
+    connect_timeout: Duration,
+    logger: &mut LogManager,
+) -> SendReportResult {
+    let requests = receiver.create_requests(remote_address, report, attachments);
+    if let Err(app_error) = requests {
+        logger.log(&app_error.to_string());
+        return SendReportResult::Other(app_error.get_message());
+    }
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(connect_timeout)
+        .timeout(Duration::from_secs(receiver.get_request_timeout_in_sec()))
+        .build();
+
+    for request in requests.unwrap() {
+        if let Err(error) = send_request(&agent, &request) {
+            logger.log(&format!("Failed to send {}.", request.description));
+            return receiver.handle_error(error, logger);
+        }
+    }
+
+    SendReportResult::Ok
+}
+
+/// Creates and sends requests of an HTTP report receiver using `fetch`.
+///
+/// ## Arguments
+/// * `receiver` receiver that describes requests.
+/// * `remote_address` string that describes remote entity's address (depends on the receiver).
+/// * `report` report to send.
+/// * `attachments` report attachments.
+/// * `logger` logger that will be used to write to logs.
+///
+/// ## Remarks
+/// The remote entity should allow cross-origin requests from the page of the game.
+#[cfg(target_arch = "wasm32")]
+pub async fn send_report_over_http(
+    receiver: &dyn HttpReportReceiver,
+    remote_address: &str,
+    report: &GameReport,
+    attachments: &[ReportAttachment],
+    logger: &mut LogManager,
+) -> SendReportResult {
+    let requests = receiver.create_requests(remote_address, report, attachments);
+    if let Err(app_error) = requests {
+        logger.log(&app_error.to_string());
+        return SendReportResult::Other(app_error.get_message());
+    }
+
+    for request in requests.unwrap() {
+        if let Err(error) = fetch(&request).await {
+            logger.log(&format!("Failed to send {}.", request.description));
+            return receiver.handle_error(error, logger);
+        }
+    }
+
+    SendReportResult::Ok
+}
+
+/// Sends the request and waits for the answer.
+#[cfg(not(target_arch = "wasm32"))]
+fn send_request(agent: &ureq::Agent, request: &HttpRequest) -> Result<(), HttpError> {
+    let mut builder = agent.post(&request.url);
+    for (name, value) in request.headers.iter() {
+        builder = builder.set(name, value);
+    }
+
+    match builder.send_bytes(&request.body) {
+        Ok(response) if (200..300).contains(&response.status()) => Ok(()),
+        Ok(response) => {
+            let code = response.status();
+            Err(HttpError::Status(
+                code,
+                response.into_string().unwrap_or_default(),
+            ))
+        }
+        Err(ureq::Error::Status(code, response)) => Err(HttpError::Status(
+            code,
+            response.into_string().unwrap_or_default(),
+        )),
+        // Don't log the URL since it might contain a token.
+        Err(ureq::Error::Transport(transport))
+            if transport.kind() == ureq::ErrorKind::ConnectionFailed
+                || transport.kind() == ureq::ErrorKind::Dns =>
+        {
+            Err(HttpError::CouldNotConnect(transport.kind().to_string()))
+        }
+        Err(ureq::Error::Transport(transport)) => {
+            Err(HttpError::Other(transport.kind().to_string()))
+        }
+    }
+}
+
+/// Sends the request using `fetch` and waits for the answer.
+#[cfg(target_arch = "wasm32")]
+async fn fetch(request: &HttpRequest) -> Result<(), HttpError> {
+    let js_error = |value: JsValue| format!("{:?}", value);
+
+    let headers = web_sys::Headers::new().map_err(|e| HttpError::Other(js_error(e)))?;
+    for (name, value) in request.headers.iter() {
+        headers
+            .set(name, value)
+            .map_err(|e| HttpError::Other(js_error(e)))?;
+    }
+
+    let body = JsValue::from(js_sys::Uint8Array::from(request.body.as_slice()));
+
+    let mut request_init = web_sys::RequestInit::new();
+    request_init.method("POST");
+    request_init.headers(&headers);
+    request_init.body(Some(&body));
+
+    let js_request = web_sys::Request::new_with_str_and_init(&request.url, &request_init)
+        .map_err(|e| HttpError::Other(js_error(e)))?;
+
+    let window = web_sys::window()
+        .ok_or_else(|| HttpError::Other(String::from("`window` is not available")))?;
+
+    // `fetch` only fails on network errors (including blocked cross-origin requests).
+    let response = JsFuture::from(window.fetch_with_request(&js_request))
+        .await
+        .map_err(|e| HttpError::CouldNotConnect(js_error(e)))?;
+    let response: web_sys::Response = response
+        .dyn_into()
+        .map_err(|e| HttpError::Other(js_error(e)))?;
+
+    if response.ok() {
+        return Ok(());
+    }
+
+    let mut body = String::new();
+    if let Ok(text) = response.text() {
+        if let Ok(text) = JsFuture::from(text).await {
+            body = text.as_string().unwrap_or_default();
+        }
+    }
+
+    Err(HttpError::Status(response.status(), body))
+}
@@ -0,0 +1,150 @@
+// Std.
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind, Read, Write};
+use std::time::Duration;
+
+// Custom.
+use shared::network::messaging::MessageStream;
+use shared::network::net_params::{HTTP_SESSION_CLOSED_HEADER, HTTP_SESSION_ID_HEADER};
+
+/// Maximum time to wait for data of the server's answer (the server answers
+/// requests that wait for new data in 30 seconds).
+const READ_TIMEOUT_IN_SEC: u64 = 60;
+
+/// Sends messages to the HTTP transport of the server (messages are sent in bodies
+/// of POST requests so that the server can be reached through HTTP proxies).
+///
+/// ## Remarks
+/// Written data is sent when the next read needs more data, the answer of the server
+/// contains everything the server sent since the previous request.
+pub struct HttpTunnel {
+    agent: ureq::Agent,
+    url: String,
+    /// ID of the session that the server created.
+    session_id: String,
+    /// Received data that was not read yet.
+    incoming: VecDeque<u8>,
+    /// Written data that was not sent yet.
+    outgoing: Vec<u8>,
+    /// Whether the server closed the session.
+    is_closed: bool,
+}
+
+impl HttpTunnel {
+    /// Creates a new session (the server starts processing it as a new connection).
+    ///
+    /// ## Arguments
+    /// * `url`: URL of the HTTP transport path for reporters
+    ///   (for example: `https://example.com/reporter`).
+    /// * `connect_timeout`: time to wait for a connection.
+    ///
+    /// ## Return
+    /// Error description if the server could not be reached or did not create a session.
+    pub fn connect(url: &str, connect_timeout: Duration) -> Result<Self, String> {
+        let mut tunnel = Self {
+            agent: ureq::AgentBuilder::new()
+                .timeout_connect(connect_timeout)
+                .timeout_read(Duration::from_secs(READ_TIMEOUT_IN_SEC))
+                .build(),
+            url: url.to_string(),
+            session_id: String::new(),
+            incoming: VecDeque::new(),
+            outgoing: Vec::new(),
+            is_closed: false,
+        };
+
+        tunnel.exchange().map_err(|e| e.to_string())?;
+        if tunnel.session_id.is_empty() {
+            return Err(format!(
+                "the server did not return the \"{}\" header",
+                HTTP_SESSION_ID_HEADER
+            ));
+        }
+
+        Ok(tunnel)
+    }
+    /// Sends written data (if any) and receives the answer.
+    fn exchange(&mut self) -> std::io::Result<()> {
+        let mut request = self
+            .agent
+            .post(&self.url)
+            .set("Content-Type", "application/octet-stream");
+        if !self.session_id.is_empty() {
+            request = request.set(HTTP_SESSION_ID_HEADER, &self.session_id);
+        }
+
+        let response = match request.send_bytes(&self.outgoing) {
+            Ok(response) => response,
+            Err(ureq::Error::Status(code, _)) => {
+                return Err(Error::new(
+                    ErrorKind::ConnectionAborted,
+                    format!("the server answered with status code {}", code),
+                ));
+            }
+            Err(ureq::Error::Transport(transport)) => {
+                return Err(Error::other(transport.to_string()));
+            }
+        };
+        self.outgoing.clear();
+
+        if let Some(session_id) = response.header(HTTP_SESSION_ID_HEADER) {
+            self.session_id = session_id.to_string();
+        }
+        self.is_closed = response.header(HTTP_SESSION_CLOSED_HEADER) == Some("true");
+
+        let mut answer = Vec::new();
+        response.into_reader().read_to_end(&mut answer)?;
+        self.incoming.extend(answer);
+
+        Ok(())
+    }
+}
+
+impl Read for HttpTunnel {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.incoming.is_empty() {
+            if self.is_closed {
+                return Ok(0);
+            }
+
+            // Send our data or wait for the server to send something.
+            self.exchange()?;
+
+            if self.incoming.is_empty() {
+                return if self.is_closed {
+                    Ok(0)
+                } else {
+                    Err(ErrorKind::WouldBlock.into())
+                };
+            }
+        }
+
+        let read_count = buf.len().min(self.incoming.len());
+        for (byte, received) in buf.iter_mut().zip(self.incoming.drain(..read_count)) {
+            *byte = received;
+        }
+
+        Ok(read_count)
+    }
+}
+
+impl Write for HttpTunnel {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.is_closed {
+            return Err(ErrorKind::BrokenPipe.into());
+        }
+
+        self.outgoing.extend_from_slice(buf);
+
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl MessageStream for HttpTunnel {
+    fn get_peer_name(&self) -> std::io::Result<String> {
+        Ok(self.url.clone())
+    }
+}
@@ -1,21 +1,48 @@
 // Std.
 use std::str::FromStr;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
 
 // External.
 use strum_macros::{Display, EnumString};
 
 // Custom.
+use self::http_client::HttpReportReceiver;
+#[cfg(not(target_arch = "wasm32"))]
+use self::http_client::HttpReportReceiverAdapter;
+use self::report_receiver_sentry::ReportReceiverSentry;
+#[cfg(not(target_arch = "wasm32"))]
 use self::report_receiver_server::ReportReceiverServer;
+use self::report_receiver_teams::ReportReceiverTeams;
+use self::report_receiver_telegram::ReportReceiverTelegram;
+#[cfg(not(target_arch = "wasm32"))]
 use crate::log_manager::LogManager;
+#[cfg(not(target_arch = "wasm32"))]
 use shared::misc::report::*;
 
-pub mod report_receiver_server;
+pub mod http_client;
+#[cfg(not(target_arch = "wasm32"))]
+mod http_tunnel;
+mod report_receiver_sentry;
+#[cfg(not(target_arch = "wasm32"))]
+mod report_receiver_server;
+mod report_receiver_teams;
+mod report_receiver_telegram;
+
+/// Default time to wait for a connection to the remote entity.
+pub const DEFAULT_CONNECT_TIMEOUT_IN_SEC: u64 = 5;
 
 /// Type of the remote "server" that we send our reports to.
 #[derive(Debug, EnumString, Display)]
 pub enum ReportReceiverType {
-    /// FBugReporter server.
+    /// FBugReporter server (not available on wasm32).
     Server,
+    /// Sentry (or a Sentry-compatible service), remote address is the project's DSN.
+    Sentry,
+    /// Telegram bot, remote address is "BOT_TOKEN|CHAT_ID".
+    Telegram,
+    /// Microsoft Teams channel, remote address is the URL of an incoming webhook.
+    Teams,
 }
 
 /// Result of the `send_report` operation.
@@ -24,13 +51,46 @@ pub enum SendReportResult {
     Ok,
     /// Unable to connect to the server (not found or inactive).
     CouldNotConnect,
+    /// The server does not accept reports from this game version.
+    VersionRejected,
+    /// The server rejected the report, contains a reason that can be shown to the user.
+    Rejected(String),
+    /// The server did not accept the solution of the proof of work challenge.
+    InvalidProofOfWork,
+    /// The server does not accept reports from this sender for the specified number of seconds.
+    RateLimited(u64),
+    /// The server says that a report field is too long, contains the name of the field.
+    FieldTooLong(String),
+    /// The server says that a report field is too short, contains the name of the field
+    /// and the minimum amount of characters.
+    FieldTooShort(String, usize),
+    /// The server failed to process the report.
+    ServerError,
+    /// The connection was closed or timed out after it was established, contains error message.
+    ConnectionLost(String),
     /// Error message without the call stack.
     /// Implementators of "report receiver" trait are recommended to log an error message with the
     /// full call stack before returning the error message.
     Other(String),
 }
 
+/// Versions that the remote entity reported.
+pub struct ServerInfo {
+    /// Network protocol version of the remote entity.
+    pub net_protocol: u16,
+    /// Application version of the remote entity.
+    pub server_version: String,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
 pub trait ReportReceiver {
+    /// Sets the maximum time to wait for a connection to the remote entity.
+    ///
+    /// ## Arguments
+    /// * `timeout`: connect timeout (if the remote address has multiple IPs
+    ///   the timeout is applied to each connection attempt).
+    fn set_connect_timeout(&mut self, timeout: Duration);
+
     /// Requests maximum allowed size of attachments (in total) in megabytes.
     ///
     /// ## Remarks
@@ -40,6 +100,7 @@ pub trait ReportReceiver {
     ///
     /// ## Arguments
     /// * `server_addr`: address of the server to connect to.
+    /// * `game_name`: name of the game (limits can differ between games).
     /// * `logger`: logger to use.
     ///
     /// ## Return
@@ -48,9 +109,29 @@ pub trait ReportReceiver {
     fn request_max_attachment_size_in_mb(
         &mut self,
         remote_address: String,
+        game_name: &str,
         logger: &mut LogManager,
     ) -> Option<usize>;
 
+    /// Requests network protocol version and application version of the remote entity.
+    ///
+    /// ## Remarks
+    /// This function is generally used to check if the reporter is compatible with
+    /// the remote entity before sending a report.
+    ///
+    /// ## Arguments
+    /// * `remote_address`: address of the remote entity to connect to.
+    /// * `logger`: logger to use.
+    ///
+    /// ## Return
+    /// `None` if this receiver does not provide such functionality or something went wrong
+    /// (see logs), otherwise versions of the remote entity.
+    fn request_server_info(
+        &mut self,
+        remote_address: String,
+        logger: &mut LogManager,
+    ) -> Option<ServerInfo>;
+
     /// Sends the specified report to the specified remote address.
     ///
     /// ## Arguments
@@ -75,6 +156,7 @@ pub trait ReportReceiver {
 ///
 /// ## Return
 /// `None` if the specified receiver type string is invalid, otherwise created report receiver.
+#[cfg(not(target_arch = "wasm32"))]
 pub fn create_report_receiver(receiver_type: &str) -> Option<Box<dyn ReportReceiver>> {
     // Parse type.
     let receiver_type = ReportReceiverType::from_str(receiver_type);
@@ -86,5 +168,36 @@ pub fn create_report_receiver(receiver_type: &str) -> Option<Box<dyn ReportRecei
     // Create object.
     match receiver_type {
         ReportReceiverType::Server => Some(Box::new(ReportReceiverServer::new())),
+        ReportReceiverType::Sentry => Some(Box::new(HttpReportReceiverAdapter::new(
+            ReportReceiverSentry,
+        ))),
+        ReportReceiverType::Telegram => Some(Box::new(HttpReportReceiverAdapter::new(
+            ReportReceiverTelegram,
+        ))),
+        ReportReceiverType::Teams => Some(Box::new(HttpReportReceiverAdapter::new(
+            ReportReceiverTeams,
+        ))),
+    }
+}
+
+/// Creates a new HTTP report receiver by parsing the specified report receiver type
+/// (see `http_client::send_report_over_http`).
+///
+/// ## Return
+/// `None` if the specified receiver type string is invalid or the receiver does not use HTTP,
+/// otherwise created report receiver.
+pub fn create_http_report_receiver(receiver_type: &str) -> Option<Box<dyn HttpReportReceiver>> {
+    // Parse type.
+    let receiver_type = ReportReceiverType::from_str(receiver_type);
+    if receiver_type.is_err() {
+        return None;
+    }
+
+    // Create object.
+    match receiver_type.unwrap() {
+        ReportReceiverType::Server => None,
+        ReportReceiverType::Sentry => Some(Box::new(ReportReceiverSentry)),
+        ReportReceiverType::Telegram => Some(Box::new(ReportReceiverTelegram)),
+        ReportReceiverType::Teams => Some(Box::new(ReportReceiverTeams)),
     }
 }
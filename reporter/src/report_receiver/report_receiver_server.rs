@@ -1,81 +1,83 @@
 // Std.
 use std::net::*;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 // Custom.
+use super::http_tunnel::HttpTunnel;
 use super::*;
 use crate::log_manager::LogManager;
-use shared::misc::error::AppError;
+use shared::misc::error::{AppError, AppErrorKind};
 use shared::network::messaging::*;
 use shared::network::net_params::*;
+use shared::network::proof_of_work::ProofOfWorkChallenge;
 use shared::network::reporter_messages::*;
 
-pub struct ReportReceiverServer {}
+/// Socket (or HTTP tunnel), established secret key and the first answer of the server.
+type SecureConnection = (
+    Box<dyn MessageStream>,
+    [u8; SECRET_KEY_SIZE],
+    ServerGreeting,
+);
+
+/// First answer of the server after a secure connection was established.
+enum ServerGreeting {
+    /// The server waits for a request (with the solved challenge if it's a report).
+    Challenge(ProofOfWorkChallenge),
+    /// The server is busy and closed the connection, try again after the specified time.
+    Busy { retry_after_in_sec: u64 },
+}
+
+pub struct ReportReceiverServer {
+    /// Time to wait for a connection to a single server address.
+    connect_timeout: Duration,
+}
 
 impl ReportReceiver for ReportReceiverServer {
+    fn set_connect_timeout(&mut self, timeout: Duration) {
+        self.connect_timeout = timeout;
+    }
+
     fn request_max_attachment_size_in_mb(
         &mut self,
         remote_address: String,
+        game_name: &str,
         logger: &mut LogManager,
     ) -> Option<usize> {
-        let result = Self::establish_secure_connection_with_server(remote_address, logger);
-
-        // Check for errors.
-        if let Err(app_error) = result {
-            if let Some(err) = app_error {
-                logger.log(&err.to_string());
-                return None;
-            } else {
-                logger.log("Could not connect to the server.");
-                return None;
-            }
-        }
-        let (mut tcp_socket, secret_key) = result.unwrap();
-
-        // Prepare message.
-        let message = ReporterRequest::MaxAttachmentSize {};
-
-        // Send message.
-        if let Some(app_error) = send_message(&mut tcp_socket, &secret_key, message) {
-            logger.log(&app_error.to_string());
-            return None;
-        }
-
-        let mut is_fin = false;
-        let result = receive_message(
-            &mut tcp_socket,
-            &secret_key,
-            None,
-            std::usize::MAX,
-            &mut is_fin,
-        );
-
-        // Check for errors.
-        if let Err(app_error) = result {
-            logger.log(&app_error.to_string());
-            return None;
-        }
-        let result = result.unwrap();
-
-        if is_fin {
-            logger.log(&AppError::new("the server closed connection unexpectedly").to_string());
-            return None;
-        }
-
-        // Deserialize.
-        let received_message = bincode::deserialize::<ReporterAnswer>(&result);
-        if let Err(e) = received_message {
-            logger.log(&AppError::new(&e.to_string()).to_string());
-            return None;
-        }
-        let received_message = received_message.unwrap();
+        let message = ReporterRequest::MaxAttachmentSize {
+            game_name: game_name.to_string(),
+        };
 
-        // Process answer.
-        match received_message {
+        match self.send_request(remote_address, message, logger)? {
             ReporterAnswer::MaxAttachmentSize {
                 max_attachments_size_in_mb,
             } => Some(max_attachments_size_in_mb),
-            _ => {
+            received_message => {
+                logger.log(
+                    &AppError::new(&format!(
+                        "received unexpected answer from the server ({:?})",
+                        received_message
+                    ))
+                    .to_string(),
+                );
+                None
+            }
+        }
+    }
+
+    fn request_server_info(
+        &mut self,
+        remote_address: String,
+        logger: &mut LogManager,
+    ) -> Option<ServerInfo> {
+        match self.send_request(remote_address, ReporterRequest::QueryServerInfo, logger)? {
+            ReporterAnswer::ServerInfo {
+                net_protocol,
+                server_version,
+            } => Some(ServerInfo {
+                net_protocol,
+                server_version,
+            }),
+            received_message => {
                 logger.log(
                     &AppError::new(&format!(
                         "received unexpected answer from the server ({:?})",
@@ -91,8 +93,10 @@ impl ReportReceiver for ReportReceiverServer {
     /// Sends the specified report to the specified remote address.
     ///
     /// ## Arguments
-    /// * `remote_address` string in the form "IP:PORT" where the first part is server's
-    /// IP address and the second one is server's port for reporters.
+    /// * `remote_address` string in the form "HOST:PORT" where the first part is server's
+    ///   IPv4 address, IPv6 address in square brackets (like "[::1]") or a domain name and
+    ///   the second one is server's port for reporters, or the URL of the server's
+    ///   HTTP transport (like "https://example.com"), see `HttpTunnel`.
     /// * `auth_token` not used.
     /// * `report` report to send.
     /// * `logger` logger that will be used to write to logs.
@@ -105,7 +109,7 @@ impl ReportReceiver for ReportReceiverServer {
         logger: &mut LogManager,
         attachments: Vec<ReportAttachment>,
     ) -> SendReportResult {
-        let result = Self::establish_secure_connection_with_server(remote_address, logger);
+        let result = self.establish_secure_connection_with_server(remote_address, logger);
         if let Err(app_error) = result {
             if let Some(err) = app_error {
                 logger.log(&err.to_string());
@@ -115,42 +119,62 @@ impl ReportReceiver for ReportReceiverServer {
                 return SendReportResult::CouldNotConnect;
             }
         }
-        let (mut tcp_socket, secret_key) = result.unwrap();
+        let (mut socket, secret_key, server_greeting) = result.unwrap();
+        let proof_of_work = match server_greeting {
+            ServerGreeting::Challenge(proof_of_work) => proof_of_work,
+            ServerGreeting::Busy { retry_after_in_sec } => {
+                logger.log("The server is busy.");
+                return SendReportResult::RateLimited(retry_after_in_sec);
+            }
+        };
+
+        // Solve proof of work challenge (if required).
+        let mut proof_of_work_nonce = 0;
+        if proof_of_work.is_enabled() {
+            logger.log(&format!(
+                "Solving proof of work challenge (difficulty: {}).",
+                proof_of_work.difficulty
+            ));
+            let start_time = Instant::now();
+            proof_of_work_nonce = proof_of_work.solve();
+            logger.log(&format!(
+                "Solved proof of work challenge in {} ms.",
+                start_time.elapsed().as_millis()
+            ));
+        }
 
         // Prepare message.
         let message = ReporterRequest::Report {
             reporter_net_protocol: NETWORK_PROTOCOL_VERSION,
             game_report: Box::new(report),
             attachments,
+            proof_of_work_nonce,
         };
 
         logger.log("Sending report message to the server.");
 
         // Send message.
-        if let Some(app_error) = send_message(&mut tcp_socket, &secret_key, message) {
+        if let Some(app_error) = send_message(&mut socket, &secret_key, message) {
             logger.log(&app_error.to_string());
-            return SendReportResult::Other(app_error.get_message());
+            return Self::error_to_send_report_result(app_error);
         }
 
         logger.log("Sent report message.");
         logger.log("Waiting for server to answer.");
 
         let mut is_fin = false;
-        let result = receive_message(
-            &mut tcp_socket,
-            &secret_key,
-            None,
-            std::usize::MAX,
-            &mut is_fin,
-        );
+        let result = receive_message(&mut socket, &secret_key, None, std::usize::MAX, &mut is_fin);
         if is_fin {
-            let app_error = AppError::new("the server closed connection unexpectedly");
+            let app_error = AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "the server closed connection unexpectedly",
+            );
             logger.log(&app_error.to_string());
-            return SendReportResult::Other(app_error.get_message());
+            return Self::error_to_send_report_result(app_error);
         }
         if let Err(app_error) = result {
             logger.log(&app_error.to_string());
-            return SendReportResult::Other(app_error.get_message());
+            return Self::error_to_send_report_result(app_error);
         }
 
         let result = result.unwrap();
@@ -158,47 +182,222 @@ impl ReportReceiver for ReportReceiverServer {
         logger.log("Received an answer from the server.");
 
         // Deserialize.
-        let received_message = bincode::deserialize::<ServerAnswer>(&result);
+        let received_message = bincode::deserialize::<ReporterAnswer>(&result);
         if let Err(e) = received_message {
             let app_error = AppError::new(&e.to_string());
             logger.log(&app_error.to_string());
             return SendReportResult::Other(app_error.get_message());
         }
-        let received_message = received_message.unwrap();
+        let result_code = match received_message.unwrap() {
+            ReporterAnswer::Report { result_code } => result_code,
+            received_message => {
+                let app_error = AppError::new(&format!(
+                    "received unexpected answer from the server ({:?})",
+                    received_message
+                ));
+                logger.log(&app_error.to_string());
+                return SendReportResult::Other(app_error.get_message());
+            }
+        };
 
         // Process answer.
-        match received_message {
+        match result_code {
             ServerAnswer::Ok => SendReportResult::Ok,
             ServerAnswer::OtherError(message) => SendReportResult::Other(message),
+            ServerAnswer::VersionRejected => SendReportResult::VersionRejected,
+            ServerAnswer::Rejected(reason) => SendReportResult::Rejected(reason),
+            ServerAnswer::InvalidProofOfWork => SendReportResult::InvalidProofOfWork,
+            ServerAnswer::RateLimited { retry_after_in_sec } => {
+                SendReportResult::RateLimited(retry_after_in_sec)
+            }
+            ServerAnswer::FieldTooLong { field } => SendReportResult::FieldTooLong(field),
+            ServerAnswer::FieldTooShort { field, min_length } => {
+                SendReportResult::FieldTooShort(field, min_length)
+            }
+            ServerAnswer::InternalError => SendReportResult::ServerError,
         }
     }
 }
 
 impl ReportReceiverServer {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            connect_timeout: Duration::from_secs(DEFAULT_CONNECT_TIMEOUT_IN_SEC),
+        }
     }
-    /// Connects to the server and establishes a secure connection.
+    /// Connects to the server, sends a request (that is not a report) and receives the answer.
     ///
     /// ## Return
-    /// `Ok` with socket and established secret key if successful, otherwise
-    /// `None` if unable to connect to the server and `Some` if internal error occurred.
+    /// `None` if something went wrong (see logs), otherwise the server's answer.
+    fn send_request(
+        &mut self,
+        remote_address: String,
+        message: ReporterRequest,
+        logger: &mut LogManager,
+    ) -> Option<ReporterAnswer> {
+        let result = self.establish_secure_connection_with_server(remote_address, logger);
+
+        // Check for errors.
+        if let Err(app_error) = result {
+            if let Some(err) = app_error {
+                logger.log(&err.to_string());
+                return None;
+            } else {
+                logger.log("Could not connect to the server.");
+                return None;
+            }
+        }
+        let (mut socket, secret_key, server_greeting) = result.unwrap();
+        if let ServerGreeting::Busy { .. } = server_greeting {
+            logger.log("The server is busy.");
+            return None;
+        }
+
+        // Send message.
+        if let Some(app_error) = send_message(&mut socket, &secret_key, message) {
+            logger.log(&app_error.to_string());
+            return None;
+        }
+
+        let mut is_fin = false;
+        let result = receive_message(&mut socket, &secret_key, None, std::usize::MAX, &mut is_fin);
+
+        // Check for errors.
+        if let Err(app_error) = result {
+            logger.log(&app_error.to_string());
+            return None;
+        }
+        let result = result.unwrap();
+
+        if is_fin {
+            logger.log(&AppError::new("the server closed connection unexpectedly").to_string());
+            return None;
+        }
+
+        // Deserialize.
+        let received_message = bincode::deserialize::<ReporterAnswer>(&result);
+        if let Err(e) = received_message {
+            logger.log(&AppError::new(&e.to_string()).to_string());
+            return None;
+        }
+
+        Some(received_message.unwrap())
+    }
+    /// Connects to the server, establishes a secure connection and receives
+    /// a proof of work challenge (or the "server is busy" answer).
+    ///
+    /// ## Return
+    /// `Ok` with socket, established secret key and the server's first answer if successful, otherwise
+    /// `None` if unable to connect to the server (using any of the server's addresses)
+    /// and `Some` if internal error occurred.
     fn establish_secure_connection_with_server(
+        &self,
         server_addr: String,
         logger: &mut LogManager,
-    ) -> Result<(TcpStream, [u8; SECRET_KEY_SIZE]), Option<AppError>> {
-        let addrs = server_addr.to_socket_addrs();
-        if let Err(e) = addrs {
+    ) -> Result<SecureConnection, Option<AppError>> {
+        let mut socket: Box<dyn MessageStream> = match Self::get_http_transport_url(&server_addr) {
+            Some(url) => match HttpTunnel::connect(&url, self.connect_timeout) {
+                Ok(tunnel) => {
+                    logger.log(&format!("Connected to the server ({}).", url));
+                    Box::new(tunnel)
+                }
+                Err(e) => {
+                    logger.log(&format!(
+                        "Failed to connect to the server using the address {} (error: {}).",
+                        url, e
+                    ));
+                    return Err(None);
+                }
+            },
+            None => Box::new(self.connect_over_tcp(&server_addr, logger)?),
+        };
+
+        let secret_key = accept_secure_connection_establishment(&mut socket, None);
+        if let Err(app_error) = secret_key {
+            return Err(Some(app_error));
+        } else {
+            logger.log("Secure connection established.");
+        }
+        let result = secret_key.unwrap().try_into();
+        if result.is_err() {
+            return Err(Some(AppError::new(
+                "failed to convert Vec<u8> to generic array",
+            )));
+        }
+        let secret_key: [u8; SECRET_KEY_SIZE] = result.unwrap();
+
+        // Receive proof of work challenge.
+        let mut is_fin = false;
+        let result = receive_message(&mut socket, &secret_key, None, std::usize::MAX, &mut is_fin);
+        if is_fin {
+            return Err(Some(AppError::new(
+                "the server closed connection unexpectedly",
+            )));
+        }
+        if let Err(app_error) = result {
+            return Err(Some(app_error));
+        }
+        let received_message = bincode::deserialize::<ReporterAnswer>(&result.unwrap());
+        if let Err(e) = received_message {
             return Err(Some(AppError::new(&e.to_string())));
         }
+        let server_greeting = match received_message.unwrap() {
+            ReporterAnswer::Challenge { proof_of_work } => ServerGreeting::Challenge(proof_of_work),
+            ReporterAnswer::Report {
+                result_code: ServerAnswer::RateLimited { retry_after_in_sec },
+            } => ServerGreeting::Busy { retry_after_in_sec },
+            received_message => {
+                return Err(Some(AppError::new(&format!(
+                    "received unexpected answer from the server ({:?})",
+                    received_message
+                ))));
+            }
+        };
+
+        Ok((socket, secret_key, server_greeting))
+    }
+    /// Returns the URL of the reporter path of the server's HTTP transport
+    /// if the specified server address is an HTTP(S) URL.
+    fn get_http_transport_url(server_addr: &str) -> Option<String> {
+        let server_addr = server_addr.trim();
+        if !server_addr.starts_with("http://") && !server_addr.starts_with("https://") {
+            return None;
+        }
+
+        Some(format!("{}/reporter", server_addr.trim_end_matches('/')))
+    }
+    /// Connects to the server using any of the server's addresses.
+    ///
+    /// ## Return
+    /// `None` if unable to connect to the server and `Some` if internal error occurred.
+    fn connect_over_tcp(
+        &self,
+        server_addr: &str,
+        logger: &mut LogManager,
+    ) -> Result<TcpStream, Option<AppError>> {
+        let addrs = Self::resolve_server_address(server_addr);
+        if let Err(app_error) = addrs {
+            return Err(Some(app_error));
+        }
         let addrs = addrs.unwrap();
 
+        // The address might be resolved to multiple IPs (dual-stack, round-robin DNS, etc.),
+        // try them one by one.
         let mut tcp_socket: Option<TcpStream> = None;
         for addr in addrs {
-            let result = TcpStream::connect_timeout(&addr, Duration::from_secs(2));
-            if let Ok(socket) = result {
-                tcp_socket = Some(socket);
-                break;
+            let result = TcpStream::connect_timeout(&addr, self.connect_timeout);
+            match result {
+                Ok(socket) => {
+                    logger.log(&format!("Connected to the server ({}).", addr));
+                    tcp_socket = Some(socket);
+                    break;
+                }
+                Err(e) => {
+                    logger.log(&format!(
+                        "Failed to connect to the server using the address {} (error: {}).",
+                        addr, e
+                    ));
+                }
             }
         }
 
@@ -206,9 +405,7 @@ impl ReportReceiverServer {
             return Err(None);
         }
 
-        let mut tcp_socket = tcp_socket.unwrap();
-
-        logger.log("Connected to the server.");
+        let tcp_socket = tcp_socket.unwrap();
 
         if let Err(e) = tcp_socket.set_nodelay(true) {
             return Err(Some(AppError::new(&e.to_string())));
@@ -217,20 +414,121 @@ impl ReportReceiverServer {
             return Err(Some(AppError::new(&e.to_string())));
         }
 
-        let secret_key = accept_secure_connection_establishment(&mut tcp_socket);
-        if let Err(app_error) = secret_key {
-            return Err(Some(app_error));
-        } else {
-            logger.log("Secure connection established.");
+        Ok(tcp_socket)
+    }
+    /// Parses the server address and resolves it (if it's a domain name).
+    ///
+    /// ## Arguments
+    /// * `server_addr`: string in the form "HOST:PORT" where host is an IPv4 address,
+    ///   IPv6 address in square brackets (like "[::1]:50123") or a domain name.
+    ///
+    /// ## Return
+    /// All socket addresses that the specified address refers to.
+    fn resolve_server_address(server_addr: &str) -> Result<Vec<SocketAddr>, AppError> {
+        let server_addr = server_addr.trim();
+
+        // IP addresses don't need to be resolved.
+        if let Ok(addr) = server_addr.parse::<SocketAddr>() {
+            return Ok(vec![addr]);
         }
-        let result = secret_key.unwrap().try_into();
-        if result.is_err() {
-            return Err(Some(AppError::new(
-                "failed to convert Vec<u8> to generic array",
+
+        // Split host and port.
+        let (host, port) = match server_addr.rsplit_once(':') {
+            Some((host, port)) if !host.is_empty() => (host, port),
+            _ => {
+                return Err(AppError::new(&format!(
+                    "expected server address in the form \"HOST:PORT\", received \"{}\"",
+                    server_addr
+                )));
+            }
+        };
+        if host.contains(':') {
+            return Err(AppError::new(&format!(
+                "IPv6 address should be enclosed in square brackets \
+                (for example, \"[::1]:50123\"), received \"{}\"",
+                server_addr
             )));
         }
-        let secret_key: [u8; SECRET_KEY_SIZE] = result.unwrap();
+        let port = port.parse::<u16>();
+        if let Err(e) = port {
+            return Err(AppError::new(&format!(
+                "failed to parse port of the server address \"{}\" (error: {})",
+                server_addr, e
+            )));
+        }
+
+        // Resolve domain name.
+        let addrs = (host, port.unwrap()).to_socket_addrs();
+        if let Err(e) = addrs {
+            return Err(AppError::new(&format!(
+                "failed to resolve server address \"{}\" (error: {})",
+                server_addr, e
+            )));
+        }
+        let addrs: Vec<SocketAddr> = addrs.unwrap().collect();
+        if addrs.is_empty() {
+            return Err(AppError::new(&format!(
+                "server address \"{}\" was not resolved to any IP address",
+                server_addr
+            )));
+        }
+
+        Ok(addrs)
+    }
+    /// Converts a network error that happened while sending the report into a result.
+    fn error_to_send_report_result(app_error: AppError) -> SendReportResult {
+        match app_error.get_kind() {
+            AppErrorKind::ConnectionClosed | AppErrorKind::Timeout => {
+                SendReportResult::ConnectionLost(app_error.get_message())
+            }
+            AppErrorKind::AddressNotResolved
+            | AppErrorKind::ConnectionRefused
+            | AppErrorKind::Protocol
+            | AppErrorKind::Internal => SendReportResult::Other(app_error.get_message()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_ipv4_address() {
+        let addrs = ReportReceiverServer::resolve_server_address("127.0.0.1:50123").unwrap();
+        assert_eq!(
+            addrs,
+            vec![SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 50123)]
+        );
+    }
+
+    #[test]
+    fn resolve_ipv6_address() {
+        let addrs = ReportReceiverServer::resolve_server_address("[::1]:50123").unwrap();
+        assert_eq!(
+            addrs,
+            vec![SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 50123)]
+        );
+
+        // Brackets are required to separate the port.
+        assert!(ReportReceiverServer::resolve_server_address("::1:50123").is_err());
+    }
+
+    #[test]
+    fn resolve_hostname() {
+        let addrs = ReportReceiverServer::resolve_server_address("localhost:50123").unwrap();
+        assert!(!addrs.is_empty());
+        for addr in addrs {
+            assert!(addr.ip().is_loopback());
+            assert_eq!(addr.port(), 50123);
+        }
+    }
 
-        Ok((tcp_socket, secret_key))
+    #[test]
+    fn resolve_address_without_port() {
+        assert!(ReportReceiverServer::resolve_server_address("localhost").is_err());
+        assert!(ReportReceiverServer::resolve_server_address("127.0.0.1").is_err());
+        assert!(ReportReceiverServer::resolve_server_address(":50123").is_err());
+        assert!(ReportReceiverServer::resolve_server_address("localhost:port").is_err());
     }
 }
@@ -0,0 +1,144 @@
+// External.
+use serde_json::{json, Value};
+
+// Custom.
+use super::http_client::*;
+use super::SendReportResult;
+use crate::log_manager::LogManager;
+use shared::misc::error::AppError;
+use shared::misc::report::*;
+
+/// Maximum amount of characters of the report text that we put in the card
+/// (Teams rejects messages bigger than 28 KB).
+const TEAMS_MAX_REPORT_TEXT_LENGTH: usize = 20000;
+
+/// Maximum time to wait for Teams to answer (after the connection was established).
+const TEAMS_REQUEST_TIMEOUT_IN_SEC: u64 = 30;
+
+/// Sends reports as message cards to a Microsoft Teams channel using an incoming webhook.
+pub struct ReportReceiverTeams;
+
+impl HttpReportReceiver for ReportReceiverTeams {
+    fn get_max_attachment_size_in_mb(&self) -> Option<usize> {
+        // Attachments are not uploaded.
+        None
+    }
+
+    fn get_request_timeout_in_sec(&self) -> u64 {
+        TEAMS_REQUEST_TIMEOUT_IN_SEC
+    }
+
+    /// Creates a request that sends the report.
+    ///
+    /// ## Arguments
+    /// * `remote_address` URL of the channel's incoming webhook.
+    /// * `report` report to send.
+    /// * `attachments` report attachements (webhooks don't accept files so only names and sizes
+    ///   of the attachments are listed in the card).
+    fn create_requests(
+        &self,
+        remote_address: &str,
+        report: &GameReport,
+        attachments: &[ReportAttachment],
+    ) -> Result<Vec<HttpRequest>, AppError> {
+        Ok(vec![HttpRequest::json(
+            "the message card",
+            remote_address.trim().to_string(),
+            &ReportReceiverTeams::create_card(report, attachments),
+        )])
+    }
+
+    fn handle_error(&self, error: HttpError, logger: &mut LogManager) -> SendReportResult {
+        match error {
+            HttpError::Status(code, body) => ReportReceiverTeams::rejected(code, &body, logger),
+            HttpError::CouldNotConnect(description) => {
+                // Don't log the URL since it contains the key.
+                logger.log(&format!("Could not connect to Teams: {}", description));
+                SendReportResult::CouldNotConnect
+            }
+            HttpError::Other(description) => {
+                let app_error = AppError::new(&format!(
+                    "failed to send a request to Teams: {}",
+                    description
+                ));
+                logger.log(&app_error.to_string());
+                SendReportResult::Other(app_error.get_message())
+            }
+        }
+    }
+}
+
+impl ReportReceiverTeams {
+    /// Creates a message card with sections for report text, game info, sender and attachments.
+    fn create_card(report: &GameReport, attachments: &[ReportAttachment]) -> Value {
+        let mut report_text: String = report
+            .report_text
+            .chars()
+            .take(TEAMS_MAX_REPORT_TEXT_LENGTH)
+            .collect();
+        if report_text.len() < report.report_text.len() {
+            report_text.push('…');
+        }
+
+        let mut game_facts = vec![
+            json!({"name": "Game", "value": report.game_name}),
+            json!({"name": "Version", "value": report.game_version}),
+            json!({"name": "Priority", "value": report.priority.to_string()}),
+            json!({"name": "OS", "value": report.client_os_info.to_string()}),
+        ];
+        for (key, value) in report.metadata.iter() {
+            game_facts.push(json!({"name": key, "value": value}));
+        }
+
+        let mut sections = vec![
+            json!({
+                "activityTitle": "Report",
+                "text": report_text,
+            }),
+            json!({
+                "activityTitle": "Game",
+                "facts": game_facts,
+            }),
+            json!({
+                "activityTitle": "Sender",
+                "facts": [
+                    {"name": "Name", "value": report.sender_name},
+                    {"name": "E-mail", "value": report.sender_email},
+                ],
+            }),
+        ];
+
+        if !attachments.is_empty() {
+            let attachment_facts: Vec<Value> = attachments
+                .iter()
+                .map(|attachment| {
+                    json!({
+                        "name": attachment.file_name,
+                        "value": format!("{:.1} KB", attachment.data.len() as f64 / 1024.0),
+                    })
+                })
+                .collect();
+
+            sections.push(json!({
+                "activityTitle": "Attachments",
+                "text": "Teams does not accept files from webhooks, \
+                    attachments must be fetched from the server.",
+                "facts": attachment_facts,
+            }));
+        }
+
+        json!({
+            "@type": "MessageCard",
+            "@context": "https://schema.org/extensions",
+            "summary": report.report_name,
+            "title": report.report_name,
+            "sections": sections,
+        })
+    }
+    /// Logs that Teams did not accept the card.
+    fn rejected(code: u16, body: &str, logger: &mut LogManager) -> SendReportResult {
+        let app_error = AppError::new(&format!("Teams returned status code {}: {}", code, body));
+        logger.log(&app_error.to_string());
+        SendReportResult::Other(app_error.get_message())
+    }
+}
@@ -0,0 +1,209 @@
+// External.
+use rand::Rng;
+use serde_json::{json, Value};
+
+// Custom.
+use super::http_client::*;
+use super::SendReportResult;
+use crate::log_manager::LogManager;
+use shared::misc::error::AppError;
+use shared::misc::report::*;
+
+/// Maximum size of a file that a bot can send.
+const TELEGRAM_MAX_ATTACHMENT_SIZE_IN_MB: usize = 50;
+
+/// Maximum size of a file that a bot can send as a photo (bigger images are sent as documents).
+const TELEGRAM_MAX_PHOTO_SIZE_IN_MB: usize = 10;
+
+/// Maximum amount of characters in a message.
+const TELEGRAM_MAX_MESSAGE_LENGTH: usize = 4096;
+
+/// Maximum time to wait for Telegram to answer (after the connection was established).
+const TELEGRAM_REQUEST_TIMEOUT_IN_SEC: u64 = 60;
+
+/// Bot API URL without the method name.
+const TELEGRAM_API_URL: &str = "https://api.telegram.org/bot";
+
+/// Sends reports as messages to a Telegram chat using a bot.
+pub struct ReportReceiverTelegram;
+
+impl HttpReportReceiver for ReportReceiverTelegram {
+    fn get_max_attachment_size_in_mb(&self) -> Option<usize> {
+        Some(TELEGRAM_MAX_ATTACHMENT_SIZE_IN_MB)
+    }
+
+    fn get_request_timeout_in_sec(&self) -> u64 {
+        TELEGRAM_REQUEST_TIMEOUT_IN_SEC
+    }
+
+    /// Creates requests that send the report.
+    ///
+    /// ## Arguments
+    /// * `remote_address` string in the form "BOT_TOKEN|CHAT_ID" where the first part is
+    ///   the token of the bot that sends the report and the second one is the ID of the chat
+    ///   (the bot should be a member of this chat).
+    /// * `report` report to send.
+    /// * `attachments` report attachements (each attachment is sent in a separate message).
+    fn create_requests(
+        &self,
+        remote_address: &str,
+        report: &GameReport,
+        attachments: &[ReportAttachment],
+    ) -> Result<Vec<HttpRequest>, AppError> {
+        let (bot_token, chat_id) = ReportReceiverTelegram::parse_remote_address(remote_address)?;
+
+        // Report text.
+        let mut requests = vec![HttpRequest::json(
+            "the report text",
+            format!("{}{}/sendMessage", TELEGRAM_API_URL, bot_token),
+            &json!({
+                "chat_id": chat_id,
+                "text": ReportReceiverTelegram::format_report(report),
+                "disable_web_page_preview": true,
+            }),
+        )];
+
+        // Attachments.
+        for attachment in attachments {
+            let mime_type = get_mime_type_from_file_name(&attachment.file_name);
+            let (method, field_name) = if (mime_type == "image/png" || mime_type == "image/jpeg")
+                && attachment.data.len() <= TELEGRAM_MAX_PHOTO_SIZE_IN_MB * 1024 * 1024
+            {
+                ("sendPhoto", "photo")
+            } else {
+                ("sendDocument", "document")
+            };
+
+            let boundary = format!("{:032x}", rand::thread_rng().gen::<u128>());
+
+            requests.push(HttpRequest {
+                description: format!(
+                    "the attachment \"{}\" (previous messages were sent)",
+                    attachment.file_name
+                ),
+                url: format!("{}{}/{}", TELEGRAM_API_URL, bot_token, method),
+                headers: vec![(
+                    String::from("Content-Type"),
+                    format!("multipart/form-data; boundary={}", boundary),
+                )],
+                body: ReportReceiverTelegram::create_multipart_body(
+                    &boundary, &chat_id, field_name, attachment,
+                ),
+            });
+        }
+
+        Ok(requests)
+    }
+
+    /// Converts an error returned by the Bot API to the result of `send_report`.
+    fn handle_error(&self, error: HttpError, logger: &mut LogManager) -> SendReportResult {
+        match error {
+            HttpError::CouldNotConnect(description) => {
+                logger.log(&format!("Could not connect to Telegram: {}", description));
+                SendReportResult::CouldNotConnect
+            }
+            HttpError::Status(code, body) => {
+                let answer: Value = serde_json::from_str(&body).unwrap_or_default();
+
+                if code == 429 {
+                    if let Some(retry_after) = answer["parameters"]["retry_after"].as_u64() {
+                        return SendReportResult::RateLimited(retry_after);
+                    }
+                }
+
+                // Prefer the description of the error (if Telegram provided one).
+                let description = answer["description"].as_str().unwrap_or(&body);
+                let app_error = AppError::new(&format!(
+                    "Telegram returned status code {}: {}",
+                    code, description
+                ));
+                logger.log(&app_error.to_string());
+                SendReportResult::Other(app_error.get_message())
+            }
+            // Don't log the URL since it contains the bot token.
+            HttpError::Other(description) => {
+                let app_error = AppError::new(&format!(
+                    "failed to send a request to Telegram: {}",
+                    description
+                ));
+                logger.log(&app_error.to_string());
+                SendReportResult::Other(app_error.get_message())
+            }
+        }
+    }
+}
+
+impl ReportReceiverTelegram {
+    /// Parses remote address in the form "BOT_TOKEN|CHAT_ID".
+    ///
+    /// ## Return
+    /// Bot token and chat ID.
+    fn parse_remote_address(remote_address: &str) -> Result<(String, String), AppError> {
+        if let Some((bot_token, chat_id)) = remote_address.split_once('|') {
+            let bot_token = bot_token.trim();
+            let chat_id = chat_id.trim();
+
+            if !bot_token.is_empty() && !chat_id.is_empty() {
+                return Ok((bot_token.to_string(), chat_id.to_string()));
+            }
+        }
+
+        // Don't show the address since it contains the bot token.
+        Err(AppError::new(
+            "the remote address for Telegram should be in the form \"BOT_TOKEN|CHAT_ID\"",
+        ))
+    }
+    /// Formats the report as a plain text message.
+    fn format_report(report: &GameReport) -> String {
+        let mut text = format!(
+            "{}\n\nGame: {} {}\nPriority: {}\n",
+            report.report_name, report.game_name, report.game_version, report.priority
+        );
+
+        if !report.sender_name.is_empty() || !report.sender_email.is_empty() {
+            text += &format!("From: {} {}\n", report.sender_name, report.sender_email);
+        }
+        text += &format!("OS: {}\n", report.client_os_info);
+        for (key, value) in report.metadata.iter() {
+            text += &format!("{}: {}\n", key, value);
+        }
+        text += &format!("\n{}", report.report_text);
+
+        // Telegram rejects messages that are too long.
+        if text.chars().count() > TELEGRAM_MAX_MESSAGE_LENGTH {
+            text = text.chars().take(TELEGRAM_MAX_MESSAGE_LENGTH - 1).collect();
+            text.push('…');
+        }
+
+        text
+    }
+    /// Creates a "multipart/form-data" body with the chat ID and the file.
+    fn create_multipart_body(
+        boundary: &str,
+        chat_id: &str,
+        field_name: &str,
+        attachment: &ReportAttachment,
+    ) -> Vec<u8> {
+        let mut body = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"chat_id\"\r\n\r\n{}\r\n",
+            boundary, chat_id
+        )
+        .into_bytes();
+
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                Content-Type: {}\r\n\r\n",
+                boundary,
+                field_name,
+                attachment.file_name.replace(['"', '\r', '\n'], "_"),
+                get_mime_type_from_file_name(&attachment.file_name)
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(&attachment.data);
+        body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+        body
+    }
+}
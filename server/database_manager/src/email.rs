@@ -0,0 +1,130 @@
+// Std.
+use std::path::PathBuf;
+use std::time::Duration;
+
+// External.
+use configparser::ini::Ini;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use platform_dirs::AppDirs;
+
+// Custom.
+use shared::misc::error::AppError;
+
+// ---------- should be the same as in the server --------------
+const SERVER_CONFIG_FILE_DIR: &str = "FBugReporter";
+const SERVER_CONFIG_FILE_NAME: &str = "server_config.ini";
+const SERVER_CONFIG_FORWARDING_SECTION_NAME: &str = "forwarding";
+const SERVER_CONFIG_SMTP_SERVER_PARAM: &str = "smtp_server";
+const SERVER_CONFIG_SMTP_USERNAME_PARAM: &str = "smtp_username";
+const SERVER_CONFIG_SMTP_PASSWORD_PARAM: &str = "smtp_password";
+// ---------------------------------------------------------
+
+const SMTP_TIMEOUT_IN_SEC: u64 = 30;
+
+/// SMTP settings from the `forwarding` section of the server config.
+pub struct SmtpSettings {
+    server: String,
+    /// Also used as the sender's email address.
+    username: String,
+    password: String,
+}
+
+impl SmtpSettings {
+    /// Reads SMTP settings from the server config.
+    ///
+    /// ## Return
+    /// `None` if the server config was not found or the SMTP server is not specified.
+    pub fn read_from_server_config() -> Option<Self> {
+        let mut config = Ini::new();
+        if config
+            .load(SmtpSettings::get_server_config_file_path()?)
+            .is_err()
+        {
+            return None;
+        }
+
+        let read = |key: &str| {
+            config
+                .get(SERVER_CONFIG_FORWARDING_SECTION_NAME, key)
+                .unwrap_or_default()
+        };
+
+        let server = read(SERVER_CONFIG_SMTP_SERVER_PARAM);
+        if server.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            server,
+            username: read(SERVER_CONFIG_SMTP_USERNAME_PARAM),
+            password: read(SERVER_CONFIG_SMTP_PASSWORD_PARAM),
+        })
+    }
+    /// Sends an email.
+    ///
+    /// ## Arguments
+    /// * `address`: email address of the receiver.
+    /// * `subject`: subject of the email.
+    /// * `body`: text of the email.
+    pub fn send_email(&self, address: &str, subject: &str, body: String) -> Result<(), AppError> {
+        let from = self.username.parse::<Mailbox>();
+        if let Err(e) = from {
+            return Err(AppError::new(&format!(
+                "SMTP username should be an email address: {}",
+                e
+            )));
+        }
+        let to = address.parse::<Mailbox>();
+        if let Err(e) = to {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let email = Message::builder()
+            .from(from.unwrap())
+            .to(to.unwrap())
+            .subject(subject)
+            .body(body);
+        if let Err(e) = email {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let transport = SmtpTransport::relay(&self.server);
+        if let Err(e) = transport {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let transport = transport
+            .unwrap()
+            .credentials(Credentials::new(
+                self.username.clone(),
+                self.password.clone(),
+            ))
+            .timeout(Some(Duration::from_secs(SMTP_TIMEOUT_IN_SEC)))
+            .build();
+
+        if let Err(e) = transport.send(&email.unwrap()) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Returns path to the server config (`None` if user directories can't be read).
+    fn get_server_config_file_path() -> Option<PathBuf> {
+        #[cfg(any(windows, unix))]
+        {
+            let mut config_path = AppDirs::new(Some(SERVER_CONFIG_FILE_DIR), true)?.config_dir;
+            config_path.push(SERVER_CONFIG_FILE_NAME);
+            Some(config_path)
+        }
+        #[cfg(not(any(windows, unix)))]
+        {
+            compile_error!("SmtpSettings is not implemented for this OS.");
+        }
+    }
+}
+
+/// Tells if the specified string is a valid email address.
+pub fn is_valid_email_address(address: &str) -> bool {
+    address.parse::<Mailbox>().is_ok()
+}
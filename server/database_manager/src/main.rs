@@ -4,12 +4,20 @@
 use std::env;
 use std::io;
 use std::io::*;
+use std::path::Path;
 
 // Custom.
+use email::{is_valid_email_address, SmtpSettings};
 use shared::misc::db_manager::*;
+use shared::misc::user::UserRole;
+
+mod email;
 
 const ERROR_LOG_PREFIX: &str = "ERROR: ";
 const INFO_LOG_PREFIX: &str = "INFO: ";
+const AUDIT_LOG_ACTOR: &str = "database_manager";
+/// Number of failed login attempts that `failed-logins` shows if no count was specified.
+const DEFAULT_FAILED_LOGIN_COUNT: u64 = 50;
 
 fn main() {
     println!(
@@ -43,165 +51,579 @@ fn main() {
         );
     }
 
-    let database_manager = DatabaseManager::new().unwrap_or_else(|e| panic!("{e}"));
+    let mut database_manager = DatabaseManager::new().unwrap_or_else(|e| panic!("{e}"));
 
     loop {
-        if let Err(e) = io::stdout().flush() {
-            println!("could not flush stdout (error: {}), continuing...", e);
-            continue;
-        }
-        let mut input = String::new();
-
-        if let Err(e) = io::stdin().read_line(&mut input) {
-            println!("unable to read input (error: {}), continuing...", e);
-            continue;
-        }
-
-        input.pop(); // pop '\n'
-        if cfg!(windows) {
-            input.pop(); // pop '\r'
-        }
+        let input = match read_line() {
+            Some(input) => input,
+            None => continue,
+        };
+        let (command, argument) = parse_command(&input);
 
-        if input == "help" {
+        if command == "help" {
             println!("\ncommands:");
-            println!("add-user <username> - adds a new user");
+            println!(
+                "add-user <username> - adds a new user (the password is sent to the user's email \
+                if SMTP is configured in the server config)"
+            );
+            println!("user-info <username> - shows user's role and email");
             println!("remove-user <username> - removes a user");
+            println!(
+                "set-user-role <username> <role> - changes user's role (viewer, triager or admin)"
+            );
+            println!(
+                "regenerate-otp-backup-codes <username> - generates new OTP backup codes for a user \
+                (old codes will no longer work)"
+            );
+            println!(
+                "reset-otp <username> - generates a new OTP secret for a user \
+                (the user will setup OTP again on the next login)"
+            );
+            println!("export <path> - exports all reports to a JSON file");
+            println!("import <path> - imports reports from a JSON file (created by 'export')");
+            println!(
+                "backup <path> - creates a copy of the database (even when the server is running)"
+            );
+            println!(
+                "expire-attachments <days> - removes data of attachments of reports that are \
+                older than the specified number of days (reports are kept)"
+            );
+            println!(
+                "optimize - shrinks the database file (reclaims space of removed data) \
+                and updates statistics of the database"
+            );
+            println!("audit-log - shows all recorded sensitive actions");
+            println!(
+                "failed-logins [count] - shows recent failed login attempts ({} by default)",
+                DEFAULT_FAILED_LOGIN_COUNT
+            );
             println!("exit - exit the application");
-        } else if input == "exit" {
+        } else if command == "exit" {
             break;
-        } else if input.contains("add-user ") {
-            let username_str: String = input
-                .chars()
-                .take(0)
-                .chain(input.chars().skip("add-user ".chars().count()))
-                .collect();
-
-            if username_str.is_empty() {
-                println!("username is empty");
-            } else {
-                println!("should this user be able to delete reports using the client application? (y/n)");
-                if let Err(e) = io::stdout().flush() {
-                    println!("could not flush stdout (error: {}), continuing...", e);
-                    continue;
-                }
-                if let Err(e) = io::stdin().read_line(&mut input) {
-                    println!("unable to read input (error: {}), continuing...", e);
+        } else if command == "add-user" {
+            let username = match parse_username(argument) {
+                Ok(username) => username,
+                Err(message) => {
+                    println!("{}", message);
                     continue;
                 }
+            };
 
-                input.pop(); // pop '\n'
-                if cfg!(windows) {
-                    input.pop(); // pop '\r'
-                }
-
-                input = String::from(
-                    input
-                        .strip_prefix(&format!("add-user {}", &username_str))
-                        .unwrap(),
-                );
+            println!(
+                "which role should this user have? (viewer - can only view reports, \
+                triager - can also change report priority, admin - can also delete reports)"
+            );
+            let input = match read_line() {
+                Some(input) => input,
+                None => continue,
+            };
+            let input = input.trim();
 
-                if input != "y" && input != "Y" && input != "n" && input != "N" {
-                    println!("'{}' is not a valid answer, try again...", input);
+            let role = match input.parse::<UserRole>() {
+                Ok(role) => role,
+                Err(_) => {
+                    println!("'{}' is not a valid role, try again...", input);
                     continue;
                 }
+            };
 
-                let mut is_admin = false;
-                if input == "y" || input == "Y" {
-                    is_admin = true;
-                }
+            println!("user's email (leave empty to skip):");
+            let input = match read_line() {
+                Some(input) => input,
+                None => continue,
+            };
+            let email = input.trim();
+            if !email.is_empty() && !is_valid_email_address(email) {
+                println!("'{}' is not a valid email, try again...", email);
+                continue;
+            }
 
-                let result = database_manager.add_user(&username_str, is_admin);
-                match result {
-                    AddUserResult::Ok { user_password } => {
+            let result = database_manager.add_user(username, role, email);
+            match result {
+                AddUserResult::Ok { user_password } => {
+                    add_audit_log_entry(&database_manager, AuditAction::AddUser, username);
+                    if email.is_empty() || !send_password_to_user(username, email, &user_password) {
                         println!(
                             "New user \"{}\" was registered, user's password is \"{}\".",
-                            username_str, user_password
+                            username, user_password
                         );
                     }
-                    AddUserResult::NameIsUsed => {
-                        println!(
-                            "A user with the username \"{}\" already exists in the database.",
-                            username_str,
-                        );
+                }
+                AddUserResult::NameIsUsed => {
+                    println!(
+                        "A user with the username \"{}\" already exists in the database.",
+                        username,
+                    );
+                }
+                AddUserResult::NameContainsForbiddenCharacters => {
+                    println!(
+                        "The username \"{}\" contains forbidden characters, \
+                        allowed characters: \"{}\".",
+                        username, USERNAME_CHARSET
+                    );
+                }
+                AddUserResult::Error(e) => {
+                    panic!("{} at [{}, {}]", e, file!(), line!());
+                }
+            }
+        } else if command == "user-info" {
+            let username = match parse_username(argument) {
+                Ok(username) => username,
+                Err(message) => {
+                    println!("{}", message);
+                    continue;
+                }
+            };
+
+            match database_manager.get_user_email(username) {
+                Ok(Some((email, is_email_verified))) => {
+                    match database_manager.get_user_role(username) {
+                        Ok(role) => println!("role: {}", role),
+                        Err(app_error) => println!("{}{}", ERROR_LOG_PREFIX, app_error),
                     }
-                    AddUserResult::NameContainsForbiddenCharacters => {
+                    if email.is_empty() {
+                        println!("email: not specified");
+                    } else if is_email_verified {
+                        println!("email: {} (verified)", email);
+                    } else {
+                        println!("email: {} (not verified)", email);
+                    }
+                }
+                Ok(None) => println!(
+                    "A user with the username \"{}\" was not found in the database.",
+                    username
+                ),
+                Err(app_error) => println!("{}{}", ERROR_LOG_PREFIX, app_error),
+            }
+        } else if command == "remove-user" {
+            let username = match parse_username(argument) {
+                Ok(username) => username,
+                Err(message) => {
+                    println!("{}", message);
+                    continue;
+                }
+            };
+
+            let remove_user_confirm_string = format!("remove user {}", username);
+            println!(
+                "Please, confirm the action, type: \"{}\"",
+                remove_user_confirm_string
+            );
+
+            let input = match read_line() {
+                Some(input) => input,
+                None => continue,
+            };
+            let input = input.trim();
+
+            if input == remove_user_confirm_string {
+                let result = database_manager.remove_user(username);
+                if let Err(app_error) = result {
+                    panic!("{} at [{}, {}]", app_error, file!(), line!());
+                } else {
+                    let result = result.unwrap();
+
+                    if result {
+                        add_audit_log_entry(&database_manager, AuditAction::RemoveUser, username);
+                        println!("The user \"{}\" was removed from the database.", username);
+                    } else {
                         println!(
-                            "The username \"{}\" contains forbidden characters, \
-                            allowed characters: \"{}\".",
-                            username_str, USERNAME_CHARSET
+                            "A user with the username \"{}\" was not found in the database.",
+                            username
                         );
                     }
-                    AddUserResult::Error(e) => {
-                        panic!("{} at [{}, {}]", e, file!(), line!());
-                    }
                 }
-            }
-        } else if input.contains("remove-user ") {
-            let username_str: String = input
-                .chars()
-                .take(0)
-                .chain(input.chars().skip("remove-user ".chars().count()))
-                .collect();
-
-            if username_str.is_empty() {
-                println!("username is empty");
             } else {
-                let remove_user_confirm_string = format!("remove user {}", &username_str);
                 println!(
-                    "Please, confirm the action, type: \"{}\"",
-                    remove_user_confirm_string
+                    "expected: {}\nreceived: {}",
+                    remove_user_confirm_string, input
                 );
-
-                if let Err(e) = io::stdout().flush() {
-                    println!("could not flush stdout (error: {}), continuing...", e);
+            }
+        } else if command == "regenerate-otp-backup-codes" {
+            let username = argument;
+            if username.is_empty() {
+                println!("username is empty");
+            } else {
+                match database_manager.regenerate_otp_backup_codes(username) {
+                    Ok(Some(backup_codes)) => {
+                        add_audit_log_entry(
+                            &database_manager,
+                            AuditAction::RegenerateOtpBackupCodes,
+                            username,
+                        );
+                        println!(
+                            "New OTP backup codes for the user \"{}\" (each code can be used once \
+                            instead of an OTP):",
+                            username
+                        );
+                        for backup_code in backup_codes {
+                            println!("{}", backup_code);
+                        }
+                    }
+                    Ok(None) => println!(
+                        "A user with the username \"{}\" was not found in the database.",
+                        username
+                    ),
+                    Err(app_error) => println!("{}{}", ERROR_LOG_PREFIX, app_error),
+                }
+            }
+        } else if command == "reset-otp" {
+            let username = match parse_username(argument) {
+                Ok(username) => username,
+                Err(message) => {
+                    println!("{}", message);
                     continue;
                 }
-                if let Err(e) = io::stdin().read_line(&mut input) {
-                    println!("unable to read input (error: {}), continuing...", e);
+            };
+
+            match database_manager.reset_user_otp(username) {
+                Ok(true) => {
+                    add_audit_log_entry(&database_manager, AuditAction::ResetOtp, username);
+                    println!(
+                        "OTP of the user \"{}\" was reset, the user will receive a new OTP QR code \
+                        on the next login (old OTP backup codes no longer work).",
+                        username
+                    );
+                }
+                Ok(false) => println!(
+                    "A user with the username \"{}\" was not found in the database.",
+                    username
+                ),
+                Err(app_error) => println!("{}{}", ERROR_LOG_PREFIX, app_error),
+            }
+        } else if command == "set-user-role" {
+            let mut args = argument.split_whitespace();
+            let (username, role) = match (args.next(), args.next(), args.next()) {
+                (Some(username), Some(role), None) => (username, role),
+                _ => {
+                    println!("expected: set-user-role <username> <role>");
                     continue;
                 }
+            };
 
-                input.pop(); // pop '\n'
-                if cfg!(windows) {
-                    input.pop(); // pop '\r'
+            let role = match role.parse::<UserRole>() {
+                Ok(role) => role,
+                Err(_) => {
+                    println!(
+                        "'{}' is not a valid role (expected viewer, triager or admin)",
+                        role
+                    );
+                    continue;
                 }
+            };
 
-                input = String::from(
-                    input
-                        .strip_prefix(&format!("remove-user {}", &username_str))
-                        .unwrap(),
-                );
+            match database_manager.set_user_role(username, role) {
+                Ok(true) => {
+                    add_audit_log_entry(
+                        &database_manager,
+                        AuditAction::SetUserRole,
+                        &format!("{} ({})", username, role),
+                    );
+                    println!("The user \"{}\" now has the role \"{}\".", username, role);
+                }
+                Ok(false) => println!(
+                    "A user with the username \"{}\" was not found in the database.",
+                    username
+                ),
+                Err(app_error) => println!("{}{}", ERROR_LOG_PREFIX, app_error),
+            }
+        } else if command == "audit-log" {
+            match database_manager.get_audit_log() {
+                Ok(entries) => {
+                    if entries.is_empty() {
+                        println!("The audit log is empty.");
+                    }
+                    for entry in entries {
+                        println!(
+                            "[{} {}] #{}: \"{}\" {} ({})",
+                            entry.date_created_at,
+                            entry.time_created_at,
+                            entry.id,
+                            entry.actor,
+                            entry.action,
+                            entry.target
+                        );
+                    }
+                }
+                Err(app_error) => println!("{}{}", ERROR_LOG_PREFIX, app_error),
+            }
+        } else if command == "failed-logins" {
+            let count = if argument.is_empty() {
+                DEFAULT_FAILED_LOGIN_COUNT
+            } else {
+                match argument.parse::<u64>() {
+                    Ok(count) => count,
+                    Err(_) => {
+                        println!("\"{}\" is not a valid number", argument);
+                        continue;
+                    }
+                }
+            };
 
-                if input == remove_user_confirm_string {
-                    let result = database_manager.remove_user(&username_str);
-                    if let Err(app_error) = result {
-                        panic!("{} at [{}, {}]", app_error, file!(), line!());
-                    } else {
-                        let result = result.unwrap();
-
-                        if result {
-                            println!(
-                                "The user \"{}\" was removed from the database.",
-                                username_str
-                            );
-                        } else {
-                            println!(
-                                "A user with the username \"{}\" was not found in the database.",
-                                username_str
-                            );
-                        }
+            match database_manager.get_recent_failed_logins(count) {
+                Ok(entries) => {
+                    if entries.is_empty() {
+                        println!("No failed login attempts were recorded.");
                     }
-                } else {
+                    for entry in entries {
+                        println!(
+                            "[{} {}] #{}: \"{}\" from {} ({})",
+                            entry.date_created_at,
+                            entry.time_created_at,
+                            entry.id,
+                            entry.username,
+                            entry.ip,
+                            entry.reason
+                        );
+                    }
+                }
+                Err(app_error) => println!("{}{}", ERROR_LOG_PREFIX, app_error),
+            }
+        } else if command == "expire-attachments" {
+            let days = match argument.parse::<u64>() {
+                Ok(days) => days,
+                Err(_) => {
+                    println!("expected: expire-attachments <days>");
+                    continue;
+                }
+            };
+
+            match database_manager.expire_old_attachments(days) {
+                Ok((count, freed_bytes)) => {
+                    add_audit_log_entry(
+                        &database_manager,
+                        AuditAction::ExpireAttachments,
+                        &format!("older than {} day(-s)", days),
+                    );
                     println!(
-                        "expected: {}\nreceived: {}",
-                        remove_user_confirm_string, input
+                        "{}Expired {} attachment(-s) ({} KB).",
+                        INFO_LOG_PREFIX,
+                        count,
+                        freed_bytes / 1024
                     );
+
+                    if count != 0 {
+                        optimize_database(&database_manager);
+                    }
+                }
+                Err(app_error) => println!("{}{}", ERROR_LOG_PREFIX, app_error),
+            }
+        } else if command == "optimize" {
+            optimize_database(&database_manager);
+        } else if command == "export" {
+            let path = argument;
+            if path.is_empty() {
+                println!("path is empty");
+            } else {
+                println!("{}Exporting reports to '{}'...", INFO_LOG_PREFIX, path);
+                match database_manager.export_all_reports(Path::new(path)) {
+                    Ok(count) => println!("{}Exported {} report(s).", INFO_LOG_PREFIX, count),
+                    Err(app_error) => println!("{}{}", ERROR_LOG_PREFIX, app_error),
+                }
+            }
+        } else if command == "import" {
+            let path = argument;
+            if path.is_empty() {
+                println!("path is empty");
+            } else {
+                println!("{}Importing reports from '{}'...", INFO_LOG_PREFIX, path);
+                match database_manager.import_reports(Path::new(path)) {
+                    Ok(count) => println!("{}Imported {} report(s).", INFO_LOG_PREFIX, count),
+                    Err(app_error) => println!("{}{}", ERROR_LOG_PREFIX, app_error),
                 }
             }
-        } else {
-            println!("command '{}' not found", input);
+        } else if command == "backup" {
+            let path = argument;
+            if path.is_empty() {
+                println!("path is empty");
+            } else {
+                match database_manager.backup(Path::new(path)) {
+                    Ok(size_in_bytes) => println!(
+                        "{}Database backup was saved to '{}' ({} KB).",
+                        INFO_LOG_PREFIX,
+                        path,
+                        size_in_bytes / 1024
+                    ),
+                    Err(app_error) => println!("{}{}", ERROR_LOG_PREFIX, app_error),
+                }
+            }
+        } else if !command.is_empty() {
+            println!("command '{}' not found", command);
         }
 
         println!();
     }
 }
+
+/// Shrinks the database file and prints its size before and after.
+fn optimize_database(database_manager: &DatabaseManager) {
+    println!(
+        "{}Optimizing the database, this requires exclusive access to the database \
+        (stop the server first) and free disk space of about the size of the database...",
+        INFO_LOG_PREFIX
+    );
+
+    match database_manager.optimize() {
+        Ok((size_before, size_after)) => println!(
+            "{}Database size: {} KB -> {} KB.",
+            INFO_LOG_PREFIX,
+            size_before / 1024,
+            size_after / 1024
+        ),
+        Err(app_error) => println!(
+            "{}Failed to optimize the database (make sure the server is not running): {}",
+            ERROR_LOG_PREFIX, app_error
+        ),
+    }
+}
+
+/// Sends the password of the new user to the user's email (if SMTP is configured
+/// in the server config).
+///
+/// ## Return
+/// `false` if the password was not sent and should be delivered to the user in another way.
+fn send_password_to_user(username: &str, email: &str, user_password: &str) -> bool {
+    let smtp_settings = match SmtpSettings::read_from_server_config() {
+        Some(smtp_settings) => smtp_settings,
+        None => {
+            println!(
+                "{}SMTP server is not specified in the server config, \
+                the password will not be sent to the user's email.",
+                INFO_LOG_PREFIX
+            );
+            return false;
+        }
+    };
+
+    let result = smtp_settings.send_email(
+        email,
+        "[FBugReporter] Your account",
+        format!(
+            "An account with the username \"{}\" was created for you.\n\n\
+            Your temporary password is \"{}\". Use it to login in the client, after that \
+            you will be asked to set a new password (this also confirms your email).",
+            username, user_password
+        ),
+    );
+    if let Err(app_error) = result {
+        println!(
+            "{}failed to send the password to the user's email: {}",
+            ERROR_LOG_PREFIX, app_error
+        );
+        return false;
+    }
+
+    println!(
+        "New user \"{}\" was registered, the password was sent to \"{}\".",
+        username, email
+    );
+
+    true
+}
+
+/// Records an action made using this application in the audit log.
+///
+/// Errors are printed but not treated as fatal because the action
+/// was already made.
+fn add_audit_log_entry(database_manager: &DatabaseManager, action: AuditAction, target: &str) {
+    // Include OS user name (if available) to know who used the database manager.
+    let actor = match env::var("USER").or_else(|_| env::var("USERNAME")) {
+        Ok(os_username) => format!("{} ({})", AUDIT_LOG_ACTOR, os_username),
+        Err(_) => String::from(AUDIT_LOG_ACTOR),
+    };
+
+    if let Err(app_error) = database_manager.add_audit_log_entry(&actor, action, target) {
+        println!(
+            "{}failed to add an audit log entry: {}",
+            ERROR_LOG_PREFIX, app_error
+        );
+    }
+}
+
+/// Reads a line from stdin (without the line ending).
+///
+/// ## Return
+/// `None` if failed to read the input (the error is printed).
+fn read_line() -> Option<String> {
+    if let Err(e) = io::stdout().flush() {
+        println!("could not flush stdout (error: {}), continuing...", e);
+        return None;
+    }
+
+    let mut input = String::new();
+    if let Err(e) = io::stdin().read_line(&mut input) {
+        println!("unable to read input (error: {}), continuing...", e);
+        return None;
+    }
+
+    Some(input.trim_end_matches(['\n', '\r']).to_string())
+}
+
+/// Splits the input into a command and its argument (both without surrounding spaces).
+///
+/// ## Example
+/// `"  add-user   john "` is split into `("add-user", "john")`.
+fn parse_command(input: &str) -> (&str, &str) {
+    let input = input.trim();
+
+    match input.split_once(char::is_whitespace) {
+        Some((command, argument)) => (command, argument.trim()),
+        None => (input, ""),
+    }
+}
+
+/// Checks that the argument of a command is a valid username.
+///
+/// ## Return
+/// Error message to show if the username is empty or contains forbidden characters.
+fn parse_username(argument: &str) -> std::result::Result<&str, String> {
+    if argument.is_empty() {
+        return Err(String::from("username is empty"));
+    }
+
+    if !argument.chars().all(|c| USERNAME_CHARSET.contains(c)) {
+        return Err(format!(
+            "The username \"{}\" contains forbidden characters, allowed characters: \"{}\".",
+            argument, USERNAME_CHARSET
+        ));
+    }
+
+    Ok(argument)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_command_with_spaces() {
+        assert_eq!(parse_command("add-user   spacey "), ("add-user", "spacey"));
+        assert_eq!(
+            parse_command("  remove-user\tjohn"),
+            ("remove-user", "john")
+        );
+        assert_eq!(parse_command("add-user john.doe"), ("add-user", "john.doe"));
+    }
+
+    #[test]
+    fn parse_command_without_argument() {
+        assert_eq!(parse_command("help"), ("help", ""));
+        assert_eq!(parse_command(" exit  "), ("exit", ""));
+        assert_eq!(parse_command("add-user "), ("add-user", ""));
+        assert_eq!(parse_command(""), ("", ""));
+    }
+
+    #[test]
+    fn parse_valid_username() {
+        let (_, argument) = parse_command("add-user   spacey ");
+        assert_eq!(parse_username(argument), Ok("spacey"));
+    }
+
+    #[test]
+    fn parse_invalid_username() {
+        assert!(parse_username("").is_err());
+        assert!(parse_username("john doe").is_err());
+        assert!(parse_username("\"john\"").is_err());
+        assert!(parse_username("john;").is_err());
+    }
+}
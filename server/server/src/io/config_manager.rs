@@ -1,20 +1,58 @@
 // Std.
-use std::{fs::create_dir_all, ops::Range, path::PathBuf, str::FromStr};
+use std::{
+    collections::BTreeMap,
+    fs::create_dir_all,
+    net::{IpAddr, Ipv4Addr},
+    ops::{Range, RangeInclusive},
+    path::PathBuf,
+    str::FromStr,
+};
 
 // External.
 use configparser::ini::Ini;
 use platform_dirs::{AppDirs, UserDirs};
 use rand::Rng;
+use regex::{Regex, RegexBuilder};
 
 // Custom.
-use super::log_manager::LOG_FILE_NAME;
+use super::log_manager::{LogBackend, LogCategory, LOG_FILE_NAME};
+use crate::network::ban_manager::BanConfig;
+use crate::network::report_forwarder::{ForwardingRoute, ForwardingTarget};
+use shared::misc::db_manager::{OtpAlgorithm, OtpParameters};
 use shared::misc::error::AppError;
+use shared::misc::password_policy::PasswordPolicy;
+use shared::misc::report::ReportLimits;
+use shared::network::dh_group::DhGroup;
+use shared::network::proof_of_work::MAX_PROOF_OF_WORK_DIFFICULTY;
 
 const RANDOM_PORT_RANGE: Range<u16> = 7000..65535;
+/// Separates game name and value in per-game parameters.
+const PER_GAME_VALUE_SEPARATOR: &str = "=>";
 
 const DEFAULT_MAX_ALLOWED_LOGIN_ATTEMPTS: u32 = 3;
 const DEFAULT_BAN_TIME_DURATION_IN_MIN: i64 = 5;
+const DEFAULT_BAN_ALERT_COOLDOWN_IN_MIN: u64 = 15;
+const DEFAULT_MAX_PASSWORD_AGE_IN_DAYS: u64 = 0;
+const DEFAULT_REQUIRE_OTP: bool = true;
+const DEFAULT_PASSWORD_MIN_LENGTH: usize = 8;
+const DEFAULT_PASSWORD_REQUIRE_LETTERS_AND_DIGITS: bool = true;
+const DEFAULT_REJECT_COMMON_PASSWORDS: bool = true;
 const DEFAULT_MAX_ATTACHMENT_SIZE_IN_MB: usize = 5;
+const DEFAULT_MAX_CONCURRENT_REPORTER_CONNECTIONS: usize = 50;
+const DEFAULT_MAX_CONCURRENT_CLIENT_CONNECTIONS: usize = 10;
+const DEFAULT_CLIENT_INACTIVITY_TIMEOUT_IN_MIN: u64 = 30;
+const DEFAULT_OTP_VERIFICATION_WINDOW: u8 = 1;
+const DEFAULT_FORWARDING_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_FORWARDING_RETRY_INTERVAL_IN_SEC: u64 = 60;
+const DEFAULT_WEBHOOK_MAX_ATTEMPTS: u32 = 10;
+const DEFAULT_WEBHOOK_INITIAL_RETRY_DELAY_IN_SEC: u64 = 30;
+const DEFAULT_LOW_DISK_SPACE_THRESHOLD_IN_MB: u64 = 1024;
+const DEFAULT_DISK_SPACE_CHECK_INTERVAL_IN_SEC: u64 = 600;
+const DEFAULT_TRACING_SERVICE_NAME: &str = "fbugreporter-server";
+const ALLOWED_OTP_DIGITS: RangeInclusive<usize> = 6..=8;
+
+const API_TOKEN_LENGTH: usize = 40;
+const API_TOKEN_CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
 
 const CONFIG_FILE_DIR: &str = "FBugReporter";
 const CONFIG_FILE_NAME: &str = "server_config.ini";
@@ -24,20 +62,248 @@ const CONFIG_SERVER_SECTION_NAME: &str = "server";
 const CONFIG_PORT_REPORTER_PARAM: &str = "port_for_reporters";
 const CONFIG_PORT_CLIENT_PARAM: &str = "port_for_clients";
 const CONFIG_MAX_ATTACHMENT_SIZE_IN_MB_PARAM: &str = "max_total_attachment_size_in_mb";
+const CONFIG_PER_GAME_MAX_ATTACHMENT_SIZE_IN_MB_PARAM: &str =
+    "per_game_max_total_attachment_size_in_mb";
+const CONFIG_MAX_CONCURRENT_REPORTER_CONNECTIONS_PARAM: &str =
+    "max_concurrent_reporter_connections";
+const CONFIG_MAX_CONCURRENT_CLIENT_CONNECTIONS_PARAM: &str = "max_concurrent_client_connections";
+const CONFIG_CLIENT_INACTIVITY_TIMEOUT_PARAM: &str = "client_inactivity_timeout_in_min";
+const CONFIG_CLIENT_KEEP_ALIVE_RESETS_INACTIVITY_PARAM: &str =
+    "client_keep_alive_resets_inactivity";
+const CONFIG_DH_GROUP_PARAM: &str = "dh_group";
 // --------------- server section end ---------------
+// --------------- reports section start ---------------
+const CONFIG_REPORTS_SECTION_NAME: &str = "reports";
+const CONFIG_ACCEPTED_GAME_NAMES_PARAM: &str = "accepted_game_names";
+const CONFIG_MIN_ACCEPTED_GAME_VERSION_PARAM: &str = "min_accepted_game_version";
+const CONFIG_BLOCKED_GAME_VERSIONS_PARAM: &str = "blocked_game_versions";
+const CONFIG_ENABLE_SPAM_FILTER_PARAM: &str = "enable_spam_filter";
+const CONFIG_SPAM_FILTER_BLOCKED_WORDS_PARAM: &str = "spam_filter_blocked_words";
+const CONFIG_SPAM_FILTER_BLOCKED_REGEX_PARAM: &str = "spam_filter_blocked_regex";
+const CONFIG_PROOF_OF_WORK_DIFFICULTY_PARAM: &str = "reporter_proof_of_work_difficulty";
+const CONFIG_SENDER_EMAIL_COOLDOWN_PARAM: &str = "sender_email_report_cooldown_in_sec";
+const CONFIG_MAX_REPORT_COUNT_PARAM: &str = "max_report_count";
+const CONFIG_MIN_REPORT_TEXT_LENGTH_PARAM: &str = "min_report_text_length";
+// --------------- reports section end ---------------
+// --------------- field rules section start ---------------
+const CONFIG_FIELD_RULES_SECTION_NAME: &str = "field_rules";
+/// Parameters with regex rules and report fields that they are applied to.
+const CONFIG_FIELD_RULE_PARAMS: [(&str, ReportLimits); 6] = [
+    ("report_name_regex", ReportLimits::ReportName),
+    ("report_text_regex", ReportLimits::ReportText),
+    ("sender_name_regex", ReportLimits::SenderName),
+    ("sender_email_regex", ReportLimits::SenderEmail),
+    ("game_name_regex", ReportLimits::GameName),
+    ("game_version_regex", ReportLimits::GameVersion),
+];
+// --------------- field rules section end ---------------
+// --------------- forwarding section start ---------------
+const CONFIG_FORWARDING_SECTION_NAME: &str = "forwarding";
+const CONFIG_FORWARDING_ROUTES_PARAM: &str = "routes";
+const CONFIG_FORWARDING_GITHUB_TOKEN_PARAM: &str = "github_token";
+const CONFIG_FORWARDING_SMTP_SERVER_PARAM: &str = "smtp_server";
+const CONFIG_FORWARDING_SMTP_USERNAME_PARAM: &str = "smtp_username";
+const CONFIG_FORWARDING_SMTP_PASSWORD_PARAM: &str = "smtp_password";
+const CONFIG_FORWARDING_MAX_ATTEMPTS_PARAM: &str = "max_attempts";
+const CONFIG_FORWARDING_RETRY_INTERVAL_PARAM: &str = "retry_interval_in_sec";
+const CONFIG_WEBHOOK_URL_PARAM: &str = "webhook_url";
+const CONFIG_WEBHOOK_MAX_ATTEMPTS_PARAM: &str = "webhook_max_attempts";
+const CONFIG_WEBHOOK_INITIAL_RETRY_DELAY_PARAM: &str = "webhook_initial_retry_delay_in_sec";
+// --------------- forwarding section end ---------------
+// --------------- rest api section start ---------------
+const CONFIG_REST_API_SECTION_NAME: &str = "rest_api";
+const CONFIG_ENABLE_REST_API_PARAM: &str = "enable_rest_api";
+const CONFIG_REST_API_PORT_PARAM: &str = "port";
+const CONFIG_REST_API_TOKEN_PARAM: &str = "token";
+const CONFIG_REST_API_FEED_TOKEN_PARAM: &str = "feed_token";
+// --------------- rest api section end ---------------
+// --------------- metrics section start ---------------
+const CONFIG_METRICS_SECTION_NAME: &str = "metrics";
+const CONFIG_ENABLE_METRICS_PARAM: &str = "enable_metrics";
+const CONFIG_METRICS_PORT_PARAM: &str = "port";
+// --------------- metrics section end ---------------
+// --------------- HTTP transport section start ---------------
+const CONFIG_HTTP_TRANSPORT_SECTION_NAME: &str = "http_transport";
+const CONFIG_ENABLE_HTTP_TRANSPORT_PARAM: &str = "enable_http_transport";
+const CONFIG_HTTP_TRANSPORT_PORT_PARAM: &str = "port";
+const CONFIG_HTTP_TRANSPORT_TRUST_FORWARDED_FOR_PARAM: &str = "trust_x_forwarded_for";
+// --------------- HTTP transport section end ---------------
+// --------------- tracing section start ---------------
+const CONFIG_TRACING_SECTION_NAME: &str = "tracing";
+const CONFIG_TRACING_OTLP_ENDPOINT_PARAM: &str = "otlp_endpoint";
+const CONFIG_TRACING_SERVICE_NAME_PARAM: &str = "service_name";
+// --------------- tracing section end ---------------
+// --------------- disk space section start ---------------
+const CONFIG_DISK_SPACE_SECTION_NAME: &str = "disk_space";
+const CONFIG_LOW_DISK_SPACE_THRESHOLD_PARAM: &str = "low_space_threshold_in_mb";
+const CONFIG_DISK_SPACE_CHECK_INTERVAL_PARAM: &str = "check_interval_in_sec";
+const CONFIG_LOW_DISK_SPACE_ALERT_PARAM: &str = "alert_target";
+// --------------- disk space section end ---------------
+// --------------- logging section start ---------------
+const CONFIG_LOGGING_SECTION_NAME: &str = "logging";
+const CONFIG_LOG_BACKEND_PARAM: &str = "backend";
+const CONFIG_MIN_LOG_CATEGORY_PARAM: &str = "min_category";
+// --------------- logging section end ---------------
 // --------------- login section start ---------------
 const CONFIG_LOGIN_SECTION_NAME: &str = "login";
 const CONFIG_MAX_ALLOWED_LOGIN_ATTEMPTS_PARAM: &str = "max_allowed_login_attempts_until_ban";
 const CONFIG_BAN_TIME_DURATION_IN_MIN: &str = "ban_time_duration_in_min";
+const CONFIG_BAN_ALERT_PARAM: &str = "ban_alert_target";
+const CONFIG_BAN_ALERT_COOLDOWN_PARAM: &str = "ban_alert_cooldown_in_min";
+const CONFIG_MAX_PASSWORD_AGE_PARAM: &str = "max_password_age_in_days";
+const CONFIG_REQUIRE_OTP_PARAM: &str = "require_otp";
+const CONFIG_PASSWORD_MIN_LENGTH_PARAM: &str = "password_min_length";
+const CONFIG_PASSWORD_REQUIRE_LETTERS_AND_DIGITS_PARAM: &str =
+    "password_require_letters_and_digits";
+const CONFIG_REJECT_COMMON_PASSWORDS_PARAM: &str = "reject_common_passwords";
+const CONFIG_OTP_ALGORITHM_PARAM: &str = "otp_algorithm";
+const CONFIG_OTP_DIGITS_PARAM: &str = "otp_digits";
+const CONFIG_OTP_PERIOD_IN_SEC_PARAM: &str = "otp_period_in_sec";
+const CONFIG_OTP_VERIFICATION_WINDOW_PARAM: &str = "otp_verification_window_in_steps";
 // --------------- login section end ---------------
 
+/// Regex that a report field should match.
+#[derive(Debug)]
+pub struct ReportFieldRule {
+    pub field: ReportLimits,
+    /// Regex as specified in the config (empty if not used).
+    pub pattern: String,
+    /// Compiled `pattern` (`None` if empty or invalid).
+    pub regex: Option<Regex>,
+}
+
 #[derive(Debug)]
 pub struct ConfigManager {
+    /// Address that all sockets are bound to (not stored in the config,
+    /// can be changed using the `--address` command-line option).
+    pub bind_address: IpAddr,
     pub port_for_reporters: u16,
     pub port_for_clients: u16,
     pub max_attachment_size_in_mb: usize,
+    /// Per-game attachment size limits as specified in the config
+    /// (for example: `Game A => 500, Game B => 10`).
+    pub per_game_max_attachment_size_list: String,
+    /// Parsed `per_game_max_attachment_size_list` (game name - limit in MB).
+    pub per_game_max_attachment_size_in_mb: BTreeMap<String, usize>,
+    pub max_concurrent_reporter_connections: usize,
+    pub max_concurrent_client_connections: usize,
+    /// Clients that did not send anything during this time are disconnected
+    /// (`0` to never disconnect inactive clients).
+    pub client_inactivity_timeout_in_min: u64,
+    /// Whether keep-alive messages that clients send while they are open
+    /// prevent the inactivity timeout (if `false` only user actions do).
+    pub client_keep_alive_resets_inactivity: bool,
+    /// Diffie-Hellman group that is used to establish secure connections
+    /// with reporters and clients.
+    pub dh_group: DhGroup,
+    /// Reports from games with other names are rejected (empty if all games are accepted).
+    pub accepted_game_names: Vec<String>,
+    /// Reports from game versions lower than this one are rejected
+    /// (empty if all versions are accepted).
+    pub min_accepted_game_version: String,
+    /// Reports from these game versions are rejected.
+    pub blocked_game_versions: Vec<String>,
+    pub enable_spam_filter: bool,
+    /// Reports that contain any of these words (case-insensitive) are rejected.
+    pub spam_filter_blocked_words: Vec<String>,
+    /// Reports that match this regex (case-insensitive) are rejected (empty if not used).
+    pub spam_filter_blocked_regex: String,
+    /// Compiled `spam_filter_blocked_regex` (`None` if empty or invalid).
+    pub spam_filter_regex: Option<Regex>,
+    /// Number of leading zero bits that reporters need to find in a hash
+    /// before sending a report (`0` if proof of work is disabled).
+    pub reporter_proof_of_work_difficulty: u8,
+    /// Minimum time between two reports with the same sender email (`0` if not limited).
+    pub sender_email_report_cooldown_in_sec: u64,
+    /// When a new report would exceed this number of stored reports
+    /// the oldest reports are removed (`0` if not limited).
+    pub max_report_count: u64,
+    /// Minimum amount of characters in the report text (`0` if not limited).
+    pub min_report_text_length: usize,
+    /// Regex rules of report fields (one for each field from `CONFIG_FIELD_RULE_PARAMS`).
+    pub report_field_rules: Vec<ReportFieldRule>,
+    /// Forwarding routes as specified in the config (see `ForwardingRoute::parse_routes`).
+    pub forwarding_route_list: String,
+    /// Parsed `forwarding_route_list` (empty if not used or invalid).
+    pub forwarding_routes: Vec<ForwardingRoute>,
+    /// Token used to create GitHub issues.
+    pub forwarding_github_token: String,
+    /// SMTP server used to send emails.
+    pub forwarding_smtp_server: String,
+    /// SMTP username (also used as the sender's email address).
+    pub forwarding_smtp_username: String,
+    pub forwarding_smtp_password: String,
+    /// Maximum number of attempts to forward a report to a receiver.
+    pub forwarding_max_attempts: u32,
+    /// Time to wait before retrying to forward a report.
+    pub forwarding_retry_interval_in_sec: u64,
+    /// URL that all new reports are sent to (empty if not used).
+    pub webhook_url: String,
+    /// Maximum number of attempts to send a report to the webhook.
+    pub webhook_max_attempts: u32,
+    /// Time to wait before the first retry (doubles after each failed attempt).
+    pub webhook_initial_retry_delay_in_sec: u64,
+    /// Whether to start the HTTP server with read-only access to reports.
+    pub enable_rest_api: bool,
+    pub rest_api_port: u16,
+    /// Token that REST API requests should specify in the `Authorization` header.
+    pub rest_api_token: String,
+    /// Token that requests to the feed of new reports should specify in the `token`
+    /// query parameter (feed readers can't set headers).
+    pub rest_api_feed_token: String,
+    /// Whether to start the HTTP server that provides metrics in Prometheus format.
+    pub enable_metrics: bool,
+    pub metrics_port: u16,
+    /// Whether to also accept reporter and client messages in bodies of HTTP requests
+    /// (for reporters that can only reach the server through HTTP proxies).
+    pub enable_http_transport: bool,
+    pub http_transport_port: u16,
+    /// Whether to take the address of the remote entity from the `X-Forwarded-For` header
+    /// of HTTP transport requests (only enable when the server is behind a reverse proxy).
+    pub http_transport_trust_x_forwarded_for: bool,
+    /// URL of the OTLP/HTTP collector that trace spans are exported to
+    /// (for example: `http://localhost:4318/v1/traces`, empty to disable tracing).
+    pub tracing_otlp_endpoint: String,
+    /// Name of the service that is attached to exported trace spans.
+    pub tracing_service_name: String,
+    /// A warning is logged when free disk space drops below this value (`0` to disable).
+    pub low_disk_space_threshold_in_mb: u64,
+    pub disk_space_check_interval_in_sec: u64,
+    /// Where to send an alert when disk space runs low as specified in the config
+    /// (for example: `email:admin@example.com`, empty if not used).
+    pub low_disk_space_alert_target: String,
+    /// Parsed `low_disk_space_alert_target` (`None` if not used or invalid).
+    pub low_disk_space_alert: Option<ForwardingTarget>,
+    /// Where logs are written to (`file` or `syslog`).
+    pub log_backend: LogBackend,
+    /// Log entries of less important categories are ignored.
+    pub min_log_category: LogCategory,
     pub max_allowed_login_attempts: u32,
     pub ban_time_duration_in_min: i64,
+    /// Where to send an alert when an IP is banned as specified in the config
+    /// (for example: `email:admin@example.com`, empty if not used).
+    pub ban_alert_target: String,
+    /// Parsed `ban_alert_target` (`None` if not used or invalid).
+    pub ban_alert: Option<ForwardingTarget>,
+    /// Minimum time between two ban alerts (bans during this time are reported in the next alert).
+    pub ban_alert_cooldown_in_min: u64,
+    /// Users need to change their password once it becomes older than this value
+    /// (`0` if passwords never expire).
+    pub max_password_age_in_days: u64,
+    /// Whether users need to enter OTP (after the password) to login.
+    /// If disabled, users are logged in right after password verification
+    /// (even if they already setup OTP).
+    pub require_otp: bool,
+    /// Minimum number of characters in new passwords.
+    pub password_min_length: usize,
+    /// Whether new passwords should contain letters and digits.
+    pub password_require_letters_and_digits: bool,
+    /// Whether commonly used passwords are rejected.
+    pub reject_common_passwords: bool,
+    pub otp_algorithm: OtpAlgorithm,
+    pub otp_digits: usize,
+    pub otp_period_in_sec: u64,
+    pub otp_verification_window: u8,
     pub config_file_path: PathBuf,
     pub log_file_path: PathBuf,
 }
@@ -75,15 +341,157 @@ impl ConfigManager {
 
         server_config
     }
+    /// Returns OTP parameters that will be used for users that did not setup OTP yet.
+    pub fn get_otp_parameters(&self) -> OtpParameters {
+        OtpParameters {
+            algorithm: self.otp_algorithm,
+            digits: self.otp_digits,
+            period_in_sec: self.otp_period_in_sec,
+        }
+    }
+    /// Returns requirements for new passwords.
+    pub fn get_password_policy(&self) -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: self.password_min_length,
+            require_letters_and_digits: self.password_require_letters_and_digits,
+            reject_common_passwords: self.reject_common_passwords,
+        }
+    }
+    /// Returns maximum allowed size of attachments (in total) for reports of the specified game.
+    pub fn get_max_attachment_size_in_mb(&self, game_name: &str) -> usize {
+        *self
+            .per_game_max_attachment_size_in_mb
+            .get(game_name)
+            .unwrap_or(&self.max_attachment_size_in_mb)
+    }
+    /// Returns the largest attachment size limit among all games.
+    pub fn get_largest_max_attachment_size_in_mb(&self) -> usize {
+        self.per_game_max_attachment_size_in_mb
+            .values()
+            .copied()
+            .fold(self.max_attachment_size_in_mb, usize::max)
+    }
+    /// Saves ban settings to the config file without changing other values in the file.
+    ///
+    /// ## Remarks
+    /// Values of this object are not changed (the ban manager stores the current settings).
+    pub fn save_ban_config(&self, ban_config: &BanConfig) -> Result<(), AppError> {
+        let mut config = Ini::new();
+        if let Err(e) = config.load(&self.config_file_path) {
+            return Err(AppError::new(&e));
+        }
+
+        // Max allowed login attempts until ban.
+        config.set(
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_MAX_ALLOWED_LOGIN_ATTEMPTS_PARAM,
+            Some(ban_config.max_allowed_login_attempts.to_string()),
+        );
+
+        // Ban time duration.
+        config.set(
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_BAN_TIME_DURATION_IN_MIN,
+            Some(ban_config.ban_time_duration_in_min.to_string()),
+        );
+
+        // Write to disk.
+        if let Err(e) = config.write(&self.config_file_path) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
     fn default() -> Self {
-        let port_for_reporters = ConfigManager::generate_random_port(0);
-        let port_for_clients = ConfigManager::generate_random_port(port_for_reporters);
+        let port_for_reporters = ConfigManager::generate_random_port(&[]);
+        let port_for_clients = ConfigManager::generate_random_port(&[port_for_reporters]);
+        let rest_api_port =
+            ConfigManager::generate_random_port(&[port_for_reporters, port_for_clients]);
+        let metrics_port = ConfigManager::generate_random_port(&[
+            port_for_reporters,
+            port_for_clients,
+            rest_api_port,
+        ]);
+        let http_transport_port = ConfigManager::generate_random_port(&[
+            port_for_reporters,
+            port_for_clients,
+            rest_api_port,
+            metrics_port,
+        ]);
+        let otp_parameters = OtpParameters::default();
         Self {
+            bind_address: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
             port_for_reporters,
             port_for_clients,
             max_attachment_size_in_mb: DEFAULT_MAX_ATTACHMENT_SIZE_IN_MB,
+            per_game_max_attachment_size_list: String::new(),
+            per_game_max_attachment_size_in_mb: BTreeMap::new(),
+            max_concurrent_reporter_connections: DEFAULT_MAX_CONCURRENT_REPORTER_CONNECTIONS,
+            max_concurrent_client_connections: DEFAULT_MAX_CONCURRENT_CLIENT_CONNECTIONS,
+            client_inactivity_timeout_in_min: DEFAULT_CLIENT_INACTIVITY_TIMEOUT_IN_MIN,
+            client_keep_alive_resets_inactivity: true,
+            dh_group: DhGroup::default(),
+            accepted_game_names: Vec::new(),
+            min_accepted_game_version: String::new(),
+            blocked_game_versions: Vec::new(),
+            enable_spam_filter: true,
+            spam_filter_blocked_words: Vec::new(),
+            spam_filter_blocked_regex: String::new(),
+            spam_filter_regex: None,
+            reporter_proof_of_work_difficulty: 0,
+            sender_email_report_cooldown_in_sec: 0,
+            max_report_count: 0,
+            min_report_text_length: ReportLimits::ReportText.default_min_length(),
+            report_field_rules: CONFIG_FIELD_RULE_PARAMS
+                .iter()
+                .map(|(_, field)| ReportFieldRule {
+                    field: *field,
+                    pattern: String::new(),
+                    regex: None,
+                })
+                .collect(),
+            forwarding_route_list: String::new(),
+            forwarding_routes: Vec::new(),
+            forwarding_github_token: String::new(),
+            forwarding_smtp_server: String::new(),
+            forwarding_smtp_username: String::new(),
+            forwarding_smtp_password: String::new(),
+            forwarding_max_attempts: DEFAULT_FORWARDING_MAX_ATTEMPTS,
+            forwarding_retry_interval_in_sec: DEFAULT_FORWARDING_RETRY_INTERVAL_IN_SEC,
+            webhook_url: String::new(),
+            webhook_max_attempts: DEFAULT_WEBHOOK_MAX_ATTEMPTS,
+            webhook_initial_retry_delay_in_sec: DEFAULT_WEBHOOK_INITIAL_RETRY_DELAY_IN_SEC,
+            enable_rest_api: false,
+            rest_api_port,
+            rest_api_token: ConfigManager::generate_random_api_token(),
+            rest_api_feed_token: ConfigManager::generate_random_api_token(),
+            enable_metrics: false,
+            metrics_port,
+            enable_http_transport: false,
+            http_transport_port,
+            http_transport_trust_x_forwarded_for: false,
+            tracing_otlp_endpoint: String::new(),
+            tracing_service_name: String::from(DEFAULT_TRACING_SERVICE_NAME),
+            low_disk_space_threshold_in_mb: DEFAULT_LOW_DISK_SPACE_THRESHOLD_IN_MB,
+            disk_space_check_interval_in_sec: DEFAULT_DISK_SPACE_CHECK_INTERVAL_IN_SEC,
+            low_disk_space_alert_target: String::new(),
+            low_disk_space_alert: None,
+            log_backend: LogBackend::File,
+            min_log_category: LogCategory::Info,
             max_allowed_login_attempts: DEFAULT_MAX_ALLOWED_LOGIN_ATTEMPTS,
             ban_time_duration_in_min: DEFAULT_BAN_TIME_DURATION_IN_MIN,
+            ban_alert_target: String::new(),
+            ban_alert: None,
+            ban_alert_cooldown_in_min: DEFAULT_BAN_ALERT_COOLDOWN_IN_MIN,
+            max_password_age_in_days: DEFAULT_MAX_PASSWORD_AGE_IN_DAYS,
+            require_otp: DEFAULT_REQUIRE_OTP,
+            password_min_length: DEFAULT_PASSWORD_MIN_LENGTH,
+            password_require_letters_and_digits: DEFAULT_PASSWORD_REQUIRE_LETTERS_AND_DIGITS,
+            reject_common_passwords: DEFAULT_REJECT_COMMON_PASSWORDS,
+            otp_algorithm: otp_parameters.algorithm,
+            otp_digits: otp_parameters.digits,
+            otp_period_in_sec: otp_parameters.period_in_sec,
+            otp_verification_window: DEFAULT_OTP_VERIFICATION_WINDOW,
             config_file_path: ConfigManager::get_config_file_path(),
             log_file_path: ConfigManager::get_log_file_path(),
         }
@@ -115,6 +523,330 @@ impl ConfigManager {
             Some(self.max_attachment_size_in_mb.to_string()),
         );
 
+        // Per-game max attachment size.
+        config.set(
+            CONFIG_SERVER_SECTION_NAME,
+            CONFIG_PER_GAME_MAX_ATTACHMENT_SIZE_IN_MB_PARAM,
+            Some(self.per_game_max_attachment_size_list.clone()),
+        );
+
+        // Max concurrent reporter connections.
+        config.set(
+            CONFIG_SERVER_SECTION_NAME,
+            CONFIG_MAX_CONCURRENT_REPORTER_CONNECTIONS_PARAM,
+            Some(self.max_concurrent_reporter_connections.to_string()),
+        );
+
+        // Max concurrent client connections.
+        config.set(
+            CONFIG_SERVER_SECTION_NAME,
+            CONFIG_MAX_CONCURRENT_CLIENT_CONNECTIONS_PARAM,
+            Some(self.max_concurrent_client_connections.to_string()),
+        );
+
+        // Client inactivity timeout.
+        config.set(
+            CONFIG_SERVER_SECTION_NAME,
+            CONFIG_CLIENT_INACTIVITY_TIMEOUT_PARAM,
+            Some(self.client_inactivity_timeout_in_min.to_string()),
+        );
+
+        // Client keep-alive resets inactivity.
+        config.set(
+            CONFIG_SERVER_SECTION_NAME,
+            CONFIG_CLIENT_KEEP_ALIVE_RESETS_INACTIVITY_PARAM,
+            Some(self.client_keep_alive_resets_inactivity.to_string()),
+        );
+
+        // Diffie-Hellman group.
+        config.set(
+            CONFIG_SERVER_SECTION_NAME,
+            CONFIG_DH_GROUP_PARAM,
+            Some(self.dh_group.to_string()),
+        );
+
+        // Reports section started.
+
+        // Accepted game names.
+        config.set(
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_ACCEPTED_GAME_NAMES_PARAM,
+            Some(self.accepted_game_names.join(", ")),
+        );
+
+        // Min accepted game version.
+        config.set(
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_MIN_ACCEPTED_GAME_VERSION_PARAM,
+            Some(self.min_accepted_game_version.clone()),
+        );
+
+        // Blocked game versions.
+        config.set(
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_BLOCKED_GAME_VERSIONS_PARAM,
+            Some(self.blocked_game_versions.join(", ")),
+        );
+
+        // Enable spam filter.
+        config.set(
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_ENABLE_SPAM_FILTER_PARAM,
+            Some(self.enable_spam_filter.to_string()),
+        );
+
+        // Spam filter blocked words.
+        config.set(
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_SPAM_FILTER_BLOCKED_WORDS_PARAM,
+            Some(self.spam_filter_blocked_words.join(", ")),
+        );
+
+        // Spam filter blocked regex.
+        config.set(
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_SPAM_FILTER_BLOCKED_REGEX_PARAM,
+            Some(self.spam_filter_blocked_regex.clone()),
+        );
+
+        // Reporter proof of work difficulty.
+        config.set(
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_PROOF_OF_WORK_DIFFICULTY_PARAM,
+            Some(self.reporter_proof_of_work_difficulty.to_string()),
+        );
+
+        // Sender email report cooldown.
+        config.set(
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_SENDER_EMAIL_COOLDOWN_PARAM,
+            Some(self.sender_email_report_cooldown_in_sec.to_string()),
+        );
+
+        // Max report count.
+        config.set(
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_MAX_REPORT_COUNT_PARAM,
+            Some(self.max_report_count.to_string()),
+        );
+
+        // Min report text length.
+        config.set(
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_MIN_REPORT_TEXT_LENGTH_PARAM,
+            Some(self.min_report_text_length.to_string()),
+        );
+
+        // Field rules section started.
+
+        // Field regex rules.
+        for ((param, _), rule) in CONFIG_FIELD_RULE_PARAMS
+            .iter()
+            .zip(self.report_field_rules.iter())
+        {
+            config.set(
+                CONFIG_FIELD_RULES_SECTION_NAME,
+                param,
+                Some(rule.pattern.clone()),
+            );
+        }
+
+        // Forwarding section started.
+
+        // Forwarding routes.
+        config.set(
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_FORWARDING_ROUTES_PARAM,
+            Some(self.forwarding_route_list.clone()),
+        );
+
+        // GitHub token.
+        config.set(
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_FORWARDING_GITHUB_TOKEN_PARAM,
+            Some(self.forwarding_github_token.clone()),
+        );
+
+        // SMTP server.
+        config.set(
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_FORWARDING_SMTP_SERVER_PARAM,
+            Some(self.forwarding_smtp_server.clone()),
+        );
+
+        // SMTP username.
+        config.set(
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_FORWARDING_SMTP_USERNAME_PARAM,
+            Some(self.forwarding_smtp_username.clone()),
+        );
+
+        // SMTP password.
+        config.set(
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_FORWARDING_SMTP_PASSWORD_PARAM,
+            Some(self.forwarding_smtp_password.clone()),
+        );
+
+        // Max forwarding attempts.
+        config.set(
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_FORWARDING_MAX_ATTEMPTS_PARAM,
+            Some(self.forwarding_max_attempts.to_string()),
+        );
+
+        // Forwarding retry interval.
+        config.set(
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_FORWARDING_RETRY_INTERVAL_PARAM,
+            Some(self.forwarding_retry_interval_in_sec.to_string()),
+        );
+
+        // Webhook URL.
+        config.set(
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_WEBHOOK_URL_PARAM,
+            Some(self.webhook_url.clone()),
+        );
+
+        // Max webhook attempts.
+        config.set(
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_WEBHOOK_MAX_ATTEMPTS_PARAM,
+            Some(self.webhook_max_attempts.to_string()),
+        );
+
+        // Webhook initial retry delay.
+        config.set(
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_WEBHOOK_INITIAL_RETRY_DELAY_PARAM,
+            Some(self.webhook_initial_retry_delay_in_sec.to_string()),
+        );
+
+        // REST API section started.
+
+        // Enable REST API.
+        config.set(
+            CONFIG_REST_API_SECTION_NAME,
+            CONFIG_ENABLE_REST_API_PARAM,
+            Some(self.enable_rest_api.to_string()),
+        );
+
+        // REST API port.
+        config.set(
+            CONFIG_REST_API_SECTION_NAME,
+            CONFIG_REST_API_PORT_PARAM,
+            Some(self.rest_api_port.to_string()),
+        );
+
+        // REST API token.
+        config.set(
+            CONFIG_REST_API_SECTION_NAME,
+            CONFIG_REST_API_TOKEN_PARAM,
+            Some(self.rest_api_token.clone()),
+        );
+
+        // REST API feed token.
+        config.set(
+            CONFIG_REST_API_SECTION_NAME,
+            CONFIG_REST_API_FEED_TOKEN_PARAM,
+            Some(self.rest_api_feed_token.clone()),
+        );
+
+        // Metrics section started.
+
+        // Enable metrics.
+        config.set(
+            CONFIG_METRICS_SECTION_NAME,
+            CONFIG_ENABLE_METRICS_PARAM,
+            Some(self.enable_metrics.to_string()),
+        );
+
+        // Metrics port.
+        config.set(
+            CONFIG_METRICS_SECTION_NAME,
+            CONFIG_METRICS_PORT_PARAM,
+            Some(self.metrics_port.to_string()),
+        );
+
+        // HTTP transport section started.
+
+        // Enable HTTP transport.
+        config.set(
+            CONFIG_HTTP_TRANSPORT_SECTION_NAME,
+            CONFIG_ENABLE_HTTP_TRANSPORT_PARAM,
+            Some(self.enable_http_transport.to_string()),
+        );
+
+        // HTTP transport port.
+        config.set(
+            CONFIG_HTTP_TRANSPORT_SECTION_NAME,
+            CONFIG_HTTP_TRANSPORT_PORT_PARAM,
+            Some(self.http_transport_port.to_string()),
+        );
+
+        // Trust X-Forwarded-For.
+        config.set(
+            CONFIG_HTTP_TRANSPORT_SECTION_NAME,
+            CONFIG_HTTP_TRANSPORT_TRUST_FORWARDED_FOR_PARAM,
+            Some(self.http_transport_trust_x_forwarded_for.to_string()),
+        );
+
+        // Tracing section started.
+
+        // Tracing OTLP endpoint.
+        config.set(
+            CONFIG_TRACING_SECTION_NAME,
+            CONFIG_TRACING_OTLP_ENDPOINT_PARAM,
+            Some(self.tracing_otlp_endpoint.clone()),
+        );
+
+        // Tracing service name.
+        config.set(
+            CONFIG_TRACING_SECTION_NAME,
+            CONFIG_TRACING_SERVICE_NAME_PARAM,
+            Some(self.tracing_service_name.clone()),
+        );
+
+        // Disk space section started.
+
+        // Low disk space threshold.
+        config.set(
+            CONFIG_DISK_SPACE_SECTION_NAME,
+            CONFIG_LOW_DISK_SPACE_THRESHOLD_PARAM,
+            Some(self.low_disk_space_threshold_in_mb.to_string()),
+        );
+
+        // Disk space check interval.
+        config.set(
+            CONFIG_DISK_SPACE_SECTION_NAME,
+            CONFIG_DISK_SPACE_CHECK_INTERVAL_PARAM,
+            Some(self.disk_space_check_interval_in_sec.to_string()),
+        );
+
+        // Low disk space alert.
+        config.set(
+            CONFIG_DISK_SPACE_SECTION_NAME,
+            CONFIG_LOW_DISK_SPACE_ALERT_PARAM,
+            Some(self.low_disk_space_alert_target.clone()),
+        );
+
+        // Logging section started.
+
+        // Log backend.
+        config.set(
+            CONFIG_LOGGING_SECTION_NAME,
+            CONFIG_LOG_BACKEND_PARAM,
+            Some(self.log_backend.to_string()),
+        );
+
+        // Min log category.
+        config.set(
+            CONFIG_LOGGING_SECTION_NAME,
+            CONFIG_MIN_LOG_CATEGORY_PARAM,
+            Some(self.min_log_category.to_string()),
+        );
+
         // Login section started.
 
         // Max allowed login attempts until ban.
@@ -131,6 +863,83 @@ impl ConfigManager {
             Some(self.ban_time_duration_in_min.to_string()),
         );
 
+        // Ban alert.
+        config.set(
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_BAN_ALERT_PARAM,
+            Some(self.ban_alert_target.clone()),
+        );
+
+        // Ban alert cooldown.
+        config.set(
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_BAN_ALERT_COOLDOWN_PARAM,
+            Some(self.ban_alert_cooldown_in_min.to_string()),
+        );
+
+        // Max password age.
+        config.set(
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_MAX_PASSWORD_AGE_PARAM,
+            Some(self.max_password_age_in_days.to_string()),
+        );
+
+        // Require OTP.
+        config.set(
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_REQUIRE_OTP_PARAM,
+            Some(self.require_otp.to_string()),
+        );
+
+        // Password min length.
+        config.set(
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_PASSWORD_MIN_LENGTH_PARAM,
+            Some(self.password_min_length.to_string()),
+        );
+
+        // Password require letters and digits.
+        config.set(
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_PASSWORD_REQUIRE_LETTERS_AND_DIGITS_PARAM,
+            Some(self.password_require_letters_and_digits.to_string()),
+        );
+
+        // Reject common passwords.
+        config.set(
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_REJECT_COMMON_PASSWORDS_PARAM,
+            Some(self.reject_common_passwords.to_string()),
+        );
+
+        // OTP algorithm.
+        config.set(
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_OTP_ALGORITHM_PARAM,
+            Some(self.otp_algorithm.to_string()),
+        );
+
+        // OTP digits.
+        config.set(
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_OTP_DIGITS_PARAM,
+            Some(self.otp_digits.to_string()),
+        );
+
+        // OTP period.
+        config.set(
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_OTP_PERIOD_IN_SEC_PARAM,
+            Some(self.otp_period_in_sec.to_string()),
+        );
+
+        // OTP verification window.
+        config.set(
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_OTP_VERIFICATION_WINDOW_PARAM,
+            Some(self.otp_verification_window.to_string()),
+        );
+
         // Write to disk.
         if let Err(e) = config.write(&self.config_file_path) {
             return Err(AppError::new(&e.to_string()));
@@ -153,7 +962,7 @@ impl ConfigManager {
             CONFIG_SERVER_SECTION_NAME,
             CONFIG_PORT_REPORTER_PARAM,
             &mut self.port_for_reporters,
-            ConfigManager::generate_random_port(0),
+            ConfigManager::generate_random_port(&[]),
         ) {
             some_values_were_empty = true;
         }
@@ -164,7 +973,7 @@ impl ConfigManager {
             CONFIG_SERVER_SECTION_NAME,
             CONFIG_PORT_CLIENT_PARAM,
             &mut self.port_for_clients,
-            ConfigManager::generate_random_port(self.port_for_reporters),
+            ConfigManager::generate_random_port(&[self.port_for_reporters]),
         ) {
             some_values_were_empty = true;
         }
@@ -180,26 +989,836 @@ impl ConfigManager {
             some_values_were_empty = true;
         }
 
-        // Login section started.
+        // Read per-game max attachment size.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_SERVER_SECTION_NAME,
+            CONFIG_PER_GAME_MAX_ATTACHMENT_SIZE_IN_MB_PARAM,
+            &mut self.per_game_max_attachment_size_list,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+        self.per_game_max_attachment_size_in_mb = BTreeMap::new();
+        match ConfigManager::parse_per_game_values(&self.per_game_max_attachment_size_list) {
+            Ok(limits) => self.per_game_max_attachment_size_in_mb = limits,
+            Err(app_error) => {
+                // Don't overwrite the value so that it could be fixed.
+                println!(
+                    "WARNING: \"{}\" is not valid, using \"{}\" for all games (error: {}).",
+                    CONFIG_PER_GAME_MAX_ATTACHMENT_SIZE_IN_MB_PARAM,
+                    CONFIG_MAX_ATTACHMENT_SIZE_IN_MB_PARAM,
+                    app_error.get_message()
+                );
+            }
+        }
 
-        // Read max allowed login attempts until ban.
+        // Read max concurrent reporter connections.
         if ConfigManager::read_value(
             config,
-            CONFIG_LOGIN_SECTION_NAME,
-            CONFIG_MAX_ALLOWED_LOGIN_ATTEMPTS_PARAM,
-            &mut self.max_allowed_login_attempts,
-            DEFAULT_MAX_ALLOWED_LOGIN_ATTEMPTS,
+            CONFIG_SERVER_SECTION_NAME,
+            CONFIG_MAX_CONCURRENT_REPORTER_CONNECTIONS_PARAM,
+            &mut self.max_concurrent_reporter_connections,
+            DEFAULT_MAX_CONCURRENT_REPORTER_CONNECTIONS,
         ) {
             some_values_were_empty = true;
         }
+        if self.max_concurrent_reporter_connections == 0 {
+            println!(
+                "WARNING: \"{}\" should not be zero, using default value instead.",
+                CONFIG_MAX_CONCURRENT_REPORTER_CONNECTIONS_PARAM
+            );
+            self.max_concurrent_reporter_connections = DEFAULT_MAX_CONCURRENT_REPORTER_CONNECTIONS;
+            some_values_were_empty = true;
+        }
 
-        // Read ban time duration.
+        // Read max concurrent client connections.
         if ConfigManager::read_value(
             config,
-            CONFIG_LOGIN_SECTION_NAME,
-            CONFIG_BAN_TIME_DURATION_IN_MIN,
-            &mut self.ban_time_duration_in_min,
-            DEFAULT_BAN_TIME_DURATION_IN_MIN,
+            CONFIG_SERVER_SECTION_NAME,
+            CONFIG_MAX_CONCURRENT_CLIENT_CONNECTIONS_PARAM,
+            &mut self.max_concurrent_client_connections,
+            DEFAULT_MAX_CONCURRENT_CLIENT_CONNECTIONS,
+        ) {
+            some_values_were_empty = true;
+        }
+        if self.max_concurrent_client_connections == 0 {
+            println!(
+                "WARNING: \"{}\" should not be zero, using default value instead.",
+                CONFIG_MAX_CONCURRENT_CLIENT_CONNECTIONS_PARAM
+            );
+            self.max_concurrent_client_connections = DEFAULT_MAX_CONCURRENT_CLIENT_CONNECTIONS;
+            some_values_were_empty = true;
+        }
+
+        // Read client inactivity timeout.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_SERVER_SECTION_NAME,
+            CONFIG_CLIENT_INACTIVITY_TIMEOUT_PARAM,
+            &mut self.client_inactivity_timeout_in_min,
+            DEFAULT_CLIENT_INACTIVITY_TIMEOUT_IN_MIN,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read client keep-alive resets inactivity.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_SERVER_SECTION_NAME,
+            CONFIG_CLIENT_KEEP_ALIVE_RESETS_INACTIVITY_PARAM,
+            &mut self.client_keep_alive_resets_inactivity,
+            true,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read Diffie-Hellman group.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_SERVER_SECTION_NAME,
+            CONFIG_DH_GROUP_PARAM,
+            &mut self.dh_group,
+            DhGroup::default(),
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Reports section started.
+
+        // Read accepted game names (separated by commas).
+        let mut accepted_game_names = String::new();
+        if ConfigManager::read_value(
+            config,
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_ACCEPTED_GAME_NAMES_PARAM,
+            &mut accepted_game_names,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+        self.accepted_game_names = ConfigManager::split_list(&accepted_game_names);
+
+        // Read min accepted game version.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_MIN_ACCEPTED_GAME_VERSION_PARAM,
+            &mut self.min_accepted_game_version,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+        self.min_accepted_game_version = self.min_accepted_game_version.trim().to_string();
+
+        // Read blocked game versions (separated by commas).
+        let mut blocked_game_versions = String::new();
+        if ConfigManager::read_value(
+            config,
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_BLOCKED_GAME_VERSIONS_PARAM,
+            &mut blocked_game_versions,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+        self.blocked_game_versions = ConfigManager::split_list(&blocked_game_versions);
+
+        // Read enable spam filter.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_ENABLE_SPAM_FILTER_PARAM,
+            &mut self.enable_spam_filter,
+            true,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read spam filter blocked words (separated by commas).
+        let mut blocked_words = String::new();
+        if ConfigManager::read_value(
+            config,
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_SPAM_FILTER_BLOCKED_WORDS_PARAM,
+            &mut blocked_words,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+        self.spam_filter_blocked_words = ConfigManager::split_list(&blocked_words)
+            .into_iter()
+            .map(|word| word.to_lowercase())
+            .collect();
+
+        // Read spam filter blocked regex.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_SPAM_FILTER_BLOCKED_REGEX_PARAM,
+            &mut self.spam_filter_blocked_regex,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+        self.spam_filter_regex = None;
+        if !self.spam_filter_blocked_regex.is_empty() {
+            match RegexBuilder::new(&self.spam_filter_blocked_regex)
+                .case_insensitive(true)
+                .build()
+            {
+                Ok(regex) => self.spam_filter_regex = Some(regex),
+                Err(e) => {
+                    // Don't overwrite the value so that it could be fixed.
+                    println!(
+                        "WARNING: \"{}\" is not a valid regex, it will be ignored (error: {}).",
+                        CONFIG_SPAM_FILTER_BLOCKED_REGEX_PARAM, e
+                    );
+                }
+            }
+        }
+
+        // Read reporter proof of work difficulty.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_PROOF_OF_WORK_DIFFICULTY_PARAM,
+            &mut self.reporter_proof_of_work_difficulty,
+            0,
+        ) {
+            some_values_were_empty = true;
+        }
+        if self.reporter_proof_of_work_difficulty > MAX_PROOF_OF_WORK_DIFFICULTY {
+            println!(
+                "WARNING: \"{}\" should not be bigger than {}, using {} instead.",
+                CONFIG_PROOF_OF_WORK_DIFFICULTY_PARAM,
+                MAX_PROOF_OF_WORK_DIFFICULTY,
+                MAX_PROOF_OF_WORK_DIFFICULTY
+            );
+            self.reporter_proof_of_work_difficulty = MAX_PROOF_OF_WORK_DIFFICULTY;
+            some_values_were_empty = true;
+        }
+
+        // Read sender email report cooldown.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_SENDER_EMAIL_COOLDOWN_PARAM,
+            &mut self.sender_email_report_cooldown_in_sec,
+            0,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read max report count.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_MAX_REPORT_COUNT_PARAM,
+            &mut self.max_report_count,
+            0,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read min report text length.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_REPORTS_SECTION_NAME,
+            CONFIG_MIN_REPORT_TEXT_LENGTH_PARAM,
+            &mut self.min_report_text_length,
+            ReportLimits::ReportText.default_min_length(),
+        ) {
+            some_values_were_empty = true;
+        }
+        if self.min_report_text_length > ReportLimits::ReportText.max_length() {
+            println!(
+                "WARNING: \"{}\" is bigger than the maximum report text length ({}), \
+                all reports will be rejected.",
+                CONFIG_MIN_REPORT_TEXT_LENGTH_PARAM,
+                ReportLimits::ReportText.max_length()
+            );
+        }
+
+        // Field rules section started.
+
+        // Read field regex rules.
+        self.report_field_rules = Vec::with_capacity(CONFIG_FIELD_RULE_PARAMS.len());
+        for (param, field) in CONFIG_FIELD_RULE_PARAMS.iter() {
+            let mut pattern = String::new();
+            if ConfigManager::read_value(
+                config,
+                CONFIG_FIELD_RULES_SECTION_NAME,
+                param,
+                &mut pattern,
+                String::new(),
+            ) {
+                some_values_were_empty = true;
+            }
+
+            let mut regex = None;
+            if !pattern.is_empty() {
+                match Regex::new(&pattern) {
+                    Ok(compiled) => regex = Some(compiled),
+                    Err(e) => {
+                        // Don't overwrite the value so that it could be fixed.
+                        println!(
+                            "WARNING: \"{}\" is not a valid regex, the rule will not be used \
+                            (error: {}).",
+                            param, e
+                        );
+                    }
+                }
+            }
+
+            self.report_field_rules.push(ReportFieldRule {
+                field: *field,
+                pattern,
+                regex,
+            });
+        }
+
+        // Forwarding section started.
+
+        // Read forwarding routes.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_FORWARDING_ROUTES_PARAM,
+            &mut self.forwarding_route_list,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+        self.forwarding_routes = Vec::new();
+        match ForwardingRoute::parse_routes(&self.forwarding_route_list) {
+            Ok(routes) => self.forwarding_routes = routes,
+            Err(app_error) => {
+                // Don't overwrite the value so that it could be fixed.
+                println!(
+                    "WARNING: \"{}\" is not valid, reports will not be forwarded (error: {}).",
+                    CONFIG_FORWARDING_ROUTES_PARAM,
+                    app_error.get_message()
+                );
+            }
+        }
+
+        // Read GitHub token.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_FORWARDING_GITHUB_TOKEN_PARAM,
+            &mut self.forwarding_github_token,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read SMTP server.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_FORWARDING_SMTP_SERVER_PARAM,
+            &mut self.forwarding_smtp_server,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read SMTP username.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_FORWARDING_SMTP_USERNAME_PARAM,
+            &mut self.forwarding_smtp_username,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read SMTP password.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_FORWARDING_SMTP_PASSWORD_PARAM,
+            &mut self.forwarding_smtp_password,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read max forwarding attempts.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_FORWARDING_MAX_ATTEMPTS_PARAM,
+            &mut self.forwarding_max_attempts,
+            DEFAULT_FORWARDING_MAX_ATTEMPTS,
+        ) {
+            some_values_were_empty = true;
+        }
+        if self.forwarding_max_attempts == 0 {
+            println!(
+                "WARNING: \"{}\" should not be zero, using default value instead.",
+                CONFIG_FORWARDING_MAX_ATTEMPTS_PARAM
+            );
+            self.forwarding_max_attempts = DEFAULT_FORWARDING_MAX_ATTEMPTS;
+            some_values_were_empty = true;
+        }
+
+        // Read forwarding retry interval.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_FORWARDING_RETRY_INTERVAL_PARAM,
+            &mut self.forwarding_retry_interval_in_sec,
+            DEFAULT_FORWARDING_RETRY_INTERVAL_IN_SEC,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read webhook URL.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_WEBHOOK_URL_PARAM,
+            &mut self.webhook_url,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+        self.webhook_url = self.webhook_url.trim().to_string();
+
+        // Read max webhook attempts.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_WEBHOOK_MAX_ATTEMPTS_PARAM,
+            &mut self.webhook_max_attempts,
+            DEFAULT_WEBHOOK_MAX_ATTEMPTS,
+        ) {
+            some_values_were_empty = true;
+        }
+        if self.webhook_max_attempts == 0 {
+            println!(
+                "WARNING: \"{}\" should not be zero, using default value instead.",
+                CONFIG_WEBHOOK_MAX_ATTEMPTS_PARAM
+            );
+            self.webhook_max_attempts = DEFAULT_WEBHOOK_MAX_ATTEMPTS;
+            some_values_were_empty = true;
+        }
+
+        // Read webhook initial retry delay.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_FORWARDING_SECTION_NAME,
+            CONFIG_WEBHOOK_INITIAL_RETRY_DELAY_PARAM,
+            &mut self.webhook_initial_retry_delay_in_sec,
+            DEFAULT_WEBHOOK_INITIAL_RETRY_DELAY_IN_SEC,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // REST API section started.
+
+        // Read enable REST API.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_REST_API_SECTION_NAME,
+            CONFIG_ENABLE_REST_API_PARAM,
+            &mut self.enable_rest_api,
+            false,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read REST API port.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_REST_API_SECTION_NAME,
+            CONFIG_REST_API_PORT_PARAM,
+            &mut self.rest_api_port,
+            ConfigManager::generate_random_port(&[self.port_for_reporters, self.port_for_clients]),
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read REST API token.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_REST_API_SECTION_NAME,
+            CONFIG_REST_API_TOKEN_PARAM,
+            &mut self.rest_api_token,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+        self.rest_api_token = self.rest_api_token.trim().to_string();
+        if self.rest_api_token.is_empty() {
+            println!(
+                "WARNING: \"{}\" should not be empty, generating a new token.",
+                CONFIG_REST_API_TOKEN_PARAM
+            );
+            self.rest_api_token = ConfigManager::generate_random_api_token();
+            some_values_were_empty = true;
+        }
+
+        // Read REST API feed token.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_REST_API_SECTION_NAME,
+            CONFIG_REST_API_FEED_TOKEN_PARAM,
+            &mut self.rest_api_feed_token,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+        self.rest_api_feed_token = self.rest_api_feed_token.trim().to_string();
+        if self.rest_api_feed_token.is_empty() {
+            println!(
+                "WARNING: \"{}\" should not be empty, generating a new token.",
+                CONFIG_REST_API_FEED_TOKEN_PARAM
+            );
+            self.rest_api_feed_token = ConfigManager::generate_random_api_token();
+            some_values_were_empty = true;
+        }
+
+        // Metrics section started.
+
+        // Read enable metrics.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_METRICS_SECTION_NAME,
+            CONFIG_ENABLE_METRICS_PARAM,
+            &mut self.enable_metrics,
+            false,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read metrics port.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_METRICS_SECTION_NAME,
+            CONFIG_METRICS_PORT_PARAM,
+            &mut self.metrics_port,
+            ConfigManager::generate_random_port(&[
+                self.port_for_reporters,
+                self.port_for_clients,
+                self.rest_api_port,
+            ]),
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // HTTP transport section started.
+
+        // Read enable HTTP transport.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_HTTP_TRANSPORT_SECTION_NAME,
+            CONFIG_ENABLE_HTTP_TRANSPORT_PARAM,
+            &mut self.enable_http_transport,
+            false,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read HTTP transport port.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_HTTP_TRANSPORT_SECTION_NAME,
+            CONFIG_HTTP_TRANSPORT_PORT_PARAM,
+            &mut self.http_transport_port,
+            ConfigManager::generate_random_port(&[
+                self.port_for_reporters,
+                self.port_for_clients,
+                self.rest_api_port,
+                self.metrics_port,
+            ]),
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read trust X-Forwarded-For.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_HTTP_TRANSPORT_SECTION_NAME,
+            CONFIG_HTTP_TRANSPORT_TRUST_FORWARDED_FOR_PARAM,
+            &mut self.http_transport_trust_x_forwarded_for,
+            false,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Tracing section started.
+
+        // Read tracing OTLP endpoint.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_TRACING_SECTION_NAME,
+            CONFIG_TRACING_OTLP_ENDPOINT_PARAM,
+            &mut self.tracing_otlp_endpoint,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+        self.tracing_otlp_endpoint = self.tracing_otlp_endpoint.trim().to_string();
+
+        // Read tracing service name.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_TRACING_SECTION_NAME,
+            CONFIG_TRACING_SERVICE_NAME_PARAM,
+            &mut self.tracing_service_name,
+            String::from(DEFAULT_TRACING_SERVICE_NAME),
+        ) {
+            some_values_were_empty = true;
+        }
+        if self.tracing_service_name.trim().is_empty() {
+            self.tracing_service_name = String::from(DEFAULT_TRACING_SERVICE_NAME);
+        }
+
+        // Disk space section started.
+
+        // Read low disk space threshold.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_DISK_SPACE_SECTION_NAME,
+            CONFIG_LOW_DISK_SPACE_THRESHOLD_PARAM,
+            &mut self.low_disk_space_threshold_in_mb,
+            DEFAULT_LOW_DISK_SPACE_THRESHOLD_IN_MB,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read disk space check interval.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_DISK_SPACE_SECTION_NAME,
+            CONFIG_DISK_SPACE_CHECK_INTERVAL_PARAM,
+            &mut self.disk_space_check_interval_in_sec,
+            DEFAULT_DISK_SPACE_CHECK_INTERVAL_IN_SEC,
+        ) {
+            some_values_were_empty = true;
+        }
+        if self.disk_space_check_interval_in_sec == 0 {
+            println!(
+                "WARNING: \"{}\" should not be zero, using default value instead.",
+                CONFIG_DISK_SPACE_CHECK_INTERVAL_PARAM
+            );
+            self.disk_space_check_interval_in_sec = DEFAULT_DISK_SPACE_CHECK_INTERVAL_IN_SEC;
+            some_values_were_empty = true;
+        }
+
+        // Read low disk space alert.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_DISK_SPACE_SECTION_NAME,
+            CONFIG_LOW_DISK_SPACE_ALERT_PARAM,
+            &mut self.low_disk_space_alert_target,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+        self.low_disk_space_alert = ConfigManager::parse_alert_target(
+            CONFIG_LOW_DISK_SPACE_ALERT_PARAM,
+            &self.low_disk_space_alert_target,
+        );
+
+        // Logging section started.
+
+        // Read log backend.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGGING_SECTION_NAME,
+            CONFIG_LOG_BACKEND_PARAM,
+            &mut self.log_backend,
+            LogBackend::File,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read min log category.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGGING_SECTION_NAME,
+            CONFIG_MIN_LOG_CATEGORY_PARAM,
+            &mut self.min_log_category,
+            LogCategory::Info,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Login section started.
+
+        // Read max allowed login attempts until ban.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_MAX_ALLOWED_LOGIN_ATTEMPTS_PARAM,
+            &mut self.max_allowed_login_attempts,
+            DEFAULT_MAX_ALLOWED_LOGIN_ATTEMPTS,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read ban time duration.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_BAN_TIME_DURATION_IN_MIN,
+            &mut self.ban_time_duration_in_min,
+            DEFAULT_BAN_TIME_DURATION_IN_MIN,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read ban alert.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_BAN_ALERT_PARAM,
+            &mut self.ban_alert_target,
+            String::new(),
+        ) {
+            some_values_were_empty = true;
+        }
+        self.ban_alert =
+            ConfigManager::parse_alert_target(CONFIG_BAN_ALERT_PARAM, &self.ban_alert_target);
+
+        // Read ban alert cooldown.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_BAN_ALERT_COOLDOWN_PARAM,
+            &mut self.ban_alert_cooldown_in_min,
+            DEFAULT_BAN_ALERT_COOLDOWN_IN_MIN,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read max password age.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_MAX_PASSWORD_AGE_PARAM,
+            &mut self.max_password_age_in_days,
+            DEFAULT_MAX_PASSWORD_AGE_IN_DAYS,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read require OTP.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_REQUIRE_OTP_PARAM,
+            &mut self.require_otp,
+            DEFAULT_REQUIRE_OTP,
+        ) {
+            some_values_were_empty = true;
+        }
+        if !self.require_otp {
+            println!(
+                "WARNING: \"{}\" is disabled, users will be able to login using only the password.",
+                CONFIG_REQUIRE_OTP_PARAM
+            );
+        }
+
+        // Read password min length.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_PASSWORD_MIN_LENGTH_PARAM,
+            &mut self.password_min_length,
+            DEFAULT_PASSWORD_MIN_LENGTH,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read password require letters and digits.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_PASSWORD_REQUIRE_LETTERS_AND_DIGITS_PARAM,
+            &mut self.password_require_letters_and_digits,
+            DEFAULT_PASSWORD_REQUIRE_LETTERS_AND_DIGITS,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read reject common passwords.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_REJECT_COMMON_PASSWORDS_PARAM,
+            &mut self.reject_common_passwords,
+            DEFAULT_REJECT_COMMON_PASSWORDS,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        let default_otp_parameters = OtpParameters::default();
+
+        // Read OTP algorithm.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_OTP_ALGORITHM_PARAM,
+            &mut self.otp_algorithm,
+            default_otp_parameters.algorithm,
+        ) {
+            some_values_were_empty = true;
+        }
+
+        // Read OTP digits.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_OTP_DIGITS_PARAM,
+            &mut self.otp_digits,
+            default_otp_parameters.digits,
+        ) {
+            some_values_were_empty = true;
+        }
+        if !ALLOWED_OTP_DIGITS.contains(&self.otp_digits) {
+            println!(
+                "WARNING: \"{}\" should be in range [{}; {}], using default value instead.",
+                CONFIG_OTP_DIGITS_PARAM,
+                ALLOWED_OTP_DIGITS.start(),
+                ALLOWED_OTP_DIGITS.end()
+            );
+            self.otp_digits = default_otp_parameters.digits;
+            some_values_were_empty = true;
+        }
+
+        // Read OTP period.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_OTP_PERIOD_IN_SEC_PARAM,
+            &mut self.otp_period_in_sec,
+            default_otp_parameters.period_in_sec,
+        ) {
+            some_values_were_empty = true;
+        }
+        if self.otp_period_in_sec == 0 {
+            println!(
+                "WARNING: \"{}\" should not be zero, using default value instead.",
+                CONFIG_OTP_PERIOD_IN_SEC_PARAM
+            );
+            self.otp_period_in_sec = default_otp_parameters.period_in_sec;
+            some_values_were_empty = true;
+        }
+
+        // Read OTP verification window.
+        if ConfigManager::read_value(
+            config,
+            CONFIG_LOGIN_SECTION_NAME,
+            CONFIG_OTP_VERIFICATION_WINDOW_PARAM,
+            &mut self.otp_verification_window,
+            DEFAULT_OTP_VERIFICATION_WINDOW,
         ) {
             some_values_were_empty = true;
         }
@@ -209,6 +1828,90 @@ impl ConfigManager {
 
         some_values_were_empty
     }
+    /// Parses a per-game parameter.
+    ///
+    /// ## Arguments
+    /// * `value`: comma-separated list of entries in the form `game name => value`.
+    ///
+    /// ## Return
+    /// Error if some entry is invalid.
+    fn parse_per_game_values<T>(value: &str) -> Result<BTreeMap<String, T>, AppError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: std::fmt::Display,
+    {
+        let mut values = BTreeMap::new();
+
+        for entry in ConfigManager::split_list(value) {
+            let (game_name, game_value) = match entry.rsplit_once(PER_GAME_VALUE_SEPARATOR) {
+                Some((game_name, game_value)) => (game_name.trim(), game_value.trim()),
+                None => {
+                    return Err(AppError::new(&format!(
+                        "\"{}\" should have the form \"game name {} value\"",
+                        entry, PER_GAME_VALUE_SEPARATOR
+                    )))
+                }
+            };
+            if game_name.is_empty() {
+                return Err(AppError::new(&format!(
+                    "\"{}\" does not have a game name",
+                    entry
+                )));
+            }
+
+            match game_value.parse::<T>() {
+                Ok(game_value) => {
+                    values.insert(game_name.to_string(), game_value);
+                }
+                Err(e) => {
+                    return Err(AppError::new(&format!(
+                        "failed to parse value of \"{}\" (error: {})",
+                        entry, e
+                    )))
+                }
+            }
+        }
+
+        Ok(values)
+    }
+    /// Splits comma-separated list of values (empty values are ignored).
+    fn split_list(value: &str) -> Vec<String> {
+        value
+            .split(',')
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect()
+    }
+    /// Parses a target of an alert (only "webhook" and "email" targets are supported).
+    ///
+    /// ## Return
+    /// `None` if the value is empty or not valid (a warning is printed).
+    fn parse_alert_target(param_name: &str, value: &str) -> Option<ForwardingTarget> {
+        if value.trim().is_empty() {
+            return None;
+        }
+
+        match ForwardingTarget::from_str(value) {
+            Ok(ForwardingTarget::GitHub { .. }) => {
+                println!(
+                    "WARNING: \"{}\" only supports \"webhook\" and \"email\" targets, \
+                    alerts will not be sent.",
+                    param_name
+                );
+                None
+            }
+            Ok(target) => Some(target),
+            Err(app_error) => {
+                // Don't overwrite the value so that it could be fixed.
+                println!(
+                    "WARNING: \"{}\" is not valid, alerts will not be sent (error: {}).",
+                    param_name,
+                    app_error.get_message()
+                );
+                None
+            }
+        }
+    }
     /// Reads a value from .ini file into `param` parameter.
     ///
     /// Returns `true` if the specified key does not exist
@@ -308,15 +2011,23 @@ impl ConfigManager {
             compile_error!("Reporter is not implemented for this OS.");
         }
     }
-    /// Generates random port value.
-    fn generate_random_port(exclude_port: u16) -> u16 {
+    /// Generates random port value that is not equal to any of the specified ports.
+    fn generate_random_port(exclude_ports: &[u16]) -> u16 {
         let mut rng = rand::thread_rng();
 
         loop {
             let port = rng.gen_range(RANDOM_PORT_RANGE);
-            if port != exclude_port {
+            if !exclude_ports.contains(&port) {
                 return port;
             }
         }
     }
+    /// Generates random token for the REST API.
+    fn generate_random_api_token() -> String {
+        let mut rng = rand::thread_rng();
+
+        (0..API_TOKEN_LENGTH)
+            .map(|_| API_TOKEN_CHARSET[rng.gen_range(0..API_TOKEN_CHARSET.len())] as char)
+            .collect()
+    }
 }
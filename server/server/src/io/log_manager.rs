@@ -1,36 +1,145 @@
 // Std.
+use std::cell::RefCell;
+use std::fmt;
 use std::fs::{File, *};
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 // External.
 use chrono::Local;
 use platform_dirs::UserDirs;
+use syslog::{Facility, Formatter3164, Logger, LoggerBackend};
 
 pub const LOG_FILE_NAME: &str = "server.log";
 const LOG_DIR_PREFIX: &str = "FBugReporter";
 const LOG_DIR: &str = "server_logs";
 const MAX_LOG_FILE_COUNT: usize = 10;
+const SYSLOG_PROCESS_NAME: &str = "fbugreporter_server";
 
+/// Categories are ordered by importance (`Info` < `Warning` < `Error`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LogCategory {
     Info,
     Warning,
     Error,
 }
 
+impl fmt::Display for LogCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogCategory::Info => write!(f, "info"),
+            LogCategory::Warning => write!(f, "warning"),
+            LogCategory::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl FromStr for LogCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "info" => Ok(LogCategory::Info),
+            "warning" => Ok(LogCategory::Warning),
+            "error" => Ok(LogCategory::Error),
+            _ => Err(format!(
+                "unknown log category \"{}\" (expected \"info\", \"warning\" or \"error\")",
+                s
+            )),
+        }
+    }
+}
+
+/// Where logs are written to (in addition to the console).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogBackend {
+    /// A new file in the `server_logs` directory for each launch.
+    File,
+    /// Local syslog (system journal), only available on Unix.
+    Syslog,
+}
+
+impl fmt::Display for LogBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogBackend::File => write!(f, "file"),
+            LogBackend::Syslog => write!(f, "syslog"),
+        }
+    }
+}
+
+impl FromStr for LogBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "file" => Ok(LogBackend::File),
+            "syslog" => Ok(LogBackend::Syslog),
+            _ => Err(format!(
+                "unknown log backend \"{}\" (expected \"file\" or \"syslog\")",
+                s
+            )),
+        }
+    }
+}
+
+enum LogSink {
+    File(PathBuf),
+    Syslog(RefCell<Logger<LoggerBackend, Formatter3164>>),
+}
+
 pub struct LogManager {
-    current_log_file: PathBuf,
+    sink: LogSink,
+    /// Entries of less important categories are ignored.
+    min_category: LogCategory,
 }
 
 impl LogManager {
-    /// Removes old log file and creates an empty one.
-    pub fn new() -> Self {
+    /// Creates a log manager that writes logs to the specified backend.
+    ///
+    /// ## Arguments
+    /// * `backend`: where to write logs to.
+    /// * `min_category`: entries of less important categories will be ignored.
+    ///
+    /// ## Remarks
+    /// When using the file backend removes old log file and creates an empty one.
+    /// If syslog is not available the file backend is used instead.
+    pub fn new(backend: LogBackend, min_category: LogCategory) -> Self {
+        if backend == LogBackend::Syslog {
+            let formatter = Formatter3164 {
+                facility: Facility::LOG_DAEMON,
+                hostname: None,
+                process: String::from(SYSLOG_PROCESS_NAME),
+                pid: std::process::id(),
+            };
+
+            match syslog::unix(formatter) {
+                Ok(logger) => {
+                    return Self {
+                        sink: LogSink::Syslog(RefCell::new(logger)),
+                        min_category,
+                    }
+                }
+                Err(e) => println!(
+                    "WARNING: failed to connect to syslog, using log file instead (error: {}).",
+                    e
+                ),
+            }
+        }
+
         Self {
-            current_log_file: LogManager::recreate_log_file(),
+            sink: LogSink::File(LogManager::recreate_log_file()),
+            min_category,
         }
     }
-    /// Prints text on the screen and writes it to log file.
+    /// Prints text on the screen and writes it to log file or syslog
+    /// (does nothing if the category is less important than the minimum category).
     pub fn print_and_log(&self, category: LogCategory, text: &str) {
+        if category < self.min_category {
+            return;
+        }
+
         let mut message = match category {
             LogCategory::Info => String::from("INFO: "),
             LogCategory::Warning => String::from("WARNING: "),
@@ -38,22 +147,36 @@ impl LogManager {
         };
         message += text;
 
-        let mut log_file = self.open_log_file();
         let datetime = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
 
-        if let Err(e) = writeln!(log_file, "[{}] {}", datetime, message) {
-            panic!("An error occurred at [{}, {}]: {:?}", file!(), line!(), e);
-        } else {
-            println!("[{}] {}", datetime, message);
+        match &self.sink {
+            LogSink::File(path) => {
+                let mut log_file = LogManager::open_log_file(path);
+                if let Err(e) = writeln!(log_file, "[{}] {}", datetime, message) {
+                    panic!("An error occurred at [{}, {}]: {:?}", file!(), line!(), e);
+                }
+            }
+            LogSink::Syslog(logger) => {
+                // Syslog adds its own timestamp and severity.
+                let mut logger = logger.borrow_mut();
+                let result = match category {
+                    LogCategory::Info => logger.info(text),
+                    LogCategory::Warning => logger.warning(text),
+                    LogCategory::Error => logger.err(text),
+                };
+                if let Err(e) = result {
+                    // Don't stop the server because of logging.
+                    println!("ERROR: failed to write to syslog (error: {}).", e);
+                }
+            }
         }
+
+        println!("[{}] {}", datetime, message);
     }
 
     /// Opens log file for writing.
-    fn open_log_file(&self) -> std::fs::File {
-        let log_file = OpenOptions::new()
-            .write(true)
-            .append(true)
-            .open(&self.current_log_file);
+    fn open_log_file(path: &Path) -> std::fs::File {
+        let log_file = OpenOptions::new().write(true).append(true).open(path);
         if let Err(e) = log_file {
             panic!("An error occurred at [{}, {}]: {:?}", file!(), line!(), e);
         }
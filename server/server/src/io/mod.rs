@@ -1,2 +1,3 @@
 pub mod config_manager;
 pub mod log_manager;
+pub mod tracing_manager;
@@ -0,0 +1,70 @@
+// External.
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{Protocol, WithExportConfig};
+use opentelemetry_sdk::{trace::SdkTracerProvider, Resource};
+use tracing_subscriber::layer::SubscriberExt;
+
+// Custom.
+use shared::misc::error::AppError;
+
+/// Exports trace spans of the server to an OpenTelemetry collector.
+///
+/// ## Remarks
+/// Spans are only exported while this object exists, if it was never created
+/// all tracing macros in the code are no-op.
+pub struct TracingManager {
+    provider: SdkTracerProvider,
+}
+
+impl TracingManager {
+    /// Starts exporting trace spans to the specified collector.
+    ///
+    /// ## Arguments
+    /// * `otlp_endpoint`: URL of the OTLP/HTTP collector that accepts traces
+    ///   (for example: `http://localhost:4318/v1/traces`).
+    /// * `service_name`: name of the service to attach to exported spans.
+    ///
+    /// ## Return
+    /// `AppError` if the exporter failed to initialize or a global
+    /// tracing subscriber was already set.
+    pub fn start(otlp_endpoint: &str, service_name: &str) -> Result<Self, AppError> {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_protocol(Protocol::HttpBinary)
+            .with_endpoint(otlp_endpoint)
+            .build();
+        if let Err(e) = exporter {
+            return Err(AppError::new(&format!(
+                "failed to create trace exporter (error: {})",
+                e
+            )));
+        }
+
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter.unwrap())
+            .with_resource(
+                Resource::builder()
+                    .with_service_name(service_name.to_string())
+                    .build(),
+            )
+            .build();
+
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_opentelemetry::layer().with_tracer(provider.tracer("fbugreporter-server")),
+        );
+        if let Err(e) = tracing::subscriber::set_global_default(subscriber) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(Self { provider })
+    }
+}
+
+impl Drop for TracingManager {
+    /// Exports the remaining spans.
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            println!("failed to shutdown trace exporter (error: {})", e);
+        }
+    }
+}
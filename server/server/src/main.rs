@@ -2,35 +2,65 @@
 
 // Std.
 use std::env;
-use std::io::Write;
+use std::io::{BufRead, Write};
+use std::net::IpAddr;
 
 // Custom.
+use crate::io::config_manager::ConfigManager;
 use crate::io::log_manager::LogManager;
 use crate::network::net_service::NetService;
 
 mod io;
 mod network;
 
+/// Options that were specified in the command line.
+#[derive(Default)]
+struct LaunchOptions {
+    start: bool,
+    under_monitor: bool,
+    /// Overrides the address that sockets are bound to.
+    address: Option<IpAddr>,
+    /// Overrides the port for reporters from the config.
+    port_for_reporters: Option<u16>,
+    /// Overrides the port for clients from the config.
+    port_for_clients: Option<u16>,
+}
+
 fn main() {
     println!("FBugReporter (server) (v{}).", env!("CARGO_PKG_VERSION"));
     println!("Type 'help' to see commands...\n");
 
-    let net_service = NetService::new(LogManager::new());
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = match parse_launch_options(&args) {
+        Ok(options) => options,
+        Err(message) => {
+            println!("ERROR: {} (type 'help' to see available options)", message);
+            return;
+        }
+    };
+
+    // Apply command-line options.
+    let mut config = ConfigManager::new();
+    if let Some(address) = options.address {
+        config.bind_address = address;
+    }
+    if let Some(port) = options.port_for_reporters {
+        config.port_for_reporters = port;
+    }
+    if let Some(port) = options.port_for_clients {
+        config.port_for_clients = port;
+    }
+
+    let net_service = NetService::new(
+        LogManager::new(config.log_backend, config.min_log_category),
+        config,
+    );
     if let Err(app_error) = net_service {
         panic!("{}", app_error);
     }
     let mut net_service = net_service.unwrap();
 
-    let mut args: Vec<String> = env::args().collect();
-
-    let mut under_monitor = false;
-    for arg in args.iter() {
-        if arg == "--under-monitor" {
-            under_monitor = true;
-        }
-    }
-
-    if !under_monitor {
+    if !options.under_monitor {
         println!();
         println!("---------------------------------------");
         println!("WARNING: you should only run the server using the 'server_monitor' app");
@@ -39,48 +69,204 @@ fn main() {
         println!();
     }
 
-    loop {
-        if let Err(e) = std::io::stdout().flush() {
-            println!("could not flush stdout (error: {}), continuing...", e);
-            continue;
-        }
-
-        let mut input = String::new();
-
-        if args.len() > 1 {
-            if args[1] == "--start" {
-                input = "start".to_string();
-            }
-            args.clear();
-        } else {
-            if let Err(e) = std::io::stdin().read_line(&mut input) {
-                println!("unable to read input (error: {}), continuing...", e);
-                continue;
-            }
-
-            input.pop(); // pop '\n'
-            if cfg!(windows) {
-                input.pop(); // pop '\r'
-            }
-        }
-
+    let mut is_started = false;
+    let mut handle_command = |input: &str| -> bool {
         if input == "help" {
             println!("\noptions:");
             println!("--start - starts the server on launch");
+            println!("--address <ip> - address to listen on (all addresses by default)");
+            println!("--port <port> - port for reporters (overrides the config)");
+            println!("--client-port <port> - port for clients (overrides the config)");
             println!("\ncommands:");
             println!("start - starts the server with the current configuration");
             println!("config - show the current server configuration");
+            println!("stats - show uptime, connection count, report count and disk space");
+            println!("set-max-attempts <n> - change the number of failed login attempts until ban");
+            println!("set-ban-duration <min> - change the ban duration (in minutes)");
             println!("exit - exit the application");
         } else if input == "start" {
-            net_service.start(under_monitor);
+            net_service.start(options.under_monitor);
+            is_started = true;
         } else if input == "config" {
             println!("{:#?}", net_service.server_config);
+            println!("{:#?}", net_service.get_ban_config());
+            net_service.print_disk_space_warning();
+        } else if input == "stats" {
+            net_service.print_stats();
+        } else if let Some(value) = input.strip_prefix("set-max-attempts ") {
+            match value.trim().parse::<u32>() {
+                Ok(max_attempts) => {
+                    if let Err(app_error) = net_service.set_max_allowed_login_attempts(max_attempts)
+                    {
+                        println!("{}", app_error);
+                    }
+                }
+                Err(_) => println!("\"{}\" is not a valid number", value),
+            }
+        } else if let Some(value) = input.strip_prefix("set-ban-duration ") {
+            match value.trim().parse::<i64>() {
+                Ok(duration_in_min) => {
+                    if let Err(app_error) = net_service.set_ban_time_duration(duration_in_min) {
+                        println!("{}", app_error);
+                    }
+                }
+                Err(_) => println!("\"{}\" is not a valid number", value),
+            }
         } else if input == "exit" {
-            break;
+            return false;
         } else {
             println!("command '{}' not found", input);
         }
 
         println!();
+        true
+    };
+
+    if options.start {
+        handle_command("start");
+    }
+
+    if read_commands(std::io::stdin().lock(), &mut handle_command) == InputEnd::Exit {
+        return;
+    }
+
+    if !is_started {
+        return;
+    }
+
+    // Happens when the server runs in the background (for example, under a service manager),
+    // keep processing connections.
+    println!("stdin was closed, commands are no longer read");
+    loop {
+        std::thread::park();
+    }
+}
+
+/// Describes why `read_commands` stopped reading commands.
+#[derive(Debug, PartialEq)]
+enum InputEnd {
+    /// The command handler asked to exit.
+    Exit,
+    /// The input was closed (end of file).
+    Eof,
+}
+
+/// Reads commands line by line and passes them to the specified handler.
+///
+/// ## Arguments
+/// * `reader`: input to read commands from (usually stdin).
+/// * `handle_command`: processes a command (without the line ending), returns `false`
+///   to stop reading commands.
+fn read_commands<R: BufRead>(
+    mut reader: R,
+    handle_command: &mut impl FnMut(&str) -> bool,
+) -> InputEnd {
+    loop {
+        if let Err(e) = std::io::stdout().flush() {
+            println!("could not flush stdout (error: {}), continuing...", e);
+        }
+
+        let mut input = String::new();
+        match reader.read_line(&mut input) {
+            Ok(0) => return InputEnd::Eof,
+            Ok(_) => {}
+            Err(e) => {
+                println!("unable to read input (error: {}), continuing...", e);
+                continue;
+            }
+        }
+
+        if !handle_command(input.trim_end_matches(['\r', '\n'])) {
+            return InputEnd::Exit;
+        }
+    }
+}
+
+/// Parses command-line arguments (without the executable path).
+///
+/// ## Return
+/// Error message if an argument is unknown or has an invalid value.
+fn parse_launch_options(args: &[String]) -> Result<LaunchOptions, String> {
+    let mut options = LaunchOptions::default();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--start" => options.start = true,
+            "--under-monitor" => options.under_monitor = true,
+            "--address" | "--port" | "--client-port" => {
+                let value = match args.next() {
+                    Some(value) => value,
+                    None => return Err(format!("expected a value after \"{}\"", arg)),
+                };
+
+                if arg == "--address" {
+                    match value.parse::<IpAddr>() {
+                        Ok(address) => options.address = Some(address),
+                        Err(_) => return Err(format!("\"{}\" is not a valid IP address", value)),
+                    }
+                    continue;
+                }
+
+                let port = match value.parse::<u16>() {
+                    Ok(port) if port != 0 => port,
+                    _ => {
+                        return Err(format!(
+                            "\"{}\" is not a valid port (expected a number from 1 to 65535)",
+                            value
+                        ))
+                    }
+                };
+                if arg == "--port" {
+                    options.port_for_reporters = Some(port);
+                } else {
+                    options.port_for_clients = Some(port);
+                }
+            }
+            _ => return Err(format!("unknown option \"{}\"", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_commands_stops_on_eof() {
+        let mut commands = Vec::new();
+        let result = read_commands("help\r\nstats\n".as_bytes(), &mut |input: &str| {
+            commands.push(input.to_string());
+            true
+        });
+
+        assert_eq!(result, InputEnd::Eof);
+        assert_eq!(commands, vec!["help", "stats"]);
+    }
+
+    #[test]
+    fn read_commands_on_closed_input() {
+        let mut command_count = 0;
+        let result = read_commands(std::io::empty(), &mut |_: &str| {
+            command_count += 1;
+            true
+        });
+
+        assert_eq!(result, InputEnd::Eof);
+        assert_eq!(command_count, 0);
+    }
+
+    #[test]
+    fn read_commands_stops_on_exit() {
+        let mut commands = Vec::new();
+        let result = read_commands("config\nexit\nstats\n".as_bytes(), &mut |input: &str| {
+            commands.push(input.to_string());
+            input != "exit"
+        });
+
+        assert_eq!(result, InputEnd::Exit);
+        assert_eq!(commands, vec!["config", "exit"]);
     }
 }
@@ -4,14 +4,21 @@ use std::net::IpAddr;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
 // External.
 use chrono::{DateTime, Local};
 use configparser::ini::Ini;
 use platform_dirs::AppDirs;
+use serde_json::json;
 
 // Custom.
+use super::metrics::ServerMetrics;
+use super::mutex_ext::MutexExt;
+use super::report_forwarder::{ForwardingTarget, ReportForwarder};
 use crate::io::{config_manager::ConfigManager, log_manager::*};
+use shared::misc::error::AppError;
 
 const BAN_FILE_NAME: &str = "server_banned_ips.ini";
 const BAN_SECTION_NAME: &str = "ban";
@@ -40,16 +47,35 @@ pub struct BannedIP {
     pub ip: IpAddr,
     pub ban_start_time: DateTime<Local>,
 }
+
+/// Ban settings that can be changed while the server is running.
+#[derive(Debug, Clone, Copy)]
+pub struct BanConfig {
+    pub max_allowed_login_attempts: u32,
+    pub ban_time_duration_in_min: i64,
+}
+
 pub struct BanManager {
-    pub config: Arc<ConfigManager>,
+    pub config: BanConfig,
+    /// Used to save changed ban settings.
+    config_manager: Arc<ConfigManager>,
     failed_ip_list: Mutex<Vec<FailedIP>>,
     banned_ip_list: Mutex<Vec<BannedIP>>,
     logger: Arc<Mutex<LogManager>>,
+    metrics: Arc<ServerMetrics>,
+    /// Time when the last ban alert was sent.
+    time_of_last_ban_alert: Option<Instant>,
+    /// Number of bans that were not reported because of the alert cooldown.
+    bans_since_last_alert: u32,
 }
 
 impl BanManager {
     /// Creates a new ban manager.
-    pub fn new(logger: Arc<Mutex<LogManager>>, config: Arc<ConfigManager>) -> Self {
+    pub fn new(
+        logger: Arc<Mutex<LogManager>>,
+        config: Arc<ConfigManager>,
+        metrics: Arc<ServerMetrics>,
+    ) -> Self {
         if config.max_allowed_login_attempts == 0 {
             panic!("max_allowed_login_attempts should not be zero or negative.");
         }
@@ -60,14 +86,21 @@ impl BanManager {
         let mut this = Self {
             failed_ip_list: Mutex::new(Vec::new()),
             banned_ip_list: Mutex::new(Vec::new()),
-            config,
+            config: BanConfig {
+                max_allowed_login_attempts: config.max_allowed_login_attempts,
+                ban_time_duration_in_min: config.ban_time_duration_in_min,
+            },
+            config_manager: config,
             logger,
+            metrics,
+            time_of_last_ban_alert: None,
+            bans_since_last_alert: 0,
         };
 
         let read_ip_count = this.load_banned_ips();
 
         if read_ip_count > 0 {
-            this.logger.lock().unwrap().print_and_log(
+            this.logger.lock_recover().print_and_log(
                 LogCategory::Info,
                 &format!(
                     "read {} banned IP(-s) from {}",
@@ -80,13 +113,128 @@ impl BanManager {
 
         this
     }
+    /// Changes the number of failed login attempts after which the IP is banned
+    /// and saves it to the config file.
+    ///
+    /// ## Arguments
+    /// * `max_allowed_login_attempts`: new number of allowed failed attempts (not zero).
+    pub fn set_max_allowed_login_attempts(
+        &mut self,
+        max_allowed_login_attempts: u32,
+    ) -> Result<(), AppError> {
+        if max_allowed_login_attempts == 0 {
+            return Err(AppError::new(
+                "max_allowed_login_attempts should not be zero",
+            ));
+        }
+
+        self.config.max_allowed_login_attempts = max_allowed_login_attempts;
+        self.logger.lock_recover().print_and_log(
+            LogCategory::Info,
+            &format!(
+                "max allowed login attempts changed to {}",
+                max_allowed_login_attempts
+            ),
+        );
+
+        self.config_manager.save_ban_config(&self.config)
+    }
+    /// Changes the duration of new and active bans and saves it to the config file.
+    ///
+    /// ## Arguments
+    /// * `ban_time_duration_in_min`: new ban duration in minutes (positive).
+    pub fn set_ban_time_duration(&mut self, ban_time_duration_in_min: i64) -> Result<(), AppError> {
+        if ban_time_duration_in_min <= 0 {
+            return Err(AppError::new(
+                "ban_time_duration_in_min should not be zero or negative",
+            ));
+        }
+
+        self.config.ban_time_duration_in_min = ban_time_duration_in_min;
+        self.logger.lock_recover().print_and_log(
+            LogCategory::Info,
+            &format!(
+                "ban duration changed to {} minute(-s)",
+                ban_time_duration_in_min
+            ),
+        );
+
+        self.config_manager.save_ban_config(&self.config)
+    }
+    /// Notifies the admin about a ban (if specified in the config) in a separate thread.
+    ///
+    /// ## Remarks
+    /// Only one alert is sent per `ban_alert_cooldown_in_min`, bans during the cooldown
+    /// are counted and reported in the next alert.
+    fn send_ban_alert(&mut self, username: &str, ip: IpAddr, failed_attempts_made: u32) {
+        let target = match &self.config_manager.ban_alert {
+            Some(target) => target.clone(),
+            None => return,
+        };
+
+        if let Some(time_of_last_ban_alert) = self.time_of_last_ban_alert {
+            let cooldown = Duration::from_secs(self.config_manager.ban_alert_cooldown_in_min * 60);
+            if time_of_last_ban_alert.elapsed() < cooldown {
+                self.bans_since_last_alert += 1;
+                return;
+            }
+        }
+
+        let unreported_ban_count = self.bans_since_last_alert;
+        self.bans_since_last_alert = 0;
+        self.time_of_last_ban_alert = Some(Instant::now());
+
+        let config_manager = self.config_manager.clone();
+        let logger = self.logger.clone();
+        let username = username.to_string();
+        let ban_time_duration_in_min = self.config.ban_time_duration_in_min;
+        thread::spawn(move || {
+            let result = match &target {
+                ForwardingTarget::Webhook { url } => ReportForwarder::send_webhook(
+                    url,
+                    json!({
+                        "event": "ip_banned",
+                        "ip": ip.to_string(),
+                        "username": username,
+                        "failed_attempts": failed_attempts_made,
+                        "ban_time_in_min": ban_time_duration_in_min,
+                        "unreported_ban_count": unreported_ban_count,
+                    }),
+                ),
+                ForwardingTarget::Email { address } => ReportForwarder::send_email(
+                    &config_manager,
+                    address,
+                    "[FBugReporter] IP banned",
+                    format!(
+                        "{} was banned for {} minute(-s) after {} failed attempts to login \
+                        as \"{}\". {} other ban(-s) happened since the previous alert.",
+                        ip,
+                        ban_time_duration_in_min,
+                        failed_attempts_made,
+                        username,
+                        unreported_ban_count
+                    ),
+                ),
+                ForwardingTarget::GitHub { .. } => {
+                    Err(AppError::new("GitHub is not supported for alerts"))
+                }
+            };
+
+            if let Err(app_error) = result {
+                logger.lock_recover().print_and_log(
+                    LogCategory::Error,
+                    &format!("failed to send ban alert to \"{}\": {}", target, app_error),
+                );
+            }
+        });
+    }
     /// Adds a failed login attempt to the IP.
     /// If this IP failed more than `max_allowed_login_attempts`
     /// it will be banned and removed from the failed ips list.
     ///
     /// Returns `AttemptResult` that shows the current IP state (failed login / banned).
     pub fn add_failed_login_attempt(&mut self, username: &str, ip: IpAddr) -> AttemptResult {
-        let mut failed_ip_guard = self.failed_ip_list.lock().unwrap();
+        let mut failed_ip_guard = self.failed_ip_list.lock_recover();
 
         // Find in failed_ip_list.
         let found_pos = failed_ip_guard.iter().position(|x| x.ip == ip);
@@ -98,6 +246,7 @@ impl BanManager {
 
         // Add current failed attempt.
         failed_attempts_made += 1;
+        self.metrics.add_failed_login();
 
         if failed_attempts_made > self.config.max_allowed_login_attempts {
             // Add to banned ips.
@@ -106,14 +255,14 @@ impl BanManager {
                 failed_ip_guard.remove(i);
             }
 
-            let mut banned_ips_guard = self.banned_ip_list.lock().unwrap();
+            let mut banned_ips_guard = self.banned_ip_list.lock_recover();
             banned_ips_guard.push(BannedIP {
                 ip,
                 ban_start_time: Local::now(),
             });
 
             {
-                self.logger.lock().unwrap().print_and_log(
+                self.logger.lock_recover().print_and_log(
                     LogCategory::Info,
                     &format!(
                         "{} was banned for {} minute(-s) due to {} failed login attempts.",
@@ -123,6 +272,11 @@ impl BanManager {
             }
 
             BanManager::store_banned_ip(banned_ips_guard.last().unwrap(), &self.logger);
+            self.metrics.add_ban();
+
+            drop(banned_ips_guard);
+            drop(failed_ip_guard);
+            self.send_ban_alert(username, ip, failed_attempts_made);
 
             AttemptResult::Ban
         } else {
@@ -137,7 +291,7 @@ impl BanManager {
                 });
             }
 
-            self.logger.lock().unwrap().print_and_log(
+            self.logger.lock_recover().print_and_log(
                 LogCategory::Info,
                 &format!(
                     "{} failed to login: {}/{} allowed failed login attempts.",
@@ -156,8 +310,8 @@ impl BanManager {
     /// - banned IP will be removed only if `ban_start_time` was
     /// `ban_time_duration_in_min` ago or longer.
     pub fn refresh_failed_and_banned_lists(&mut self) {
-        let mut failed_list_guard = self.failed_ip_list.lock().unwrap();
-        let mut banned_list_guard = self.banned_ip_list.lock().unwrap();
+        let mut failed_list_guard = self.failed_ip_list.lock_recover();
+        let mut banned_list_guard = self.banned_ip_list.lock_recover();
 
         // Refresh failed ips list.
         let mut _failed_list_len_before = failed_list_guard.len();
@@ -184,7 +338,7 @@ impl BanManager {
         if _failed_list_len_before != failed_list_guard.len()
             || _banned_list_len_before != banned_list_guard.len()
         {
-            self.logger.lock().unwrap().print_and_log(
+            self.logger.lock_recover().print_and_log(
                 LogCategory::Info,
                 &format!(
                     "Refreshed failed and banned ip lists to remove old entries:\n\
@@ -206,7 +360,7 @@ impl BanManager {
     /// If the specified IP is in the ban list, this function will also
     /// check if the ban time has passed and the IP is no longer banned.
     pub fn is_ip_banned(&self, ip: IpAddr) -> bool {
-        let mut banned_list_guard = self.banned_ip_list.lock().unwrap();
+        let mut banned_list_guard = self.banned_ip_list.lock_recover();
         let is_banned = banned_list_guard.iter().position(|x| x.ip == ip);
 
         match is_banned {
@@ -215,7 +369,7 @@ impl BanManager {
                 let time_diff = Local::now() - banned_list_guard[banned_ip_index].ban_start_time;
 
                 if time_diff.num_minutes() < self.config.ban_time_duration_in_min {
-                    self.logger.lock().unwrap().print_and_log(
+                    self.logger.lock_recover().print_and_log(
                         LogCategory::Info,
                         &format!(
                             "banned IP address ({}) attempted to connect. \
@@ -232,7 +386,7 @@ impl BanManager {
             }
             None => {
                 // Check if user failed to login before.
-                let mut failed_list_guard = self.failed_ip_list.lock().unwrap();
+                let mut failed_list_guard = self.failed_ip_list.lock_recover();
                 let failed_before = failed_list_guard.iter().position(|x| x.ip == ip);
 
                 if let Some(failed_index) = failed_before {
@@ -252,7 +406,7 @@ impl BanManager {
     }
     /// Removes the specified IP from the failed ips list.
     pub fn remove_ip_from_failed_ips_list(&mut self, ip: IpAddr) {
-        let mut failed_ip_list_guard = self.failed_ip_list.lock().unwrap();
+        let mut failed_ip_list_guard = self.failed_ip_list.lock_recover();
 
         let index_to_remove = failed_ip_list_guard.iter().position(|x| x.ip == ip);
         if let Some(index) = index_to_remove {
@@ -278,7 +432,7 @@ impl BanManager {
 
         let banned_ips = banned_ips.unwrap();
 
-        let mut guard = self.banned_ip_list.lock().unwrap();
+        let mut guard = self.banned_ip_list.lock_recover();
 
         let mut count_ip = 0;
 
@@ -290,7 +444,7 @@ impl BanManager {
             // Try parse IP.
             let ip = IpAddr::from_str(key);
             if let Err(e) = ip {
-                self.logger.lock().unwrap().print_and_log(
+                self.logger.lock_recover().print_and_log(
                     LogCategory::Warning,
                     &format!("failed to parse banned ip '{}', error: {}", key, e),
                 );
@@ -301,7 +455,7 @@ impl BanManager {
             // Try parse datetime.
             let datetime = DateTime::<Local>::from_str(value.as_ref().unwrap());
             if let Err(e) = datetime {
-                self.logger.lock().unwrap().print_and_log(
+                self.logger.lock_recover().print_and_log(
                     LogCategory::Warning,
                     &format!(
                         "failed to parse datetime '{}', error: {}",
@@ -334,7 +488,7 @@ impl BanManager {
             Some(ip.ban_start_time.to_string()),
         );
         if let Err(e) = config.write(Self::get_ban_file_path()) {
-            logger.lock().unwrap().print_and_log(
+            logger.lock_recover().print_and_log(
                 LogCategory::Error,
                 &format!(
                     "failed to write banned ip {} to file, error: {}",
@@ -349,7 +503,7 @@ impl BanManager {
         let mut config = Ini::new();
         let map = config.load(Self::get_ban_file_path());
         if map.is_err() {
-            logger.lock().unwrap().print_and_log(
+            logger.lock_recover().print_and_log(
                 LogCategory::Warning,
                 &format!(
                     "trying to remove banned ip {} from the {} file but this file does not exist.",
@@ -364,7 +518,7 @@ impl BanManager {
             .remove_key(BAN_SECTION_NAME, &ip.ip.to_string())
             .is_none()
         {
-            logger.lock().unwrap().print_and_log(
+            logger.lock_recover().print_and_log(
                 LogCategory::Warning,
                 &format!(
                     "trying to remove banned ip {} from the {} file but this entry does not exist.",
@@ -376,7 +530,7 @@ impl BanManager {
         }
 
         if let Err(e) = config.write(Self::get_ban_file_path()) {
-            logger.lock().unwrap().print_and_log(
+            logger.lock_recover().print_and_log(
                 LogCategory::Error,
                 &format!(
                     "failed to update file {} after removing the banned ip {}, error: {}.",
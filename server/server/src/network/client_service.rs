@@ -8,32 +8,47 @@ use chrono::{DateTime, Local};
 use sha2::{Digest, Sha512};
 use totp_rs::{Algorithm, TOTP};
 
-const TOTP_ALGORITHM: Algorithm = Algorithm::SHA1; // if changed, change protocol version
-
 // Custom.
 use super::ban_manager::*;
+use super::mutex_ext::MutexExt;
 use super::net_service::MAX_MESSAGE_SIZE_IN_BYTES_WITHOUT_ATTACHMENTS;
+use crate::io::config_manager::ConfigManager;
 use crate::io::log_manager::*;
-use shared::misc::db_manager::DatabaseManager;
+use shared::misc::db_manager::{
+    AuditAction, DatabaseManager, FailedLoginReason, OtpAlgorithm, OtpParameters,
+};
 use shared::misc::error::AppError;
+use shared::misc::password_policy::PasswordPolicy;
+use shared::misc::report::{ReportPriority, REPORT_COMMENT_MAX_LENGTH};
+use shared::misc::user::UserRole;
 use shared::network::client_messages::*;
+use shared::network::dh_group::DhGroup;
 use shared::network::messaging::*;
 use shared::network::net_params::*;
+use shared::network::server_identity::ServerIdentity;
 
 const KEEP_ALIVE_CHECK_INTERVAL_MS: u64 = 60000; // 1 minute
-const DISCONNECT_IF_INACTIVE_IN_SEC: u64 = 1800; // 30 minutes
+const SESSION_TOKEN_LIFETIME_IN_SEC: u64 = 600; // 10 minutes (after the session was closed)
+const OTP_ISSUER: &str = "FBugReporter";
+const MAX_STATS_DAY_COUNT: u64 = 366; // limits the size of the answer
 
 pub struct ClientService {
     logger: Arc<Mutex<LogManager>>,
     database: Arc<Mutex<DatabaseManager>>,
-    socket: TcpStream,
+    socket: Box<dyn MessageStream + Send>,
     socket_addr: SocketAddr,
     secret_key: [u8; SECRET_KEY_SIZE],
     connected_count: Arc<Mutex<usize>>,
     exit_error: Option<Result<String, AppError>>,
     ban_manager: Option<Arc<Mutex<BanManager>>>,
     username: Option<String>,
+    /// Token that the client can use to resume this session (if logged in).
+    session_token: Option<String>,
+    /// Time of the last received message (keep-alive messages are ignored
+    /// if they should not reset the inactivity timeout).
     time_of_last_received_message: DateTime<Local>,
+    server_config: Arc<ConfigManager>,
+    server_identity: Arc<ServerIdentity>,
 }
 
 impl ClientService {
@@ -41,23 +56,28 @@ impl ClientService {
     ///
     /// ## Arguments
     /// * `logger`: log manager for logging.
-    /// * `socket`: connected client socket.
+    /// * `socket`: connected client socket (or HTTP transport session).
     /// * `addr`: client socket address.
     /// * `connected_users_count`: shared variable that stores total connections.
     /// * `database`: database manager that handles the database.
     /// * `ban_manager`: ban manager for banning clients.
+    /// * `server_config`: server configuration.
+    /// * `server_identity`: key pair that is used to sign the key exchange.
+    #[allow(clippy::too_many_arguments)] // shared server state is passed as is
     pub fn new(
         logger: Arc<Mutex<LogManager>>,
-        socket: TcpStream,
+        socket: Box<dyn MessageStream + Send>,
         addr: SocketAddr,
         connected_count: Arc<Mutex<usize>>,
         database: Arc<Mutex<DatabaseManager>>,
         ban_manager: Option<Arc<Mutex<BanManager>>>,
+        server_config: Arc<ConfigManager>,
+        server_identity: Arc<ServerIdentity>,
     ) -> Self {
         {
-            let mut guard = connected_count.lock().unwrap();
+            let mut guard = connected_count.lock_recover();
             *guard += 1;
-            logger.lock().unwrap().print_and_log(
+            logger.lock_recover().print_and_log(
                 LogCategory::Info,
                 &format!(
                     "accepted connection with client {}:{}\n------------------------- [connected: {}] -------------------------",
@@ -68,8 +88,6 @@ impl ClientService {
             );
         }
 
-        let socket_addr = socket.peer_addr().unwrap();
-
         ClientService {
             logger,
             socket,
@@ -79,8 +97,13 @@ impl ClientService {
             database,
             ban_manager,
             username: None,
-            socket_addr,
+            session_token: None,
+            // Use the address from `accept` because `peer_addr` fails
+            // if the peer has already disconnected.
+            socket_addr: addr,
             time_of_last_received_message: Local::now(),
+            server_config,
+            server_identity,
         }
     }
 
@@ -92,7 +115,16 @@ impl ClientService {
     /// Only not banned clients should be processed here.
     /// This function assumes the client is not banned.
     pub fn process(mut self) {
-        let secret_key = start_establishing_secure_connection(&mut self.socket);
+        let _connection_span =
+            tracing::info_span!("client_connection", address = %self.socket_addr).entered();
+
+        let secret_key = tracing::info_span!("secure_connection").in_scope(|| {
+            start_establishing_secure_connection(
+                &mut self.socket,
+                self.server_config.dh_group,
+                &self.server_identity,
+            )
+        });
         if let Err(app_error) = secret_key {
             self.exit_error = Some(Err(app_error));
             return;
@@ -142,6 +174,21 @@ impl ClientService {
 
         // Connected.
         let result = self.wait_for_client_requests();
+
+        // Allow the client to resume this session for some time
+        // (for example, after a network issue).
+        if let Some(session_token) = self.session_token.as_ref() {
+            if let Err(app_error) = self
+                .database
+                .lock_recover()
+                .extend_session_token(session_token, SESSION_TOKEN_LIFETIME_IN_SEC)
+            {
+                self.logger
+                    .lock_recover()
+                    .print_and_log(LogCategory::Error, &app_error.to_string());
+            }
+        }
+
         if let Err(app_error) = result {
             self.exit_error = Some(Err(app_error));
             return;
@@ -153,6 +200,62 @@ impl ClientService {
         }
     }
 
+    /// Tells the client that the server is busy (used when the maximum number
+    /// of concurrent client connections was reached).
+    ///
+    /// ## Remarks
+    /// Server info requests are still answered since they don't start a session.
+    /// The first request is received (unlike reporters, clients send it without
+    /// waiting for the server) but it can't contain attachments.
+    ///
+    /// ## Arguments
+    /// * `socket`: connected client socket (or HTTP transport session).
+    /// * `dh_group`: Diffie-Hellman group to use for the key exchange.
+    /// * `server_identity`: key pair that is used to sign the key exchange.
+    pub fn answer_server_busy(
+        mut socket: Box<dyn MessageStream + Send>,
+        dh_group: DhGroup,
+        server_identity: &ServerIdentity,
+    ) -> Result<(), AppError> {
+        let secret_key =
+            start_establishing_secure_connection(&mut socket, dh_group, server_identity)?;
+        let secret_key: [u8; SECRET_KEY_SIZE] = match secret_key.try_into() {
+            Ok(secret_key) => secret_key,
+            Err(_) => return Err(AppError::new("failed to convert Vec<u8> to generic array")),
+        };
+
+        // Wait for the first request.
+        let mut is_fin = false;
+        let message = receive_message(
+            &mut socket,
+            &secret_key,
+            Some(MAX_WAIT_TIME_IN_READ_WRITE_MS),
+            MAX_MESSAGE_SIZE_IN_BYTES_WITHOUT_ATTACHMENTS,
+            &mut is_fin,
+        )?;
+
+        let answer = match bincode::deserialize::<ClientRequest>(&message) {
+            Ok(ClientRequest::QueryServerInfo) => ClientAnswer::ServerInfo {
+                net_protocol: NETWORK_PROTOCOL_VERSION,
+                server_version: String::from(env!("CARGO_PKG_VERSION")),
+            },
+            Ok(_) => ClientAnswer::LoginAnswer {
+                is_ok: false,
+                is_admin: false,
+                fail_reason: Some(ClientLoginFailReason::ServerBusy),
+                otp_backup_codes: Vec::new(),
+                role: UserRole::default(),
+                session_token: String::new(),
+            },
+            Err(e) => return Err(AppError::new(&e.to_string())),
+        };
+        if let Some(app_error) = send_message(&mut socket, &secret_key, answer) {
+            return Err(app_error);
+        }
+
+        Ok(())
+    }
+
     /// Processes the client message.
     ///
     /// Returns `Option<String>` as `Ok`:
@@ -164,6 +267,7 @@ impl ClientService {
     ///
     /// Returns `AppError` as `Err` if there was an internal error
     /// (bug).
+    #[tracing::instrument(skip_all, fields(request = <&'static str>::from(&message)))]
     fn handle_client_message(
         &mut self,
         message: ClientRequest,
@@ -201,8 +305,13 @@ impl ClientService {
 
                 Ok(result)
             }
-            ClientRequest::QueryReportsSummary { page, amount } => {
-                self.handle_client_reports_request(page, amount)?;
+            ClientRequest::QueryReportsSummary {
+                page,
+                amount,
+                sort_by_priority,
+                unread_only,
+            } => {
+                self.handle_client_reports_request(page, amount, sort_by_priority, unread_only)?;
                 Ok(None)
             }
             ClientRequest::QueryReport { report_id } => {
@@ -225,56 +334,180 @@ impl ClientService {
                 self.handle_client_delete_report_request(report_id)?;
                 Ok(None)
             }
+            ClientRequest::SetReportPriority {
+                report_id,
+                priority,
+            } => {
+                self.handle_client_set_report_priority_request(report_id, priority)?;
+                Ok(None)
+            }
+            ClientRequest::QueryReportComments { report_id } => {
+                self.handle_client_report_comments_request(report_id)?;
+                Ok(None)
+            }
+            ClientRequest::AddReportComment { report_id, text } => {
+                self.handle_client_add_report_comment_request(report_id, text)?;
+                Ok(None)
+            }
+            ClientRequest::QueryStats { day_count } => {
+                self.handle_client_stats_request(day_count)?;
+                Ok(None)
+            }
+            ClientRequest::QueryServerInfo => {
+                let answer = ClientAnswer::ServerInfo {
+                    net_protocol: NETWORK_PROTOCOL_VERSION,
+                    server_version: String::from(env!("CARGO_PKG_VERSION")),
+                };
+                if let Some(app_error) = send_message(&mut self.socket, &self.secret_key, answer) {
+                    return Err(app_error);
+                }
+
+                // Not a session, close the connection.
+                Ok(Some(String::from("answered server info request")))
+            }
+            ClientRequest::ResumeSession {
+                client_net_protocol,
+                session_token,
+            } => self.handle_client_resume_session_request(client_net_protocol, session_token),
+            ClientRequest::KeepAlive => {
+                // Nothing to answer, only resets the inactivity timeout (if enabled).
+                Ok(None)
+            }
+            ClientRequest::Logout => {
+                if let Some(session_token) = self.session_token.take() {
+                    self.database
+                        .lock_recover()
+                        .remove_session_token(&session_token)?;
+                }
+
+                Ok(Some(String::from("the user logged out")))
+            }
         }
     }
 
-    /// Processes the client login request.
+    /// Processes the client request to resume a recently closed session.
     ///
     /// Returns `Option<String>` as `Ok`:
     /// - if `Some(String)` then there was a "soft" error
-    /// (typically means that there was an error in client
-    /// data (wrong credentials, protocol version, etc...)
-    /// and we don't need to consider this as a bug,
+    ///   (typically means that the token is wrong or expired)
+    ///   and we don't need to consider this as a bug,
     /// - if `None` then the operation finished successfully.
     ///
     /// Returns `AppError` as `Err` if there was an internal error
     /// (bug).
-    fn handle_client_login_request(
+    fn handle_client_resume_session_request(
         &mut self,
         client_net_protocol: u16,
-        username: String,
-        mut password: Vec<u8>,
-        otp: String,
-        new_password: Option<Vec<u8>>,
+        session_token: String,
     ) -> Result<Option<String>, AppError> {
+        if let Some(username) = self.username.as_ref() {
+            return Err(AppError::new(&format!(
+                "client \"{}\" tried to resume a session while being logged in",
+                username
+            )));
+        }
+
         // Check protocol version.
         if client_net_protocol != NETWORK_PROTOCOL_VERSION {
+            return self.answer_client_wrong_protocol(client_net_protocol, "unknown");
+        }
+
+        let username = self
+            .database
+            .lock_recover()
+            .use_session_token(&session_token)?;
+        if username.is_none() {
             let answer = ClientAnswer::LoginAnswer {
                 is_ok: false,
                 is_admin: false,
-                fail_reason: Some(ClientLoginFailReason::WrongProtocol {
-                    server_protocol: NETWORK_PROTOCOL_VERSION,
-                }),
+                fail_reason: Some(ClientLoginFailReason::SessionExpired),
+                otp_backup_codes: Vec::new(),
+                role: UserRole::default(),
+                session_token: String::new(),
             };
             if let Some(app_error) = send_message(&mut self.socket, &self.secret_key, answer) {
                 return Err(app_error);
             }
 
             return Ok(Some(format!(
-                "wrong protocol version ({} != {}) (username: {})",
-                client_net_protocol, NETWORK_PROTOCOL_VERSION, username
+                "{} tried to resume a session using a wrong or expired session token",
+                self.socket_addr
             )));
         }
+        let username = username.unwrap();
+
+        self.logger.lock_recover().print_and_log(
+            LogCategory::Info,
+            &format!("{} ({}) resumed the session", &username, self.socket_addr),
+        );
+
+        self.answer_client_logged_in(username, Vec::new())?;
+
+        Ok(None)
+    }
+
+    /// Tells the client that it uses a different network protocol version.
+    ///
+    /// Returns a "soft" error message to close the connection with.
+    fn answer_client_wrong_protocol(
+        &mut self,
+        client_net_protocol: u16,
+        username: &str,
+    ) -> Result<Option<String>, AppError> {
+        let answer = ClientAnswer::LoginAnswer {
+            is_ok: false,
+            is_admin: false,
+            fail_reason: Some(ClientLoginFailReason::WrongProtocol {
+                server_protocol: NETWORK_PROTOCOL_VERSION,
+            }),
+            otp_backup_codes: Vec::new(),
+            role: UserRole::default(),
+            session_token: String::new(),
+        };
+        if let Some(app_error) = send_message(&mut self.socket, &self.secret_key, answer) {
+            return Err(app_error);
+        }
+
+        Ok(Some(format!(
+            "wrong protocol version ({} != {}) (username: {})",
+            client_net_protocol, NETWORK_PROTOCOL_VERSION, username
+        )))
+    }
+
+    /// Processes the client login request.
+    ///
+    /// Returns `Option<String>` as `Ok`:
+    /// - if `Some(String)` then there was a "soft" error
+    /// (typically means that there was an error in client
+    /// data (wrong credentials, protocol version, etc...)
+    /// and we don't need to consider this as a bug,
+    /// - if `None` then the operation finished successfully.
+    ///
+    /// Returns `AppError` as `Err` if there was an internal error
+    /// (bug).
+    fn handle_client_login_request(
+        &mut self,
+        client_net_protocol: u16,
+        username: String,
+        mut password: Vec<u8>,
+        otp: String,
+        new_password: Option<Vec<u8>>,
+    ) -> Result<Option<String>, AppError> {
+        // Check protocol version.
+        if client_net_protocol != NETWORK_PROTOCOL_VERSION {
+            return self.answer_client_wrong_protocol(client_net_protocol, &username);
+        }
 
         // Get user's password and salt.
-        let database_guard = self.database.lock().unwrap();
+        let database_guard = self.database.lock_recover();
         let (db_password, salt) = database_guard.get_user_password_and_salt(&username)?;
         drop(database_guard);
 
         // Check if user exists.
         if db_password.is_empty() {
             // No user was found for this username.
-            let result = self.answer_client_wrong_credentials(&username)?;
+            let result =
+                self.answer_client_wrong_credentials(&username, FailedLoginReason::UnknownUser)?;
 
             return Ok(Some(result));
         }
@@ -290,27 +523,43 @@ impl ClientService {
 
         if password_hash != db_password {
             // Wrong password.
-            let result = self.answer_client_wrong_credentials(&username)?;
+            let result =
+                self.answer_client_wrong_credentials(&username, FailedLoginReason::WrongPassword)?;
 
             return Ok(Some(result));
         }
 
-        // See if user needs to set first password.
+        // See if the password has expired.
+        if self.server_config.max_password_age_in_days != 0
+            && self
+                .database
+                .lock_recover()
+                .require_password_change_if_expired(
+                    &username,
+                    self.server_config.max_password_age_in_days,
+                )?
+        {
+            self.logger.lock_recover().print_and_log(
+                LogCategory::Info,
+                &format!("the password of {} has expired.", &username),
+            );
+        }
+
+        // See if user needs to set first password (or a new password).
         let mut _need_change_password = false;
         {
             _need_change_password = self
                 .database
-                .lock()
-                .unwrap()
+                .lock_recover()
                 .is_user_needs_to_change_password(&username)?;
         }
 
         if _need_change_password && new_password.is_none() {
-            // Need to set first password.
-            self.logger.lock().unwrap().print_and_log(
+            // Need to set first password (or a new password).
+            self.logger.lock_recover().print_and_log(
                 LogCategory::Info,
                 &format!(
-                    "{} logged in but needs to set the first password, disconnecting...",
+                    "{} logged in but needs to set a new password, disconnecting...",
                     &username
                 ),
             );
@@ -318,7 +567,12 @@ impl ClientService {
             let answer = ClientAnswer::LoginAnswer {
                 is_ok: false,
                 is_admin: false,
-                fail_reason: Some(ClientLoginFailReason::NeedFirstPassword),
+                fail_reason: Some(ClientLoginFailReason::NeedFirstPassword {
+                    password_policy: self.server_config.get_password_policy(),
+                }),
+                otp_backup_codes: Vec::new(),
+                role: UserRole::default(),
+                session_token: String::new(),
             };
             if let Some(app_error) = send_message(&mut self.socket, &self.secret_key, answer) {
                 return Err(app_error);
@@ -328,11 +582,39 @@ impl ClientService {
         }
 
         if let Some(new_password) = new_password {
+            // The client checks the length and the characters of the new password
+            // (we only receive its hash), make sure that it's not a common password.
+            if self.server_config.reject_common_passwords
+                && PasswordPolicy::is_common_password_hash(&new_password)
+            {
+                self.logger.lock_recover().print_and_log(
+                    LogCategory::Info,
+                    &format!("{} tried to set a common password.", &username),
+                );
+
+                let answer = ClientAnswer::LoginAnswer {
+                    is_ok: false,
+                    is_admin: false,
+                    fail_reason: Some(ClientLoginFailReason::WeakPassword {
+                        reason: String::from(
+                            "This password is too common, please, use another one.",
+                        ),
+                    }),
+                    otp_backup_codes: Vec::new(),
+                    role: UserRole::default(),
+                    session_token: String::new(),
+                };
+                if let Some(app_error) = send_message(&mut self.socket, &self.secret_key, answer) {
+                    return Err(app_error);
+                }
+
+                return Ok(None);
+            }
+
             // Set first password.
             let result = self
                 .database
-                .lock()
-                .unwrap()
+                .lock_recover()
                 .update_user_password(&username, new_password)?;
             if result {
                 return Ok(Some(format!(
@@ -342,45 +624,49 @@ impl ClientService {
                 )));
             }
 
-            self.logger.lock().unwrap().print_and_log(
+            self.database.lock_recover().add_audit_log_entry(
+                &username,
+                AuditAction::SetFirstPassword,
+                &username,
+            )?;
+            self.logger.lock_recover().print_and_log(
                 LogCategory::Info,
                 &format!("{} set first password.", &username),
             );
         }
 
         // Check if user needs to setup OTP (receive OTP QR code).
-        {
-            let db_guard = self.database.lock().unwrap();
+        // If OTP is not required, users are logged in right after password verification.
+        let mut otp_backup_codes = Vec::new();
+        if self.server_config.require_otp {
+            let db_guard = self.database.lock_recover();
             let _need_setup_otp = db_guard.is_user_needs_setup_otp(&username)?;
 
             // Get OTP secret.
             let otp_secret = db_guard.get_otp_secret_key_for_user(&username)?;
 
-            drop(db_guard);
-
             if _need_setup_otp && otp.is_empty() {
+                // Save current OTP parameters for this user so that
+                // changes in the server config will not affect this user.
+                let otp_parameters = self.server_config.get_otp_parameters();
+                db_guard.set_otp_parameters_for_user(&username, &otp_parameters)?;
+                drop(db_guard);
+
                 // Generate QR code.
-                let totp = TOTP::new(
-                    TOTP_ALGORITHM,
-                    6,
-                    1,
-                    30,
+                let totp = ClientService::create_totp(
+                    &otp_parameters,
+                    self.server_config.otp_verification_window,
                     otp_secret,
-                    Some(String::from("FBugReporter")),
-                    username.clone(),
-                );
-                if let Err(e) = totp {
-                    return Err(AppError::new(&format!("{:?}", e)));
-                }
-                let totp = totp.unwrap();
+                    &username,
+                )?;
 
                 let qr_code = totp.get_qr();
                 if let Err(e) = qr_code {
-                    return Err(AppError::new(&e.to_string()));
+                    return Err(AppError::new(&e));
                 }
                 let qr_code = qr_code.unwrap();
 
-                self.logger.lock().unwrap().print_and_log(
+                self.logger.lock_recover().print_and_log(
                     LogCategory::Info,
                     &format!(
                         "{} logged in but needs to setup OTP, disconnecting...",
@@ -393,6 +679,9 @@ impl ClientService {
                     is_ok: false,
                     is_admin: false,
                     fail_reason: Some(ClientLoginFailReason::SetupOTP { qr_code }),
+                    otp_backup_codes: Vec::new(),
+                    role: UserRole::default(),
+                    session_token: String::new(),
                 };
                 if let Some(app_error) = send_message(&mut self.socket, &self.secret_key, answer) {
                     return Err(app_error);
@@ -400,12 +689,19 @@ impl ClientService {
 
                 return Ok(None);
             } else {
+                // Get OTP parameters that this user used during OTP setup.
+                let otp_parameters = db_guard.get_otp_parameters_for_user(&username)?;
+                drop(db_guard);
+
                 if otp.is_empty() {
                     // Need OTP.
                     let answer = ClientAnswer::LoginAnswer {
                         is_ok: false,
                         is_admin: false,
                         fail_reason: Some(ClientLoginFailReason::NeedOTP),
+                        otp_backup_codes: Vec::new(),
+                        role: UserRole::default(),
+                        session_token: String::new(),
                     };
                     if let Some(app_error) =
                         send_message(&mut self.socket, &self.secret_key, answer)
@@ -419,34 +715,68 @@ impl ClientService {
                     )));
                 }
 
-                // Generate current OTP.
-                let totp = TOTP::new(TOTP_ALGORITHM, 6, 1, 30, otp_secret, None, String::new());
-                if let Err(e) = totp {
-                    return Err(AppError::new(&format!("{:?}", e)));
-                }
-                let totp = totp.unwrap();
+                let totp = ClientService::create_totp(
+                    &otp_parameters,
+                    self.server_config.otp_verification_window,
+                    otp_secret,
+                    &username,
+                )?;
 
                 let time = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH);
                 if let Err(e) = time {
                     return Err(AppError::new(&e.to_string()));
                 }
                 let time = time.unwrap().as_secs();
-                let token = totp.generate(time);
 
-                if token != otp {
-                    self.logger.lock().unwrap().print_and_log(
+                // Check OTP against current time step and nearby time steps
+                // in case the client's clock is a bit off.
+                let mut is_otp_valid = totp.check(&otp, time);
+
+                if !is_otp_valid && !_need_setup_otp {
+                    // Maybe this is a backup code.
+                    let guard = self.database.lock_recover();
+                    is_otp_valid = guard.use_otp_backup_code(&username, &otp)?;
+                    if is_otp_valid {
+                        guard.add_audit_log_entry(
+                            &username,
+                            AuditAction::UseOtpBackupCode,
+                            &username,
+                        )?;
+                    }
+                    drop(guard);
+                    if is_otp_valid {
+                        self.logger.lock_recover().print_and_log(
+                            LogCategory::Warning,
+                            &format!("{} used an OTP backup code to login.", &username),
+                        );
+                    }
+                }
+
+                if !is_otp_valid {
+                    self.logger.lock_recover().print_and_log(
                         LogCategory::Info,
                         &format!("{} tried to login using wrong OTP code.", &username),
                     );
-                    let result = self.answer_client_wrong_credentials(&username)?;
+                    let result = self
+                        .answer_client_wrong_credentials(&username, FailedLoginReason::WrongOtp)?;
 
                     return Ok(Some(result));
                 } else if _need_setup_otp {
-                    self.database
-                        .lock()
-                        .unwrap()
-                        .set_user_finished_otp_setup(&username)?;
-                    self.logger.lock().unwrap().print_and_log(
+                    let guard = self.database.lock_recover();
+                    guard.set_user_finished_otp_setup(&username)?;
+
+                    // Generate backup codes in case the user will lose the authenticator app.
+                    let backup_codes = guard.regenerate_otp_backup_codes(&username)?;
+                    drop(guard);
+                    if backup_codes.is_none() {
+                        return Err(AppError::new(&format!(
+                            "user \"{}\" was not found while generating OTP backup codes",
+                            username
+                        )));
+                    }
+                    otp_backup_codes = backup_codes.unwrap();
+
+                    self.logger.lock_recover().print_and_log(
                         LogCategory::Info,
                         &format!("{} finished OTP setup.", &username),
                     );
@@ -454,18 +784,44 @@ impl ClientService {
             }
         }
 
-        let mut _is_admin = false;
         {
-            let guard = self.database.lock().unwrap();
+            // Mark user as logged in.
+            self.logger.lock_recover().print_and_log(
+                LogCategory::Info,
+                &format!("{} ({}) logged in", &username, self.socket_addr),
+            );
+        }
+
+        self.answer_client_logged_in(username, otp_backup_codes)?;
+
+        Ok(None)
+    }
+
+    /// Remembers that the user logged in (or resumed the session),
+    /// generates a new session token and sends it to the client.
+    ///
+    /// ## Arguments
+    /// * `username`: user that logged in.
+    /// * `otp_backup_codes`: OTP backup codes to send (only once OTP setup is finished).
+    fn answer_client_logged_in(
+        &mut self,
+        username: String,
+        otp_backup_codes: Vec<String>,
+    ) -> Result<(), AppError> {
+        let mut _role = UserRole::default();
+        let mut _session_token = String::new();
+        {
+            let guard = self.database.lock_recover();
 
             // Update last login time/date/ip.
-            guard.update_user_last_login(
-                &username,
-                &self.socket.peer_addr().unwrap().ip().to_string(),
-            )?;
+            guard.update_user_last_login(&username, &self.socket_addr.ip().to_string())?;
+
+            // Get user's role.
+            _role = guard.get_user_role(&username)?;
 
-            // Check if user is admin.
-            _is_admin = guard.is_user_admin(&username)?;
+            // Generate a token to resume the session later.
+            _session_token =
+                guard.create_session_token(&username, SESSION_TOKEN_LIFETIME_IN_SEC)?;
         }
 
         {
@@ -473,48 +829,56 @@ impl ClientService {
             self.ban_manager
                 .as_ref()
                 .unwrap()
-                .lock()
-                .unwrap()
-                .remove_ip_from_failed_ips_list(self.socket.peer_addr().unwrap().ip());
-        }
-
-        {
-            // Mark user as logged in.
-            self.logger.lock().unwrap().print_and_log(
-                LogCategory::Info,
-                &format!("{} ({}) logged in", &username, self.socket_addr),
-            );
+                .lock_recover()
+                .remove_ip_from_failed_ips_list(self.socket_addr.ip());
         }
 
         self.username = Some(username);
+        self.session_token = Some(_session_token.clone());
 
         // Answer "connected".
         let answer = ClientAnswer::LoginAnswer {
             is_ok: true,
-            is_admin: _is_admin,
+            is_admin: _role == UserRole::Admin,
             fail_reason: None,
+            otp_backup_codes,
+            role: _role,
+            session_token: _session_token,
         };
         if let Some(app_error) = send_message(&mut self.socket, &self.secret_key, answer) {
             return Err(app_error);
         }
 
-        Ok(None)
+        Ok(())
     }
 
     /// Handles client's "query reports" request.
     ///
     /// Will query reports and send them to the client.
-    fn handle_client_reports_request(&mut self, page: u64, amount: u64) -> Result<(), AppError> {
+    /// If `unread_only` is `true` only reports that this user did not read yet are sent.
+    fn handle_client_reports_request(
+        &mut self,
+        page: u64,
+        amount: u64,
+        sort_by_priority: bool,
+        unread_only: bool,
+    ) -> Result<(), AppError> {
+        let username = self.username.clone().unwrap_or_default();
+
         // Get reports from database.
-        let guard = self.database.lock().unwrap();
-        let reports = guard.get_reports(page, amount)?;
-        let report_count = guard.get_report_count()?;
+        let guard = self.database.lock_recover();
+        let reports = guard.get_reports(page, amount, sort_by_priority, &username, unread_only)?;
+        let report_count = if unread_only {
+            guard.get_unread_report_count(&username)?
+        } else {
+            guard.get_report_count()?
+        };
         drop(guard);
 
         // Get disk space.
         let (mut _total_disk_space_mb, mut _used_disk_space_mb) = (0u64, 0u64);
         {
-            let guard = self.database.lock().unwrap();
+            let guard = self.database.lock_recover();
             (_total_disk_space_mb, _used_disk_space_mb) = guard.get_disk_space_mb();
         }
 
@@ -537,54 +901,52 @@ impl ClientService {
 
     /// Handles client's "delete report" request.
     ///
-    /// Looks if the client has admin privileges and removes a report
+    /// Looks if the client is allowed to delete reports and removes a report
     /// with the specified ID.
     fn handle_client_delete_report_request(&mut self, report_id: u64) -> Result<(), AppError> {
-        // Check if this user has admin privileges.
+        // Check if this user is allowed to delete reports.
         {
             let mut username = String::new();
             if self.username.is_some() {
                 username = self.username.as_ref().unwrap().clone();
             }
-            let result = self.database.lock().unwrap().is_user_admin(&username);
-            if let Err(e) = result {
-                return Err(AppError::new(&e.to_string()));
-            }
-            let is_admin = result.unwrap();
+            let role = self.database.lock_recover().get_user_role(&username)?;
 
-            if is_admin {
-                self.logger.lock().unwrap().print_and_log(
+            if role.can_delete_reports() {
+                self.logger.lock_recover().print_and_log(
                     LogCategory::Info,
                     &format!(
-                        "admin client \"{}\" requested to delete a report with id {}",
-                        &username, report_id
+                        "{} client \"{}\" requested to delete a report with id {}",
+                        role, &username, report_id
                     ),
                 )
             } else {
                 let message = format!(
-                    "client \"{}\" tried to \
-                    delete a report with id {} without admin privileges",
-                    &username, is_admin
+                    "{} client \"{}\" tried to \
+                    delete a report with id {} without required privileges",
+                    role, &username, report_id
                 );
                 self.logger
-                    .lock()
-                    .unwrap()
+                    .lock_recover()
                     .print_and_log(LogCategory::Warning, &message);
                 return Err(AppError::new(&message));
             }
         }
 
         // Remove report from database.
-        let found = self.database.lock().unwrap().remove_report(report_id)?;
-        if !found {
-            let mut username = String::new();
-            if self.username.is_some() {
-                username = self.username.as_ref().unwrap().clone();
-            }
-            self.logger.lock().unwrap().print_and_log(
+        let found = self.database.lock_recover().remove_report(report_id)?;
+        let username = self.username.clone().unwrap_or_default();
+        if found {
+            self.database.lock_recover().add_audit_log_entry(
+                &username,
+                AuditAction::DeleteReport,
+                &format!("report {}", report_id),
+            )?;
+        } else {
+            self.logger.lock_recover().print_and_log(
                 LogCategory::Warning,
                 &format!(
-                    "admin client \"{}\" tried to \
+                    "client \"{}\" tried to \
                     delete a report with id {} while a report with this id does not exist",
                     &username, report_id
                 ),
@@ -605,6 +967,172 @@ impl ClientService {
         Ok(())
     }
 
+    /// Handles client's "set report priority" request.
+    ///
+    /// Looks if the client is allowed to change reports and changes priority
+    /// of a report with the specified ID.
+    fn handle_client_set_report_priority_request(
+        &mut self,
+        report_id: u64,
+        priority: ReportPriority,
+    ) -> Result<(), AppError> {
+        // Check if this user is allowed to change reports.
+        let username = self.username.clone().unwrap_or_default();
+        let role = self.database.lock_recover().get_user_role(&username)?;
+        if !role.can_change_reports() {
+            let message = format!(
+                "{} client \"{}\" tried to \
+                change priority of a report with id {} without required privileges",
+                role, &username, report_id
+            );
+            self.logger
+                .lock_recover()
+                .print_and_log(LogCategory::Warning, &message);
+            return Err(AppError::new(&message));
+        }
+
+        self.logger.lock_recover().print_and_log(
+            LogCategory::Info,
+            &format!(
+                "{} client \"{}\" requested to change priority of a report with id {} to {}",
+                role, &username, report_id, priority
+            ),
+        );
+
+        // Change priority in the database.
+        let found = self
+            .database
+            .lock_recover()
+            .set_report_priority(report_id, priority)?;
+        if found {
+            self.database.lock_recover().add_audit_log_entry(
+                &username,
+                AuditAction::SetReportPriority,
+                &format!("report {} (new priority: {})", report_id, priority),
+            )?;
+        }
+
+        // Prepare message to send.
+        let message = ClientAnswer::SetReportPriorityResult {
+            is_found_and_changed: found,
+        };
+
+        // Send result.
+        let result = send_message(&mut self.socket, &self.secret_key, message);
+        if let Some(app_error) = result {
+            return Err(app_error);
+        }
+
+        Ok(())
+    }
+
+    /// Handles client's "query report comments" request.
+    ///
+    /// Sends all comments of a report with the specified ID to the client.
+    fn handle_client_report_comments_request(&mut self, report_id: u64) -> Result<(), AppError> {
+        let comments = self
+            .database
+            .lock_recover()
+            .get_report_comments(report_id)?;
+
+        // Prepare message to send.
+        let message = ClientAnswer::ReportComments { comments };
+
+        // Send comments.
+        let result = send_message(&mut self.socket, &self.secret_key, message);
+        if let Some(app_error) = result {
+            return Err(app_error);
+        }
+
+        Ok(())
+    }
+
+    /// Handles client's "query stats" request.
+    ///
+    /// Sends the number of reports per game and per day to the client.
+    fn handle_client_stats_request(&mut self, day_count: u64) -> Result<(), AppError> {
+        let day_count = day_count.min(MAX_STATS_DAY_COUNT);
+
+        let stats = self.database.lock_recover().get_report_stats(day_count)?;
+
+        // Send stats.
+        let result = send_message(
+            &mut self.socket,
+            &self.secret_key,
+            ClientAnswer::Stats { stats },
+        );
+        if let Some(app_error) = result {
+            return Err(app_error);
+        }
+
+        Ok(())
+    }
+
+    /// Handles client's "add report comment" request.
+    ///
+    /// Looks if the client is allowed to change reports and adds a comment
+    /// to a report with the specified ID.
+    fn handle_client_add_report_comment_request(
+        &mut self,
+        report_id: u64,
+        text: String,
+    ) -> Result<(), AppError> {
+        // Check if this user is allowed to change reports.
+        let username = self.username.clone().unwrap_or_default();
+        let role = self.database.lock_recover().get_user_role(&username)?;
+        if !role.can_change_reports() {
+            let message = format!(
+                "{} client \"{}\" tried to \
+                comment a report with id {} without required privileges",
+                role, &username, report_id
+            );
+            self.logger
+                .lock_recover()
+                .print_and_log(LogCategory::Warning, &message);
+            return Err(AppError::new(&message));
+        }
+
+        let text = text.trim();
+        if text.is_empty() || text.chars().count() > REPORT_COMMENT_MAX_LENGTH {
+            let message = format!(
+                "{} client \"{}\" tried to \
+                add a comment with invalid length to a report with id {}",
+                role, &username, report_id
+            );
+            self.logger
+                .lock_recover()
+                .print_and_log(LogCategory::Warning, &message);
+            return Err(AppError::new(&message));
+        }
+
+        self.logger.lock_recover().print_and_log(
+            LogCategory::Info,
+            &format!(
+                "{} client \"{}\" requested to comment a report with id {}",
+                role, &username, report_id
+            ),
+        );
+
+        // Add comment to the database.
+        let found = self
+            .database
+            .lock_recover()
+            .add_report_comment(report_id, &username, text)?;
+
+        // Prepare message to send.
+        let message = ClientAnswer::AddReportCommentResult {
+            is_found_and_added: found,
+        };
+
+        // Send result.
+        let result = send_message(&mut self.socket, &self.secret_key, message);
+        if let Some(app_error) = result {
+            return Err(app_error);
+        }
+
+        Ok(())
+    }
+
     // Handles client's "query attachment" request.
     ///
     /// Queries the specified attachment from the database and returns
@@ -616,7 +1144,7 @@ impl ClientService {
             if self.username.is_some() {
                 username = self.username.as_ref().unwrap().clone();
             }
-            self.logger.lock().unwrap().print_and_log(
+            self.logger.lock_recover().print_and_log(
                 LogCategory::Info,
                 &format!(
                     "client \"{}\" requested an attachment with id {}",
@@ -626,7 +1154,7 @@ impl ClientService {
         }
 
         // Get attachment from database.
-        let mut guard = self.database.lock().unwrap();
+        let mut guard = self.database.lock_recover();
         let result = guard.get_attachment(attachment_id);
         drop(guard);
 
@@ -669,7 +1197,7 @@ impl ClientService {
             if self.username.is_some() {
                 username = self.username.as_ref().unwrap().clone();
             }
-            self.logger.lock().unwrap().print_and_log(
+            self.logger.lock_recover().print_and_log(
                 LogCategory::Info,
                 &format!(
                     "client \"{}\" requested a report with id {}",
@@ -679,9 +1207,8 @@ impl ClientService {
         }
 
         // Get reports from database.
-        let mut guard = self.database.lock().unwrap();
+        let mut guard = self.database.lock_recover();
         let result = guard.get_report(report_id);
-        drop(guard);
 
         // Check report.
         if let Err(app_error) = result {
@@ -689,6 +1216,14 @@ impl ClientService {
         }
         let report = result.unwrap();
 
+        // Mark as read (read state is stored per user).
+        if let Some(username) = &self.username {
+            if let Err(app_error) = guard.mark_report_as_read(username, report_id) {
+                return Some(app_error);
+            }
+        }
+        drop(guard);
+
         // Prepare message to send.
         let message = ClientAnswer::Report {
             id: report.id,
@@ -701,7 +1236,14 @@ impl ClientService {
             sender_name: report.sender_name,
             sender_email: report.sender_email,
             os_info: report.os_info,
+            priority: report.priority,
             attachments: report.attachments,
+            metadata: report.metadata,
+            reporter_net_protocol: report.reporter_net_protocol,
+            reporter_version: report.reporter_version,
+            is_markdown: report.is_markdown,
+            session_id: report.session_id,
+            playtime_seconds: report.playtime_seconds,
         };
 
         // Send reports.
@@ -713,24 +1255,33 @@ impl ClientService {
         None
     }
 
-    /// Sends `ClientLoginAnswer` with `WrongCredentials` message
-    /// to the client.
+    /// Records the failed login attempt in the database and sends
+    /// `ClientLoginAnswer` with `WrongCredentials` message to the client.
     ///
     /// Returns `String` as `Ok` with message to show
     /// (i.e. "wrong credentials for username ...").
     ///
     /// Returns `AppError` as `Err` if there was an internal error
     /// (bug).
-    fn answer_client_wrong_credentials(&mut self, username: &str) -> Result<String, AppError> {
+    fn answer_client_wrong_credentials(
+        &mut self,
+        username: &str,
+        reason: FailedLoginReason,
+    ) -> Result<String, AppError> {
+        self.database.lock_recover().add_failed_login(
+            username,
+            &self.socket_addr.ip().to_string(),
+            reason,
+        )?;
+
         let mut _result = AttemptResult::Ban;
         {
             _result = self
                 .ban_manager
                 .as_ref()
                 .unwrap()
-                .lock()
-                .unwrap()
-                .add_failed_login_attempt(username, self.socket.peer_addr().unwrap().ip());
+                .lock_recover()
+                .add_failed_login_attempt(username, self.socket_addr.ip());
         }
 
         match _result {
@@ -745,12 +1296,14 @@ impl ClientService {
                                 .ban_manager
                                 .as_ref()
                                 .unwrap()
-                                .lock()
-                                .unwrap()
+                                .lock_recover()
                                 .config
                                 .max_allowed_login_attempts,
                         },
                     }),
+                    otp_backup_codes: Vec::new(),
+                    role: UserRole::default(),
+                    session_token: String::new(),
                 };
                 if let Some(app_error) = send_message(&mut self.socket, &self.secret_key, _answer) {
                     return Err(app_error);
@@ -766,12 +1319,14 @@ impl ClientService {
                                 .ban_manager
                                 .as_ref()
                                 .unwrap()
-                                .lock()
-                                .unwrap()
+                                .lock_recover()
                                 .config
                                 .ban_time_duration_in_min,
                         },
                     }),
+                    otp_backup_codes: Vec::new(),
+                    role: UserRole::default(),
+                    session_token: String::new(),
                 };
                 if let Some(app_error) = send_message(&mut self.socket, &self.secret_key, _answer) {
                     return Err(app_error);
@@ -816,8 +1371,6 @@ impl ClientService {
                     return Ok(Some(message));
                 }
                 continue;
-            } else {
-                self.time_of_last_received_message = Local::now();
             }
 
             // Deserialize.
@@ -827,6 +1380,19 @@ impl ClientService {
             }
             let message = message.unwrap();
 
+            if matches!(message, ClientRequest::KeepAlive)
+                && !self.server_config.client_keep_alive_resets_inactivity
+            {
+                // Keep-alive messages don't let the receive operation time out,
+                // check inactivity here.
+                let result = self.check_client_keep_alive();
+                if let Err(message) = result {
+                    return Ok(Some(message));
+                }
+            } else {
+                self.time_of_last_received_message = Local::now();
+            }
+
             // Handle message.
             let result = self.handle_client_message(message)?;
             if result.is_some() {
@@ -840,26 +1406,67 @@ impl ClientService {
     /// returns `Err(String)` if the connection was lost
     /// (contains connection lost message).
     fn check_client_keep_alive(&mut self) -> Result<(), String> {
+        let disconnect_if_inactive_in_sec =
+            self.server_config.client_inactivity_timeout_in_min * 60;
+        if disconnect_if_inactive_in_sec == 0 {
+            // Inactive clients are not disconnected.
+            return Ok(());
+        }
+
         let time_diff = Local::now() - self.time_of_last_received_message;
 
-        if time_diff.num_seconds() >= DISCONNECT_IF_INACTIVE_IN_SEC as i64 {
+        if time_diff.num_seconds() >= disconnect_if_inactive_in_sec as i64 {
             // Disconnect.
             if self.username.is_some() {
                 return Err(format!(
                     "disconnecting user \"{}\" due to inactivity for {} second(-s)",
                     self.username.as_ref().unwrap(),
-                    DISCONNECT_IF_INACTIVE_IN_SEC
+                    disconnect_if_inactive_in_sec
                 ));
             } else {
                 return Err(format!(
                     "disconnecting socket {} due to inactivity for {} second(-s)",
-                    self.socket_addr, DISCONNECT_IF_INACTIVE_IN_SEC
+                    self.socket_addr, disconnect_if_inactive_in_sec
                 ));
             }
         }
 
         Ok(())
     }
+    /// Creates TOTP generator for the specified user.
+    ///
+    /// ## Arguments
+    /// * `otp_parameters`: parameters that the user used during OTP setup.
+    /// * `verification_window`: amount of accepted time steps before/after the current one.
+    /// * `otp_secret`: OTP secret of the user.
+    /// * `username`: name of the user (will be displayed in authenticator apps).
+    fn create_totp(
+        otp_parameters: &OtpParameters,
+        verification_window: u8,
+        otp_secret: String,
+        username: &str,
+    ) -> Result<TOTP, AppError> {
+        let algorithm = match otp_parameters.algorithm {
+            OtpAlgorithm::Sha1 => Algorithm::SHA1,
+            OtpAlgorithm::Sha256 => Algorithm::SHA256,
+            OtpAlgorithm::Sha512 => Algorithm::SHA512,
+        };
+
+        let totp = TOTP::new(
+            algorithm,
+            otp_parameters.digits,
+            verification_window,
+            otp_parameters.period_in_sec,
+            otp_secret.into_bytes(),
+            Some(String::from(OTP_ISSUER)),
+            String::from(username),
+        );
+        if let Err(e) = totp {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(totp.unwrap())
+    }
 }
 
 impl Drop for ClientService {
@@ -883,7 +1490,7 @@ impl Drop for ClientService {
             }
         }
 
-        let mut connected_count = self.connected_count.lock().unwrap();
+        let mut connected_count = self.connected_count.lock_recover();
         *connected_count -= 1;
         let message = format!(
             "{}{}\n------------------------- [connected: {}] -------------------------",
@@ -891,8 +1498,97 @@ impl Drop for ClientService {
         );
 
         self.logger
-            .lock()
-            .unwrap()
+            .lock_recover()
             .print_and_log(LogCategory::Info, &message);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_OTP_SECRET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+    #[test]
+    fn enroll_and_verify_otp_with_sha256() {
+        let otp_parameters = OtpParameters {
+            algorithm: OtpAlgorithm::Sha256,
+            digits: 8,
+            period_in_sec: 60,
+        };
+
+        // Enroll (this is what the user scans using the QR code).
+        let totp = ClientService::create_totp(
+            &otp_parameters,
+            1,
+            String::from(TEST_OTP_SECRET),
+            "test.user",
+        )
+        .unwrap();
+        let url = totp.get_url();
+        assert!(url.contains("algorithm=SHA256"));
+        assert!(url.contains("digits=8"));
+        assert!(url.contains("period=60"));
+
+        // Generate code like an authenticator app would.
+        let authenticator = TOTP::from_url(url).unwrap();
+        let time = 1_700_000_000;
+        let token = authenticator.generate(time);
+        assert_eq!(token.len(), 8);
+
+        // Verify it like the server does on login.
+        let server_totp = ClientService::create_totp(
+            &otp_parameters,
+            1,
+            String::from(TEST_OTP_SECRET),
+            "test.user",
+        )
+        .unwrap();
+        assert_eq!(server_totp.generate(time), token);
+
+        // Default parameters should produce a different code.
+        let default_totp = ClientService::create_totp(
+            &OtpParameters::default(),
+            1,
+            String::from(TEST_OTP_SECRET),
+            "test.user",
+        )
+        .unwrap();
+        assert_ne!(default_totp.generate(time), token);
+    }
+
+    #[test]
+    fn accept_otp_from_nearby_time_steps() {
+        let otp_parameters = OtpParameters::default();
+        let time = 1_700_000_000;
+        let period = otp_parameters.period_in_sec;
+
+        let authenticator = ClientService::create_totp(
+            &otp_parameters,
+            0,
+            String::from(TEST_OTP_SECRET),
+            "test.user",
+        )
+        .unwrap();
+        let previous_token = authenticator.generate(time - period);
+        let next_token = authenticator.generate(time + period);
+        let far_token = authenticator.generate(time + period * 2);
+
+        // Exact check.
+        assert!(authenticator.check(&authenticator.generate(time), time));
+        assert!(!authenticator.check(&previous_token, time));
+        assert!(!authenticator.check(&next_token, time));
+
+        // ±1 window.
+        let server_totp = ClientService::create_totp(
+            &otp_parameters,
+            1,
+            String::from(TEST_OTP_SECRET),
+            "test.user",
+        )
+        .unwrap();
+        assert!(server_totp.check(&previous_token, time));
+        assert!(server_totp.check(&next_token, time));
+        assert!(!server_totp.check(&far_token, time));
+    }
+}
@@ -0,0 +1,141 @@
+// Std.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// External.
+use serde_json::json;
+
+// Custom.
+use super::mutex_ext::MutexExt;
+use super::report_forwarder::{ForwardingTarget, ReportForwarder};
+use crate::io::config_manager::ConfigManager;
+use crate::io::log_manager::*;
+use shared::misc::db_manager::DatabaseManager;
+use shared::misc::error::AppError;
+
+/// Periodically checks free space on the disk with the database and warns
+/// (logs and sends an alert if specified in the config) when it runs low.
+#[derive(Default)]
+pub struct DiskSpaceMonitor {
+    /// `true` if free disk space was below the threshold during the last check.
+    is_low: AtomicBool,
+}
+
+impl DiskSpaceMonitor {
+    /// Tells if free disk space was below the threshold during the last check.
+    pub fn is_disk_space_low(&self) -> bool {
+        self.is_low.load(Ordering::SeqCst)
+    }
+    /// Starts a thread that checks free disk space
+    /// (does nothing if the threshold in the config is zero).
+    ///
+    /// ## Arguments
+    /// * `server_config`: server configuration.
+    /// * `database`: database manager that handles the database.
+    /// * `logger`: log manager for logging.
+    pub fn start(
+        self: &Arc<Self>,
+        server_config: Arc<ConfigManager>,
+        database: Arc<Mutex<DatabaseManager>>,
+        logger: Arc<Mutex<LogManager>>,
+    ) {
+        if server_config.low_disk_space_threshold_in_mb == 0 {
+            return;
+        }
+
+        let this = self.clone();
+        thread::spawn(move || loop {
+            this.check(&server_config, &database, &logger);
+            thread::sleep(Duration::from_secs(
+                server_config.disk_space_check_interval_in_sec,
+            ));
+        });
+    }
+    /// Checks free disk space and warns if it became lower than the threshold.
+    fn check(
+        &self,
+        server_config: &ConfigManager,
+        database: &Arc<Mutex<DatabaseManager>>,
+        logger: &Arc<Mutex<LogManager>>,
+    ) {
+        let (total_space_mb, used_space_mb) = database.lock_recover().get_disk_space_mb();
+        if total_space_mb == 0 {
+            // Unknown.
+            return;
+        }
+        let free_space_mb = total_space_mb.saturating_sub(used_space_mb);
+        let threshold_mb = server_config.low_disk_space_threshold_in_mb;
+
+        if free_space_mb >= threshold_mb {
+            if self.is_low.swap(false, Ordering::SeqCst) {
+                logger.lock_recover().print_and_log(
+                    LogCategory::Info,
+                    &format!("free disk space is back to normal ({} MB)", free_space_mb),
+                );
+            }
+            return;
+        }
+
+        // Only warn once until the space is freed.
+        if self.is_low.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        logger.lock_recover().print_and_log(
+            LogCategory::Warning,
+            &format!(
+                "free disk space is low: {} MB left out of {} MB (the threshold is {} MB), \
+                new reports might fail to be saved",
+                free_space_mb, total_space_mb, threshold_mb
+            ),
+        );
+
+        if let Some(target) = &server_config.low_disk_space_alert {
+            if let Err(app_error) =
+                DiskSpaceMonitor::send_alert(server_config, target, free_space_mb, total_space_mb)
+            {
+                logger.lock_recover().print_and_log(
+                    LogCategory::Error,
+                    &format!(
+                        "failed to send low disk space alert to \"{}\": {}",
+                        target, app_error
+                    ),
+                );
+            }
+        }
+    }
+    /// Notifies the admin that disk space is low.
+    fn send_alert(
+        server_config: &ConfigManager,
+        target: &ForwardingTarget,
+        free_space_mb: u64,
+        total_space_mb: u64,
+    ) -> Result<(), AppError> {
+        match target {
+            ForwardingTarget::Webhook { url } => ReportForwarder::send_webhook(
+                url,
+                json!({
+                    "event": "low_disk_space",
+                    "free_space_in_mb": free_space_mb,
+                    "total_space_in_mb": total_space_mb,
+                    "threshold_in_mb": server_config.low_disk_space_threshold_in_mb,
+                }),
+            ),
+            ForwardingTarget::Email { address } => ReportForwarder::send_email(
+                server_config,
+                address,
+                "[FBugReporter] Low disk space",
+                format!(
+                    "Free disk space on the server is low: {} MB left out of {} MB \
+                    (the threshold is {} MB). New reports might fail to be saved.",
+                    free_space_mb, total_space_mb, server_config.low_disk_space_threshold_in_mb
+                ),
+            ),
+            ForwardingTarget::GitHub { .. } => {
+                Err(AppError::new("GitHub is not supported for alerts"))
+            }
+        }
+    }
+}
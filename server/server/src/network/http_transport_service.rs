@@ -0,0 +1,388 @@
+// Std.
+use std::collections::{HashMap, VecDeque};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// External.
+use rand::{distributions::Alphanumeric, Rng};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+// Custom.
+use super::mutex_ext::MutexExt;
+use super::net_service::{ConnectionAcceptor, MAX_MESSAGE_SIZE_IN_BYTES_WITHOUT_ATTACHMENTS};
+use crate::io::config_manager::ConfigManager;
+use crate::io::log_manager::*;
+use shared::misc::error::AppError;
+use shared::network::messaging::MessageStream;
+use shared::network::net_params::{HTTP_SESSION_CLOSED_HEADER, HTTP_SESSION_ID_HEADER};
+
+/// Number of threads that process HTTP transport requests
+/// (each request waits until the service answers).
+const WORKER_THREAD_COUNT: usize = 8;
+
+/// Maximum time a request waits for the service to answer, after that
+/// the request is answered with the data that is available (might be empty).
+const MAX_ANSWER_WAIT_TIME_IN_SEC: u64 = 30;
+
+const SESSION_ID_LENGTH: usize = 32;
+
+/// Optional HTTP server that accepts the same (encrypted) messages as reporter and client
+/// ports but in bodies of `POST /reporter` and `POST /client` requests.
+///
+/// ## Remarks
+/// A request without the session ID header starts a new session (a new connection),
+/// the answer contains the session ID. All following requests of the session should
+/// specify this ID, bodies of such requests are read by the service as if they were
+/// received from a socket and the answer contains everything that the service sent back.
+pub struct HttpTransportService {
+    server: Server,
+    logger: Arc<Mutex<LogManager>>,
+    server_config: Arc<ConfigManager>,
+    accept_reporter: Arc<ConnectionAcceptor>,
+    accept_client: Arc<ConnectionAcceptor>,
+    /// Streams of active sessions (session ID - stream).
+    sessions: Mutex<HashMap<String, HttpStream>>,
+}
+
+/// Data that is exchanged between an HTTP transport session and the service that processes it.
+#[derive(Default)]
+struct HttpStreamState {
+    /// Received data that was not read by the service yet.
+    incoming: VecDeque<u8>,
+    /// Data that the service sent but that was not returned in an answer yet.
+    outgoing: Vec<u8>,
+    /// Whether the service tried to read data that was not received yet.
+    is_waiting_for_input: bool,
+    /// Whether the service finished processing the session.
+    is_finished: bool,
+}
+
+/// Stream of an HTTP transport session, one copy is used by the service,
+/// the other by the HTTP server (when the service drops its copy the session is finished).
+struct HttpStream {
+    state: Arc<(Mutex<HttpStreamState>, Condvar)>,
+    peer_addr: SocketAddr,
+    is_service_side: bool,
+}
+
+impl HttpTransportService {
+    /// Creates a new HTTP server on the HTTP transport port from the config.
+    ///
+    /// ## Arguments
+    /// * `logger`: log manager for logging.
+    /// * `server_config`: server configuration.
+    /// * `accept_reporter`: starts processing of a new reporter session.
+    /// * `accept_client`: starts processing of a new client session.
+    pub fn new(
+        logger: Arc<Mutex<LogManager>>,
+        server_config: Arc<ConfigManager>,
+        accept_reporter: Arc<ConnectionAcceptor>,
+        accept_client: Arc<ConnectionAcceptor>,
+    ) -> Result<Self, AppError> {
+        let server = Server::http(SocketAddr::new(
+            server_config.bind_address,
+            server_config.http_transport_port,
+        ));
+        if let Err(e) = server {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(Self {
+            server: server.unwrap(),
+            logger,
+            server_config,
+            accept_reporter,
+            accept_client,
+            sessions: Mutex::new(HashMap::new()),
+        })
+    }
+    /// Starts processing requests (does not return).
+    pub fn process(self) {
+        self.logger.lock_recover().print_and_log(
+            LogCategory::Info,
+            &format!(
+                "ready to accept HTTP transport requests on {}",
+                SocketAddr::new(
+                    self.server_config.bind_address,
+                    self.server_config.http_transport_port
+                )
+            ),
+        );
+
+        let service = Arc::new(self);
+
+        let mut handles = Vec::with_capacity(WORKER_THREAD_COUNT);
+        for _ in 0..WORKER_THREAD_COUNT {
+            let service = service.clone();
+            handles.push(thread::spawn(move || {
+                for request in service.server.incoming_requests() {
+                    service.handle_request(request);
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+    /// Passes the request body to the session and answers with the data that the service sent.
+    fn handle_request(&self, mut request: Request) {
+        if *request.method() != Method::Post {
+            let _ =
+                request.respond(Response::from_string("method not allowed").with_status_code(405));
+            return;
+        }
+
+        let accept_connection = match request.url() {
+            "/reporter" => &self.accept_reporter,
+            "/client" => &self.accept_client,
+            _ => {
+                let _ = request.respond(Response::from_string("not found").with_status_code(404));
+                return;
+            }
+        };
+
+        let peer_addr = match self.get_peer_addr(&request) {
+            Some(addr) => addr,
+            None => {
+                let _ = request.respond(Response::from_string("bad request").with_status_code(400));
+                return;
+            }
+        };
+
+        // Read body (the service checks message size again after decrypting).
+        let max_body_size = MAX_MESSAGE_SIZE_IN_BYTES_WITHOUT_ATTACHMENTS
+            + (self.server_config.get_largest_max_attachment_size_in_mb() * 1024 * 1024);
+        let mut body = Vec::new();
+        let result = request
+            .as_reader()
+            .take(max_body_size as u64 + 1)
+            .read_to_end(&mut body);
+        if result.is_err() {
+            let _ = request.respond(Response::from_string("bad request").with_status_code(400));
+            return;
+        }
+        if body.len() > max_body_size {
+            let _ =
+                request.respond(Response::from_string("payload too large").with_status_code(413));
+            return;
+        }
+
+        let session_id = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv(HTTP_SESSION_ID_HEADER))
+            .map(|header| header.value.to_string());
+
+        // Get or create session.
+        let (session_id, stream) = match session_id {
+            Some(session_id) => {
+                let mut sessions_guard = self.sessions.lock_recover();
+                sessions_guard.retain(|_, stream| !stream.is_finished());
+
+                match sessions_guard.get(&session_id) {
+                    Some(stream) => (session_id, stream.clone_server_side()),
+                    None => {
+                        let _ = request.respond(
+                            Response::from_string("session not found").with_status_code(404),
+                        );
+                        return;
+                    }
+                }
+            }
+            None => {
+                let stream = HttpStream::new(peer_addr);
+                if !accept_connection(Box::new(stream.clone_service_side()), peer_addr) {
+                    let _ = request.respond(
+                        Response::from_string("connection rejected").with_status_code(503),
+                    );
+                    return;
+                }
+
+                let session_id: String = rand::thread_rng()
+                    .sample_iter(&Alphanumeric)
+                    .take(SESSION_ID_LENGTH)
+                    .map(char::from)
+                    .collect();
+
+                let mut sessions_guard = self.sessions.lock_recover();
+                sessions_guard.retain(|_, stream| !stream.is_finished());
+                sessions_guard.insert(session_id.clone(), stream.clone_server_side());
+
+                (session_id, stream)
+            }
+        };
+
+        let (answer, is_finished) = stream.exchange(body);
+
+        // Only ASCII strings.
+        let mut response = Response::from_data(answer)
+            .with_header(
+                Header::from_bytes(HTTP_SESSION_ID_HEADER.as_bytes(), session_id.as_bytes())
+                    .unwrap(),
+            )
+            .with_header(
+                Header::from_bytes(&b"Content-Type"[..], &b"application/octet-stream"[..]).unwrap(),
+            );
+        if is_finished {
+            response = response.with_header(
+                Header::from_bytes(HTTP_SESSION_CLOSED_HEADER.as_bytes(), &b"true"[..]).unwrap(),
+            );
+            self.sessions.lock_recover().remove(&session_id);
+        }
+
+        // Errors are ignored because the remote entity could just disconnect.
+        let _ = request.respond(response);
+    }
+    /// Returns the address of the remote entity (takes `X-Forwarded-For` into account
+    /// if it's enabled in the config).
+    ///
+    /// ## Return
+    /// `None` if the address is unknown or `X-Forwarded-For` is invalid.
+    fn get_peer_addr(&self, request: &Request) -> Option<SocketAddr> {
+        let remote_addr = *request.remote_addr()?;
+
+        if !self.server_config.http_transport_trust_x_forwarded_for {
+            return Some(remote_addr);
+        }
+
+        let forwarded_for = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("X-Forwarded-For"));
+        let forwarded_for = match forwarded_for {
+            Some(header) => header.value.as_str(),
+            None => return Some(remote_addr),
+        };
+
+        // The first address is the address of the original sender.
+        let ip = forwarded_for
+            .split(',')
+            .next()?
+            .trim()
+            .parse::<IpAddr>()
+            .ok()?;
+
+        Some(SocketAddr::new(ip, remote_addr.port()))
+    }
+}
+
+impl HttpStream {
+    fn new(peer_addr: SocketAddr) -> Self {
+        Self {
+            state: Arc::new((Mutex::new(HttpStreamState::default()), Condvar::new())),
+            peer_addr,
+            is_service_side: false,
+        }
+    }
+    fn clone_service_side(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            peer_addr: self.peer_addr,
+            is_service_side: true,
+        }
+    }
+    fn clone_server_side(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            peer_addr: self.peer_addr,
+            is_service_side: false,
+        }
+    }
+    fn is_finished(&self) -> bool {
+        self.state.0.lock_recover().is_finished
+    }
+    /// Passes the data to the service and waits for its answer.
+    ///
+    /// ## Return
+    /// Data that the service sent and whether the service finished processing the session.
+    fn exchange(&self, data: Vec<u8>) -> (Vec<u8>, bool) {
+        let (state, condvar) = &*self.state;
+
+        let is_poll = data.is_empty();
+
+        let mut state_guard = state.lock_recover();
+        if !is_poll {
+            state_guard.incoming.extend(data);
+            state_guard.is_waiting_for_input = false;
+        }
+
+        // Wait until the service has read everything and waits for more data
+        // (when polling wait until the service sends something).
+        let result = condvar.wait_timeout_while(
+            state_guard,
+            Duration::from_secs(MAX_ANSWER_WAIT_TIME_IN_SEC),
+            |state| {
+                if state.is_finished {
+                    return false;
+                }
+                if is_poll {
+                    return state.outgoing.is_empty();
+                }
+                !state.is_waiting_for_input || !state.incoming.is_empty()
+            },
+        );
+        let mut state_guard = match result {
+            Ok((guard, _)) => guard,
+            Err(poisoned) => poisoned.into_inner().0,
+        };
+
+        (
+            std::mem::take(&mut state_guard.outgoing),
+            state_guard.is_finished,
+        )
+    }
+}
+
+impl Read for HttpStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let (state, condvar) = &*self.state;
+        let mut state_guard = state.lock_recover();
+
+        if state_guard.incoming.is_empty() {
+            state_guard.is_waiting_for_input = true;
+            condvar.notify_all();
+            return Err(ErrorKind::WouldBlock.into());
+        }
+
+        let read_count = buf.len().min(state_guard.incoming.len());
+        for (byte, received) in buf.iter_mut().zip(state_guard.incoming.drain(..read_count)) {
+            *byte = received;
+        }
+
+        Ok(read_count)
+    }
+}
+
+impl Write for HttpStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.state.0.lock_recover().outgoing.extend_from_slice(buf);
+        self.state.1.notify_all();
+
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl MessageStream for HttpStream {
+    fn get_peer_name(&self) -> std::io::Result<String> {
+        Ok(self.peer_addr.to_string())
+    }
+}
+
+impl Drop for HttpStream {
+    /// Finishes the session if the service no longer processes it.
+    fn drop(&mut self) {
+        if !self.is_service_side {
+            return;
+        }
+
+        self.state.0.lock_recover().is_finished = true;
+        self.state.1.notify_all();
+    }
+}
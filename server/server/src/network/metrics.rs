@@ -0,0 +1,168 @@
+// Std.
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Reasons why the server did not save a report.
+#[derive(Clone, Copy)]
+pub enum ReportRejectReason {
+    WrongProtocol,
+    InvalidProofOfWork,
+    FieldTooLong,
+    FieldTooShort,
+    FieldRuleViolated,
+    TooManyMetadataEntries,
+    AttachmentsTooBig,
+    GameNotAccepted,
+    VersionRejected,
+    Spam,
+    RateLimited,
+    InternalError,
+}
+
+impl ReportRejectReason {
+    const ALL: [ReportRejectReason; 12] = [
+        ReportRejectReason::WrongProtocol,
+        ReportRejectReason::InvalidProofOfWork,
+        ReportRejectReason::FieldTooLong,
+        ReportRejectReason::FieldTooShort,
+        ReportRejectReason::FieldRuleViolated,
+        ReportRejectReason::TooManyMetadataEntries,
+        ReportRejectReason::AttachmentsTooBig,
+        ReportRejectReason::GameNotAccepted,
+        ReportRejectReason::VersionRejected,
+        ReportRejectReason::Spam,
+        ReportRejectReason::RateLimited,
+        ReportRejectReason::InternalError,
+    ];
+
+    /// Returns the value of the `reason` label.
+    pub fn label(self) -> &'static str {
+        match self {
+            ReportRejectReason::WrongProtocol => "wrong_protocol",
+            ReportRejectReason::InvalidProofOfWork => "invalid_proof_of_work",
+            ReportRejectReason::FieldTooLong => "field_too_long",
+            ReportRejectReason::FieldTooShort => "field_too_short",
+            ReportRejectReason::FieldRuleViolated => "field_rule_violated",
+            ReportRejectReason::TooManyMetadataEntries => "too_many_metadata_entries",
+            ReportRejectReason::AttachmentsTooBig => "attachments_too_big",
+            ReportRejectReason::GameNotAccepted => "game_not_accepted",
+            ReportRejectReason::VersionRejected => "version_rejected",
+            ReportRejectReason::Spam => "spam",
+            ReportRejectReason::RateLimited => "rate_limited",
+            ReportRejectReason::InternalError => "internal_error",
+        }
+    }
+}
+
+/// Counters that are collected while the server is running
+/// (reset on restart).
+#[derive(Default)]
+pub struct ServerMetrics {
+    /// Reports that were saved (including duplicates merged into existing reports).
+    reports_received: AtomicU64,
+    /// Indexed by `ReportRejectReason`.
+    reports_rejected: [AtomicU64; ReportRejectReason::ALL.len()],
+    failed_logins: AtomicU64,
+    bans: AtomicU64,
+}
+
+impl ServerMetrics {
+    pub fn add_received_report(&self) {
+        self.reports_received.fetch_add(1, Ordering::Relaxed);
+    }
+    pub fn add_rejected_report(&self, reason: ReportRejectReason) {
+        self.reports_rejected[reason as usize].fetch_add(1, Ordering::Relaxed);
+    }
+    pub fn add_failed_login(&self) {
+        self.failed_logins.fetch_add(1, Ordering::Relaxed);
+    }
+    pub fn add_ban(&self) {
+        self.bans.fetch_add(1, Ordering::Relaxed);
+    }
+    /// Formats all metrics in the Prometheus text format.
+    ///
+    /// ## Arguments
+    /// * `active_connections`: current number of connected reporters and clients.
+    /// * `database_size_in_bytes`: size of the database files (including the WAL file).
+    pub fn to_prometheus_text(
+        &self,
+        active_connections: usize,
+        database_size_in_bytes: u64,
+    ) -> String {
+        let mut text = String::new();
+
+        ServerMetrics::write_metric(
+            &mut text,
+            "fbugreporter_reports_received_total",
+            "counter",
+            "Number of received reports that were saved.",
+        );
+        text += &format!(
+            "fbugreporter_reports_received_total {}\n",
+            self.reports_received.load(Ordering::Relaxed)
+        );
+
+        ServerMetrics::write_metric(
+            &mut text,
+            "fbugreporter_reports_rejected_total",
+            "counter",
+            "Number of received reports that were not saved.",
+        );
+        for reason in ReportRejectReason::ALL {
+            text += &format!(
+                "fbugreporter_reports_rejected_total{{reason=\"{}\"}} {}\n",
+                reason.label(),
+                self.reports_rejected[reason as usize].load(Ordering::Relaxed)
+            );
+        }
+
+        ServerMetrics::write_metric(
+            &mut text,
+            "fbugreporter_active_connections",
+            "gauge",
+            "Number of connected reporters and clients.",
+        );
+        text += &format!("fbugreporter_active_connections {}\n", active_connections);
+
+        ServerMetrics::write_metric(
+            &mut text,
+            "fbugreporter_failed_logins_total",
+            "counter",
+            "Number of failed client login attempts.",
+        );
+        text += &format!(
+            "fbugreporter_failed_logins_total {}\n",
+            self.failed_logins.load(Ordering::Relaxed)
+        );
+
+        ServerMetrics::write_metric(
+            &mut text,
+            "fbugreporter_bans_total",
+            "counter",
+            "Number of IP addresses that were banned due to failed login attempts.",
+        );
+        text += &format!(
+            "fbugreporter_bans_total {}\n",
+            self.bans.load(Ordering::Relaxed)
+        );
+
+        ServerMetrics::write_metric(
+            &mut text,
+            "fbugreporter_database_size_bytes",
+            "gauge",
+            "Size of the database files (including the WAL file).",
+        );
+        text += &format!(
+            "fbugreporter_database_size_bytes {}\n",
+            database_size_in_bytes
+        );
+
+        text
+    }
+    /// Writes `HELP` and `TYPE` lines of a metric.
+    fn write_metric(text: &mut String, name: &str, metric_type: &str, help: &str) {
+        *text += &format!(
+            "# HELP {} {}\n# TYPE {} {}\n",
+            name, help, name, metric_type
+        );
+    }
+}
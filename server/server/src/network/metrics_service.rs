@@ -0,0 +1,103 @@
+// Std.
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+// External.
+use tiny_http::{Header, Method, Request, Response, Server};
+
+// Custom.
+use super::metrics::ServerMetrics;
+use super::mutex_ext::MutexExt;
+use crate::io::config_manager::ConfigManager;
+use crate::io::log_manager::*;
+use shared::misc::db_manager::DatabaseManager;
+use shared::misc::error::AppError;
+
+/// Optional HTTP server that provides server metrics in Prometheus text format
+/// on `GET /metrics`.
+pub struct MetricsService {
+    server: Server,
+    logger: Arc<Mutex<LogManager>>,
+    metrics: Arc<ServerMetrics>,
+    connected_count: Arc<Mutex<usize>>,
+    server_config: Arc<ConfigManager>,
+}
+
+impl MetricsService {
+    /// Creates a new HTTP server on the metrics port from the config.
+    ///
+    /// ## Arguments
+    /// * `logger`: log manager for logging.
+    /// * `metrics`: metrics to provide.
+    /// * `connected_count`: shared variable that stores total connections.
+    /// * `server_config`: server configuration.
+    pub fn new(
+        logger: Arc<Mutex<LogManager>>,
+        metrics: Arc<ServerMetrics>,
+        connected_count: Arc<Mutex<usize>>,
+        server_config: Arc<ConfigManager>,
+    ) -> Result<Self, AppError> {
+        let server = Server::http(SocketAddr::new(
+            server_config.bind_address,
+            server_config.metrics_port,
+        ));
+        if let Err(e) = server {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(Self {
+            server: server.unwrap(),
+            logger,
+            metrics,
+            connected_count,
+            server_config,
+        })
+    }
+    /// Starts processing requests (does not return).
+    pub fn process(self) {
+        self.logger.lock_recover().print_and_log(
+            LogCategory::Info,
+            &format!(
+                "ready to accept metrics requests on {}",
+                SocketAddr::new(
+                    self.server_config.bind_address,
+                    self.server_config.metrics_port
+                )
+            ),
+        );
+
+        // Requests are cheap so a single thread is enough.
+        for request in self.server.incoming_requests() {
+            self.handle_request(request);
+        }
+    }
+    /// Sends a response to the request.
+    fn handle_request(&self, request: Request) {
+        let response = if *request.method() != Method::Get {
+            Response::from_string("method not allowed").with_status_code(405)
+        } else if request.url() != "/metrics" {
+            Response::from_string("not found").with_status_code(404)
+        } else {
+            let active_connections = *self.connected_count.lock_recover();
+
+            // The database might not exist yet (then the size is zero).
+            let database_size_in_bytes = DatabaseManager::get_database_files_size();
+
+            Response::from_string(
+                self.metrics
+                    .to_prometheus_text(active_connections, database_size_in_bytes),
+            )
+            .with_header(
+                // Only ASCII strings.
+                Header::from_bytes(
+                    &b"Content-Type"[..],
+                    &b"text/plain; version=0.0.4; charset=utf-8"[..],
+                )
+                .unwrap(),
+            )
+        };
+
+        // Errors are ignored because the client could just disconnect.
+        let _ = request.respond(response);
+    }
+}
@@ -1,4 +1,14 @@
 pub mod ban_manager;
 pub mod client_service;
+pub mod disk_space_monitor;
+pub mod http_transport_service;
+pub mod metrics;
+pub mod metrics_service;
+pub mod mutex_ext;
 pub mod net_service;
+pub mod report_forwarder;
 pub mod reporter_service;
+pub mod rest_api_service;
+pub mod sender_cooldown;
+pub mod webhook_queue;
+pub mod worker_pool;
@@ -0,0 +1,20 @@
+// Std.
+use std::sync::{Mutex, MutexGuard};
+
+/// Extends `Mutex` to not panic when a thread panicked while holding the lock.
+pub trait MutexExt<T> {
+    /// Acquires the mutex even if it was poisoned.
+    ///
+    /// ## Remarks
+    /// A panic while processing a single connection poisons all mutexes that the
+    /// connection was holding, since the server state (logger, database, bans) stays
+    /// valid after such panics we continue to use it instead of failing all
+    /// other connections.
+    fn lock_recover(&self) -> MutexGuard<'_, T>;
+}
+
+impl<T> MutexExt<T> for Mutex<T> {
+    fn lock_recover(&self) -> MutexGuard<'_, T> {
+        self.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
@@ -2,68 +2,236 @@
 use std::net::*;
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 
 // Custom.
+use super::ban_manager::BanConfig;
+use super::mutex_ext::MutexExt;
 use crate::{
     io::config_manager::ConfigManager,
     io::log_manager::*,
+    io::tracing_manager::TracingManager,
     network::{
-        ban_manager::BanManager, client_service::ClientService, reporter_service::ReporterService,
+        ban_manager::BanManager, client_service::ClientService,
+        disk_space_monitor::DiskSpaceMonitor, http_transport_service::HttpTransportService,
+        metrics::ServerMetrics, metrics_service::MetricsService, report_forwarder::ReportForwarder,
+        reporter_service::ReporterService, rest_api_service::RestApiService,
+        sender_cooldown::SenderCooldown, worker_pool::WorkerPool,
     },
 };
 use shared::misc::db_manager::*;
 use shared::misc::error::AppError;
+use shared::network::messaging::MessageStream;
+use shared::network::server_identity::ServerIdentity;
 
 pub const MAX_MESSAGE_SIZE_IN_BYTES_WITHOUT_ATTACHMENTS: usize = 131_072; // 128 kB
 
+/// Name of the file (next to the config file) that stores the private key of the server.
+const SERVER_KEY_FILE_NAME: &str = "server_key";
+
+/// Name of the file (next to the config file) that stores the public key of the server
+/// (clients can trust it, see `trusted_server_key_path` in the client config).
+const SERVER_PUBLIC_KEY_FILE_NAME: &str = "server_key.pub";
+
+/// Starts processing of a new connection (socket or HTTP transport session)
+/// of a reporter or a client.
+///
+/// ## Return
+/// `false` if the connection was rejected (the stream is dropped), `true` if the stream
+/// is processed (even if only to tell that the server is busy).
+pub type ConnectionAcceptor =
+    dyn Fn(Box<dyn MessageStream + Send>, SocketAddr) -> bool + Send + Sync;
+
+/// How often to check that reporters and clients are still processed.
+const LISTENER_STATE_CHECK_INTERVAL_IN_SEC: u64 = 1;
+
+/// Maximum amount of connections that are told that the server is busy at the same time
+/// (other connections that exceed the limit of concurrent connections are dropped).
+const BUSY_ANSWER_WORKER_COUNT: usize = 2;
+
+/// Time after which reporters that were told that the server is busy can try again.
+const SERVER_BUSY_RETRY_AFTER_IN_SEC: u64 = 30;
+
 pub struct NetService {
     pub logger: Arc<Mutex<LogManager>>,
     pub server_config: Arc<ConfigManager>,
     connected_socket_count: Arc<Mutex<usize>>,
     database: Arc<Mutex<DatabaseManager>>,
     ban_manager: Arc<Mutex<BanManager>>,
+    reporter_worker_pool: Arc<WorkerPool>,
+    client_worker_pool: Arc<WorkerPool>,
+    /// Tells connections that exceed the limit of concurrent connections that the server is busy.
+    busy_answer_worker_pool: Arc<WorkerPool>,
+    sender_cooldown: Arc<SenderCooldown>,
+    report_forwarder: Arc<ReportForwarder>,
+    metrics: Arc<ServerMetrics>,
+    disk_space_monitor: Arc<DiskSpaceMonitor>,
+    /// Key pair that is used to sign the key exchange.
+    server_identity: Arc<ServerIdentity>,
+    /// Exports trace spans (`None` if tracing is disabled in the config).
+    tracing_manager: Option<TracingManager>,
+    /// Time when `start` was called (`None` if the server was not started yet).
+    start_time: Option<Instant>,
 }
 
 impl NetService {
     /// Creates a new instance of the `NetService`.
     ///
-    /// Returns `AppError` if something went wrong
+    /// ## Arguments
+    /// * `logger`: log manager for logging.
+    /// * `config`: server configuration.
+    ///
+    /// Returns `AppError` if the configuration is invalid or something went wrong
     /// when initializing/connecting to the database.
-    pub fn new(logger: LogManager) -> Result<Self, AppError> {
-        let config = Arc::new(ConfigManager::new());
+    pub fn new(logger: LogManager, config: ConfigManager) -> Result<Self, AppError> {
+        let config = Arc::new(config);
 
         if config.port_for_clients == config.port_for_reporters {
             return Err(AppError::new(
                 "client and reporter ports should not be equal",
             ));
         }
+        if config.enable_rest_api
+            && (config.rest_api_port == config.port_for_clients
+                || config.rest_api_port == config.port_for_reporters)
+        {
+            return Err(AppError::new(
+                "REST API port should not be equal to client or reporter ports",
+            ));
+        }
+        if config.enable_metrics
+            && (config.metrics_port == config.port_for_clients
+                || config.metrics_port == config.port_for_reporters
+                || (config.enable_rest_api && config.metrics_port == config.rest_api_port))
+        {
+            return Err(AppError::new(
+                "metrics port should not be equal to client, reporter or REST API ports",
+            ));
+        }
+        if config.enable_http_transport
+            && (config.http_transport_port == config.port_for_clients
+                || config.http_transport_port == config.port_for_reporters
+                || (config.enable_rest_api && config.http_transport_port == config.rest_api_port)
+                || (config.enable_metrics && config.http_transport_port == config.metrics_port))
+        {
+            return Err(AppError::new(
+                "HTTP transport port should not be equal to client, reporter, REST API or \
+                metrics ports",
+            ));
+        }
 
         let db = DatabaseManager::new()?;
 
+        let server_identity = ServerIdentity::load_or_generate(
+            &config.config_file_path.with_file_name(SERVER_KEY_FILE_NAME),
+        )?;
+
+        // Export the public key so that it can be given to clients.
+        let public_key_path = config
+            .config_file_path
+            .with_file_name(SERVER_PUBLIC_KEY_FILE_NAME);
+        if let Err(e) = std::fs::write(
+            &public_key_path,
+            server_identity.get_public_key().to_string(),
+        ) {
+            return Err(AppError::new(&format!(
+                "failed to save the server public key to \"{}\" (error: {})",
+                public_key_path.display(),
+                e
+            )));
+        }
+
         let logger = Arc::new(Mutex::new(logger));
 
+        let reporter_worker_pool =
+            WorkerPool::new("reporter", config.max_concurrent_reporter_connections)?;
+        let client_worker_pool =
+            WorkerPool::new("client", config.max_concurrent_client_connections)?;
+        let busy_answer_worker_pool = WorkerPool::new("busy answer", BUSY_ANSWER_WORKER_COUNT)?;
+
+        let sender_cooldown = SenderCooldown::new(config.sender_email_report_cooldown_in_sec);
+
+        let database = Arc::new(Mutex::new(db));
+
+        let report_forwarder =
+            ReportForwarder::new(config.clone(), database.clone(), logger.clone());
+
+        let metrics = Arc::new(ServerMetrics::default());
+
         Ok(Self {
             server_config: config.clone(),
             logger: logger.clone(),
             connected_socket_count: Arc::new(Mutex::new(0)),
-            database: Arc::new(Mutex::new(db)),
-            ban_manager: Arc::new(Mutex::new(BanManager::new(logger, config))),
+            database,
+            ban_manager: Arc::new(Mutex::new(BanManager::new(logger, config, metrics.clone()))),
+            reporter_worker_pool: Arc::new(reporter_worker_pool),
+            client_worker_pool: Arc::new(client_worker_pool),
+            busy_answer_worker_pool: Arc::new(busy_answer_worker_pool),
+            sender_cooldown: Arc::new(sender_cooldown),
+            report_forwarder: Arc::new(report_forwarder),
+            metrics,
+            disk_space_monitor: Arc::new(DiskSpaceMonitor::default()),
+            server_identity: Arc::new(server_identity),
+            tracing_manager: None,
+            start_time: None,
         })
     }
     /// Starts waiting for client and reporter requests.
-    pub fn start(&mut self, blocking: bool) {
+    ///
+    /// ## Arguments
+    /// * `exit_on_failure`: whether to exit the process if reporters or clients are no
+    ///   longer processed (so that the server monitor will restart the server).
+    pub fn start(&mut self, exit_on_failure: bool) {
         {
-            self.logger
-                .lock()
-                .unwrap()
-                .print_and_log(LogCategory::Info, "starting");
+            let logger_guard = self.logger.lock_recover();
+            logger_guard.print_and_log(LogCategory::Info, "starting");
+            logger_guard.print_and_log(
+                LogCategory::Info,
+                &format!(
+                    "server public key: {} (see \"{}\")",
+                    self.server_identity.get_public_key(),
+                    self.server_config
+                        .config_file_path
+                        .with_file_name(SERVER_PUBLIC_KEY_FILE_NAME)
+                        .display()
+                ),
+            );
+        }
+
+        self.start_time = Some(Instant::now());
+
+        // Export trace spans.
+        if !self.server_config.tracing_otlp_endpoint.is_empty() && self.tracing_manager.is_none() {
+            match TracingManager::start(
+                &self.server_config.tracing_otlp_endpoint,
+                &self.server_config.tracing_service_name,
+            ) {
+                Ok(tracing_manager) => {
+                    self.logger.lock_recover().print_and_log(
+                        LogCategory::Info,
+                        &format!(
+                            "exporting trace spans to {}",
+                            self.server_config.tracing_otlp_endpoint
+                        ),
+                    );
+                    self.tracing_manager = Some(tracing_manager);
+                }
+                Err(app_error) => {
+                    self.logger
+                        .lock_recover()
+                        .print_and_log(LogCategory::Error, &app_error.to_string());
+                }
+            }
         }
 
         // Create socket for reporters.
-        let listener_socker_reporters =
-            TcpListener::bind(format!("0.0.0.0:{}", self.server_config.port_for_reporters));
+        let reporters_addr = SocketAddr::new(
+            self.server_config.bind_address,
+            self.server_config.port_for_reporters,
+        );
+        let listener_socker_reporters = TcpListener::bind(reporters_addr);
         if let Err(ref e) = listener_socker_reporters {
-            self.logger.lock().unwrap().print_and_log(
+            self.logger.lock_recover().print_and_log(
                 LogCategory::Error,
                 &AppError::new(&e.to_string()).to_string(),
             );
@@ -71,10 +239,13 @@ impl NetService {
         let listener_socker_reporters = listener_socker_reporters.unwrap();
 
         // Create socket for clients.
-        let listener_socker_clients =
-            TcpListener::bind(format!("0.0.0.0:{}", self.server_config.port_for_clients));
+        let clients_addr = SocketAddr::new(
+            self.server_config.bind_address,
+            self.server_config.port_for_clients,
+        );
+        let listener_socker_clients = TcpListener::bind(clients_addr);
         if let Err(ref e) = listener_socker_clients {
-            self.logger.lock().unwrap().print_and_log(
+            self.logger.lock_recover().print_and_log(
                 LogCategory::Error,
                 &AppError::new(&e.to_string()).to_string(),
             );
@@ -82,134 +253,354 @@ impl NetService {
         let listener_socker_clients = listener_socker_clients.unwrap();
 
         {
-            let logger_guard = self.logger.lock().unwrap();
+            let logger_guard = self.logger.lock_recover();
             logger_guard.print_and_log(
                 LogCategory::Info,
-                &format!(
-                    "ready to accept client connections on port {}",
-                    self.server_config.port_for_clients
-                ),
+                &format!("ready to accept client connections on {}", clients_addr),
             );
             logger_guard.print_and_log(
                 LogCategory::Info,
-                &format!(
-                    "ready to accept reporter connections on port {}",
-                    self.server_config.port_for_reporters
-                ),
+                &format!("ready to accept reporter connections on {}", reporters_addr),
             );
         }
 
+        let accept_reporter = self.create_reporter_acceptor();
+        let accept_client = self.create_client_acceptor();
+
         // Process reporters.
         let logger_copy = self.logger.clone();
-        let connected_clone = self.connected_socket_count.clone();
-        let database_clone = self.database.clone();
-        let config_clone = self.server_config.clone();
+        let accept_reporter_clone = accept_reporter.clone();
         let reporter_handle = thread::spawn(move || {
-            NetService::process_reporter_connections(
+            NetService::process_connections(
                 listener_socker_reporters,
                 logger_copy,
-                connected_clone,
-                database_clone,
-                config_clone,
+                accept_reporter_clone,
             );
         });
 
         // Process clients.
         let logger_copy = self.logger.clone();
-        let connected_clone = self.connected_socket_count.clone();
-        let database_clone = self.database.clone();
-        let ban_manager_clone = self.ban_manager.clone();
+        let accept_client_clone = accept_client.clone();
         let client_handle = thread::spawn(move || {
-            NetService::process_client_connections(
+            NetService::process_connections(
                 listener_socker_clients,
                 logger_copy,
-                connected_clone,
-                database_clone,
-                ban_manager_clone,
+                accept_client_clone,
             );
         });
 
-        if blocking {
-            reporter_handle.join().unwrap();
-            client_handle.join().unwrap();
+        // Process reporter and client messages sent over HTTP.
+        if self.server_config.enable_http_transport {
+            match HttpTransportService::new(
+                self.logger.clone(),
+                self.server_config.clone(),
+                accept_reporter,
+                accept_client,
+            ) {
+                Ok(http_transport_service) => {
+                    thread::spawn(move || http_transport_service.process());
+                }
+                Err(app_error) => {
+                    self.logger
+                        .lock_recover()
+                        .print_and_log(LogCategory::Error, &app_error.to_string());
+                }
+            }
         }
-    }
-    /// Waits for reporter connections.
-    fn process_reporter_connections(
-        listener_socket: TcpListener,
-        logger: Arc<Mutex<LogManager>>,
-        connected_count: Arc<Mutex<usize>>,
-        database_manager: Arc<Mutex<DatabaseManager>>,
-        server_config: Arc<ConfigManager>,
-    ) {
-        loop {
-            // Wait for connection.
-            let accept_result = listener_socket.accept();
-            if let Err(ref e) = accept_result {
-                logger.lock().unwrap().print_and_log(
-                    LogCategory::Error,
-                    &AppError::new(&e.to_string()).to_string(),
-                );
-                continue;
+
+        // Process REST API requests.
+        if self.server_config.enable_rest_api {
+            match RestApiService::new(
+                self.logger.clone(),
+                self.database.clone(),
+                self.server_config.clone(),
+            ) {
+                Ok(rest_api_service) => {
+                    thread::spawn(move || rest_api_service.process());
+                }
+                Err(app_error) => {
+                    self.logger
+                        .lock_recover()
+                        .print_and_log(LogCategory::Error, &app_error.to_string());
+                }
             }
+        }
 
-            let (socket, addr) = accept_result.unwrap();
+        self.disk_space_monitor.start(
+            self.server_config.clone(),
+            self.database.clone(),
+            self.logger.clone(),
+        );
 
-            if let Err(e) = socket.set_nodelay(true) {
-                logger.lock().unwrap().print_and_log(
-                    LogCategory::Error,
-                    &AppError::new(&e.to_string()).to_string(),
-                );
-                continue;
+        // Process metrics requests.
+        if self.server_config.enable_metrics {
+            match MetricsService::new(
+                self.logger.clone(),
+                self.metrics.clone(),
+                self.connected_socket_count.clone(),
+                self.server_config.clone(),
+            ) {
+                Ok(metrics_service) => {
+                    thread::spawn(move || metrics_service.process());
+                }
+                Err(app_error) => {
+                    self.logger
+                        .lock_recover()
+                        .print_and_log(LogCategory::Error, &app_error.to_string());
+                }
             }
-            if let Err(e) = socket.set_nonblocking(true) {
-                logger.lock().unwrap().print_and_log(
+        }
+
+        if exit_on_failure {
+            // Don't block so that commands can still be entered.
+            let logger_copy = self.logger.clone();
+            thread::spawn(move || {
+                while !reporter_handle.is_finished() && !client_handle.is_finished() {
+                    thread::sleep(Duration::from_secs(LISTENER_STATE_CHECK_INTERVAL_IN_SEC));
+                }
+
+                logger_copy.lock_recover().print_and_log(
                     LogCategory::Error,
-                    &AppError::new(&e.to_string()).to_string(),
+                    "stopped processing reporters or clients, exiting",
+                );
+                std::process::exit(1);
+            });
+        }
+    }
+    /// Returns ban settings that are currently used.
+    pub fn get_ban_config(&self) -> BanConfig {
+        self.ban_manager.lock_recover().config
+    }
+    /// Changes the number of failed login attempts after which the IP is banned
+    /// (used right away and saved to the config file).
+    pub fn set_max_allowed_login_attempts(
+        &self,
+        max_allowed_login_attempts: u32,
+    ) -> Result<(), AppError> {
+        self.ban_manager
+            .lock_recover()
+            .set_max_allowed_login_attempts(max_allowed_login_attempts)
+    }
+    /// Changes the ban duration (used right away and saved to the config file).
+    pub fn set_ban_time_duration(&self, ban_time_duration_in_min: i64) -> Result<(), AppError> {
+        self.ban_manager
+            .lock_recover()
+            .set_ban_time_duration(ban_time_duration_in_min)
+    }
+    /// Prints server uptime, the number of connections, reports and used disk space.
+    pub fn print_stats(&self) {
+        match self.start_time {
+            Some(start_time) => {
+                let uptime_in_sec = start_time.elapsed().as_secs();
+                println!(
+                    "uptime: {}d {}h {}m {}s",
+                    uptime_in_sec / 86400,
+                    uptime_in_sec % 86400 / 3600,
+                    uptime_in_sec % 3600 / 60,
+                    uptime_in_sec % 60
                 );
-                continue;
             }
+            None => println!("uptime: the server is not started"),
+        }
 
-            let logger_copy = logger.clone();
-            let connected_count_clone = connected_count.clone();
-            let database_clone = database_manager.clone();
-            let max_attachment_size_in_mb = server_config.max_attachment_size_in_mb;
+        println!(
+            "connected reporters and clients: {}",
+            *self.connected_socket_count.lock_recover()
+        );
+
+        let database_guard = self.database.lock_recover();
+        match database_guard.get_report_count() {
+            Ok(report_count) => println!("reports in the database: {}", report_count),
+            Err(app_error) => println!("reports in the database: unknown ({})", app_error),
+        }
 
-            let handle = thread::Builder::new()
-                .name(format!("reporter socket {}:{}", addr.ip(), addr.port()))
-                .spawn(move || {
+        let (total_disk_space_mb, used_disk_space_mb) = database_guard.get_disk_space_mb();
+        if total_disk_space_mb == 0 {
+            println!("disk space: unknown");
+        } else {
+            println!(
+                "disk space: {} / {} MB used ({} MB free)",
+                used_disk_space_mb,
+                total_disk_space_mb,
+                total_disk_space_mb.saturating_sub(used_disk_space_mb)
+            );
+        }
+
+        self.print_disk_space_warning();
+    }
+    /// Prints a warning if free disk space was low during the last check.
+    pub fn print_disk_space_warning(&self) {
+        if self.disk_space_monitor.is_disk_space_low() {
+            println!(
+                "WARNING: free disk space is below {} MB",
+                self.server_config.low_disk_space_threshold_in_mb
+            );
+        }
+    }
+    /// Creates a function that starts processing of a new reporter connection.
+    fn create_reporter_acceptor(&self) -> Arc<ConnectionAcceptor> {
+        let logger = self.logger.clone();
+        let connected_count = self.connected_socket_count.clone();
+        let database = self.database.clone();
+        let server_config = self.server_config.clone();
+        let worker_pool = self.reporter_worker_pool.clone();
+        let busy_answer_worker_pool = self.busy_answer_worker_pool.clone();
+        let sender_cooldown = self.sender_cooldown.clone();
+        let report_forwarder = self.report_forwarder.clone();
+        let metrics = self.metrics.clone();
+        let server_identity = self.server_identity.clone();
+
+        Arc::new(
+            move |socket: Box<dyn MessageStream + Send>, addr: SocketAddr| {
+                let logger_copy = logger.clone();
+                let connected_count_clone = connected_count.clone();
+                let database_clone = database.clone();
+                let config_clone = server_config.clone();
+                let sender_cooldown_clone = sender_cooldown.clone();
+                let report_forwarder_clone = report_forwarder.clone();
+                let metrics_clone = metrics.clone();
+                let server_identity_clone = server_identity.clone();
+
+                let result = worker_pool.execute(socket, move |socket| {
                     let reporter_service = ReporterService::new(
                         logger_copy,
                         socket,
                         addr,
                         connected_count_clone,
                         database_clone,
-                        max_attachment_size_in_mb,
+                        config_clone,
+                        sender_cooldown_clone,
+                        report_forwarder_clone,
+                        metrics_clone,
+                        server_identity_clone,
                     );
                     reporter_service.process();
                 });
-            if let Err(ref e) = handle {
-                logger.lock().unwrap().print_and_log(
-                    LogCategory::Error,
-                    &AppError::new(&e.to_string()).to_string(),
+                let socket = match result {
+                    Ok(()) => return true,
+                    Err(socket) => socket,
+                };
+
+                logger.lock_recover().print_and_log(
+                    LogCategory::Warning,
+                    &format!(
+                        "rejected connection with reporter {}:{} because the maximum \
+                        number of concurrent reporter connections ({}) was reached",
+                        addr.ip(),
+                        addr.port(),
+                        worker_pool.get_worker_count()
+                    ),
                 );
-                continue;
-            }
-        }
+
+                // Tell the reporter to try again later (drop the socket if busy with that too).
+                let dh_group = server_config.dh_group;
+                let server_identity_clone = server_identity.clone();
+                busy_answer_worker_pool
+                    .execute(socket, move |socket| {
+                        // The reporter might have already disconnected, nothing to do.
+                        let _ = ReporterService::answer_server_busy(
+                            socket,
+                            dh_group,
+                            &server_identity_clone,
+                            SERVER_BUSY_RETRY_AFTER_IN_SEC,
+                        );
+                    })
+                    .is_ok()
+            },
+        )
     }
-    /// Waits for client connections.
-    fn process_client_connections(
+    /// Creates a function that starts processing of a new client connection.
+    fn create_client_acceptor(&self) -> Arc<ConnectionAcceptor> {
+        let logger = self.logger.clone();
+        let connected_count = self.connected_socket_count.clone();
+        let database = self.database.clone();
+        let ban_manager = self.ban_manager.clone();
+        let server_config = self.server_config.clone();
+        let worker_pool = self.client_worker_pool.clone();
+        let busy_answer_worker_pool = self.busy_answer_worker_pool.clone();
+        let server_identity = self.server_identity.clone();
+
+        Arc::new(
+            move |socket: Box<dyn MessageStream + Send>, addr: SocketAddr| {
+                {
+                    let mut ban_manager_guard = ban_manager.lock_recover();
+
+                    ban_manager_guard.refresh_failed_and_banned_lists();
+
+                    // Check if this IP is banned.
+                    if ban_manager_guard.is_ip_banned(addr.ip()) {
+                        return false;
+                    }
+                }
+
+                let logger_clone = logger.clone();
+                let connected_count_clone = connected_count.clone();
+                let database_clone = database.clone();
+                let ban_manager_clone = ban_manager.clone();
+                let config_clone = server_config.clone();
+                let server_identity_clone = server_identity.clone();
+
+                let result = worker_pool.execute(socket, move |socket| {
+                    let user_service = ClientService::new(
+                        logger_clone,
+                        socket,
+                        addr,
+                        connected_count_clone,
+                        database_clone,
+                        Some(ban_manager_clone),
+                        config_clone,
+                        server_identity_clone,
+                    );
+                    user_service.process();
+                });
+                let socket = match result {
+                    Ok(()) => return true,
+                    Err(socket) => socket,
+                };
+
+                logger.lock_recover().print_and_log(
+                    LogCategory::Warning,
+                    &format!(
+                        "rejected connection with client {}:{} because the maximum \
+                        number of concurrent client connections ({}) was reached",
+                        addr.ip(),
+                        addr.port(),
+                        worker_pool.get_worker_count()
+                    ),
+                );
+
+                // Tell the client to try again later (drop the socket if busy with that too).
+                let dh_group = server_config.dh_group;
+                let server_identity_clone = server_identity.clone();
+                busy_answer_worker_pool
+                    .execute(socket, move |socket| {
+                        // The client might have already disconnected, nothing to do.
+                        let _ = ClientService::answer_server_busy(
+                            socket,
+                            dh_group,
+                            &server_identity_clone,
+                        );
+                    })
+                    .is_ok()
+            },
+        )
+    }
+    /// Waits for reporter or client connections.
+    ///
+    /// ## Arguments
+    /// * `listener_socket`: socket to accept connections on.
+    /// * `logger`: log manager for logging.
+    /// * `accept_connection`: starts processing of an accepted connection.
+    fn process_connections(
         listener_socket: TcpListener,
         logger: Arc<Mutex<LogManager>>,
-        connected_count: Arc<Mutex<usize>>,
-        database_manager: Arc<Mutex<DatabaseManager>>,
-        ban_manager: Arc<Mutex<BanManager>>,
+        accept_connection: Arc<ConnectionAcceptor>,
     ) {
         loop {
             // Wait for connection.
             let accept_result = listener_socket.accept();
             if let Err(ref e) = accept_result {
-                logger.lock().unwrap().print_and_log(
+                logger.lock_recover().print_and_log(
                     LogCategory::Error,
                     &AppError::new(&e.to_string()).to_string(),
                 );
@@ -219,56 +610,21 @@ impl NetService {
             let (socket, addr) = accept_result.unwrap();
 
             if let Err(e) = socket.set_nodelay(true) {
-                logger.lock().unwrap().print_and_log(
+                logger.lock_recover().print_and_log(
                     LogCategory::Error,
                     &AppError::new(&e.to_string()).to_string(),
                 );
                 continue;
             }
             if let Err(e) = socket.set_nonblocking(true) {
-                logger.lock().unwrap().print_and_log(
+                logger.lock_recover().print_and_log(
                     LogCategory::Error,
                     &AppError::new(&e.to_string()).to_string(),
                 );
                 continue;
             }
 
-            {
-                let mut ban_manager_guard = ban_manager.lock().unwrap();
-
-                ban_manager_guard.refresh_failed_and_banned_lists();
-
-                // Check if this IP is banned.
-                if ban_manager_guard.is_ip_banned(addr.ip()) {
-                    continue;
-                }
-            }
-
-            let logger_clone = logger.clone();
-            let connected_count_clone = connected_count.clone();
-            let database_clone = database_manager.clone();
-            let ban_manager_clone = ban_manager.clone();
-
-            let handle = thread::Builder::new()
-                .name(format!("client socket {}:{}", addr.ip(), addr.port()))
-                .spawn(move || {
-                    let user_service = ClientService::new(
-                        logger_clone,
-                        socket,
-                        addr,
-                        connected_count_clone,
-                        database_clone,
-                        Some(ban_manager_clone),
-                    );
-                    user_service.process();
-                });
-            if let Err(ref e) = handle {
-                logger.lock().unwrap().print_and_log(
-                    LogCategory::Error,
-                    &AppError::new(&e.to_string()).to_string(),
-                );
-                continue;
-            }
+            accept_connection(Box::new(socket), addr);
         }
     }
 }
@@ -0,0 +1,572 @@
+// Std.
+use std::str::FromStr;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// External.
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use regex::{Regex, RegexBuilder};
+use serde_json::json;
+
+// Custom.
+use super::mutex_ext::MutexExt;
+use crate::io::config_manager::ConfigManager;
+use crate::io::log_manager::*;
+use crate::network::webhook_queue::WebhookQueue;
+use shared::misc::db_manager::DatabaseManager;
+use shared::misc::error::AppError;
+use shared::misc::report::GameReport;
+
+const FORWARDING_TIMEOUT_IN_SEC: u64 = 30;
+const GITHUB_API_URL: &str = "https://api.github.com";
+const USER_AGENT: &str = "FBugReporter";
+
+/// Separates routes in the config value.
+const ROUTE_SEPARATOR: char = ',';
+/// Separates game name pattern and target in a route.
+const ROUTE_TARGET_SEPARATOR: &str = "=>";
+
+/// Where a report is forwarded to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ForwardingTarget {
+    /// Creates an issue in a GitHub repository (`owner/repo`).
+    GitHub { repository: String },
+    /// Sends a POST request with the report (as JSON) to the URL.
+    Webhook { url: String },
+    /// Sends an email to the address (using SMTP settings from the config).
+    Email { address: String },
+}
+
+impl FromStr for ForwardingTarget {
+    type Err = AppError;
+
+    /// Parses a target in the form `type:address`, for example: `github:owner/repo`,
+    /// `webhook:https://example.com/hook` or `email:me@example.com`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (target_type, address) = match value.split_once(':') {
+            Some((target_type, address)) => (target_type.trim(), address.trim()),
+            None => {
+                return Err(AppError::new(&format!(
+                    "forwarding target \"{}\" does not have a type (expected \"type:address\")",
+                    value
+                )))
+            }
+        };
+        if address.is_empty() {
+            return Err(AppError::new(&format!(
+                "forwarding target \"{}\" does not have an address",
+                value
+            )));
+        }
+
+        match target_type.to_lowercase().as_str() {
+            "github" => {
+                if address.split('/').filter(|part| !part.is_empty()).count() != 2 {
+                    return Err(AppError::new(&format!(
+                        "GitHub repository \"{}\" should be specified as \"owner/repo\"",
+                        address
+                    )));
+                }
+                Ok(ForwardingTarget::GitHub {
+                    repository: address.to_string(),
+                })
+            }
+            "webhook" => Ok(ForwardingTarget::Webhook {
+                url: address.to_string(),
+            }),
+            "email" => Ok(ForwardingTarget::Email {
+                address: address.to_string(),
+            }),
+            _ => Err(AppError::new(&format!(
+                "unknown forwarding target type \"{}\" (expected \"github\", \"webhook\" or \"email\")",
+                target_type
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for ForwardingTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ForwardingTarget::GitHub { repository } => write!(f, "github:{}", repository),
+            ForwardingTarget::Webhook { url } => write!(f, "webhook:{}", url),
+            ForwardingTarget::Email { address } => write!(f, "email:{}", address),
+        }
+    }
+}
+
+/// Tells where to forward reports of the games that match the pattern.
+#[derive(Debug, Clone)]
+pub struct ForwardingRoute {
+    /// Game name pattern as specified in the config (`*` matches any characters).
+    pub game_name_pattern: String,
+    pub target: ForwardingTarget,
+    /// Compiled `game_name_pattern`.
+    regex: Regex,
+}
+
+impl ForwardingRoute {
+    /// Parses routes from the config value.
+    ///
+    /// ## Arguments
+    /// * `value`: routes separated by commas, each route has the form `game name pattern => target`,
+    ///   for example: `Game A => github:owner/repo, Game B* => email:me@example.com`.
+    ///
+    /// ## Return
+    /// Error if some route is invalid.
+    pub fn parse_routes(value: &str) -> Result<Vec<ForwardingRoute>, AppError> {
+        let mut routes = Vec::new();
+
+        for route in value
+            .split(ROUTE_SEPARATOR)
+            .map(|route| route.trim())
+            .filter(|route| !route.is_empty())
+        {
+            let (pattern, target) = match route.split_once(ROUTE_TARGET_SEPARATOR) {
+                Some((pattern, target)) => (pattern.trim(), target.trim()),
+                None => {
+                    return Err(AppError::new(&format!(
+                    "forwarding route \"{}\" should have the form \"game name pattern {} target\"",
+                    route, ROUTE_TARGET_SEPARATOR
+                )))
+                }
+            };
+            if pattern.is_empty() {
+                return Err(AppError::new(&format!(
+                    "forwarding route \"{}\" does not have a game name pattern",
+                    route
+                )));
+            }
+
+            // Convert wildcard pattern to regex.
+            let regex = format!(
+                "^{}$",
+                pattern
+                    .split('*')
+                    .map(regex::escape)
+                    .collect::<Vec<String>>()
+                    .join(".*")
+            );
+            let regex = RegexBuilder::new(&regex).case_insensitive(true).build();
+            if let Err(e) = regex {
+                return Err(AppError::new(&e.to_string()));
+            }
+
+            routes.push(ForwardingRoute {
+                game_name_pattern: pattern.to_string(),
+                target: ForwardingTarget::from_str(target)?,
+                regex: regex.unwrap(),
+            });
+        }
+
+        Ok(routes)
+    }
+    /// Tells if reports of the specified game should be forwarded using this route.
+    pub fn is_matching(&self, game_name: &str) -> bool {
+        self.regex.is_match(game_name)
+    }
+}
+
+/// A report waiting to be forwarded.
+struct ForwardingJob {
+    report_id: u64,
+    report: GameReport,
+    /// Pairs of "file name" - "size in bytes".
+    attachments: Vec<(String, usize)>,
+    target: ForwardingTarget,
+    attempt_count: u32,
+    next_attempt_time: Instant,
+}
+
+/// Forwards saved reports to other report receivers (GitHub, webhooks, email)
+/// according to the forwarding routes from the config.
+///
+/// Reports are forwarded in a separate thread so that slow or unavailable
+/// receivers don't delay saving reports, failed attempts are retried later.
+/// Additionally, all reports are sent to the webhook from the config (if specified).
+pub struct ReportForwarder {
+    server_config: Arc<ConfigManager>,
+    /// `None` if no forwarding routes are specified.
+    job_sender: Option<Mutex<Sender<ForwardingJob>>>,
+    webhook_queue: WebhookQueue,
+}
+
+impl ReportForwarder {
+    /// Creates a new forwarder and starts a thread that forwards reports
+    /// (if some forwarding routes are specified in the config).
+    pub fn new(
+        server_config: Arc<ConfigManager>,
+        database: Arc<Mutex<DatabaseManager>>,
+        logger: Arc<Mutex<LogManager>>,
+    ) -> Self {
+        let webhook_queue = WebhookQueue::new(server_config.clone(), database, logger.clone());
+
+        if server_config.forwarding_routes.is_empty() {
+            return Self {
+                server_config,
+                job_sender: None,
+                webhook_queue,
+            };
+        }
+
+        {
+            let logger_guard = logger.lock_recover();
+            for route in server_config.forwarding_routes.iter() {
+                logger_guard.print_and_log(
+                    LogCategory::Info,
+                    &format!(
+                        "reports of games matching \"{}\" will be forwarded to \"{}\"",
+                        route.game_name_pattern, route.target
+                    ),
+                );
+            }
+        }
+
+        let (job_sender, job_receiver) = mpsc::channel();
+
+        let config_clone = server_config.clone();
+        thread::spawn(move || {
+            ReportForwarder::process_jobs(job_receiver, config_clone, logger);
+        });
+
+        Self {
+            server_config,
+            job_sender: Some(Mutex::new(job_sender)),
+            webhook_queue,
+        }
+    }
+    /// Queues the report to be forwarded to all receivers with matching routes
+    /// and to the webhook.
+    ///
+    /// ## Arguments
+    /// * `report_id`: ID of the saved report.
+    /// * `report`: saved report.
+    /// * `attachments`: pairs of "file name" - "size in bytes" of the report attachments.
+    ///
+    /// ## Return
+    /// Error if failed to add the report to the webhook queue.
+    pub fn forward_report(
+        &self,
+        report_id: u64,
+        report: &GameReport,
+        attachments: &[(String, usize)],
+    ) -> Result<(), AppError> {
+        self.webhook_queue.add(
+            report_id,
+            &ReportForwarder::get_report_json(report_id, report, attachments).to_string(),
+        )?;
+
+        let job_sender = match &self.job_sender {
+            Some(job_sender) => job_sender,
+            None => return Ok(()),
+        };
+
+        for route in self
+            .server_config
+            .forwarding_routes
+            .iter()
+            .filter(|route| route.is_matching(&report.game_name))
+        {
+            // The receiver only stops together with the server so ignore errors.
+            let _ = job_sender.lock_recover().send(ForwardingJob {
+                report_id,
+                report: report.clone(),
+                attachments: attachments.to_vec(),
+                target: route.target.clone(),
+                attempt_count: 0,
+                next_attempt_time: Instant::now(),
+            });
+        }
+
+        Ok(())
+    }
+    /// Waits for new jobs and forwards reports (retrying failed attempts).
+    fn process_jobs(
+        job_receiver: Receiver<ForwardingJob>,
+        server_config: Arc<ConfigManager>,
+        logger: Arc<Mutex<LogManager>>,
+    ) {
+        let retry_interval = Duration::from_secs(server_config.forwarding_retry_interval_in_sec);
+        let mut pending_jobs: Vec<ForwardingJob> = Vec::new();
+
+        loop {
+            // Wait for a new job or until the next retry.
+            let now = Instant::now();
+            let wait_time = pending_jobs
+                .iter()
+                .map(|job| job.next_attempt_time.saturating_duration_since(now))
+                .min();
+            let received = match wait_time {
+                Some(wait_time) => job_receiver.recv_timeout(wait_time),
+                None => job_receiver
+                    .recv()
+                    .map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match received {
+                Ok(job) => pending_jobs.push(job),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+
+            // Process jobs that are ready.
+            let now = Instant::now();
+            let (ready_jobs, not_ready_jobs): (Vec<ForwardingJob>, Vec<ForwardingJob>) =
+                pending_jobs
+                    .into_iter()
+                    .partition(|job| job.next_attempt_time <= now);
+            pending_jobs = not_ready_jobs;
+
+            for mut job in ready_jobs {
+                job.attempt_count += 1;
+
+                let result = ReportForwarder::forward(&job, &server_config);
+                if let Err(app_error) = result {
+                    if job.attempt_count >= server_config.forwarding_max_attempts {
+                        logger.lock_recover().print_and_log(
+                            LogCategory::Error,
+                            &format!(
+                                "failed to forward the report with id {} to \"{}\", giving up \
+                                after {} attempt(-s), last error: {}",
+                                job.report_id, job.target, job.attempt_count, app_error
+                            ),
+                        );
+                        continue;
+                    }
+
+                    logger.lock_recover().print_and_log(
+                        LogCategory::Warning,
+                        &format!(
+                            "failed to forward the report with id {} to \"{}\" (attempt {}/{}), \
+                            will retry in {} second(-s), error: {}",
+                            job.report_id,
+                            job.target,
+                            job.attempt_count,
+                            server_config.forwarding_max_attempts,
+                            retry_interval.as_secs(),
+                            app_error
+                        ),
+                    );
+                    job.next_attempt_time = Instant::now() + retry_interval;
+                    pending_jobs.push(job);
+                    continue;
+                }
+
+                logger.lock_recover().print_and_log(
+                    LogCategory::Info,
+                    &format!(
+                        "forwarded the report with id {} to \"{}\"",
+                        job.report_id, job.target
+                    ),
+                );
+            }
+        }
+    }
+    /// Sends the report to the job's target.
+    fn forward(job: &ForwardingJob, server_config: &ConfigManager) -> Result<(), AppError> {
+        match &job.target {
+            ForwardingTarget::GitHub { repository } => {
+                if server_config.forwarding_github_token.is_empty() {
+                    return Err(AppError::new(
+                        "GitHub token is not specified in the server config",
+                    ));
+                }
+
+                let result = ureq::post(&format!("{}/repos/{}/issues", GITHUB_API_URL, repository))
+                    .timeout(Duration::from_secs(FORWARDING_TIMEOUT_IN_SEC))
+                    .set("User-Agent", USER_AGENT)
+                    .set("Accept", "application/vnd.github+json")
+                    .set(
+                        "Authorization",
+                        &format!("Bearer {}", server_config.forwarding_github_token),
+                    )
+                    .send_json(json!({
+                        "title": job.report.report_name,
+                        "body": ReportForwarder::format_report(job, true),
+                    }));
+                if let Err(e) = result {
+                    return Err(AppError::new(&e.to_string()));
+                }
+            }
+            ForwardingTarget::Webhook { url } => {
+                ReportForwarder::send_webhook(
+                    url,
+                    ReportForwarder::get_report_json(job.report_id, &job.report, &job.attachments),
+                )?;
+            }
+            ForwardingTarget::Email { address } => {
+                ReportForwarder::send_email(
+                    server_config,
+                    address,
+                    &format!("[{}] {}", job.report.game_name, job.report.report_name),
+                    ReportForwarder::format_report(job, false),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+    /// Sends a POST request with the specified JSON.
+    pub fn send_webhook(url: &str, body: serde_json::Value) -> Result<(), AppError> {
+        let result = ureq::post(url)
+            .timeout(Duration::from_secs(FORWARDING_TIMEOUT_IN_SEC))
+            .set("User-Agent", USER_AGENT)
+            .send_json(body);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Sends an email using SMTP settings from the config.
+    ///
+    /// ## Arguments
+    /// * `server_config`: server configuration.
+    /// * `address`: email address of the receiver.
+    /// * `subject`: subject of the email.
+    /// * `body`: text of the email.
+    pub fn send_email(
+        server_config: &ConfigManager,
+        address: &str,
+        subject: &str,
+        body: String,
+    ) -> Result<(), AppError> {
+        if server_config.forwarding_smtp_server.is_empty() {
+            return Err(AppError::new(
+                "SMTP server is not specified in the server config",
+            ));
+        }
+
+        let from = server_config.forwarding_smtp_username.parse::<Mailbox>();
+        if let Err(e) = from {
+            return Err(AppError::new(&format!(
+                "SMTP username should be an email address: {}",
+                e
+            )));
+        }
+        let to = address.parse::<Mailbox>();
+        if let Err(e) = to {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let email = Message::builder()
+            .from(from.unwrap())
+            .to(to.unwrap())
+            .subject(subject)
+            .body(body);
+        if let Err(e) = email {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let transport = SmtpTransport::relay(&server_config.forwarding_smtp_server);
+        if let Err(e) = transport {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let transport = transport
+            .unwrap()
+            .credentials(Credentials::new(
+                server_config.forwarding_smtp_username.clone(),
+                server_config.forwarding_smtp_password.clone(),
+            ))
+            .timeout(Some(Duration::from_secs(FORWARDING_TIMEOUT_IN_SEC)))
+            .build();
+
+        if let Err(e) = transport.send(&email.unwrap()) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Returns the report as JSON (sent to webhooks).
+    fn get_report_json(
+        report_id: u64,
+        report: &GameReport,
+        attachments: &[(String, usize)],
+    ) -> serde_json::Value {
+        json!({
+            "id": report_id,
+            "title": report.report_name,
+            "text": report.report_text,
+            "sender_name": report.sender_name,
+            "sender_email": report.sender_email,
+            "game_name": report.game_name,
+            "game_version": report.game_version,
+            "os_info": report.client_os_info.to_string(),
+            "priority": report.priority.to_string(),
+            "metadata": report.metadata,
+            "session_id": report.session_id,
+            "playtime_seconds": report.playtime_seconds,
+            "attachments": attachments
+                .iter()
+                .map(|(file_name, size_in_bytes)| {
+                    json!({"file_name": file_name, "size_in_bytes": size_in_bytes})
+                })
+                .collect::<Vec<_>>(),
+        })
+    }
+    /// Formats the report as a human-readable text.
+    ///
+    /// ## Arguments
+    /// * `job`: job with the report to format.
+    /// * `use_markdown`: whether to use Markdown or plain text.
+    fn format_report(job: &ForwardingJob, use_markdown: bool) -> String {
+        let report = &job.report;
+        let (header, bullet) = if use_markdown {
+            ("### ", "- ")
+        } else {
+            ("", "")
+        };
+
+        let sender = if report.sender_name.is_empty() && report.sender_email.is_empty() {
+            String::from("no information provided")
+        } else if report.sender_email.is_empty() {
+            report.sender_name.clone()
+        } else {
+            format!("{} ({})", report.sender_name, report.sender_email)
+        };
+
+        let mut text = format!(
+            "{}Report #{}\n\n\
+            {}Game: {} (version: {})\n\
+            {}Sender: {}\n\
+            {}OS info: {}\n\
+            {}Priority: {}\n\n\
+            {}Text\n\n{}\n",
+            header,
+            job.report_id,
+            bullet,
+            report.game_name,
+            report.game_version,
+            bullet,
+            sender,
+            bullet,
+            report.client_os_info,
+            bullet,
+            report.priority,
+            header,
+            report.report_text
+        );
+
+        if !report.metadata.is_empty() {
+            text += &format!("\n{}Metadata\n\n", header);
+            for (key, value) in report.metadata.iter() {
+                text += &format!("{}{}: {}\n", bullet, key, value);
+            }
+        }
+
+        if !job.attachments.is_empty() {
+            text += &format!(
+                "\n{}Attachments (stored on the FBugReporter server)\n\n",
+                header
+            );
+            for (file_name, size_in_bytes) in job.attachments.iter() {
+                text += &format!("{}{} ({} bytes)\n", bullet, file_name, size_in_bytes);
+            }
+        }
+
+        text
+    }
+}
@@ -1,26 +1,41 @@
 // Std.
+use std::cmp::Ordering;
 use std::net::*;
 use std::sync::{Arc, Mutex};
 
 // Custom.
+use super::metrics::{ReportRejectReason, ServerMetrics};
+use super::mutex_ext::MutexExt;
 use super::net_service::MAX_MESSAGE_SIZE_IN_BYTES_WITHOUT_ATTACHMENTS;
+use super::report_forwarder::ReportForwarder;
+use super::sender_cooldown::SenderCooldown;
+use crate::io::config_manager::{ConfigManager, ReportFieldRule};
 use crate::io::log_manager::*;
-use shared::misc::db_manager::DatabaseManager;
+use shared::misc::db_manager::{DatabaseManager, SaveReportResult};
 use shared::misc::error::AppError;
 use shared::misc::report::*;
+use shared::network::dh_group::DhGroup;
 use shared::network::messaging::*;
 use shared::network::net_params::*;
+use shared::network::proof_of_work::ProofOfWorkChallenge;
 use shared::network::reporter_messages::*;
+use shared::network::server_identity::ServerIdentity;
 
 pub struct ReporterService {
     logger: Arc<Mutex<LogManager>>,
     database: Arc<Mutex<DatabaseManager>>,
-    socket: TcpStream,
+    socket: Box<dyn MessageStream + Send>,
     socket_addr: SocketAddr,
     secret_key: [u8; SECRET_KEY_SIZE],
     connected_count: Arc<Mutex<usize>>,
     exit_error: Option<Result<String, AppError>>,
-    max_total_attachment_size_in_mb: usize,
+    server_config: Arc<ConfigManager>,
+    /// Challenge that was sent to the reporter.
+    proof_of_work: ProofOfWorkChallenge,
+    sender_cooldown: Arc<SenderCooldown>,
+    report_forwarder: Arc<ReportForwarder>,
+    metrics: Arc<ServerMetrics>,
+    server_identity: Arc<ServerIdentity>,
 }
 
 impl ReporterService {
@@ -28,23 +43,32 @@ impl ReporterService {
     ///
     /// ## Arguments
     /// * `logger`: log manager for logging.
-    /// * `socket`: connected reporter socket.
+    /// * `socket`: connected reporter socket (or HTTP transport session).
     /// * `addr`: reporter socket address.
     /// * `connected_users_count`: shared variable that stores total connections.
     /// * `database`: database manager that handles the database.
-    /// * `max_attachment_size_in_mb`: maximum size of report attachments (in total) in MB.
+    /// * `server_config`: server configuration.
+    /// * `sender_cooldown`: shared tracker of the last reports from each sender email.
+    /// * `report_forwarder`: forwards saved reports to other report receivers.
+    /// * `metrics`: server metrics to update.
+    /// * `server_identity`: key pair that is used to sign the key exchange.
+    #[allow(clippy::too_many_arguments)] // shared server state is passed as is
     pub fn new(
         logger: Arc<Mutex<LogManager>>,
-        socket: TcpStream,
+        socket: Box<dyn MessageStream + Send>,
         addr: SocketAddr,
         connected_count: Arc<Mutex<usize>>,
         database: Arc<Mutex<DatabaseManager>>,
-        max_attachment_size_in_mb: usize,
+        server_config: Arc<ConfigManager>,
+        sender_cooldown: Arc<SenderCooldown>,
+        report_forwarder: Arc<ReportForwarder>,
+        metrics: Arc<ServerMetrics>,
+        server_identity: Arc<ServerIdentity>,
     ) -> Self {
         {
-            let mut guard = connected_count.lock().unwrap();
+            let mut guard = connected_count.lock_recover();
             *guard += 1;
-            logger.lock().unwrap().print_and_log(
+            logger.lock_recover().print_and_log(
                 LogCategory::Info,
                 &format!(
                     "accepted connection with reporter {}:{}\n------------------------- \
@@ -56,8 +80,6 @@ impl ReporterService {
             );
         }
 
-        let socket_addr = socket.peer_addr().unwrap();
-
         Self {
             logger,
             socket,
@@ -65,8 +87,13 @@ impl ReporterService {
             exit_error: None,
             secret_key: [0; SECRET_KEY_SIZE],
             database,
-            socket_addr,
-            max_total_attachment_size_in_mb: max_attachment_size_in_mb,
+            socket_addr: addr,
+            server_config,
+            proof_of_work: ProofOfWorkChallenge::default(),
+            sender_cooldown,
+            report_forwarder,
+            metrics,
+            server_identity,
         }
     }
 
@@ -74,7 +101,16 @@ impl ReporterService {
     ///
     /// After this function is finished the object should be destroyed.
     pub fn process(mut self) {
-        let secret_key = start_establishing_secure_connection(&mut self.socket);
+        let _connection_span =
+            tracing::info_span!("reporter_connection", address = %self.socket_addr).entered();
+
+        let secret_key = tracing::info_span!("secure_connection").in_scope(|| {
+            start_establishing_secure_connection(
+                &mut self.socket,
+                self.server_config.dh_group,
+                &self.server_identity,
+            )
+        });
         if let Err(app_error) = secret_key {
             self.exit_error = Some(Err(app_error));
             return;
@@ -88,8 +124,23 @@ impl ReporterService {
         }
         self.secret_key = result.unwrap();
 
+        // Send proof of work challenge (difficulty 0 if disabled).
+        self.proof_of_work =
+            ProofOfWorkChallenge::new(self.server_config.reporter_proof_of_work_difficulty);
+        if let Some(app_error) = send_message(
+            &mut self.socket,
+            &self.secret_key,
+            ReporterAnswer::Challenge {
+                proof_of_work: self.proof_of_work.clone(),
+            },
+        ) {
+            self.exit_error = Some(Err(app_error));
+            return;
+        }
+
+        // The game is not known yet so use the largest limit (checked again for the game later).
         let max_allowed_message_size = MAX_MESSAGE_SIZE_IN_BYTES_WITHOUT_ATTACHMENTS
-            + (self.max_total_attachment_size_in_mb * 1024 * 1024);
+            + (self.server_config.get_largest_max_attachment_size_in_mb() * 1024 * 1024);
 
         // Wait for message.
         let mut is_fin = false; // don't check, react to FIN as error
@@ -126,6 +177,44 @@ impl ReporterService {
         }
     }
 
+    /// Tells the reporter that the server is busy (used when the maximum number
+    /// of concurrent reporter connections was reached).
+    ///
+    /// ## Remarks
+    /// The "rate limited" result is sent instead of the proof of work challenge
+    /// so that nothing is received from the reporter.
+    ///
+    /// ## Arguments
+    /// * `socket`: connected reporter socket (or HTTP transport session).
+    /// * `dh_group`: Diffie-Hellman group to use for the key exchange.
+    /// * `server_identity`: key pair that is used to sign the key exchange.
+    /// * `retry_after_in_sec`: time after which the reporter can try again.
+    pub fn answer_server_busy(
+        mut socket: Box<dyn MessageStream + Send>,
+        dh_group: DhGroup,
+        server_identity: &ServerIdentity,
+        retry_after_in_sec: u64,
+    ) -> Result<(), AppError> {
+        let secret_key =
+            start_establishing_secure_connection(&mut socket, dh_group, server_identity)?;
+        let secret_key: [u8; SECRET_KEY_SIZE] = match secret_key.try_into() {
+            Ok(secret_key) => secret_key,
+            Err(_) => return Err(AppError::new("failed to convert Vec<u8> to generic array")),
+        };
+
+        if let Some(app_error) = send_message(
+            &mut socket,
+            &secret_key,
+            ReporterAnswer::Report {
+                result_code: ServerAnswer::RateLimited { retry_after_in_sec },
+            },
+        ) {
+            return Err(app_error);
+        }
+
+        Ok(())
+    }
+
     /// Processes the client message.
     ///
     /// Returns `Option<String>` as `Ok`:
@@ -145,14 +234,39 @@ impl ReporterService {
                 reporter_net_protocol,
                 game_report,
                 attachments,
-            } => self.handle_report_request(reporter_net_protocol, game_report, attachments),
-            ReporterRequest::MaxAttachmentSize {} => {
-                let result = self.handle_attachment_size_query_request();
+                proof_of_work_nonce,
+            } => self.handle_report_request(
+                reporter_net_protocol,
+                game_report,
+                attachments,
+                proof_of_work_nonce,
+            ),
+            ReporterRequest::MaxAttachmentSize { game_name } => {
+                let result = self.handle_attachment_size_query_request(&game_name);
                 if let Some(app_error) = result {
                     return Err(app_error);
                 }
                 Ok(None)
             }
+            ReporterRequest::QueryServerInfo => {
+                // Log event.
+                self.logger.lock_recover().print_and_log(
+                    LogCategory::Info,
+                    &format!(
+                        "received server info request from reporter {}",
+                        self.socket_addr
+                    ),
+                );
+
+                let answer = ReporterAnswer::ServerInfo {
+                    net_protocol: NETWORK_PROTOCOL_VERSION,
+                    server_version: String::from(env!("CARGO_PKG_VERSION")),
+                };
+                if let Some(app_error) = send_message(&mut self.socket, &self.secret_key, answer) {
+                    return Err(app_error);
+                }
+                Ok(None)
+            }
         }
     }
 
@@ -166,14 +280,23 @@ impl ReporterService {
     /// - if `None` then the operation finished successfully.
     ///
     /// Returns `AppError` as `Err` if there was an error.
+    #[tracing::instrument(
+        skip_all,
+        fields(game = %game_report.game_name, attachments = attachments.len())
+    )]
     fn handle_report_request(
         &mut self,
         reporter_net_protocol: u16,
         game_report: Box<GameReport>,
         attachments: Vec<ReportAttachment>,
+        proof_of_work_nonce: u64,
     ) -> Result<Option<String>, AppError> {
         // Check protocol version.
         if reporter_net_protocol != NETWORK_PROTOCOL_VERSION {
+            self.metrics
+                .add_rejected_report(ReportRejectReason::WrongProtocol);
+
+            // Reporters of other versions might not know newer answers so use `OtherError`.
             let result_code = ServerAnswer::OtherError("wrong protocol".to_string());
 
             // Notify reporter.
@@ -191,10 +314,51 @@ impl ReporterService {
             )));
         }
 
+        // Check proof of work.
+        if !self.proof_of_work.verify(proof_of_work_nonce) {
+            self.metrics
+                .add_rejected_report(ReportRejectReason::InvalidProofOfWork);
+
+            // Notify reporter.
+            if let Some(app_error) = send_message(
+                &mut self.socket,
+                &self.secret_key,
+                ReporterAnswer::Report {
+                    result_code: ServerAnswer::InvalidProofOfWork,
+                },
+            ) {
+                return Err(app_error);
+            }
+
+            return Ok(Some(format!(
+                "invalid proof of work (nonce {} does not solve the challenge)",
+                proof_of_work_nonce
+            )));
+        }
+
         // Check field limits.
-        if let Err((field, length)) = Self::check_report_field_limits(&game_report) {
-            let result_code =
-                ServerAnswer::OtherError("report field(s) exceed limit (too long)".to_string());
+        if let Err((field, length)) =
+            Self::check_report_field_limits(&game_report, self.server_config.min_report_text_length)
+        {
+            // Fields that are not too long are too short.
+            let is_too_short = length <= field.max_length();
+
+            let result_code = if is_too_short {
+                self.metrics
+                    .add_rejected_report(ReportRejectReason::FieldTooShort);
+
+                ServerAnswer::FieldTooShort {
+                    field: field.to_string(),
+                    min_length: self.server_config.min_report_text_length,
+                }
+            } else {
+                self.metrics
+                    .add_rejected_report(ReportRejectReason::FieldTooLong);
+
+                ServerAnswer::FieldTooLong {
+                    field: field.to_string(),
+                }
+            };
 
             // Notify reporter.
             if let Some(app_error) = send_message(
@@ -205,6 +369,13 @@ impl ReporterService {
                 return Err(app_error);
             }
 
+            if is_too_short {
+                return Ok(Some(format!(
+                    "report text is too short ({} characters while at least {} are required)",
+                    length, self.server_config.min_report_text_length
+                )));
+            }
+
             return Ok(Some(format!(
                 "report exceeds report field limits ({:?} has length of {} characters \
                     while the limit is {})",
@@ -214,14 +385,221 @@ impl ReporterService {
             )));
         }
 
-        // Calculate attachments size.
+        // Check field rules.
+        if let Some(rule) = Self::find_violated_field_rule(&self.server_config, &game_report) {
+            self.metrics
+                .add_rejected_report(ReportRejectReason::FieldRuleViolated);
+
+            // Notify reporter.
+            if let Some(app_error) = send_message(
+                &mut self.socket,
+                &self.secret_key,
+                ReporterAnswer::Report {
+                    result_code: ServerAnswer::Rejected(format!(
+                        "the field {} does not have the format required by the server",
+                        rule.field
+                    )),
+                },
+            ) {
+                return Err(app_error);
+            }
+
+            return Ok(Some(format!(
+                "report field {} does not match the regex \"{}\" from the config",
+                rule.field, rule.pattern
+            )));
+        }
+
+        // Check metadata entry count.
+        if game_report.metadata.len() > REPORT_METADATA_MAX_ENTRY_COUNT {
+            self.metrics
+                .add_rejected_report(ReportRejectReason::TooManyMetadataEntries);
+
+            // Notify reporter.
+            if let Some(app_error) = send_message(
+                &mut self.socket,
+                &self.secret_key,
+                ReporterAnswer::Report {
+                    result_code: ServerAnswer::Rejected(format!(
+                        "the report has too many metadata entries (the limit is {})",
+                        REPORT_METADATA_MAX_ENTRY_COUNT
+                    )),
+                },
+            ) {
+                return Err(app_error);
+            }
+
+            return Ok(Some(format!(
+                "report has {} metadata entries while the limit is {}",
+                game_report.metadata.len(),
+                REPORT_METADATA_MAX_ENTRY_COUNT
+            )));
+        }
+
+        // Check playtime.
+        if game_report.playtime_seconds > REPORT_MAX_PLAYTIME_SECONDS {
+            self.metrics
+                .add_rejected_report(ReportRejectReason::FieldTooLong);
+
+            // Notify reporter.
+            if let Some(app_error) = send_message(
+                &mut self.socket,
+                &self.secret_key,
+                ReporterAnswer::Report {
+                    result_code: ServerAnswer::Rejected(format!(
+                        "the report playtime is too big (the limit is {} seconds)",
+                        REPORT_MAX_PLAYTIME_SECONDS
+                    )),
+                },
+            ) {
+                return Err(app_error);
+            }
+
+            return Ok(Some(format!(
+                "report has playtime of {} seconds while the limit is {}",
+                game_report.playtime_seconds, REPORT_MAX_PLAYTIME_SECONDS
+            )));
+        }
+
+        // Check attachments size.
         let mut attachments_size_in_bytes: usize = 0;
         for attachment in attachments.iter() {
             attachments_size_in_bytes += attachment.data.len();
         }
+        let max_attachments_size_in_mb = self
+            .server_config
+            .get_max_attachment_size_in_mb(&game_report.game_name);
+        if attachments_size_in_bytes > max_attachments_size_in_mb * 1024 * 1024 {
+            self.metrics
+                .add_rejected_report(ReportRejectReason::AttachmentsTooBig);
+
+            // Notify reporter.
+            if let Some(app_error) = send_message(
+                &mut self.socket,
+                &self.secret_key,
+                ReporterAnswer::Report {
+                    result_code: ServerAnswer::Rejected(format!(
+                        "the attachments are too big (the limit is {} MB)",
+                        max_attachments_size_in_mb
+                    )),
+                },
+            ) {
+                return Err(app_error);
+            }
+
+            return Ok(Some(format!(
+                "report attachments of game \"{}\" have size of ~{} KB while the limit is {} MB",
+                game_report.game_name,
+                attachments_size_in_bytes / 1024,
+                max_attachments_size_in_mb
+            )));
+        }
+
+        // Check game name.
+        if !self.server_config.accepted_game_names.is_empty()
+            && !self
+                .server_config
+                .accepted_game_names
+                .contains(&game_report.game_name)
+        {
+            self.metrics
+                .add_rejected_report(ReportRejectReason::GameNotAccepted);
+
+            // Notify reporter.
+            if let Some(app_error) = send_message(
+                &mut self.socket,
+                &self.secret_key,
+                ReporterAnswer::Report {
+                    result_code: ServerAnswer::Rejected(String::from(
+                        "the server does not accept reports from this game",
+                    )),
+                },
+            ) {
+                return Err(app_error);
+            }
+
+            return Ok(Some(format!(
+                "rejected a report from game \"{}\" (see \"accepted_game_names\" in the config)",
+                game_report.game_name
+            )));
+        }
+
+        // Check game version.
+        if !Self::is_game_version_accepted(&self.server_config, &game_report.game_version) {
+            self.metrics
+                .add_rejected_report(ReportRejectReason::VersionRejected);
+
+            // Notify reporter.
+            if let Some(app_error) = send_message(
+                &mut self.socket,
+                &self.secret_key,
+                ReporterAnswer::Report {
+                    result_code: ServerAnswer::VersionRejected,
+                },
+            ) {
+                return Err(app_error);
+            }
+
+            return Ok(Some(format!(
+                "rejected a report from game version \"{}\" (see reports section of the config)",
+                game_report.game_version
+            )));
+        }
+
+        // Check for spam.
+        if let Some(matched_text) = Self::find_spam(&self.server_config, &game_report) {
+            self.metrics.add_rejected_report(ReportRejectReason::Spam);
+
+            // Notify reporter (without telling what exactly was matched).
+            if let Some(app_error) = send_message(
+                &mut self.socket,
+                &self.secret_key,
+                ReporterAnswer::Report {
+                    result_code: ServerAnswer::Rejected(String::from(
+                        "the report was rejected by the spam filter",
+                    )),
+                },
+            ) {
+                return Err(app_error);
+            }
+
+            return Ok(Some(format!(
+                "rejected a report by the spam filter (matched \"{}\")",
+                matched_text
+            )));
+        }
+
+        // Check sender cooldown.
+        if let Err(time_left) = self
+            .sender_cooldown
+            .register_report(&game_report.sender_email)
+        {
+            // Round up so that the reporter does not retry too early.
+            let retry_after_in_sec = time_left.as_secs() + 1;
+
+            self.metrics
+                .add_rejected_report(ReportRejectReason::RateLimited);
+
+            // Notify reporter.
+            if let Some(app_error) = send_message(
+                &mut self.socket,
+                &self.secret_key,
+                ReporterAnswer::Report {
+                    result_code: ServerAnswer::RateLimited { retry_after_in_sec },
+                },
+            ) {
+                return Err(app_error);
+            }
+
+            return Ok(Some(format!(
+                "rejected a report from \"{}\" because the sender's cooldown is not finished \
+                    ({} second(-s) left)",
+                game_report.sender_email, retry_after_in_sec
+            )));
+        }
 
         // Log event.
-        self.logger.lock().unwrap().print_and_log(
+        self.logger.lock_recover().print_and_log(
             LogCategory::Info,
             &format!(
                 "received a report (attachments size ~{} KB) from reporter {}",
@@ -230,32 +608,91 @@ impl ReporterService {
             ),
         );
 
-        {
-            if let Err(app_error) = self
-                .database
-                .lock()
-                .unwrap()
-                .save_report(*game_report, attachments)
-            {
-                let result_code = ServerAnswer::OtherError(app_error.get_message());
+        // Keep the report to forward it after saving.
+        let forwarded_report = game_report.clone();
+        let forwarded_attachments: Vec<(String, usize)> = attachments
+            .iter()
+            .map(|attachment| (attachment.file_name.clone(), attachment.data.len()))
+            .collect();
+
+        let result = self.database.lock_recover().save_report(
+            *game_report,
+            attachments,
+            self.server_config.max_report_count,
+            reporter_net_protocol,
+        );
+        if let Err(app_error) = result {
+            self.metrics
+                .add_rejected_report(ReportRejectReason::InternalError);
 
-                // Notify reporter of our failure.
-                if let Some(app_error) = send_message(
-                    &mut self.socket,
-                    &self.secret_key,
-                    ReporterAnswer::Report { result_code },
-                ) {
-                    return Err(app_error);
-                }
+            // Nothing was saved, allow the sender to retry.
+            self.sender_cooldown
+                .forget_report(&forwarded_report.sender_email);
 
+            let result_code = ServerAnswer::InternalError;
+
+            // Notify reporter of our failure.
+            if let Some(app_error) = send_message(
+                &mut self.socket,
+                &self.secret_key,
+                ReporterAnswer::Report { result_code },
+            ) {
                 return Err(app_error);
             }
+
+            return Err(app_error);
         }
 
-        self.logger.lock().unwrap().print_and_log(
-            LogCategory::Info,
-            &format!("saved a report from reporter {}", self.socket_addr),
-        );
+        self.metrics.add_received_report();
+
+        let message = match result.unwrap() {
+            SaveReportResult::Saved {
+                report_id,
+                removed_report_ids,
+            } => {
+                if !removed_report_ids.is_empty() {
+                    let logger_guard = self.logger.lock_recover();
+                    for removed_report_id in removed_report_ids {
+                        logger_guard.print_and_log(
+                            LogCategory::Info,
+                            &format!(
+                                "removed the oldest report with id {} because the maximum \
+                                number of reports ({}) was reached",
+                                removed_report_id, self.server_config.max_report_count
+                            ),
+                        );
+                    }
+                }
+
+                // Duplicates are not forwarded to not create the same issue/message again.
+                if let Err(app_error) = self.report_forwarder.forward_report(
+                    report_id,
+                    &forwarded_report,
+                    &forwarded_attachments,
+                ) {
+                    // The report is saved so don't fail.
+                    self.logger
+                        .lock_recover()
+                        .print_and_log(LogCategory::Error, &app_error.to_string());
+                }
+
+                format!(
+                    "saved a report from reporter {} (report id: {})",
+                    self.socket_addr, report_id
+                )
+            }
+            SaveReportResult::Merged {
+                report_id,
+                occurrence_count,
+            } => format!(
+                "received a duplicate of the report with id {} from reporter {} \
+                    (occurrences: {})",
+                report_id, self.socket_addr, occurrence_count
+            ),
+        };
+        self.logger
+            .lock_recover()
+            .print_and_log(LogCategory::Info, &message);
 
         // Answer "OK".
         if let Some(app_error) = send_message(
@@ -271,14 +708,50 @@ impl ReporterService {
         Ok(None)
     }
 
+    /// Returns the first field rule from the config that the report does not satisfy.
+    fn find_violated_field_rule<'a>(
+        server_config: &'a ConfigManager,
+        report: &GameReport,
+    ) -> Option<&'a ReportFieldRule> {
+        server_config.report_field_rules.iter().find(|rule| {
+            let regex = match &rule.regex {
+                Some(regex) => regex,
+                None => return false,
+            };
+
+            let value = match rule.field {
+                ReportLimits::ReportName => &report.report_name,
+                ReportLimits::ReportText => &report.report_text,
+                ReportLimits::SenderName => &report.sender_name,
+                ReportLimits::SenderEmail => &report.sender_email,
+                ReportLimits::GameName => &report.game_name,
+                ReportLimits::GameVersion => &report.game_version,
+                // Rules are not used for metadata and session info.
+                ReportLimits::MetadataKey
+                | ReportLimits::MetadataValue
+                | ReportLimits::SessionId => return false,
+            };
+
+            !regex.is_match(value)
+        })
+    }
     /// Returns [`Ok`] if the fields have the correct length (amount of characters, not byte count),
     /// otherwise returns the field type and its received length (not the limit, actual length).
-    fn check_report_field_limits(report: &GameReport) -> Result<(), (ReportLimits, usize)> {
+    ///
+    /// ## Arguments
+    /// * `report`: report to check.
+    /// * `min_report_text_length`: minimum amount of characters in the report text.
+    fn check_report_field_limits(
+        report: &GameReport,
+        min_report_text_length: usize,
+    ) -> Result<(), (ReportLimits, usize)> {
         if report.report_name.chars().count() > ReportLimits::ReportName.max_length() {
             return Err((ReportLimits::ReportName, report.report_name.chars().count()));
         }
 
-        if report.report_text.chars().count() > ReportLimits::ReportText.max_length() {
+        if report.report_text.chars().count() > ReportLimits::ReportText.max_length()
+            || report.report_text.chars().count() < min_report_text_length
+        {
             return Err((ReportLimits::ReportText, report.report_text.chars().count()));
         }
 
@@ -304,22 +777,132 @@ impl ReporterService {
             ));
         }
 
+        for (key, value) in report.metadata.iter() {
+            if key.chars().count() > ReportLimits::MetadataKey.max_length() {
+                return Err((ReportLimits::MetadataKey, key.chars().count()));
+            }
+
+            if value.chars().count() > ReportLimits::MetadataValue.max_length() {
+                return Err((ReportLimits::MetadataValue, value.chars().count()));
+            }
+        }
+
+        if report.session_id.chars().count() > ReportLimits::SessionId.max_length() {
+            return Err((ReportLimits::SessionId, report.session_id.chars().count()));
+        }
+
         Ok(())
     }
 
+    /// Checks if reports from the specified game version are accepted
+    /// according to the server configuration.
+    ///
+    /// ## Remarks
+    /// Versions are compared with the minimum accepted version only if both of them
+    /// consist of numbers separated by dots (like "1.2.3"), otherwise only the
+    /// list of blocked versions is checked.
+    fn is_game_version_accepted(server_config: &ConfigManager, game_version: &str) -> bool {
+        let game_version = game_version.trim();
+
+        if server_config
+            .blocked_game_versions
+            .iter()
+            .any(|blocked_version| blocked_version == game_version)
+        {
+            return false;
+        }
+
+        if server_config.min_accepted_game_version.is_empty() {
+            return true;
+        }
+
+        match (
+            Self::parse_game_version(game_version),
+            Self::parse_game_version(&server_config.min_accepted_game_version),
+        ) {
+            (Some(version), Some(min_version)) => {
+                Self::compare_game_versions(&version, &min_version) != Ordering::Less
+            }
+            _ => true,
+        }
+    }
+
+    /// Checks report name and text using the spam filter from the server configuration.
+    ///
+    /// ## Return
+    /// `None` if the report is not spam, otherwise the text that was matched by the filter.
+    fn find_spam(server_config: &ConfigManager, report: &GameReport) -> Option<String> {
+        if !server_config.enable_spam_filter {
+            return None;
+        }
+
+        for text in [&report.report_name, &report.report_text] {
+            // Blocked words are stored in lowercase.
+            let lowercase_text = text.to_lowercase();
+            if let Some(word) = server_config
+                .spam_filter_blocked_words
+                .iter()
+                .find(|word| lowercase_text.contains(word.as_str()))
+            {
+                return Some(word.clone());
+            }
+
+            if let Some(regex) = &server_config.spam_filter_regex {
+                if let Some(regex_match) = regex.find(text) {
+                    return Some(regex_match.as_str().to_string());
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Parses version string like "1.2.3" or "v1.2" into numbers.
+    ///
+    /// ## Return
+    /// `None` if the version has some other format.
+    fn parse_game_version(version: &str) -> Option<Vec<u64>> {
+        let version = version.trim();
+        let version = version
+            .strip_prefix('v')
+            .or_else(|| version.strip_prefix('V'))
+            .unwrap_or(version);
+
+        version
+            .split('.')
+            .map(|number| number.parse::<u64>().ok())
+            .collect()
+    }
+
+    /// Compares two parsed versions, missing numbers are considered zeros
+    /// (so "1.2" is equal to "1.2.0").
+    fn compare_game_versions(a: &[u64], b: &[u64]) -> Ordering {
+        for i in 0..a.len().max(b.len()) {
+            let ordering = a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0));
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+
+        Ordering::Equal
+    }
+
     /// Processes reporter's attachment size request.
-    fn handle_attachment_size_query_request(&mut self) -> Option<AppError> {
+    ///
+    /// ## Arguments
+    /// * `game_name`: name of the game that the reporter sends reports for.
+    fn handle_attachment_size_query_request(&mut self, game_name: &str) -> Option<AppError> {
         // Log event.
-        self.logger.lock().unwrap().print_and_log(
+        self.logger.lock_recover().print_and_log(
             LogCategory::Info,
             &format!(
-                "received maximum attachment size request from reporter {}",
-                self.socket_addr
+                "received maximum attachment size request (game \"{}\") from reporter {}",
+                game_name, self.socket_addr
             ),
         );
 
         let answer = ReporterAnswer::MaxAttachmentSize {
-            max_attachments_size_in_mb: self.max_total_attachment_size_in_mb,
+            max_attachments_size_in_mb: self.server_config.get_max_attachment_size_in_mb(game_name),
         };
         if let Some(app_error) = send_message(&mut self.socket, &self.secret_key, answer) {
             return Some(app_error);
@@ -345,7 +928,7 @@ impl Drop for ReporterService {
             }
         }
 
-        let mut connected_count = self.connected_count.lock().unwrap();
+        let mut connected_count = self.connected_count.lock_recover();
         *connected_count -= 1;
         let message = format!(
             "{}{}\n------------------------- [connected: {}] -------------------------",
@@ -353,8 +936,61 @@ impl Drop for ReporterService {
         );
 
         self.logger
-            .lock()
-            .unwrap()
+            .lock_recover()
             .print_and_log(LogCategory::Info, &message);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    // Std.
+    use std::thread;
+
+    use super::*;
+
+    #[test]
+    fn answer_server_busy_instead_of_challenge() {
+        let key_path = std::env::temp_dir().join(format!(
+            "fbugreporter_test_reporter_busy_key_{}",
+            std::process::id()
+        ));
+        let server_identity = ServerIdentity::load_or_generate(&key_path).unwrap();
+        std::fs::remove_file(&key_path).unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let server_addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (socket, _) = listener.accept().unwrap();
+            socket.set_nonblocking(true).unwrap();
+            ReporterService::answer_server_busy(
+                Box::new(socket),
+                DhGroup::default(),
+                &server_identity,
+                30,
+            )
+        });
+
+        let mut socket = TcpStream::connect(server_addr).unwrap();
+        let secret_key: [u8; SECRET_KEY_SIZE] =
+            accept_secure_connection_establishment(&mut socket, None)
+                .unwrap()
+                .try_into()
+                .unwrap();
+
+        // The answer is sent without waiting for a report.
+        let mut is_fin = false;
+        let answer =
+            receive_message(&mut socket, &secret_key, None, usize::MAX, &mut is_fin).unwrap();
+        match bincode::deserialize::<ReporterAnswer>(&answer).unwrap() {
+            ReporterAnswer::Report { result_code } => assert_eq!(
+                result_code,
+                ServerAnswer::RateLimited {
+                    retry_after_in_sec: 30
+                }
+            ),
+            answer => panic!("unexpected answer {:?}", answer),
+        }
+
+        assert!(server.join().unwrap().is_ok());
+    }
+}
@@ -0,0 +1,507 @@
+// Std.
+use std::io::Cursor;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// External.
+use chrono::{Local, NaiveDateTime, TimeZone};
+use serde_json::json;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+// Custom.
+use super::mutex_ext::MutexExt;
+use crate::io::config_manager::ConfigManager;
+use crate::io::log_manager::*;
+use shared::misc::db_manager::DatabaseManager;
+use shared::misc::error::AppError;
+use shared::misc::report::{get_mime_type_from_file_name, ReportSummary};
+
+/// How many requests can be processed at the same time.
+const WORKER_THREAD_COUNT: usize = 4;
+/// Amount of reports per page if not specified in the request.
+const DEFAULT_REPORTS_PER_PAGE: u64 = 20;
+const MAX_REPORTS_PER_PAGE: u64 = 100;
+
+type HttpResponse = Response<Cursor<Vec<u8>>>;
+
+/// Optional HTTP server that provides read-only access to reports
+/// (for dashboards, scripts and other tools).
+///
+/// All requests should specify the token from the config
+/// in the `Authorization: Bearer <token>` header (except for the feed of new reports,
+/// it uses a separate token in the `token` query parameter).
+pub struct RestApiService {
+    server: Arc<Server>,
+    logger: Arc<Mutex<LogManager>>,
+    database: Arc<Mutex<DatabaseManager>>,
+    server_config: Arc<ConfigManager>,
+}
+
+impl RestApiService {
+    /// Creates a new HTTP server on the REST API port from the config.
+    ///
+    /// ## Arguments
+    /// * `logger`: log manager for logging.
+    /// * `database`: database manager that handles the database.
+    /// * `server_config`: server configuration.
+    pub fn new(
+        logger: Arc<Mutex<LogManager>>,
+        database: Arc<Mutex<DatabaseManager>>,
+        server_config: Arc<ConfigManager>,
+    ) -> Result<Self, AppError> {
+        let server = Server::http(SocketAddr::new(
+            server_config.bind_address,
+            server_config.rest_api_port,
+        ));
+        if let Err(e) = server {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(Self {
+            server: Arc::new(server.unwrap()),
+            logger,
+            database,
+            server_config,
+        })
+    }
+    /// Starts processing requests (does not return).
+    pub fn process(self) {
+        self.logger.lock_recover().print_and_log(
+            LogCategory::Info,
+            &format!(
+                "ready to accept REST API requests on {}",
+                SocketAddr::new(
+                    self.server_config.bind_address,
+                    self.server_config.rest_api_port
+                )
+            ),
+        );
+
+        let service = Arc::new(self);
+
+        let mut handles = Vec::with_capacity(WORKER_THREAD_COUNT);
+        for _ in 0..WORKER_THREAD_COUNT {
+            let service = service.clone();
+            handles.push(thread::spawn(move || {
+                for request in service.server.incoming_requests() {
+                    service.handle_request(request);
+                }
+            }));
+        }
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+    /// Checks the token and sends a response to the request.
+    fn handle_request(&self, request: Request) {
+        let remote_addr = match request.remote_addr() {
+            Some(addr) => addr.to_string(),
+            None => String::from("unknown address"),
+        };
+
+        if !self.is_authorized(&request) {
+            self.logger.lock_recover().print_and_log(
+                LogCategory::Warning,
+                &format!(
+                    "rejected REST API request \"{} {}\" from {} because it does not have \
+                    a valid token",
+                    request.method(),
+                    RestApiService::get_url_without_query(request.url()),
+                    remote_addr
+                ),
+            );
+            RestApiService::respond(request, RestApiService::error_response(401, "unauthorized"));
+            return;
+        }
+
+        let response = if *request.method() != Method::Get {
+            Ok(RestApiService::error_response(405, "method not allowed"))
+        } else {
+            self.route(&request)
+        };
+
+        let response = match response {
+            Ok(response) => response,
+            Err(app_error) => {
+                self.logger.lock_recover().print_and_log(
+                    LogCategory::Error,
+                    &format!(
+                        "failed to process REST API request \"{}\" from {}: {}",
+                        RestApiService::get_url_without_query(request.url()),
+                        remote_addr,
+                        app_error
+                    ),
+                );
+                RestApiService::error_response(500, "internal server error")
+            }
+        };
+
+        RestApiService::respond(request, response);
+    }
+    /// Tells if the request has a valid `Authorization` header
+    /// (or a valid `token` query parameter for the feed).
+    fn is_authorized(&self, request: &Request) -> bool {
+        if let Some(("/feed", query)) = request.url().split_once('?') {
+            return match RestApiService::get_query_value(query, "token") {
+                Some(token) => {
+                    RestApiService::is_token_equal(token, &self.server_config.rest_api_feed_token)
+                }
+                None => false,
+            };
+        }
+
+        let header = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Authorization"));
+        let header = match header {
+            Some(header) => header.value.as_str(),
+            None => return false,
+        };
+
+        let token = match header.strip_prefix("Bearer ") {
+            Some(token) => token.trim(),
+            None => return false,
+        };
+
+        RestApiService::is_token_equal(token, &self.server_config.rest_api_token)
+    }
+    /// Compares all bytes of the tokens (not stopping on the first mismatch)
+    /// to not leak the expected token via timing.
+    fn is_token_equal(token: &str, expected: &str) -> bool {
+        let expected = expected.as_bytes();
+        let token = token.as_bytes();
+        if token.len() != expected.len() {
+            return false;
+        }
+        token
+            .iter()
+            .zip(expected)
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+    }
+    /// Returns a response to the `GET` request.
+    fn route(&self, request: &Request) -> Result<HttpResponse, AppError> {
+        let url = request.url();
+        let (path, query) = match url.split_once('?') {
+            Some((path, query)) => (path, query),
+            None => (url, ""),
+        };
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        match segments.as_slice() {
+            ["reports"] => self.get_reports(query),
+            ["feed"] => self.get_feed(query, &self.get_base_url(request)),
+            ["reports", id] => match id.parse::<u64>() {
+                Ok(id) => self.get_report(id),
+                Err(_) => Ok(RestApiService::error_response(400, "invalid report id")),
+            },
+            ["attachments", id] => match id.parse::<usize>() {
+                Ok(id) => self.get_attachment(id),
+                Err(_) => Ok(RestApiService::error_response(400, "invalid attachment id")),
+            },
+            _ => Ok(RestApiService::error_response(404, "not found")),
+        }
+    }
+    /// Handles `GET /reports?page=&amount=`.
+    ///
+    /// ## Arguments
+    /// * `query`: query part of the URL (without `?`).
+    fn get_reports(&self, query: &str) -> Result<HttpResponse, AppError> {
+        let mut page: u64 = 1;
+        let mut amount = DEFAULT_REPORTS_PER_PAGE;
+
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let parameter = match key {
+                "page" => &mut page,
+                "amount" => &mut amount,
+                _ => continue,
+            };
+            match value.parse::<u64>() {
+                Ok(value) => *parameter = value,
+                Err(_) => {
+                    return Ok(RestApiService::error_response(
+                        400,
+                        &format!("invalid value of the \"{}\" parameter", key),
+                    ))
+                }
+            }
+        }
+        let page = page.max(1);
+        let amount = amount.clamp(1, MAX_REPORTS_PER_PAGE);
+
+        let guard = self.database.lock_recover();
+        let reports = guard.get_reports(page, amount, false, "", false)?;
+        let total_reports = guard.get_report_count()?;
+        drop(guard);
+
+        let reports = reports
+            .iter()
+            .map(|report| {
+                json!({
+                    "id": report.id,
+                    "title": report.title,
+                    "game_name": report.game,
+                    "date": report.date,
+                    "time": report.time,
+                    "priority": report.priority.to_string(),
+                    "occurrence_count": report.occurrence_count,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(RestApiService::json_response(
+            200,
+            &json!({
+                "page": page,
+                "amount": amount,
+                "total_reports": total_reports,
+                "reports": reports,
+            }),
+        ))
+    }
+    /// Handles `GET /feed?token=&amount=`, responds with an Atom feed of the newest reports.
+    ///
+    /// ## Arguments
+    /// * `query`: query part of the URL (without `?`).
+    /// * `base_url`: URL of the REST API that is used in links to reports.
+    fn get_feed(&self, query: &str, base_url: &str) -> Result<HttpResponse, AppError> {
+        let amount = match RestApiService::get_query_value(query, "amount") {
+            Some(amount) => match amount.parse::<u64>() {
+                Ok(amount) => amount,
+                Err(_) => {
+                    return Ok(RestApiService::error_response(
+                        400,
+                        "invalid value of the \"amount\" parameter",
+                    ))
+                }
+            },
+            None => DEFAULT_REPORTS_PER_PAGE,
+        };
+        let amount = amount.clamp(1, MAX_REPORTS_PER_PAGE);
+
+        let reports = self.database.lock_recover().get_newest_reports(amount)?;
+
+        // The feed was updated when the newest report was received.
+        let updated = match reports.first() {
+            Some(report) => RestApiService::get_report_date_time(report),
+            None => Local::now().to_rfc3339(),
+        };
+
+        let mut feed = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+            <feed xmlns=\"http://www.w3.org/2005/Atom\">\n\
+            <title>FBugReporter reports</title>\n\
+            <id>{0}/feed</id>\n\
+            <link rel=\"self\" href=\"{0}/feed\"/>\n\
+            <author><name>FBugReporter</name></author>\n\
+            <updated>{1}</updated>\n",
+            escape_xml(base_url),
+            updated
+        );
+
+        for report in reports {
+            let report_url = escape_xml(&format!("{}/reports/{}", base_url, report.id));
+            feed += &format!(
+                "<entry>\n\
+                <title>[{}] {}</title>\n\
+                <id>{}</id>\n\
+                <link href=\"{}\"/>\n\
+                <updated>{}</updated>\n\
+                <summary>Report #{} (priority: {}, received {} time(-s)).</summary>\n\
+                </entry>\n",
+                escape_xml(&report.game),
+                escape_xml(&report.title),
+                report_url,
+                report_url,
+                RestApiService::get_report_date_time(&report),
+                report.id,
+                report.priority,
+                report.occurrence_count
+            );
+        }
+
+        feed += "</feed>\n";
+
+        Ok(
+            Response::from_data(feed.into_bytes()).with_header(RestApiService::header(
+                "Content-Type",
+                "application/atom+xml; charset=utf-8",
+            )),
+        )
+    }
+    /// Handles `GET /reports/{id}`.
+    fn get_report(&self, report_id: u64) -> Result<HttpResponse, AppError> {
+        let mut guard = self.database.lock_recover();
+        if !guard.is_report_exists(report_id)? {
+            return Ok(RestApiService::error_response(404, "report not found"));
+        }
+        let report = guard.get_report(report_id)?;
+        let comments = guard.get_report_comments(report_id)?;
+        drop(guard);
+
+        Ok(RestApiService::json_response(
+            200,
+            &json!({
+                "id": report.id,
+                "title": report.title,
+                "text": report.text,
+                "game_name": report.game_name,
+                "game_version": report.game_version,
+                "date": report.date,
+                "time": report.time,
+                "sender_name": report.sender_name,
+                "sender_email": report.sender_email,
+                "os_info": report.os_info,
+                "priority": report.priority.to_string(),
+                "metadata": report.metadata,
+                "reporter_net_protocol": report.reporter_net_protocol,
+                "reporter_version": report.reporter_version,
+                "is_markdown": report.is_markdown,
+                "session_id": report.session_id,
+                "playtime_seconds": report.playtime_seconds,
+                "attachments": report
+                    .attachments
+                    .iter()
+                    .map(|attachment| {
+                        json!({
+                            "id": attachment.id,
+                            "file_name": attachment.file_name,
+                            "size_in_bytes": attachment.size_in_bytes,
+                            "mime_type": attachment.mime_type,
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+                "comments": comments
+                    .iter()
+                    .map(|comment| {
+                        json!({
+                            "id": comment.id,
+                            "author": comment.author,
+                            "text": comment.text,
+                            "date": comment.date,
+                            "time": comment.time,
+                        })
+                    })
+                    .collect::<Vec<_>>(),
+            }),
+        ))
+    }
+    /// Handles `GET /attachments/{id}`, responds with the attachment's file.
+    fn get_attachment(&self, attachment_id: usize) -> Result<HttpResponse, AppError> {
+        let attachment = self.database.lock_recover().get_attachment(attachment_id)?;
+        let attachment = match attachment {
+            Some(attachment) => attachment,
+            None => return Ok(RestApiService::error_response(404, "attachment not found")),
+        };
+
+        // Header values can only contain ASCII (and quotes would break the header).
+        let file_name: String = attachment
+            .file_name
+            .chars()
+            .map(|c| {
+                if c.is_ascii() && !c.is_ascii_control() && c != '"' && c != '\\' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        Ok(Response::from_data(attachment.data)
+            .with_header(RestApiService::header(
+                "Content-Type",
+                get_mime_type_from_file_name(&attachment.file_name),
+            ))
+            .with_header(RestApiService::header(
+                "Content-Disposition",
+                &format!("attachment; filename=\"{}\"", file_name),
+            )))
+    }
+    /// Returns the URL that the request was sent to (without path),
+    /// uses the `Host` header if it's specified.
+    fn get_base_url(&self, request: &Request) -> String {
+        let host = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Host"))
+            .map(|header| header.value.to_string());
+
+        match host {
+            Some(host) => format!("http://{}", host),
+            None => format!(
+                "http://{}",
+                SocketAddr::new(
+                    self.server_config.bind_address,
+                    self.server_config.rest_api_port
+                )
+            ),
+        }
+    }
+    /// Returns the date and time when the report was received in RFC 3339 format
+    /// (reports store the local time of the server).
+    fn get_report_date_time(report: &ReportSummary) -> String {
+        NaiveDateTime::parse_from_str(
+            &format!("{} {}", report.date, report.time),
+            "%Y-%m-%d %H:%M:%S",
+        )
+        .ok()
+        .and_then(|date_time| Local.from_local_datetime(&date_time).earliest())
+        .unwrap_or_else(Local::now)
+        .to_rfc3339()
+    }
+    /// Returns the value of the specified parameter from the query part of the URL.
+    fn get_query_value<'a>(query: &'a str, key: &str) -> Option<&'a str> {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(pair_key, _)| *pair_key == key)
+            .map(|(_, value)| value)
+    }
+    /// Removes the query part of the URL so that tokens are not logged.
+    fn get_url_without_query(url: &str) -> &str {
+        url.split('?').next().unwrap_or(url)
+    }
+    /// Creates a response with the specified JSON.
+    fn json_response(status_code: u16, value: &serde_json::Value) -> HttpResponse {
+        Response::from_data(value.to_string().into_bytes())
+            .with_status_code(status_code)
+            .with_header(RestApiService::header("Content-Type", "application/json"))
+    }
+    /// Creates a response with a JSON that describes an error.
+    fn error_response(status_code: u16, message: &str) -> HttpResponse {
+        RestApiService::json_response(status_code, &json!({ "error": message }))
+    }
+    /// Creates an HTTP header.
+    fn header(field: &str, value: &str) -> Header {
+        // Only called with ASCII strings.
+        Header::from_bytes(field.as_bytes(), value.as_bytes()).unwrap()
+    }
+    /// Sends the response (errors are ignored because the client could just disconnect).
+    fn respond(request: Request, response: HttpResponse) {
+        let _ = request.respond(response);
+    }
+}
+
+/// Replaces characters that have a special meaning in XML.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters are not allowed in XML 1.0.
+            c if c.is_control() && c != '\n' && c != '\t' && c != '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
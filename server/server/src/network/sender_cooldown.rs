@@ -0,0 +1,103 @@
+// Std.
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Custom.
+use super::mutex_ext::MutexExt;
+
+/// Limits how often reports can be sent from the same sender email.
+///
+/// Unlike IP based limits this also works when many players share
+/// the same IP address (CGNAT, for example).
+pub struct SenderCooldown {
+    cooldown: Duration,
+    /// Pairs of "sender email" - "time of the last accepted report".
+    last_report_time: Mutex<HashMap<String, Instant>>,
+}
+
+impl SenderCooldown {
+    /// Creates a new cooldown tracker.
+    ///
+    /// ## Arguments
+    /// * `cooldown_in_sec`: minimum time between two reports from the same email,
+    ///   `0` to disable the cooldown.
+    pub fn new(cooldown_in_sec: u64) -> Self {
+        Self {
+            cooldown: Duration::from_secs(cooldown_in_sec),
+            last_report_time: Mutex::new(HashMap::new()),
+        }
+    }
+    /// Checks if the sender is allowed to send a report now and if allowed
+    /// remembers the current time as the time of the sender's last report.
+    ///
+    /// ## Arguments
+    /// * `sender_email`: email from the report (reports without email are always allowed).
+    ///
+    /// ## Return
+    /// `Ok` if the report is allowed, otherwise `Err` with time left until
+    /// the sender can send a new report.
+    pub fn register_report(&self, sender_email: &str) -> Result<(), Duration> {
+        let sender_email = sender_email.trim().to_lowercase();
+        if self.cooldown.is_zero() || sender_email.is_empty() {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let mut last_report_time = self.last_report_time.lock_recover();
+
+        if let Some(time) = last_report_time.get(&sender_email) {
+            let elapsed = now.duration_since(*time);
+            if elapsed < self.cooldown {
+                return Err(self.cooldown - elapsed);
+            }
+        }
+
+        // Forget senders that can already send new reports (to not grow indefinitely).
+        last_report_time.retain(|_, time| now.duration_since(*time) < self.cooldown);
+
+        last_report_time.insert(sender_email, now);
+
+        Ok(())
+    }
+    /// Removes the sender's last report registered by `register_report`
+    /// (for example, when the report could not be saved) so that the sender
+    /// can send the report again right away.
+    ///
+    /// ## Arguments
+    /// * `sender_email`: email from the report.
+    pub fn forget_report(&self, sender_email: &str) {
+        let sender_email = sender_email.trim().to_lowercase();
+        if self.cooldown.is_zero() || sender_email.is_empty() {
+            return;
+        }
+
+        self.last_report_time.lock_recover().remove(&sender_email);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forgotten_report_does_not_start_cooldown() {
+        let sender_cooldown = SenderCooldown::new(60);
+
+        assert!(sender_cooldown
+            .register_report("Player@example.com")
+            .is_ok());
+        assert!(sender_cooldown
+            .register_report("player@example.com")
+            .is_err());
+
+        // The report was not saved.
+        sender_cooldown.forget_report(" player@example.com");
+        assert!(sender_cooldown
+            .register_report("player@example.com")
+            .is_ok());
+        assert!(sender_cooldown
+            .register_report("player@example.com")
+            .is_err());
+    }
+}
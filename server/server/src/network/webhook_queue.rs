@@ -0,0 +1,184 @@
+// Std.
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// Custom.
+use super::mutex_ext::MutexExt;
+use crate::io::config_manager::ConfigManager;
+use crate::io::log_manager::*;
+use shared::misc::db_manager::DatabaseManager;
+use shared::misc::error::AppError;
+
+const WEBHOOK_TIMEOUT_IN_SEC: u64 = 30;
+/// How often to check for deliveries that should be retried.
+const CHECK_INTERVAL_IN_SEC: u64 = 10;
+/// Maximum time between two attempts (exponential backoff stops growing after this value).
+const MAX_RETRY_DELAY_IN_SEC: u64 = 21600; // 6 hours
+const USER_AGENT: &str = "FBugReporter";
+
+/// Sends new reports to the webhook from the config.
+///
+/// Deliveries are stored in the database so that they survive restarts,
+/// failed deliveries are retried with exponential backoff.
+pub struct WebhookQueue {
+    database: Arc<Mutex<DatabaseManager>>,
+    /// `None` if the webhook is not specified in the config.
+    url: Option<String>,
+    /// Used to wake up the delivery thread when a new delivery is added.
+    wake_sender: Option<Mutex<Sender<()>>>,
+}
+
+impl WebhookQueue {
+    /// Creates a new queue and starts a thread that sends queued deliveries
+    /// (if the webhook is specified in the config).
+    pub fn new(
+        server_config: Arc<ConfigManager>,
+        database: Arc<Mutex<DatabaseManager>>,
+        logger: Arc<Mutex<LogManager>>,
+    ) -> Self {
+        if server_config.webhook_url.is_empty() {
+            return Self {
+                database,
+                url: None,
+                wake_sender: None,
+            };
+        }
+
+        logger.lock_recover().print_and_log(
+            LogCategory::Info,
+            &format!(
+                "all new reports will be sent to the webhook \"{}\"",
+                server_config.webhook_url
+            ),
+        );
+
+        let (wake_sender, wake_receiver) = mpsc::channel();
+
+        let url = server_config.webhook_url.clone();
+        let database_clone = database.clone();
+        thread::spawn(move || loop {
+            WebhookQueue::send_due_deliveries(&server_config, &database_clone, &logger);
+
+            match wake_receiver.recv_timeout(Duration::from_secs(CHECK_INTERVAL_IN_SEC)) {
+                Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        });
+
+        Self {
+            database,
+            url: Some(url),
+            wake_sender: Some(Mutex::new(wake_sender)),
+        }
+    }
+    /// Adds a delivery to the queue (does nothing if the webhook is not used).
+    ///
+    /// ## Arguments
+    /// * `report_id`: ID of the saved report.
+    /// * `payload`: JSON to send.
+    pub fn add(&self, report_id: u64, payload: &str) -> Result<(), AppError> {
+        let url = match &self.url {
+            Some(url) => url,
+            None => return Ok(()),
+        };
+
+        self.database
+            .lock_recover()
+            .add_webhook_delivery(report_id, url, payload)?;
+
+        if let Some(wake_sender) = &self.wake_sender {
+            // The receiver only stops together with the server so ignore errors.
+            let _ = wake_sender.lock_recover().send(());
+        }
+
+        Ok(())
+    }
+    /// Sends all deliveries that should be attempted now.
+    fn send_due_deliveries(
+        server_config: &ConfigManager,
+        database: &Mutex<DatabaseManager>,
+        logger: &Mutex<LogManager>,
+    ) {
+        let deliveries = database.lock_recover().get_due_webhook_deliveries();
+        if let Err(app_error) = deliveries {
+            logger
+                .lock_recover()
+                .print_and_log(LogCategory::Error, &app_error.to_string());
+            return;
+        }
+
+        for delivery in deliveries.unwrap() {
+            // Don't lock the database while waiting for the webhook.
+            let result = ureq::post(&delivery.url)
+                .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_IN_SEC))
+                .set("User-Agent", USER_AGENT)
+                .set("Content-Type", "application/json")
+                .send_string(&delivery.payload);
+
+            let result = match result {
+                Ok(_) => {
+                    logger.lock_recover().print_and_log(
+                        LogCategory::Info,
+                        &format!(
+                            "sent the report with id {} to the webhook \"{}\"",
+                            delivery.report_id, delivery.url
+                        ),
+                    );
+                    database.lock_recover().remove_webhook_delivery(delivery.id)
+                }
+                Err(e) => {
+                    let attempt_count = delivery.attempt_count + 1;
+                    if attempt_count >= server_config.webhook_max_attempts {
+                        logger.lock_recover().print_and_log(
+                            LogCategory::Error,
+                            &format!(
+                                "failed to send the report with id {} to the webhook \"{}\", \
+                                giving up after {} attempt(-s), last error: {}",
+                                delivery.report_id, delivery.url, attempt_count, e
+                            ),
+                        );
+                        database.lock_recover().remove_webhook_delivery(delivery.id)
+                    } else {
+                        let delay_in_sec = WebhookQueue::get_retry_delay_in_sec(
+                            server_config.webhook_initial_retry_delay_in_sec,
+                            attempt_count,
+                        );
+                        logger.lock_recover().print_and_log(
+                            LogCategory::Warning,
+                            &format!(
+                                "failed to send the report with id {} to the webhook \"{}\" \
+                                (attempt {}/{}), will retry in {} second(-s), error: {}",
+                                delivery.report_id,
+                                delivery.url,
+                                attempt_count,
+                                server_config.webhook_max_attempts,
+                                delay_in_sec,
+                                e
+                            ),
+                        );
+                        database.lock_recover().postpone_webhook_delivery(
+                            delivery.id,
+                            attempt_count,
+                            delay_in_sec,
+                        )
+                    }
+                }
+            };
+            if let Err(app_error) = result {
+                logger
+                    .lock_recover()
+                    .print_and_log(LogCategory::Error, &app_error.to_string());
+            }
+        }
+    }
+    /// Returns time to wait after the specified number of failed attempts
+    /// (doubles after each attempt).
+    fn get_retry_delay_in_sec(initial_delay_in_sec: u64, attempt_count: u32) -> u64 {
+        let multiplier = 2u64.saturating_pow(attempt_count.saturating_sub(1));
+        initial_delay_in_sec
+            .saturating_mul(multiplier)
+            .min(MAX_RETRY_DELAY_IN_SEC)
+    }
+}
@@ -0,0 +1,158 @@
+// Std.
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+// Custom.
+use super::mutex_ext::MutexExt;
+use shared::misc::error::AppError;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Fixed amount of worker threads that process jobs (connections).
+///
+/// Unlike spawning a thread per connection this limits the amount
+/// of connections that are processed at the same time.
+pub struct WorkerPool {
+    sender: Sender<Job>,
+    busy_worker_count: Arc<AtomicUsize>,
+    worker_count: usize,
+}
+
+impl WorkerPool {
+    /// Creates a new pool and spawns worker threads.
+    ///
+    /// ## Arguments
+    /// * `name`: name of the pool (used in names of worker threads).
+    /// * `worker_count`: number of worker threads (maximum amount of jobs processed at the same time).
+    pub fn new(name: &str, worker_count: usize) -> Result<Self, AppError> {
+        if worker_count == 0 {
+            return Err(AppError::new("worker count should not be zero"));
+        }
+
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let busy_worker_count = Arc::new(AtomicUsize::new(0));
+
+        for i in 0..worker_count {
+            let receiver_clone = receiver.clone();
+            let busy_worker_count_clone = busy_worker_count.clone();
+
+            let result = thread::Builder::new()
+                .name(format!("{} worker #{}", name, i))
+                .spawn(move || WorkerPool::run_worker(receiver_clone, busy_worker_count_clone));
+            if let Err(e) = result {
+                return Err(AppError::new(&e.to_string()));
+            }
+        }
+
+        Ok(Self {
+            sender,
+            busy_worker_count,
+            worker_count,
+        })
+    }
+    /// Passes the job to an idle worker.
+    ///
+    /// ## Arguments
+    /// * `input`: value that will be passed to the job (for example, a socket).
+    /// * `job`: function to execute.
+    ///
+    /// ## Return
+    /// `Err` with the specified input if all workers are busy
+    /// (in this case the job is dropped without being executed).
+    pub fn execute<T, F>(&self, input: T, job: F) -> Result<(), T>
+    where
+        T: Send + 'static,
+        F: FnOnce(T) + Send + 'static,
+    {
+        // Reserve a worker.
+        let reserved =
+            self.busy_worker_count
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |busy| {
+                    if busy < self.worker_count {
+                        Some(busy + 1)
+                    } else {
+                        None
+                    }
+                });
+        if reserved.is_err() {
+            return Err(input);
+        }
+
+        // Keep the input if the job can't be sent.
+        let input = Arc::new(Mutex::new(Some(input)));
+        let input_clone = input.clone();
+        let result = self.sender.send(Box::new(move || {
+            let input = input_clone.lock_recover().take();
+            if let Some(input) = input {
+                job(input);
+            }
+        }));
+        if result.is_err() {
+            // All workers are gone, should not happen.
+            self.busy_worker_count.fetch_sub(1, Ordering::SeqCst);
+            return Err(input.lock_recover().take().unwrap());
+        }
+
+        Ok(())
+    }
+    /// Returns the maximum amount of jobs that can be processed at the same time.
+    pub fn get_worker_count(&self) -> usize {
+        self.worker_count
+    }
+    /// Waits for new jobs and executes them.
+    fn run_worker(receiver: Arc<Mutex<Receiver<Job>>>, busy_worker_count: Arc<AtomicUsize>) {
+        loop {
+            let job = receiver.lock_recover().recv();
+            if job.is_err() {
+                // Pool was dropped.
+                return;
+            }
+
+            // Don't lose this worker if the job panics.
+            let _ = panic::catch_unwind(AssertUnwindSafe(job.unwrap()));
+
+            busy_worker_count.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execute_returns_input_when_all_workers_are_busy() {
+        let pool = WorkerPool::new("test", 1).unwrap();
+
+        // Occupy the only worker.
+        let (release_sender, release_receiver) = mpsc::channel::<()>();
+        let (started_sender, started_receiver) = mpsc::channel::<()>();
+        assert!(pool
+            .execute(release_receiver, move |release_receiver| {
+                started_sender.send(()).unwrap();
+                release_receiver.recv().unwrap();
+            })
+            .is_ok());
+        started_receiver.recv().unwrap();
+
+        // The input should be given back instead of being dropped.
+        assert_eq!(pool.execute(42, |_| {}), Err(42));
+
+        // Free the worker and wait for it to be available again.
+        release_sender.send(()).unwrap();
+        let (done_sender, done_receiver) = mpsc::channel::<i32>();
+        loop {
+            let done_sender = done_sender.clone();
+            let result = pool.execute(42, move |input| done_sender.send(input).unwrap());
+            if result.is_ok() {
+                break;
+            }
+            thread::yield_now();
+        }
+        assert_eq!(done_receiver.recv().unwrap(), 42);
+    }
+}
@@ -49,6 +49,7 @@ fn main() {
     loop {
         let mut process = match Command::new(path.clone())
             .args(["--start", "--under-monitor"])
+            .args(env::args().skip(1)) // pass options like `--port` to the server
             .spawn()
         {
             Ok(process) => process,
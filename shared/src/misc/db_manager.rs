@@ -1,17 +1,28 @@
 // Std.
 use core::panic;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
 use std::{fs::create_dir_all, path::PathBuf};
 
 // External.
-use chrono::prelude::*;
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{prelude::*, Duration};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use platform_dirs::AppDirs;
 use rand::Rng;
-use rusqlite::{params, Connection, Result};
+use rusqlite::{blob::Blob, params, Connection, DatabaseName, OptionalExtension, Result, ToSql};
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
 use sha2::{Digest, Sha512};
+use strum_macros::{Display, EnumString};
 use sysinfo::{DiskExt, System, SystemExt};
 
 // Custom.
 use super::report::*;
+use super::user::UserRole;
 use crate::misc::error::AppError;
 
 pub const DATABASE_DIR: &str = "FBugReporter";
@@ -20,32 +31,99 @@ pub const DATABASE_NAME: &str = "database.db3";
 const REPORT_TABLE_NAME: &str = "report";
 const USER_TABLE_NAME: &str = "user";
 const ATTACHMENT_TABLE_NAME: &str = "attachment";
+const OTP_BACKUP_CODE_TABLE_NAME: &str = "otp_backup_code";
+const AUDIT_LOG_TABLE_NAME: &str = "audit_log";
+const REPORT_READ_TABLE_NAME: &str = "report_read";
+const REPORT_COMMENT_TABLE_NAME: &str = "report_comment";
+const WEBHOOK_DELIVERY_TABLE_NAME: &str = "webhook_delivery";
+const REPORT_METADATA_TABLE_NAME: &str = "report_metadata";
+const SESSION_TOKEN_TABLE_NAME: &str = "session_token";
+const FAILED_LOGIN_TABLE_NAME: &str = "failed_login";
 const VERSION_TABLE_NAME: &str = "version";
 
 const REPORT_TABLE_HASH: &[u8] = &[
-    158, 21, 8, 202, 214, 61, 94, 63, 56, 50, 126, 200, 244, 198, 125, 37, 0, 153, 116, 141, 15,
-    11, 48, 160, 50, 110, 165, 128, 140, 85, 196, 95, 193, 74, 107, 154, 149, 99, 110, 89, 146,
-    124, 113, 35, 202, 214, 246, 205, 201, 243, 112, 11, 110, 0, 72, 42, 46, 178, 157, 141, 234,
-    148, 214, 28,
+    88, 183, 183, 174, 144, 204, 31, 98, 6, 173, 146, 138, 58, 63, 202, 153, 80, 45, 172, 102, 107,
+    85, 124, 66, 212, 246, 64, 207, 30, 141, 16, 94, 254, 172, 123, 133, 235, 98, 68, 17, 100, 15,
+    156, 85, 116, 56, 183, 23, 179, 122, 5, 20, 205, 187, 46, 17, 48, 162, 162, 37, 22, 218, 128,
+    147,
 ];
 const USER_TABLE_HASH: &[u8] = &[
-    179, 199, 233, 204, 132, 161, 204, 15, 152, 12, 233, 72, 42, 79, 252, 183, 189, 251, 215, 202,
-    231, 239, 91, 23, 60, 246, 24, 163, 210, 30, 170, 135, 202, 217, 94, 240, 145, 21, 214, 49, 30,
-    222, 23, 219, 226, 1, 250, 93, 145, 60, 222, 228, 75, 190, 7, 226, 183, 74, 167, 19, 26, 142,
-    161, 64,
+    102, 177, 215, 176, 32, 0, 11, 44, 253, 253, 91, 77, 149, 78, 189, 172, 106, 124, 102, 255,
+    129, 101, 135, 221, 126, 150, 51, 157, 37, 35, 158, 55, 47, 135, 95, 3, 169, 244, 206, 13, 179,
+    10, 230, 155, 152, 172, 23, 133, 250, 10, 160, 5, 188, 79, 153, 87, 236, 56, 197, 143, 98, 169,
+    139, 157,
 ];
 const ATTACHMENT_TABLE_HASH: &[u8] = &[
-    93, 157, 12, 163, 145, 37, 18, 255, 0, 224, 239, 160, 43, 13, 61, 12, 238, 220, 239, 125, 195,
-    173, 133, 252, 189, 49, 48, 195, 211, 138, 4, 122, 22, 114, 178, 251, 159, 196, 114, 234, 5,
-    206, 193, 119, 119, 140, 106, 6, 44, 56, 184, 114, 190, 215, 142, 70, 38, 27, 98, 115, 88, 138,
-    125, 139,
+    50, 130, 177, 216, 214, 152, 79, 249, 96, 14, 220, 168, 123, 226, 221, 155, 1, 216, 121, 250,
+    202, 3, 192, 79, 187, 164, 138, 146, 234, 120, 24, 123, 177, 19, 106, 214, 185, 82, 23, 154,
+    137, 107, 86, 56, 65, 163, 239, 37, 200, 238, 210, 53, 12, 227, 97, 43, 205, 174, 156, 206, 62,
+    41, 156, 21,
 ];
 
-const SUPPORTED_DATABASE_VERSION: u64 = 1;
+const OTP_BACKUP_CODE_TABLE_HASH: &[u8] = &[
+    140, 251, 222, 46, 93, 179, 192, 0, 96, 3, 187, 175, 219, 81, 159, 239, 35, 63, 245, 21, 169,
+    118, 212, 152, 188, 132, 61, 69, 171, 189, 158, 202, 30, 33, 163, 34, 107, 55, 112, 181, 242,
+    202, 245, 229, 9, 224, 213, 3, 205, 145, 237, 214, 185, 31, 199, 108, 242, 84, 27, 179, 233,
+    64, 235, 115,
+];
+const AUDIT_LOG_TABLE_HASH: &[u8] = &[
+    121, 35, 246, 251, 69, 73, 159, 79, 189, 188, 116, 2, 139, 251, 77, 61, 93, 125, 88, 145, 37,
+    165, 62, 14, 181, 48, 89, 216, 50, 92, 139, 250, 19, 18, 119, 210, 183, 113, 233, 32, 73, 126,
+    240, 228, 115, 179, 221, 177, 204, 175, 220, 11, 242, 196, 109, 172, 28, 84, 156, 243, 82, 249,
+    251, 32,
+];
+const REPORT_READ_TABLE_HASH: &[u8] = &[
+    226, 94, 108, 178, 157, 6, 79, 136, 148, 167, 51, 89, 117, 243, 4, 11, 79, 116, 47, 183, 109,
+    113, 169, 72, 225, 2, 21, 169, 201, 0, 68, 236, 188, 115, 249, 73, 137, 91, 141, 145, 146, 157,
+    83, 187, 166, 96, 227, 125, 197, 70, 23, 30, 191, 217, 220, 145, 204, 250, 114, 154, 83, 140,
+    92, 110,
+];
+const REPORT_COMMENT_TABLE_HASH: &[u8] = &[
+    73, 27, 6, 145, 101, 66, 205, 170, 215, 210, 156, 214, 228, 153, 212, 5, 132, 56, 244, 124,
+    235, 125, 29, 102, 233, 103, 94, 207, 34, 66, 65, 17, 37, 207, 219, 0, 207, 72, 56, 34, 183,
+    89, 6, 3, 62, 10, 176, 135, 100, 167, 249, 124, 195, 142, 199, 215, 195, 221, 246, 42, 124,
+    168, 224, 141,
+];
+const WEBHOOK_DELIVERY_TABLE_HASH: &[u8] = &[
+    185, 188, 254, 18, 0, 198, 213, 9, 150, 248, 25, 8, 250, 32, 70, 51, 75, 31, 72, 31, 147, 236,
+    101, 226, 54, 240, 158, 68, 193, 18, 207, 15, 238, 8, 186, 200, 57, 190, 86, 219, 232, 242,
+    209, 189, 180, 244, 144, 2, 110, 57, 158, 194, 246, 251, 41, 64, 7, 236, 113, 168, 3, 252, 10,
+    150,
+];
+const REPORT_METADATA_TABLE_HASH: &[u8] = &[
+    205, 100, 130, 230, 175, 49, 255, 63, 20, 34, 237, 177, 10, 108, 29, 37, 4, 116, 215, 80, 13,
+    157, 209, 72, 149, 81, 88, 59, 7, 169, 218, 12, 196, 39, 149, 88, 149, 233, 172, 54, 215, 70,
+    135, 106, 89, 241, 63, 110, 48, 87, 220, 247, 238, 10, 237, 33, 123, 212, 248, 246, 201, 191,
+    81, 123,
+];
+const SESSION_TOKEN_TABLE_HASH: &[u8] = &[
+    121, 50, 118, 50, 174, 156, 255, 228, 102, 178, 195, 233, 204, 144, 137, 108, 131, 211, 234,
+    46, 23, 4, 131, 57, 35, 186, 16, 170, 31, 104, 198, 51, 63, 14, 223, 115, 41, 243, 224, 166,
+    134, 121, 192, 61, 115, 211, 11, 8, 238, 160, 33, 13, 97, 204, 181, 248, 71, 253, 40, 111, 168,
+    40, 189, 106,
+];
+const FAILED_LOGIN_TABLE_HASH: &[u8] = &[
+    244, 172, 40, 229, 93, 46, 121, 130, 218, 79, 161, 141, 123, 233, 227, 174, 237, 136, 23, 143,
+    151, 61, 237, 162, 46, 196, 130, 23, 233, 118, 162, 52, 80, 230, 61, 223, 200, 222, 241, 176,
+    64, 21, 22, 208, 109, 79, 63, 73, 228, 38, 16, 182, 30, 205, 121, 66, 53, 160, 233, 78, 45, 42,
+    255, 192,
+];
+const SUPPORTED_DATABASE_VERSION: u64 = 14;
+
+/// Maximum width and height of attachment thumbnails (in pixels).
+const ATTACHMENT_THUMBNAIL_SIZE: u32 = 128;
 
 const SALT_LENGTH: u64 = 32;
 const OTP_SECRET_LENGTH: u64 = 256;
 const PASSWORD_LENGTH: u64 = 32;
+const OTP_BACKUP_CODE_COUNT: usize = 10;
+const OTP_BACKUP_CODE_LENGTH: usize = 10;
+const SESSION_TOKEN_LENGTH_IN_BYTES: usize = 32;
+/// Oldest failed login attempts are removed once there are more records.
+const MAX_FAILED_LOGIN_RECORD_COUNT: u64 = 10000;
+
+// Used for generating OTP backup codes (without similar looking characters).
+const OTP_BACKUP_CODE_CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
 
 // Used for generating random salt, password, otp and etc.
 const CHARSET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
@@ -56,6 +134,111 @@ pub const USERNAME_CHARSET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ\
                         abcdefghijklmnopqrstuvwxyz\
                         0123456789.";
 
+/// Hash algorithm used to generate TOTP codes.
+#[derive(Debug, Clone, Copy, PartialEq, Default, EnumString, Display)]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum OtpAlgorithm {
+    #[default]
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+/// Parameters used to generate TOTP codes of a user.
+///
+/// Stored per-user when the user receives the OTP QR code so that
+/// changing server defaults does not break already configured authenticator apps.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtpParameters {
+    pub algorithm: OtpAlgorithm,
+    pub digits: usize,
+    pub period_in_sec: u64,
+}
+
+impl Default for OtpParameters {
+    fn default() -> Self {
+        Self {
+            algorithm: OtpAlgorithm::default(),
+            digits: 6,
+            period_in_sec: 30,
+        }
+    }
+}
+
+/// Sensitive actions that are recorded in the audit log.
+#[derive(Debug, Clone, Copy, PartialEq, EnumString, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum AuditAction {
+    AddUser,
+    RemoveUser,
+    SetUserRole,
+    SetFirstPassword,
+    RegenerateOtpBackupCodes,
+    UseOtpBackupCode,
+    ResetOtp,
+    DeleteReport,
+    SetReportPriority,
+    ExpireAttachments,
+}
+
+/// Entry of the audit log.
+pub struct AuditLogEntry {
+    pub id: u64,
+    pub actor: String,
+    pub action: String,
+    pub target: String,
+    pub date_created_at: String,
+    pub time_created_at: String,
+}
+
+/// Why a login attempt failed.
+#[derive(Debug, Clone, Copy, PartialEq, EnumString, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum FailedLoginReason {
+    UnknownUser,
+    WrongPassword,
+    WrongOtp,
+}
+
+/// Recorded failed login attempt.
+pub struct FailedLoginEntry {
+    pub id: u64,
+    /// Username that was used to login.
+    pub username: String,
+    pub ip: String,
+    pub reason: String,
+    pub date_created_at: String,
+    pub time_created_at: String,
+}
+
+/// Report that is waiting to be sent to a webhook.
+pub struct WebhookDelivery {
+    pub id: u64,
+    pub report_id: u64,
+    pub url: String,
+    /// Data to send.
+    pub payload: String,
+    /// Number of failed attempts to send the payload.
+    pub attempt_count: u32,
+}
+
+/// Incremental reader over attachment data stored in the database.
+///
+/// Reads data directly from the database file so that
+/// the whole attachment is not loaded into the memory.
+pub struct AttachmentReader<'conn> {
+    pub file_name: String,
+    /// Size of the attachment data (after decompression).
+    pub size_in_bytes: usize,
+    reader: Box<dyn Read + 'conn>,
+}
+
+impl Read for AttachmentReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reader.read(buf)
+    }
+}
+
 pub enum AddUserResult {
     Ok { user_password: String },
     NameIsUsed,
@@ -63,6 +246,97 @@ pub enum AddUserResult {
     Error(AppError),
 }
 
+/// Result of `save_report`.
+pub enum SaveReportResult {
+    /// The report was added as a new report.
+    Saved {
+        report_id: u64,
+        /// IDs of the oldest reports that were removed to not exceed the maximum report count.
+        removed_report_ids: Vec<u64>,
+    },
+    /// A report with the same content already exists, instead of adding a new
+    /// report its occurrence count was incremented (attachments are not saved).
+    Merged {
+        report_id: u64,
+        occurrence_count: u64,
+    },
+}
+
+/// Report as it's stored in the JSON file created by `export_all_reports`.
+#[derive(Serialize, Deserialize)]
+struct ExportedReport {
+    report_name: String,
+    report_text: String,
+    sender_name: String,
+    sender_email: String,
+    game_name: String,
+    game_version: String,
+    os_info: String,
+    date_created_at: String,
+    time_created_at: String,
+    #[serde(default)]
+    priority: ReportPriority,
+    #[serde(default = "default_occurrence_count")]
+    occurrence_count: u64,
+    attachments: Vec<ExportedAttachment>,
+    #[serde(default)]
+    metadata: BTreeMap<String, String>,
+    #[serde(default)]
+    reporter_net_protocol: u16,
+    #[serde(default)]
+    reporter_version: String,
+    #[serde(default)]
+    is_markdown: bool,
+    #[serde(default)]
+    session_id: String,
+    #[serde(default)]
+    playtime_seconds: u64,
+}
+
+/// Used for reports that were exported before occurrence count was added.
+fn default_occurrence_count() -> u64 {
+    1
+}
+
+/// Attachment as it's stored in the JSON file created by `export_all_reports`.
+#[derive(Serialize, Deserialize)]
+struct ExportedAttachment {
+    file_name: String,
+    mime_type: String,
+    data: String, // base64
+}
+
+/// Inserts reports into the database while they are being deserialized
+/// so that we don't need to keep all reports in the memory.
+struct ExportedReportVisitor<'a> {
+    connection: &'a Connection,
+}
+
+impl<'de, 'a> Visitor<'de> for ExportedReportVisitor<'a> {
+    type Value = u64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of reports")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut imported_count: u64 = 0;
+
+        while let Some(report) = seq.next_element::<ExportedReport>()? {
+            if let Err(app_error) = DatabaseManager::insert_exported_report(self.connection, report)
+            {
+                return Err(serde::de::Error::custom(app_error.get_message()));
+            }
+            imported_count += 1;
+        }
+
+        Ok(imported_count)
+    }
+}
+
 pub struct DatabaseManager {
     connection: Connection,
     database_disk_mount_point: String,
@@ -88,6 +362,9 @@ impl DatabaseManager {
         // Enable foreign keys.
         Self::enable_foreign_keys(&mut connection)?;
 
+        // Enable WAL mode.
+        Self::enable_wal_mode(&mut connection)?;
+
         // Check 'version' table.
         Self::create_version_table_if_not_found(&mut connection)?;
 
@@ -100,9 +377,36 @@ impl DatabaseManager {
         // Check 'attachment' table.
         Self::create_attachment_table_if_not_found(&mut connection)?;
 
+        // Check 'otp_backup_code' table.
+        Self::create_otp_backup_code_table_if_not_found(&mut connection)?;
+
+        // Check 'audit_log' table.
+        Self::create_audit_log_table_if_not_found(&mut connection)?;
+
+        // Check 'report_read' table.
+        Self::create_report_read_table_if_not_found(&mut connection)?;
+
+        // Check 'report_comment' table.
+        Self::create_report_comment_table_if_not_found(&mut connection)?;
+
+        // Check 'webhook_delivery' table.
+        Self::create_webhook_delivery_table_if_not_found(&mut connection)?;
+
+        // Check 'report_metadata' table.
+        Self::create_report_metadata_table_if_not_found(&mut connection)?;
+
+        // Check 'session_token' table.
+        Self::create_session_token_table_if_not_found(&mut connection)?;
+
+        // Check 'failed_login' table.
+        Self::create_failed_login_table_if_not_found(&mut connection)?;
+
         // Handle old database version.
         Self::handle_old_database_version(&mut connection)?;
 
+        // Check indexes.
+        Self::create_indexes_if_not_found(&mut connection)?;
+
         Ok(Self {
             connection,
             database_disk_mount_point: Self::determine_database_disk_mount_point(),
@@ -186,35 +490,175 @@ impl DatabaseManager {
             None => Err(AppError::new("database returned none")),
         }
     }
+    /// Returns the number of reports per game and per day.
+    ///
+    /// ## Arguments
+    /// * `day_count`: number of last days (including today) to return counts for.
+    ///
+    /// ## Remarks
+    /// Reports that were received multiple times (see `occurrence_count`) are counted once
+    /// on the day they were received for the first time.
+    #[tracing::instrument(skip(self))]
+    pub fn get_report_stats(&self, day_count: u64) -> Result<ReportStats, AppError> {
+        let today = Local::now().date_naive();
+        let first_day = today - Duration::days(day_count.saturating_sub(1) as i64);
+
+        let per_game = Self::query_report_counts(
+            &self.connection,
+            &format!(
+                "SELECT game_name, COUNT(id) FROM {} GROUP BY game_name \
+                 ORDER BY COUNT(id) DESC, game_name",
+                REPORT_TABLE_NAME
+            ),
+            params![],
+        )?;
+
+        let counts: BTreeMap<String, u64> = Self::query_report_counts(
+            &self.connection,
+            &format!(
+                "SELECT date_created_at, COUNT(id) FROM {} WHERE date_created_at >= ?1 \
+                 GROUP BY date_created_at",
+                REPORT_TABLE_NAME
+            ),
+            params![first_day.to_string()],
+        )?
+        .into_iter()
+        .collect();
+
+        // Also include days without reports.
+        let per_day = first_day
+            .iter_days()
+            .take_while(|day| *day <= today)
+            .map(|day| {
+                let day = day.to_string();
+                let count = counts.get(&day).copied().unwrap_or(0);
+                (day, count)
+            })
+            .collect();
+
+        Ok(ReportStats { per_game, per_day })
+    }
+    /// Returns the amount of reports that the specified user did not read yet.
+    pub fn get_unread_report_count(&self, username: &str) -> Result<u64, AppError> {
+        let result = self.connection.query_row(
+            &format!(
+                "SELECT count(id) FROM {} WHERE {}",
+                REPORT_TABLE_NAME,
+                Self::get_unread_report_condition()
+            ),
+            params![username],
+            |row| row.get(0),
+        );
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(result.unwrap())
+    }
+    /// Remembers that the specified user has read the specified report.
+    ///
+    /// ## Remarks
+    /// Does nothing if the report does not exist (for example, was just removed).
+    pub fn mark_report_as_read(&self, username: &str, report_id: u64) -> Result<(), AppError> {
+        if let Err(e) = self.connection.execute(
+            &format!(
+                "INSERT OR IGNORE INTO {} (fk_user_id, fk_report_id) \
+                SELECT {}.id, {}.id FROM {}, {} \
+                WHERE {}.username = ?1 AND {}.id = ?2",
+                REPORT_READ_TABLE_NAME,
+                USER_TABLE_NAME,
+                REPORT_TABLE_NAME,
+                USER_TABLE_NAME,
+                REPORT_TABLE_NAME,
+                USER_TABLE_NAME,
+                REPORT_TABLE_NAME
+            ),
+            params![username, report_id],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
     /// Returns summary of reports from the database.
     ///
     /// ## Arguments:
     /// * `page`: a "page" to query reports from
     /// * `amount`: amount of reports to query
+    /// * `sort_by_priority`: whether to sort reports by priority (highest first) or by ID
+    /// * `username`: user that requested the reports (used to determine unread reports)
+    /// * `unread_only`: whether to only return reports that the user did not read yet
     ///
     /// In the database reports exist as a "list"
     /// to implement "paging" in client application we use 2 values:
     /// `page` and `amount` when querying reports. To query reports
     /// we calculate starting id as `(page - 1) * amount` and select
     /// `amount` rows starting from this starting id.
-    pub fn get_reports(&self, mut page: u64, amount: u64) -> Result<Vec<ReportSummary>, AppError> {
+    #[tracing::instrument(skip(self, username))]
+    pub fn get_reports(
+        &self,
+        mut page: u64,
+        amount: u64,
+        sort_by_priority: bool,
+        username: &str,
+        unread_only: bool,
+    ) -> Result<Vec<ReportSummary>, AppError> {
         if page == 0 {
             page = 1;
         }
 
         let start_row: u64 = (page - 1) * amount;
 
+        let order_by = if sort_by_priority {
+            "priority DESC, id"
+        } else {
+            "id"
+        };
+
+        let unread_condition = Self::get_unread_report_condition();
+        let filter = if unread_only {
+            format!("WHERE {}", unread_condition)
+        } else {
+            String::new()
+        };
+
+        self.query_report_summaries(
+            &format!(
+                "{} ORDER BY {} LIMIT {} OFFSET {}",
+                filter, order_by, amount, start_row
+            ),
+            username,
+        )
+    }
+    /// Returns the most recently received reports (newest first).
+    ///
+    /// ## Arguments:
+    /// * `amount`: maximum amount of reports to return
+    pub fn get_newest_reports(&self, amount: u64) -> Result<Vec<ReportSummary>, AppError> {
+        self.query_report_summaries(&format!("ORDER BY id DESC LIMIT {}", amount), "")
+    }
+    /// Returns summaries of reports selected by the specified SQL clauses.
+    ///
+    /// ## Arguments:
+    /// * `clauses`: SQL clauses after `FROM report` (`WHERE`, `ORDER BY`, `LIMIT`)
+    /// * `username`: user that requested the reports (used to determine unread reports)
+    fn query_report_summaries(
+        &self,
+        clauses: &str,
+        username: &str,
+    ) -> Result<Vec<ReportSummary>, AppError> {
+        let unread_condition = Self::get_unread_report_condition();
+
         let mut stmt = self
             .connection
             .prepare(&format!(
-                "SELECT id, report_name, game_name, date_created_at, time_created_at \
-                 FROM {} \
-                 ORDER BY id LIMIT {} \
-                 OFFSET {}",
-                REPORT_TABLE_NAME, amount, start_row
+                "SELECT id, report_name, game_name, date_created_at, time_created_at, priority, \
+                 occurrence_count, {} \
+                 FROM {} {}",
+                unread_condition, REPORT_TABLE_NAME, clauses
             ))
             .unwrap();
-        let result = stmt.query([]);
+        let result = stmt.query(params![username]);
         if let Err(e) = result {
             return Err(AppError::new(&e.to_string()));
         }
@@ -270,12 +714,36 @@ impl DatabaseManager {
             }
             let time: String = time.unwrap();
 
+            // Get report priority.
+            let priority = row.get(5);
+            if let Err(e) = priority {
+                return Err(AppError::new(&e.to_string()));
+            }
+            let priority = Self::priority_from_database_value(priority.unwrap())?;
+
+            // Get report occurrence count.
+            let occurrence_count = row.get(6);
+            if let Err(e) = occurrence_count {
+                return Err(AppError::new(&e.to_string()));
+            }
+            let occurrence_count: u64 = occurrence_count.unwrap();
+
+            // Get read state.
+            let is_unread = row.get(7);
+            if let Err(e) = is_unread {
+                return Err(AppError::new(&e.to_string()));
+            }
+            let is_unread: bool = is_unread.unwrap();
+
             reports.push(ReportSummary {
                 id,
                 title,
                 game,
                 date,
                 time,
+                priority,
+                occurrence_count,
+                is_unread,
             })
         }
     }
@@ -284,57 +752,107 @@ impl DatabaseManager {
     /// ## Return
     /// `Err(AppError)` if something went wrong, otherwise
     /// `Ok(Some(ReportAttachment))` if attachment is found,
-    /// `Ok(None)` if attachment is not found.
+    /// `Ok(None)` if attachment is not found (or expired).
     pub fn get_attachment(
         &mut self,
         attachment_id: usize,
     ) -> Result<Option<ReportAttachment>, AppError> {
-        let mut stmt = self
-            .connection
-            .prepare(&format!(
-                "SELECT file_name, data \
-                 FROM {} \
-                 WHERE id == {}",
-                ATTACHMENT_TABLE_NAME, attachment_id
-            ))
-            .unwrap();
-        let result = stmt.query([]);
-        if let Err(e) = result {
-            return Err(AppError::new(&e.to_string()));
-        }
-
-        let mut rows = result.unwrap();
-
-        let row = rows.next();
-        if let Err(e) = row {
-            return Err(AppError::new(&e.to_string()));
-        }
-        let row = row.unwrap();
-        if row.is_none() {
+        let reader = self.open_attachment(attachment_id)?;
+        if reader.is_none() {
             return Ok(None); // not found
         }
+        let mut reader = reader.unwrap();
 
-        let row = row.unwrap();
-
-        // Get file name.
-        let file_name = row.get(0);
-        if let Err(e) = file_name {
+        let mut data: Vec<u8> = Vec::with_capacity(reader.size_in_bytes);
+        if let Err(e) = reader.read_to_end(&mut data) {
             return Err(AppError::new(&e.to_string()));
         }
-        let file_name: String = file_name.unwrap();
 
-        // Get data.
-        let data = row.get(1);
-        if let Err(e) = data {
+        Ok(Some(ReportAttachment {
+            file_name: reader.file_name,
+            data,
+        }))
+    }
+    /// Opens the attachment by ID for incremental reading.
+    ///
+    /// Unlike `get_attachment` the attachment data is not loaded into the memory
+    /// but instead read from the database as the returned reader is used
+    /// (compressed attachments are decompressed on the fly).
+    ///
+    /// ## Return
+    /// `Err(AppError)` if something went wrong, otherwise
+    /// `Ok(Some(AttachmentReader))` if attachment is found,
+    /// `Ok(None)` if attachment is not found (or expired).
+    #[tracing::instrument(skip(self))]
+    pub fn open_attachment(
+        &self,
+        attachment_id: usize,
+    ) -> Result<Option<AttachmentReader<'_>>, AppError> {
+        let result = self.connection.query_row(
+            &format!(
+                "SELECT file_name, size_in_bytes, is_compressed \
+                 FROM {} \
+                 WHERE id == ?1 AND is_expired == 0",
+                ATTACHMENT_TABLE_NAME
+            ),
+            params![attachment_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, usize>(1)?,
+                    row.get::<_, bool>(2)?,
+                ))
+            },
+        );
+        let (file_name, size_in_bytes, is_compressed) = match result {
+            Ok(values) => values,
+            Err(rusqlite::Error::QueryReturnedNoRows) => return Ok(None), // not found
+            Err(e) => return Err(AppError::new(&e.to_string())),
+        };
+
+        let blob: Blob<'_> = match self.connection.blob_open(
+            DatabaseName::Main,
+            ATTACHMENT_TABLE_NAME,
+            "data",
+            attachment_id as i64,
+            true,
+        ) {
+            Ok(blob) => blob,
+            Err(e) => return Err(AppError::new(&e.to_string())),
+        };
+
+        let reader: Box<dyn Read + '_> = if is_compressed {
+            Box::new(GzDecoder::new(blob))
+        } else {
+            Box::new(blob)
+        };
+
+        Ok(Some(AttachmentReader {
+            file_name,
+            size_in_bytes,
+            reader,
+        }))
+    }
+    /// Tells if a report with the specified ID exists in the database.
+    pub fn is_report_exists(&self, report_id: u64) -> Result<bool, AppError> {
+        let result = self.connection.query_row(
+            &format!(
+                "SELECT EXISTS(SELECT 1 FROM {} WHERE id == ?1)",
+                REPORT_TABLE_NAME
+            ),
+            params![report_id],
+            |row| row.get::<_, bool>(0),
+        );
+        if let Err(e) = result {
             return Err(AppError::new(&e.to_string()));
         }
-        let data: Vec<u8> = data.unwrap();
 
-        Ok(Some(ReportAttachment { file_name, data }))
+        Ok(result.unwrap())
     }
     /// Returns a report with the specified ID from the database.
     ///
     /// Returns error if a report with the specified ID does not exist.
+    #[tracing::instrument(skip(self))]
     pub fn get_report(&mut self, report_id: u64) -> Result<ReportData, AppError> {
         let transaction = self.connection.transaction();
         if let Err(e) = transaction {
@@ -345,7 +863,9 @@ impl DatabaseManager {
         let mut stmt = transaction
             .prepare(&format!(
                 "SELECT id, report_name, report_text, sender_name, sender_email, \
-                game_name, game_version, os_info, date_created_at, time_created_at \
+                game_name, game_version, os_info, date_created_at, time_created_at, priority, \
+                reporter_net_protocol, reporter_version, is_markdown, session_id, \
+                playtime_seconds \
                 FROM {} WHERE id == {}",
                 REPORT_TABLE_NAME, report_id
             ))
@@ -375,7 +895,14 @@ impl DatabaseManager {
                 sender_name: String::new(),
                 sender_email: String::new(),
                 os_info: String::new(),
+                priority: ReportPriority::default(),
                 attachments: Vec::new(),
+                metadata: BTreeMap::new(),
+                reporter_net_protocol: 0,
+                reporter_version: String::new(),
+                is_markdown: false,
+                session_id: String::new(),
+                playtime_seconds: 0,
             });
         }
 
@@ -451,13 +978,55 @@ impl DatabaseManager {
         }
         let time: String = time.unwrap();
 
+        // Get report priority.
+        let priority = row.get(10);
+        if let Err(e) = priority {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let priority = Self::priority_from_database_value(priority.unwrap())?;
+
+        // Get reporter network protocol.
+        let reporter_net_protocol = row.get(11);
+        if let Err(e) = reporter_net_protocol {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let reporter_net_protocol: u16 = reporter_net_protocol.unwrap();
+
+        // Get reporter version.
+        let reporter_version = row.get(12);
+        if let Err(e) = reporter_version {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let reporter_version: String = reporter_version.unwrap();
+
+        // Get text format.
+        let is_markdown = row.get(13);
+        if let Err(e) = is_markdown {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let is_markdown: bool = is_markdown.unwrap();
+
+        // Get game session ID.
+        let session_id = row.get(14);
+        if let Err(e) = session_id {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let session_id: String = session_id.unwrap();
+
+        // Get playtime.
+        let playtime_seconds = row.get(15);
+        if let Err(e) = playtime_seconds {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let playtime_seconds: u64 = playtime_seconds.unwrap();
+
         drop(rows);
         drop(stmt);
 
         // Query attachments.
         let mut stmt = transaction
             .prepare(&format!(
-                "SELECT id, file_name, size_in_bytes \
+                "SELECT id, file_name, size_in_bytes, mime_type, thumbnail, is_expired \
                  FROM {} \
                  WHERE fk_report_id == {}",
                 ATTACHMENT_TABLE_NAME, report_id
@@ -504,16 +1073,42 @@ impl DatabaseManager {
             }
             let size_in_bytes: usize = size_in_bytes.unwrap();
 
+            // Get MIME type.
+            let mime_type = row.get(3);
+            if let Err(e) = mime_type {
+                return Err(AppError::new(&e.to_string()));
+            }
+            let mime_type: String = mime_type.unwrap();
+
+            // Get thumbnail.
+            let thumbnail = row.get(4);
+            if let Err(e) = thumbnail {
+                return Err(AppError::new(&e.to_string()));
+            }
+            let thumbnail: Option<Vec<u8>> = thumbnail.unwrap();
+
+            // Get expired state.
+            let is_expired = row.get(5);
+            if let Err(e) = is_expired {
+                return Err(AppError::new(&e.to_string()));
+            }
+            let is_expired: bool = is_expired.unwrap();
+
             attachments.push(ReportAttachmentSummary {
                 id: attachment_id,
                 file_name,
                 size_in_bytes,
+                mime_type,
+                thumbnail,
+                is_expired,
             })
         }
 
         drop(rows);
         drop(stmt);
 
+        let metadata = Self::query_report_metadata(&transaction, report_id)?;
+
         // Commit transaction.
         if let Err(e) = transaction.commit() {
             return Err(AppError::new(&e.to_string()));
@@ -530,16 +1125,23 @@ impl DatabaseManager {
             sender_name,
             sender_email,
             os_info,
+            priority,
             attachments,
+            metadata,
+            reporter_net_protocol,
+            reporter_version,
+            is_markdown,
+            session_id,
+            playtime_seconds,
         })
     }
     /// Adds a new user to the database.
     ///
     /// ## Arguments:
     /// * `username` login of the new user
-    /// * `is_admin` whether the user should have admin privileges or not
-    /// (be able to delete reports using the client application).
-    pub fn add_user(&self, username: &str, is_admin: bool) -> AddUserResult {
+    /// * `role` what the user can do using the client application.
+    /// * `email` email of the user (empty if not specified).
+    pub fn add_user(&self, username: &str, role: UserRole, email: &str) -> AddUserResult {
         // Check if username contains forbidden characters.
         let is_ok = username
             .chars()
@@ -595,6 +1197,9 @@ impl DatabaseManager {
             })
             .collect();
 
+        // Actual OTP parameters are set once the user receives the OTP QR code.
+        let otp_parameters = OtpParameters::default();
+
         if let Err(e) = self.connection.execute(
             // password = hash(salt + hash(password))
             &format!(
@@ -606,15 +1211,21 @@ impl DatabaseManager {
                 need_change_password,
                 need_setup_otp,
                 otp_secret_key,
-                is_admin,
                 last_login_date,
                 last_login_time,
                 last_login_ip,
                 date_registered,
-                time_registered
+                time_registered,
+                otp_algorithm,
+                otp_digits,
+                otp_period,
+                role,
+                password_changed_at,
+                email,
+                email_verified
             ) 
             VALUES 
-            (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
                 USER_TABLE_NAME
             ),
             params![
@@ -624,12 +1235,18 @@ impl DatabaseManager {
                 1, // change password
                 1, // have not received OTP QR code
                 otp_secret,
-                is_admin,
                 datetime.date_naive().to_string(),
                 datetime.time().format("%H:%M:%S").to_string(),
                 "",
                 datetime.date_naive().to_string(),
-                datetime.time().format("%H:%M:%S").to_string()
+                datetime.time().format("%H:%M:%S").to_string(),
+                otp_parameters.algorithm.to_string(),
+                otp_parameters.digits,
+                otp_parameters.period_in_sec,
+                role.value(),
+                datetime.timestamp(),
+                email,
+                0 // not verified
             ],
         ) {
             return AddUserResult::Error(AppError::new(&e.to_string()));
@@ -673,6 +1290,7 @@ impl DatabaseManager {
     /// Returns `Ok(true)` if the report was found and removed,
     /// `Ok(false)` if the report was not found.
     /// On failure returns error description via `AppError`.
+    #[tracing::instrument(skip(self))]
     pub fn remove_report(&self, report_id: u64) -> Result<bool, AppError> {
         // Remove report.
         if let Err(e) = self.connection.execute(
@@ -688,60 +1306,237 @@ impl DatabaseManager {
 
         Ok(true)
     }
-    /// Get password and salt of a user.
+    /// Removes data (and thumbnails) of attachments of reports that are older than
+    /// the specified number of days, reports and names of their attachments are kept.
     ///
-    /// If the user is not found returned `Ok` values will be empty.
-    pub fn get_user_password_and_salt(
-        &self,
-        username: &str,
-    ) -> Result<(Vec<u8>, String), AppError> {
-        let mut stmt = self
-            .connection
-            .prepare(&format!(
-                "SELECT password, salt FROM {} WHERE username='{}'",
-                USER_TABLE_NAME, username
-            ))
-            .unwrap();
-        let result = stmt.query([]);
-        if let Err(e) = result {
+    /// ## Remarks
+    /// Expired attachments are shown in reports but can't be downloaded
+    /// (`open_attachment` does not find them). Use `optimize` to shrink the database file.
+    ///
+    /// ## Return
+    /// Number of expired attachments and the number of bytes they occupied in the database.
+    pub fn expire_old_attachments(&mut self, older_than_days: u64) -> Result<(u64, u64), AppError> {
+        let oldest_date_to_keep = (Local::now() - Duration::days(older_than_days as i64))
+            .date_naive()
+            .to_string();
+
+        let transaction = self.connection.transaction();
+        if let Err(e) = transaction {
             return Err(AppError::new(&e.to_string()));
         }
+        let transaction = transaction.unwrap();
 
-        let mut rows = result.unwrap();
+        let condition = format!(
+            "is_expired == 0 AND fk_report_id IN (SELECT id FROM {} WHERE date_created_at < ?1)",
+            REPORT_TABLE_NAME
+        );
 
-        let row = rows.next();
-        if let Err(e) = row {
+        let result: Result<u64> = transaction.query_row(
+            &format!(
+                "SELECT IFNULL(SUM(LENGTH(data) + IFNULL(LENGTH(thumbnail), 0)), 0) \
+                 FROM {} WHERE {}",
+                ATTACHMENT_TABLE_NAME, condition
+            ),
+            params![oldest_date_to_keep],
+            |row| row.get(0),
+        );
+        if let Err(e) = result {
             return Err(AppError::new(&e.to_string()));
         }
-        let row = row.unwrap();
-        if row.is_none() {
-            return Ok((Vec::new(), String::from("")));
-        }
-        let row = row.unwrap();
+        let freed_bytes = result.unwrap();
 
-        // Get password.
-        let password: Result<Vec<u8>> = row.get(0);
-        if let Err(e) = password {
+        let result = transaction.execute(
+            &format!(
+                "UPDATE {} SET data = ZEROBLOB(0), thumbnail = NULL, is_compressed = 0, \
+                 is_expired = 1 WHERE {}",
+                ATTACHMENT_TABLE_NAME, condition
+            ),
+            params![oldest_date_to_keep],
+        );
+        if let Err(e) = result {
             return Err(AppError::new(&e.to_string()));
         }
-        let password = password.unwrap();
+        let expired_count = result.unwrap() as u64;
 
-        // Get salt.
-        let salt: Result<String> = row.get(1);
-        if let Err(e) = salt {
+        if let Err(e) = transaction.commit() {
             return Err(AppError::new(&e.to_string()));
         }
-        let salt = salt.unwrap();
 
-        Ok((password, salt))
+        Ok((expired_count, freed_bytes))
     }
-    /// Updates the following user's values in the database:
-    /// - last_login_date
-    /// - last_login_time
-    /// - last_login_ip
-    pub fn update_user_last_login(&self, username: &str, ip: &str) -> Result<(), AppError> {
-        let datetime = Local::now();
-
+    /// Rebuilds the database file so that it no longer contains free space
+    /// (for example, after reports were removed or attachments were expired)
+    /// and updates statistics that are used to run queries faster.
+    ///
+    /// ## Remarks
+    /// Fails if the database is being used by another connection at the moment.
+    /// Requires free disk space of about the size of the database.
+    ///
+    /// ## Return
+    /// Size of the database files (in bytes, see `get_database_files_size`) before and after
+    /// the optimization.
+    pub fn optimize(&self) -> Result<(u64, u64), AppError> {
+        let size_before = Self::get_database_files_size();
+
+        if let Err(e) = self
+            .connection
+            .execute_batch("VACUUM; PRAGMA optimize; PRAGMA wal_checkpoint(TRUNCATE);")
+        {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok((size_before, Self::get_database_files_size()))
+    }
+    /// Changes priority of a report.
+    ///
+    /// Returns `Ok(true)` if the report was found and changed,
+    /// `Ok(false)` if the report was not found.
+    /// On failure returns error description via `AppError`.
+    #[tracing::instrument(skip(self, priority))]
+    pub fn set_report_priority(
+        &self,
+        report_id: u64,
+        priority: ReportPriority,
+    ) -> Result<bool, AppError> {
+        let result = self.connection.execute(
+            &format!(
+                "UPDATE {} SET priority = ?1 WHERE id = ?2",
+                REPORT_TABLE_NAME
+            ),
+            params![priority.value(), report_id],
+        );
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(result.unwrap() != 0)
+    }
+    /// Adds a comment to a report.
+    ///
+    /// ## Arguments
+    /// * `report_id`: ID of the report to comment.
+    /// * `author`: username of the user that wrote the comment.
+    /// * `text`: text of the comment.
+    ///
+    /// ## Return
+    /// `Ok(true)` if the comment was added, `Ok(false)` if the report was not found.
+    #[tracing::instrument(skip(self, author, text))]
+    pub fn add_report_comment(
+        &self,
+        report_id: u64,
+        author: &str,
+        text: &str,
+    ) -> Result<bool, AppError> {
+        let datetime = Local::now();
+
+        let result = self.connection.execute(
+            &format!(
+                "INSERT INTO {} (author, text, date_created_at, time_created_at, fk_report_id) \
+                SELECT ?1, ?2, ?3, ?4, id FROM {} WHERE id = ?5",
+                REPORT_COMMENT_TABLE_NAME, REPORT_TABLE_NAME
+            ),
+            params![
+                author,
+                text,
+                datetime.date_naive().to_string(),
+                datetime.time().format("%H:%M:%S").to_string(),
+                report_id
+            ],
+        );
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(result.unwrap() != 0)
+    }
+    /// Returns comments of a report (oldest first).
+    #[tracing::instrument(skip(self))]
+    pub fn get_report_comments(&self, report_id: u64) -> Result<Vec<ReportComment>, AppError> {
+        let stmt = self.connection.prepare(&format!(
+            "SELECT id, author, text, date_created_at, time_created_at \
+            FROM {} WHERE fk_report_id = ?1 ORDER BY id",
+            REPORT_COMMENT_TABLE_NAME
+        ));
+        if let Err(e) = stmt {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let mut stmt = stmt.unwrap();
+
+        let comments = stmt.query_map(params![report_id], |row| {
+            Ok(ReportComment {
+                id: row.get(0)?,
+                author: row.get(1)?,
+                text: row.get(2)?,
+                date: row.get(3)?,
+                time: row.get(4)?,
+            })
+        });
+        if let Err(e) = comments {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let comments: Result<Vec<ReportComment>> = comments.unwrap().collect();
+        if let Err(e) = comments {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(comments.unwrap())
+    }
+    /// Get password and salt of a user.
+    ///
+    /// If the user is not found returned `Ok` values will be empty.
+    #[tracing::instrument(skip_all)]
+    pub fn get_user_password_and_salt(
+        &self,
+        username: &str,
+    ) -> Result<(Vec<u8>, String), AppError> {
+        let mut stmt = self
+            .connection
+            .prepare(&format!(
+                "SELECT password, salt FROM {} WHERE username='{}'",
+                USER_TABLE_NAME, username
+            ))
+            .unwrap();
+        let result = stmt.query([]);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let mut rows = result.unwrap();
+
+        let row = rows.next();
+        if let Err(e) = row {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let row = row.unwrap();
+        if row.is_none() {
+            return Ok((Vec::new(), String::from("")));
+        }
+        let row = row.unwrap();
+
+        // Get password.
+        let password: Result<Vec<u8>> = row.get(0);
+        if let Err(e) = password {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let password = password.unwrap();
+
+        // Get salt.
+        let salt: Result<String> = row.get(1);
+        if let Err(e) = salt {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let salt = salt.unwrap();
+
+        Ok((password, salt))
+    }
+    /// Updates the following user's values in the database:
+    /// - last_login_date
+    /// - last_login_time
+    /// - last_login_ip
+    pub fn update_user_last_login(&self, username: &str, ip: &str) -> Result<(), AppError> {
+        let datetime = Local::now();
+
         if let Err(e) = self.connection.execute(
             &format!(
                 "UPDATE {} 
@@ -765,14 +1560,90 @@ impl DatabaseManager {
         Ok(())
     }
     /// Saves a new report to the database.
+    ///
+    /// The report and all of its attachments are inserted in a single transaction
+    /// so if something goes wrong nothing will be saved.
+    ///
+    /// ## Remarks
+    /// If a report from the same game with the same name and text (see `get_report_content_hash`)
+    /// already exists, the new report is not inserted, instead the occurrence count
+    /// and the last seen date of the existing report are updated.
+    ///
+    /// If `max_report_count` is not zero and a new report would exceed it,
+    /// the oldest reports are removed before inserting the new one.
+    ///
+    /// `reporter_net_protocol` is stored to find reports sent by a specific reporter
+    /// (merged duplicates keep the values of the first report).
+    #[tracing::instrument(skip_all, fields(game = %game_report.game_name, attachments = attachments.len()))]
     pub fn save_report(
         &self,
         game_report: GameReport,
         attachments: Vec<ReportAttachment>,
-    ) -> Result<(), AppError> {
-        // Insert report into the database.
+        max_report_count: u64,
+        reporter_net_protocol: u16,
+    ) -> Result<SaveReportResult, AppError> {
+        let transaction = self.connection.unchecked_transaction();
+        if let Err(e) = transaction {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let transaction = transaction.unwrap();
+
+        let content_hash =
+            Self::get_report_content_hash(&game_report.report_name, &game_report.report_text);
         let datetime = Local::now();
-        let result: Result<u64> = self.connection.query_row(
+        let date = datetime.date_naive().to_string();
+        let time = datetime.time().format("%H:%M:%S").to_string();
+
+        // Look for the same report.
+        let result: Result<Option<(u64, u64)>> = transaction
+            .query_row(
+                &format!(
+                    "SELECT id, occurrence_count FROM {} \
+                    WHERE content_hash = ?1 AND game_name = ?2 \
+                    ORDER BY id LIMIT 1",
+                    REPORT_TABLE_NAME
+                ),
+                params![content_hash, game_report.game_name],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional();
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        if let Some((report_id, occurrence_count)) = result.unwrap() {
+            // Update the existing report.
+            let occurrence_count = occurrence_count + 1;
+            if let Err(e) = transaction.execute(
+                &format!(
+                    "UPDATE {} SET occurrence_count = ?1, last_seen_date = ?2, last_seen_time = ?3 \
+                    WHERE id = ?4",
+                    REPORT_TABLE_NAME
+                ),
+                params![occurrence_count, date, time, report_id],
+            ) {
+                return Err(AppError::new(&e.to_string()));
+            }
+
+            // Commit transaction.
+            if let Err(e) = transaction.commit() {
+                return Err(AppError::new(&e.to_string()));
+            }
+
+            return Ok(SaveReportResult::Merged {
+                report_id,
+                occurrence_count,
+            });
+        }
+
+        // Make space for the new report.
+        let mut removed_report_ids = Vec::new();
+        if max_report_count != 0 {
+            removed_report_ids = Self::remove_oldest_reports(&transaction, max_report_count - 1)?;
+        }
+
+        // Insert report into the database.
+        let result: Result<u64> = transaction.query_row(
             &format!(
                 "INSERT INTO {} 
             (
@@ -784,10 +1655,19 @@ impl DatabaseManager {
                 game_version, 
                 os_info, 
                 date_created_at, 
-                time_created_at
+                time_created_at,
+                priority,
+                content_hash,
+                last_seen_date,
+                last_seen_time,
+                reporter_net_protocol,
+                reporter_version,
+                is_markdown,
+                session_id,
+                playtime_seconds
             ) 
             VALUES 
-            (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9) 
+            (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?8, ?9, ?12, ?13, ?14, ?15, ?16) 
             RETURNING id",
                 REPORT_TABLE_NAME
             ),
@@ -799,8 +1679,15 @@ impl DatabaseManager {
                 game_report.game_name,
                 game_report.game_version,
                 game_report.client_os_info.to_string(),
-                datetime.date_naive().to_string(),
-                datetime.time().format("%H:%M:%S").to_string(),
+                date,
+                time,
+                game_report.priority.value(),
+                content_hash,
+                reporter_net_protocol,
+                game_report.reporter_version,
+                game_report.is_markdown,
+                game_report.session_id,
+                game_report.playtime_seconds,
             ],
             |row| row.get(0),
         );
@@ -812,159 +1699,412 @@ impl DatabaseManager {
 
         // Insert report attachments into the database.
         for attachment in attachments {
-            let data_size_in_bytes = attachment.data.len();
-            let result = self.connection.execute(
-                &format!(
-                    "INSERT INTO {} 
-                    (
-                        file_name,
-                        data, 
-                        size_in_bytes,
-                        fk_report_id
-                    ) 
-                    VALUES 
-                    (?1, ?2, ?3, ?4)",
-                    ATTACHMENT_TABLE_NAME
-                ),
-                params![
-                    attachment.file_name,
-                    attachment.data,
-                    data_size_in_bytes,
-                    report_id
-                ],
-            );
-            if let Err(e) = result {
-                return Err(AppError::new(&e.to_string()));
-            }
-        }
-
-        Ok(())
-    }
-    /// Check if a given user needs to change the password.
-    ///
-    /// Returns `Ok(true)` if need to change the password, `Ok(false)` if not.
-    /// On failure returns `AppError`.
-    pub fn is_user_needs_to_change_password(&self, username: &str) -> Result<bool, AppError> {
-        let mut stmt = self
-            .connection
-            .prepare(&format!(
-                "SELECT need_change_password FROM {} WHERE username='{}'",
-                USER_TABLE_NAME, username
-            ))
-            .unwrap();
-        let result = stmt.query([]);
-        if let Err(e) = result {
-            return Err(AppError::new(&e.to_string()));
+            Self::insert_attachment(
+                &transaction,
+                report_id,
+                &attachment.file_name,
+                &attachment.data,
+            )?;
         }
 
-        let mut rows = result.unwrap();
+        Self::insert_report_metadata(&transaction, report_id, &game_report.metadata)?;
 
-        let row = rows.next();
-        if let Err(e) = row {
-            return Err(AppError::new(&e.to_string()));
-        }
-        let row = row.unwrap();
-        if row.is_none() {
-            return Err(AppError::new(&format!(
-                "database returned None for username {}",
-                username
-            )));
-        }
-        let row = row.unwrap();
-        let need_change_password = row.get(0);
-        if let Err(e) = need_change_password {
+        // Commit transaction.
+        if let Err(e) = transaction.commit() {
             return Err(AppError::new(&e.to_string()));
         }
-        let need_change_password: i32 = need_change_password.unwrap();
 
-        if need_change_password == 1 {
-            Ok(true)
-        } else if need_change_password == 0 {
-            Ok(false)
-        } else {
-            Err(AppError::new(&format!(
-                "database returned 'need_change_password' equal to '{}' for user {}",
-                need_change_password, username
-            )))
-        }
+        Ok(SaveReportResult::Saved {
+            report_id,
+            removed_report_ids,
+        })
     }
-    /// Check if a given user has admin privileges.
+    /// Writes all reports (with attachments) from the database to a JSON file.
     ///
-    /// Returns `Ok(true)` if yes, `Ok(false)` if no.
-    /// On failure returns `AppError`.
-    pub fn is_user_admin(&self, username: &str) -> Result<bool, AppError> {
-        let mut stmt = self
-            .connection
-            .prepare(&format!(
-                "SELECT is_admin FROM {} WHERE username='{}'",
-                USER_TABLE_NAME, username
-            ))
-            .unwrap();
-        let result = stmt.query([]);
-        if let Err(e) = result {
-            return Err(AppError::new(&e.to_string()));
-        }
-
-        let mut rows = result.unwrap();
-
-        let row = rows.next();
-        if let Err(e) = row {
-            return Err(AppError::new(&e.to_string()));
-        }
-        let row = row.unwrap();
-        if row.is_none() {
-            return Err(AppError::new(&format!(
-                "database returned None for username {}",
-                username
-            )));
-        }
-        let row = row.unwrap();
-        let is_admin = row.get(0);
-        if let Err(e) = is_admin {
+    /// ## Arguments
+    /// * `path`: path to the JSON file to create (will be overwritten if exists).
+    ///
+    /// ## Return
+    /// The number of exported reports.
+    ///
+    /// ## Remarks
+    /// Reports are written one by one so that the whole database
+    /// is never loaded into the memory. Attachments are base64-encoded.
+    pub fn export_all_reports(&self, path: &Path) -> Result<u64, AppError> {
+        let file = File::create(path);
+        if let Err(e) = file {
             return Err(AppError::new(&e.to_string()));
         }
-        let is_admin: i32 = is_admin.unwrap();
+        let mut writer = BufWriter::new(file.unwrap());
 
-        if is_admin == 1 {
-            Ok(true)
-        } else if is_admin == 0 {
-            Ok(false)
-        } else {
-            Err(AppError::new(&format!(
-                "database returned 'is_admin' equal to '{}' for user {}",
-                is_admin, username
-            )))
-        }
-    }
-    /// Check if a given user needs to setup OTP (receive OTP QR code).
-    ///
-    /// Returns `Ok(true)` if need OTP QR code, `Ok(false)` if not.
-    /// On failure returns `AppError`.
-    pub fn is_user_needs_setup_otp(&self, username: &str) -> Result<bool, AppError> {
         let mut stmt = self
             .connection
             .prepare(&format!(
-                "SELECT need_setup_otp FROM {} WHERE username='{}'",
-                USER_TABLE_NAME, username
+                "SELECT id, report_name, report_text, sender_name, sender_email, \
+                game_name, game_version, os_info, date_created_at, time_created_at, priority, \
+                occurrence_count, reporter_net_protocol, reporter_version, is_markdown, \
+                session_id, playtime_seconds \
+                FROM {} ORDER BY id",
+                REPORT_TABLE_NAME
             ))
             .unwrap();
         let result = stmt.query([]);
         if let Err(e) = result {
             return Err(AppError::new(&e.to_string()));
         }
-
         let mut rows = result.unwrap();
 
-        let row = rows.next();
-        if let Err(e) = row {
+        if let Err(e) = writer.write_all(b"[") {
             return Err(AppError::new(&e.to_string()));
         }
-        let row = row.unwrap();
-        if row.is_none() {
-            return Err(AppError::new(&format!(
-                "database returned None for username {}",
-                username
-            )));
+
+        let mut exported_count: u64 = 0;
+        loop {
+            let row = rows.next();
+            if let Err(e) = row {
+                return Err(AppError::new(&e.to_string()));
+            }
+            let row = row.unwrap();
+            if row.is_none() {
+                break;
+            }
+            let row = row.unwrap();
+
+            // Get report id.
+            let report_id = row.get(0);
+            if let Err(e) = report_id {
+                return Err(AppError::new(&e.to_string()));
+            }
+            let report_id: u64 = report_id.unwrap();
+
+            // Get report fields.
+            let mut fields: Vec<String> = Vec::new();
+            for i in 1..10 {
+                let field = row.get(i);
+                if let Err(e) = field {
+                    return Err(AppError::new(&e.to_string()));
+                }
+                fields.push(field.unwrap());
+            }
+            let mut fields = fields.into_iter();
+
+            // Get report priority.
+            let priority = row.get(10);
+            if let Err(e) = priority {
+                return Err(AppError::new(&e.to_string()));
+            }
+            let priority = Self::priority_from_database_value(priority.unwrap())?;
+
+            // Get report occurrence count.
+            let occurrence_count = row.get(11);
+            if let Err(e) = occurrence_count {
+                return Err(AppError::new(&e.to_string()));
+            }
+
+            // Get reporter versions.
+            let reporter_net_protocol = row.get(12);
+            if let Err(e) = reporter_net_protocol {
+                return Err(AppError::new(&e.to_string()));
+            }
+            let reporter_version = row.get(13);
+            if let Err(e) = reporter_version {
+                return Err(AppError::new(&e.to_string()));
+            }
+
+            // Get text format.
+            let is_markdown = row.get(14);
+            if let Err(e) = is_markdown {
+                return Err(AppError::new(&e.to_string()));
+            }
+
+            // Get game session info.
+            let session_id = row.get(15);
+            if let Err(e) = session_id {
+                return Err(AppError::new(&e.to_string()));
+            }
+            let playtime_seconds = row.get(16);
+            if let Err(e) = playtime_seconds {
+                return Err(AppError::new(&e.to_string()));
+            }
+
+            let report = ExportedReport {
+                report_name: fields.next().unwrap(),
+                report_text: fields.next().unwrap(),
+                sender_name: fields.next().unwrap(),
+                sender_email: fields.next().unwrap(),
+                game_name: fields.next().unwrap(),
+                game_version: fields.next().unwrap(),
+                os_info: fields.next().unwrap(),
+                date_created_at: fields.next().unwrap(),
+                time_created_at: fields.next().unwrap(),
+                priority,
+                occurrence_count: occurrence_count.unwrap(),
+                attachments: self.get_exported_attachments(report_id)?,
+                metadata: Self::query_report_metadata(&self.connection, report_id)?,
+                reporter_net_protocol: reporter_net_protocol.unwrap(),
+                reporter_version: reporter_version.unwrap(),
+                is_markdown: is_markdown.unwrap(),
+                session_id: session_id.unwrap(),
+                playtime_seconds: playtime_seconds.unwrap(),
+            };
+
+            if exported_count != 0 {
+                if let Err(e) = writer.write_all(b",") {
+                    return Err(AppError::new(&e.to_string()));
+                }
+            }
+            if let Err(e) = serde_json::to_writer(&mut writer, &report) {
+                return Err(AppError::new(&e.to_string()));
+            }
+
+            exported_count += 1;
+        }
+
+        if let Err(e) = writer.write_all(b"]") {
+            return Err(AppError::new(&e.to_string()));
+        }
+        if let Err(e) = writer.flush() {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(exported_count)
+    }
+    /// Reads reports from a JSON file (created by `export_all_reports`)
+    /// and adds them to the database.
+    ///
+    /// ## Arguments
+    /// * `path`: path to the JSON file to import.
+    ///
+    /// ## Return
+    /// The number of imported reports.
+    ///
+    /// ## Remarks
+    /// Reports are read and inserted one by one, if an error occurs
+    /// no reports will be added (everything is done in a single transaction).
+    /// Imported reports will receive new IDs.
+    pub fn import_reports(&mut self, path: &Path) -> Result<u64, AppError> {
+        let file = File::open(path);
+        if let Err(e) = file {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let reader = BufReader::new(file.unwrap());
+
+        let transaction = self.connection.transaction();
+        if let Err(e) = transaction {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let transaction = transaction.unwrap();
+
+        let mut deserializer = serde_json::Deserializer::from_reader(reader);
+        let result = deserializer.deserialize_seq(ExportedReportVisitor {
+            connection: &transaction,
+        });
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let imported_count = result.unwrap();
+
+        // Commit transaction.
+        if let Err(e) = transaction.commit() {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(imported_count)
+    }
+    /// Creates a consistent copy of the database using SQLite's online backup API,
+    /// can be safely used while the server is running.
+    ///
+    /// ## Arguments
+    /// * `path`: path to the backup file (will be overwritten if exists).
+    ///
+    /// ## Return
+    /// Size of the resulting backup file in bytes.
+    pub fn backup(&self, path: &Path) -> Result<u64, AppError> {
+        if let Err(e) = self.connection.backup(DatabaseName::Main, path, None) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let metadata = std::fs::metadata(path);
+        if let Err(e) = metadata {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(metadata.unwrap().len())
+    }
+    /// Check if a given user needs to change the password.
+    ///
+    /// Returns `Ok(true)` if need to change the password, `Ok(false)` if not.
+    /// On failure returns `AppError`.
+    pub fn is_user_needs_to_change_password(&self, username: &str) -> Result<bool, AppError> {
+        let mut stmt = self
+            .connection
+            .prepare(&format!(
+                "SELECT need_change_password FROM {} WHERE username='{}'",
+                USER_TABLE_NAME, username
+            ))
+            .unwrap();
+        let result = stmt.query([]);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let mut rows = result.unwrap();
+
+        let row = rows.next();
+        if let Err(e) = row {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let row = row.unwrap();
+        if row.is_none() {
+            return Err(AppError::new(&format!(
+                "database returned None for username {}",
+                username
+            )));
+        }
+        let row = row.unwrap();
+        let need_change_password = row.get(0);
+        if let Err(e) = need_change_password {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let need_change_password: i32 = need_change_password.unwrap();
+
+        if need_change_password == 1 {
+            Ok(true)
+        } else if need_change_password == 0 {
+            Ok(false)
+        } else {
+            Err(AppError::new(&format!(
+                "database returned 'need_change_password' equal to '{}' for user {}",
+                need_change_password, username
+            )))
+        }
+    }
+    /// Requires the user to change the password (on the next login)
+    /// if the password was changed too long ago.
+    ///
+    /// ## Arguments
+    /// * `username`: user to check.
+    /// * `max_password_age_in_days`: maximum allowed age of the password.
+    ///
+    /// ## Return
+    /// `true` if the password has expired and now needs to be changed.
+    pub fn require_password_change_if_expired(
+        &self,
+        username: &str,
+        max_password_age_in_days: u64,
+    ) -> Result<bool, AppError> {
+        let min_password_changed_at =
+            Local::now().timestamp() - (max_password_age_in_days * 24 * 60 * 60) as i64;
+
+        let result = self.connection.execute(
+            &format!(
+                "UPDATE {} SET need_change_password = 1 \
+                WHERE username = ?1 AND password_changed_at < ?2",
+                USER_TABLE_NAME
+            ),
+            params![username, min_password_changed_at],
+        );
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(result.unwrap() > 0)
+    }
+    /// Returns role of the user.
+    pub fn get_user_role(&self, username: &str) -> Result<UserRole, AppError> {
+        let result = self.connection.query_row(
+            &format!("SELECT role FROM {} WHERE username = ?1", USER_TABLE_NAME),
+            params![username],
+            |row| row.get::<usize, i32>(0),
+        );
+        if let Err(e) = result {
+            return Err(AppError::new(&format!(
+                "failed to get role for username {} (error: {})",
+                username, e
+            )));
+        }
+        let role = result.unwrap();
+
+        match UserRole::from_value(role) {
+            Some(role) => Ok(role),
+            None => Err(AppError::new(&format!(
+                "database returned 'role' equal to '{}' for user {}",
+                role, username
+            ))),
+        }
+    }
+    /// Returns email of the user and whether it was verified or not.
+    ///
+    /// ## Remarks
+    /// The email is considered verified once the user sets a new password (since the
+    /// initial password is sent to this email).
+    ///
+    /// ## Return
+    /// `None` if the user was not found, otherwise email (empty if not specified)
+    /// and `true` if it was verified.
+    pub fn get_user_email(&self, username: &str) -> Result<Option<(String, bool)>, AppError> {
+        let result = self.connection.query_row(
+            &format!(
+                "SELECT email, email_verified FROM {} WHERE username = ?1",
+                USER_TABLE_NAME
+            ),
+            params![username],
+            |row| Ok((row.get::<usize, String>(0)?, row.get::<usize, bool>(1)?)),
+        );
+
+        match result {
+            Ok(email) => Ok(Some(email)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(AppError::new(&e.to_string())),
+        }
+    }
+    /// Changes role of the user.
+    ///
+    /// ## Return
+    /// `false` if the user was not found.
+    pub fn set_user_role(&self, username: &str, role: UserRole) -> Result<bool, AppError> {
+        let result = self.connection.execute(
+            &format!(
+                "UPDATE {} SET role = ?1 WHERE username = ?2",
+                USER_TABLE_NAME
+            ),
+            params![role.value(), username],
+        );
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(result.unwrap() > 0)
+    }
+    /// Check if a given user needs to setup OTP (receive OTP QR code).
+    ///
+    /// Returns `Ok(true)` if need OTP QR code, `Ok(false)` if not.
+    /// On failure returns `AppError`.
+    pub fn is_user_needs_setup_otp(&self, username: &str) -> Result<bool, AppError> {
+        let mut stmt = self
+            .connection
+            .prepare(&format!(
+                "SELECT need_setup_otp FROM {} WHERE username='{}'",
+                USER_TABLE_NAME, username
+            ))
+            .unwrap();
+        let result = stmt.query([]);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let mut rows = result.unwrap();
+
+        let row = rows.next();
+        if let Err(e) = row {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let row = row.unwrap();
+        if row.is_none() {
+            return Err(AppError::new(&format!(
+                "database returned None for username {}",
+                username
+            )));
         }
         let row = row.unwrap();
         let need_setup_otp = row.get(0);
@@ -984,13 +2124,1569 @@ impl DatabaseManager {
             )))
         }
     }
-    /// Returns OTP secret key.
-    pub fn get_otp_secret_key_for_user(&self, username: &str) -> Result<String, AppError> {
-        let mut stmt = self
-            .connection
+    /// Returns OTP secret key.
+    pub fn get_otp_secret_key_for_user(&self, username: &str) -> Result<String, AppError> {
+        let mut stmt = self
+            .connection
+            .prepare(&format!(
+                "SELECT otp_secret_key FROM {} WHERE username='{}'",
+                USER_TABLE_NAME, username
+            ))
+            .unwrap();
+        let result = stmt.query([]);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let mut rows = result.unwrap();
+
+        let row = rows.next();
+        if let Err(e) = row {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let row = row.unwrap();
+        if row.is_none() {
+            return Err(AppError::new(&format!(
+                "database returned None for username {}",
+                username
+            )));
+        }
+        let row = row.unwrap();
+        let otp_secret = row.get::<usize, String>(0);
+        if let Err(e) = otp_secret {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let otp_secret = otp_secret.unwrap();
+
+        Ok(otp_secret)
+    }
+    /// Returns parameters that should be used to generate OTP codes for the user.
+    pub fn get_otp_parameters_for_user(&self, username: &str) -> Result<OtpParameters, AppError> {
+        let result = self.connection.query_row(
+            &format!(
+                "SELECT otp_algorithm, otp_digits, otp_period FROM {} WHERE username = ?1",
+                USER_TABLE_NAME
+            ),
+            params![username],
+            |row| {
+                Ok((
+                    row.get::<usize, String>(0)?,
+                    row.get::<usize, usize>(1)?,
+                    row.get::<usize, u64>(2)?,
+                ))
+            },
+        );
+        if let Err(e) = result {
+            return Err(AppError::new(&format!(
+                "failed to get OTP parameters for username {} (error: {})",
+                username, e
+            )));
+        }
+        let (algorithm, digits, period_in_sec) = result.unwrap();
+
+        let algorithm = algorithm.parse::<OtpAlgorithm>();
+        if let Err(e) = algorithm {
+            return Err(AppError::new(&format!(
+                "database returned unknown OTP algorithm for user {} (error: {})",
+                username, e
+            )));
+        }
+
+        Ok(OtpParameters {
+            algorithm: algorithm.unwrap(),
+            digits,
+            period_in_sec,
+        })
+    }
+    /// Sets parameters that will be used to generate OTP codes for the user.
+    ///
+    /// Should be called when the user receives the OTP QR code.
+    pub fn set_otp_parameters_for_user(
+        &self,
+        username: &str,
+        otp_parameters: &OtpParameters,
+    ) -> Result<(), AppError> {
+        let result = self.connection.execute(
+            &format!(
+                "UPDATE {} SET otp_algorithm = ?1, otp_digits = ?2, otp_period = ?3 \
+                WHERE username = ?4",
+                USER_TABLE_NAME
+            ),
+            params![
+                otp_parameters.algorithm.to_string(),
+                otp_parameters.digits,
+                otp_parameters.period_in_sec,
+                username
+            ],
+        );
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Sets new password for user.
+    ///
+    /// Returns `Ok(true)` if user don't need to change password (error), `Ok(false)`
+    /// if changed successfully.
+    ///
+    /// On failure returns `AppError`.
+    pub fn update_user_password(
+        &self,
+        username: &str,
+        mut new_password: Vec<u8>,
+    ) -> Result<bool, AppError> {
+        // Check if user needs to change his password.
+        let result = self.is_user_needs_to_change_password(username);
+        if let Err(app_error) = result {
+            return Err(app_error);
+        }
+        let need_change_password = result.unwrap();
+        if !need_change_password {
+            return Ok(true);
+        }
+
+        let result = self.get_user_password_and_salt(username);
+        if let Err(app_error) = result {
+            return Err(app_error);
+        }
+        let (_current_password, salt) = result.unwrap();
+
+        // Salt + 'password hash' hash.
+        let mut value: Vec<u8> = Vec::from(salt.as_bytes());
+        value.append(&mut new_password);
+        let mut hasher = Sha512::new();
+        hasher.update(value.as_slice());
+        let password = hasher.finalize().to_vec();
+
+        // Update password and 'need_to_change_password' in database.
+        let result = self.connection.execute(
+            &format!(
+                "UPDATE {} SET password = ?1, need_change_password = 0, password_changed_at = ?2, \
+                email_verified = (email != '') WHERE username = '{}'",
+                USER_TABLE_NAME, username
+            ),
+            params![password, Local::now().timestamp()],
+        );
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(false)
+    }
+    /// Marks that user have setup OTP and no longer needs OTP QR code.
+    pub fn set_user_finished_otp_setup(&self, username: &str) -> Result<(), AppError> {
+        // Check if user needs to setup OTP.
+        let result = self.is_user_needs_setup_otp(username);
+        if let Err(app_error) = result {
+            return Err(app_error);
+        }
+        let need_change_password = result.unwrap();
+        if !need_change_password {
+            return Err(AppError::new(&format!(
+                "user \"{}\" already setup OTP \
+                    but we requested to finish OTP setup",
+                username
+            )));
+        }
+
+        // Update 'need_setup_otp' in database.
+        let result = self.connection.execute(
+            &format!(
+                "UPDATE {} SET need_setup_otp = 0 WHERE username = '{}'",
+                USER_TABLE_NAME, username
+            ),
+            [],
+        );
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Generates new OTP backup codes for the user, old backup codes
+    /// of this user (if existed) are removed.
+    ///
+    /// ## Return
+    /// `None` if the user was not found, otherwise generated backup codes
+    /// (only hashes of these codes are stored in the database).
+    pub fn regenerate_otp_backup_codes(
+        &self,
+        username: &str,
+    ) -> Result<Option<Vec<String>>, AppError> {
+        if !self.is_user_exists(username)? {
+            return Ok(None);
+        }
+
+        let (_password, salt) = self.get_user_password_and_salt(username)?;
+
+        // Generate codes.
+        let mut rng = rand::thread_rng();
+        let backup_codes: Vec<String> = (0..OTP_BACKUP_CODE_COUNT)
+            .map(|_| {
+                let code: String = (0..OTP_BACKUP_CODE_LENGTH)
+                    .map(|_| {
+                        let idx = rng.gen_range(0..OTP_BACKUP_CODE_CHARSET.len());
+                        OTP_BACKUP_CODE_CHARSET[idx] as char
+                    })
+                    .collect();
+
+                // Split into two parts for readability.
+                format!(
+                    "{}-{}",
+                    &code[..OTP_BACKUP_CODE_LENGTH / 2],
+                    &code[OTP_BACKUP_CODE_LENGTH / 2..]
+                )
+            })
+            .collect();
+
+        let transaction = self.connection.unchecked_transaction();
+        if let Err(e) = transaction {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let transaction = transaction.unwrap();
+
+        // Remove old codes.
+        if let Err(e) = transaction.execute(
+            &format!(
+                "DELETE FROM {} WHERE fk_user_id = (SELECT id FROM {} WHERE username = ?1)",
+                OTP_BACKUP_CODE_TABLE_NAME, USER_TABLE_NAME
+            ),
+            params![username],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        // Insert new codes.
+        for backup_code in backup_codes.iter() {
+            if let Err(e) = transaction.execute(
+                &format!(
+                    "INSERT INTO {} (code_hash, is_used, fk_user_id) \
+                    VALUES (?1, 0, (SELECT id FROM {} WHERE username = ?2))",
+                    OTP_BACKUP_CODE_TABLE_NAME, USER_TABLE_NAME
+                ),
+                params![
+                    DatabaseManager::hash_otp_backup_code(&salt, backup_code),
+                    username
+                ],
+            ) {
+                return Err(AppError::new(&e.to_string()));
+            }
+        }
+
+        if let Err(e) = transaction.commit() {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(Some(backup_codes))
+    }
+    /// Generates a new OTP secret for the user so that the user will setup OTP again
+    /// on the next login, OTP backup codes and session tokens of the user are removed.
+    ///
+    /// ## Return
+    /// `false` if the user was not found.
+    pub fn reset_user_otp(&self, username: &str) -> Result<bool, AppError> {
+        // Generate OTP secret.
+        let mut rng = rand::thread_rng();
+        let otp_secret: String = (0..OTP_SECRET_LENGTH)
+            .map(|_| {
+                let idx = rng.gen_range(0..CHARSET.len());
+                CHARSET[idx] as char
+            })
+            .collect();
+
+        let transaction = self.connection.unchecked_transaction();
+        if let Err(e) = transaction {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let transaction = transaction.unwrap();
+
+        // Set new secret.
+        let result = transaction.execute(
+            &format!(
+                "UPDATE {} SET otp_secret_key = ?1, need_setup_otp = 1 WHERE username = ?2",
+                USER_TABLE_NAME
+            ),
+            params![otp_secret, username],
+        );
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+        if result.unwrap() == 0 {
+            return Ok(false);
+        }
+
+        // Remove backup codes and session tokens (so that OTP can't be bypassed).
+        for table_name in [OTP_BACKUP_CODE_TABLE_NAME, SESSION_TOKEN_TABLE_NAME] {
+            if let Err(e) = transaction.execute(
+                &format!(
+                    "DELETE FROM {} WHERE fk_user_id = (SELECT id FROM {} WHERE username = ?1)",
+                    table_name, USER_TABLE_NAME
+                ),
+                params![username],
+            ) {
+                return Err(AppError::new(&e.to_string()));
+            }
+        }
+
+        if let Err(e) = transaction.commit() {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(true)
+    }
+    /// Marks the specified OTP backup code of the user as used.
+    ///
+    /// ## Return
+    /// `true` if the code was valid and not used before (it can't be used again),
+    /// `false` if the code is wrong or was already used.
+    pub fn use_otp_backup_code(&self, username: &str, backup_code: &str) -> Result<bool, AppError> {
+        let (_password, salt) = self.get_user_password_and_salt(username)?;
+        if salt.is_empty() {
+            // No such user.
+            return Ok(false);
+        }
+
+        let result = self.connection.execute(
+            &format!(
+                "UPDATE {} SET is_used = 1 \
+                WHERE fk_user_id = (SELECT id FROM {} WHERE username = ?1) \
+                AND code_hash = ?2 AND is_used = 0",
+                OTP_BACKUP_CODE_TABLE_NAME, USER_TABLE_NAME
+            ),
+            params![
+                username,
+                DatabaseManager::hash_otp_backup_code(&salt, backup_code)
+            ],
+        );
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(result.unwrap() > 0)
+    }
+    /// Records a sensitive action in the audit log.
+    ///
+    /// ## Arguments
+    /// * `actor`: who made the action (username or application name).
+    /// * `action`: made action.
+    /// * `target`: description of the action target (for example: "report 42").
+    pub fn add_audit_log_entry(
+        &self,
+        actor: &str,
+        action: AuditAction,
+        target: &str,
+    ) -> Result<(), AppError> {
+        let datetime = Local::now();
+
+        if let Err(e) = self.connection.execute(
+            &format!(
+                "INSERT INTO {} (actor, action, target, date_created_at, time_created_at) \
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                AUDIT_LOG_TABLE_NAME
+            ),
+            params![
+                actor,
+                action.to_string(),
+                target,
+                datetime.date_naive().to_string(),
+                datetime.time().format("%H:%M:%S").to_string()
+            ],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Returns all audit log entries (oldest first).
+    pub fn get_audit_log(&self) -> Result<Vec<AuditLogEntry>, AppError> {
+        let stmt = self.connection.prepare(&format!(
+            "SELECT id, actor, action, target, date_created_at, time_created_at \
+            FROM {} ORDER BY id",
+            AUDIT_LOG_TABLE_NAME
+        ));
+        if let Err(e) = stmt {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let mut stmt = stmt.unwrap();
+
+        let entries = stmt.query_map([], |row| {
+            Ok(AuditLogEntry {
+                id: row.get(0)?,
+                actor: row.get(1)?,
+                action: row.get(2)?,
+                target: row.get(3)?,
+                date_created_at: row.get(4)?,
+                time_created_at: row.get(5)?,
+            })
+        });
+        if let Err(e) = entries {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let entries: Result<Vec<AuditLogEntry>> = entries.unwrap().collect();
+        if let Err(e) = entries {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(entries.unwrap())
+    }
+    /// Records a failed login attempt (the oldest records are removed
+    /// if there are too many of them).
+    ///
+    /// ## Arguments
+    /// * `username`: username that was used to login.
+    /// * `ip`: IP address of the client.
+    /// * `reason`: why the attempt failed.
+    pub fn add_failed_login(
+        &self,
+        username: &str,
+        ip: &str,
+        reason: FailedLoginReason,
+    ) -> Result<(), AppError> {
+        let datetime = Local::now();
+
+        if let Err(e) = self.connection.execute(
+            &format!(
+                "INSERT INTO {} (username, ip, reason, date_created_at, time_created_at) \
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                FAILED_LOGIN_TABLE_NAME
+            ),
+            params![
+                username,
+                ip,
+                reason.to_string(),
+                datetime.date_naive().to_string(),
+                datetime.time().format("%H:%M:%S").to_string()
+            ],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        if let Err(e) = self.connection.execute(
+            &format!(
+                "DELETE FROM {0} WHERE id NOT IN \
+                (SELECT id FROM {0} ORDER BY id DESC LIMIT ?1)",
+                FAILED_LOGIN_TABLE_NAME
+            ),
+            params![MAX_FAILED_LOGIN_RECORD_COUNT],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Returns the most recent failed login attempts (oldest first).
+    ///
+    /// ## Arguments
+    /// * `count`: maximum number of records to return.
+    pub fn get_recent_failed_logins(&self, count: u64) -> Result<Vec<FailedLoginEntry>, AppError> {
+        let stmt = self.connection.prepare(&format!(
+            "SELECT * FROM (SELECT id, username, ip, reason, date_created_at, time_created_at \
+            FROM {} ORDER BY id DESC LIMIT ?1) ORDER BY id",
+            FAILED_LOGIN_TABLE_NAME
+        ));
+        if let Err(e) = stmt {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let mut stmt = stmt.unwrap();
+
+        let entries = stmt.query_map(params![count], |row| {
+            Ok(FailedLoginEntry {
+                id: row.get(0)?,
+                username: row.get(1)?,
+                ip: row.get(2)?,
+                reason: row.get(3)?,
+                date_created_at: row.get(4)?,
+                time_created_at: row.get(5)?,
+            })
+        });
+        if let Err(e) = entries {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let entries: Result<Vec<FailedLoginEntry>> = entries.unwrap().collect();
+        if let Err(e) = entries {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(entries.unwrap())
+    }
+    /// Adds a report to the webhook delivery queue.
+    ///
+    /// ## Arguments
+    /// * `report_id`: ID of the report (the delivery is removed together with the report).
+    /// * `url`: webhook URL.
+    /// * `payload`: data to send.
+    pub fn add_webhook_delivery(
+        &self,
+        report_id: u64,
+        url: &str,
+        payload: &str,
+    ) -> Result<(), AppError> {
+        if let Err(e) = self.connection.execute(
+            &format!(
+                "INSERT INTO {} (url, payload, attempt_count, next_attempt_time, fk_report_id) \
+                VALUES (?1, ?2, 0, ?3, ?4)",
+                WEBHOOK_DELIVERY_TABLE_NAME
+            ),
+            params![url, payload, Local::now().timestamp(), report_id],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Returns webhook deliveries that should be attempted now (oldest first).
+    pub fn get_due_webhook_deliveries(&self) -> Result<Vec<WebhookDelivery>, AppError> {
+        let stmt = self.connection.prepare(&format!(
+            "SELECT id, fk_report_id, url, payload, attempt_count \
+            FROM {} WHERE next_attempt_time <= ?1 ORDER BY id",
+            WEBHOOK_DELIVERY_TABLE_NAME
+        ));
+        if let Err(e) = stmt {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let mut stmt = stmt.unwrap();
+
+        let deliveries = stmt.query_map(params![Local::now().timestamp()], |row| {
+            Ok(WebhookDelivery {
+                id: row.get(0)?,
+                report_id: row.get(1)?,
+                url: row.get(2)?,
+                payload: row.get(3)?,
+                attempt_count: row.get(4)?,
+            })
+        });
+        if let Err(e) = deliveries {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let deliveries: Result<Vec<WebhookDelivery>> = deliveries.unwrap().collect();
+        if let Err(e) = deliveries {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(deliveries.unwrap())
+    }
+    /// Postpones a webhook delivery after a failed attempt.
+    ///
+    /// ## Arguments
+    /// * `delivery_id`: ID of the delivery.
+    /// * `attempt_count`: new number of failed attempts.
+    /// * `delay_in_sec`: time to wait before the next attempt.
+    pub fn postpone_webhook_delivery(
+        &self,
+        delivery_id: u64,
+        attempt_count: u32,
+        delay_in_sec: u64,
+    ) -> Result<(), AppError> {
+        if let Err(e) = self.connection.execute(
+            &format!(
+                "UPDATE {} SET attempt_count = ?1, next_attempt_time = ?2 WHERE id = ?3",
+                WEBHOOK_DELIVERY_TABLE_NAME
+            ),
+            params![
+                attempt_count,
+                Local::now().timestamp() + delay_in_sec as i64,
+                delivery_id
+            ],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Removes a webhook delivery from the queue (after it was sent or abandoned).
+    pub fn remove_webhook_delivery(&self, delivery_id: u64) -> Result<(), AppError> {
+        if let Err(e) = self.connection.execute(
+            &format!("DELETE FROM {} WHERE id = ?1", WEBHOOK_DELIVERY_TABLE_NAME),
+            params![delivery_id],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Generates a new token that can be used to resume a session of the user.
+    ///
+    /// ## Remarks
+    /// Only the hash of the token is stored in the database. Expired tokens
+    /// of all users are removed.
+    ///
+    /// ## Arguments
+    /// * `username`: user that logged in.
+    /// * `lifetime_in_sec`: time after which the token can no longer be used.
+    ///
+    /// ## Return
+    /// Generated token.
+    pub fn create_session_token(
+        &self,
+        username: &str,
+        lifetime_in_sec: u64,
+    ) -> Result<String, AppError> {
+        self.remove_expired_session_tokens()?;
+
+        let mut rng = rand::thread_rng();
+        let session_token: String = (0..SESSION_TOKEN_LENGTH_IN_BYTES)
+            .map(|_| format!("{:02x}", rng.gen::<u8>()))
+            .collect();
+
+        if let Err(e) = self.connection.execute(
+            &format!(
+                "INSERT INTO {} (token_hash, expiration_time, fk_user_id) \
+                VALUES (?1, ?2, (SELECT id FROM {} WHERE username = ?3))",
+                SESSION_TOKEN_TABLE_NAME, USER_TABLE_NAME
+            ),
+            params![
+                DatabaseManager::hash_session_token(&session_token),
+                Local::now().timestamp() + lifetime_in_sec as i64,
+                username
+            ],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(session_token)
+    }
+    /// Removes the specified session token if it's not expired.
+    ///
+    /// ## Remarks
+    /// The token can be used only once, create a new token for the resumed session.
+    ///
+    /// ## Return
+    /// `None` if the token is wrong or expired, otherwise name of the user
+    /// that owns the token.
+    #[tracing::instrument(skip_all)]
+    pub fn use_session_token(&self, session_token: &str) -> Result<Option<String>, AppError> {
+        let token_hash = DatabaseManager::hash_session_token(session_token);
+
+        let username = self
+            .connection
+            .query_row(
+                &format!(
+                    "SELECT {}.username FROM {} INNER JOIN {} ON {}.id = {}.fk_user_id \
+                    WHERE {}.token_hash = ?1 AND {}.expiration_time > ?2",
+                    USER_TABLE_NAME,
+                    SESSION_TOKEN_TABLE_NAME,
+                    USER_TABLE_NAME,
+                    USER_TABLE_NAME,
+                    SESSION_TOKEN_TABLE_NAME,
+                    SESSION_TOKEN_TABLE_NAME,
+                    SESSION_TOKEN_TABLE_NAME
+                ),
+                params![token_hash, Local::now().timestamp()],
+                |row| row.get::<_, String>(0),
+            )
+            .optional();
+        if let Err(e) = username {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        self.remove_session_token(session_token)?;
+
+        Ok(username.unwrap())
+    }
+    /// Sets the time after which the specified session token can no longer be used.
+    ///
+    /// ## Arguments
+    /// * `session_token`: token to update (nothing is changed if the token does not exist).
+    /// * `lifetime_in_sec`: time (starting from now) after which the token expires.
+    pub fn extend_session_token(
+        &self,
+        session_token: &str,
+        lifetime_in_sec: u64,
+    ) -> Result<(), AppError> {
+        if let Err(e) = self.connection.execute(
+            &format!(
+                "UPDATE {} SET expiration_time = ?1 WHERE token_hash = ?2",
+                SESSION_TOKEN_TABLE_NAME
+            ),
+            params![
+                Local::now().timestamp() + lifetime_in_sec as i64,
+                DatabaseManager::hash_session_token(session_token)
+            ],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Removes the specified session token (for example, when the user logs out).
+    pub fn remove_session_token(&self, session_token: &str) -> Result<(), AppError> {
+        if let Err(e) = self.connection.execute(
+            &format!(
+                "DELETE FROM {} WHERE token_hash = ?1",
+                SESSION_TOKEN_TABLE_NAME
+            ),
+            params![DatabaseManager::hash_session_token(session_token)],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Check if a given user exists in the database.
+    ///
+    /// Returns `Ok(true)` if the user exists, `Ok(false)` if not.
+    /// On failure returns `AppError`.
+    fn is_user_exists(&self, username: &str) -> Result<bool, AppError> {
+        let mut stmt = self
+            .connection
+            .prepare(&format!(
+                "SELECT id FROM {} WHERE username='{}'",
+                USER_TABLE_NAME, username
+            ))
+            .unwrap();
+        let result = stmt.query([]);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let mut rows = result.unwrap();
+
+        let row = rows.next();
+        if let Err(e) = row {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let row = row.unwrap();
+        if row.is_none() {
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+    /// Returns all attachments (with decompressed and base64-encoded data)
+    /// of the specified report (expired attachments are skipped).
+    fn get_exported_attachments(
+        &self,
+        report_id: u64,
+    ) -> Result<Vec<ExportedAttachment>, AppError> {
+        let mut stmt = self
+            .connection
+            .prepare(&format!(
+                "SELECT file_name, mime_type, data, is_compressed \
+                 FROM {} \
+                 WHERE fk_report_id == {} AND is_expired == 0",
+                ATTACHMENT_TABLE_NAME, report_id
+            ))
+            .unwrap();
+        let result = stmt.query_map([], |row| {
+            Ok((
+                row.get::<usize, String>(0)?,
+                row.get::<usize, String>(1)?,
+                row.get::<usize, Vec<u8>>(2)?,
+                row.get::<usize, bool>(3)?,
+            ))
+        });
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let mut attachments: Vec<ExportedAttachment> = Vec::new();
+        for row in result.unwrap() {
+            if let Err(e) = row {
+                return Err(AppError::new(&e.to_string()));
+            }
+            let (file_name, mime_type, mut data, is_compressed) = row.unwrap();
+
+            if is_compressed {
+                data = Self::decompress_attachment_data(&data)?;
+            }
+
+            attachments.push(ExportedAttachment {
+                file_name,
+                mime_type,
+                data: general_purpose::STANDARD.encode(data),
+            });
+        }
+
+        Ok(attachments)
+    }
+    /// Inserts a report from a JSON file (see `import_reports`) into the database.
+    fn insert_exported_report(
+        connection: &Connection,
+        report: ExportedReport,
+    ) -> Result<(), AppError> {
+        let result: Result<u64> = connection.query_row(
+            &format!(
+                "INSERT INTO {} 
+            (
+                report_name, 
+                report_text, 
+                sender_name, 
+                sender_email, 
+                game_name, 
+                game_version, 
+                os_info, 
+                date_created_at, 
+                time_created_at,
+                priority,
+                content_hash,
+                occurrence_count,
+                last_seen_date,
+                last_seen_time,
+                reporter_net_protocol,
+                reporter_version,
+                is_markdown,
+                session_id,
+                playtime_seconds
+            ) 
+            VALUES 
+            (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?8, ?9, ?13, ?14, ?15, ?16, ?17) 
+            RETURNING id",
+                REPORT_TABLE_NAME
+            ),
+            params![
+                report.report_name,
+                report.report_text,
+                report.sender_name,
+                report.sender_email,
+                report.game_name,
+                report.game_version,
+                report.os_info,
+                report.date_created_at,
+                report.time_created_at,
+                report.priority.value(),
+                Self::get_report_content_hash(&report.report_name, &report.report_text),
+                report.occurrence_count,
+                report.reporter_net_protocol,
+                report.reporter_version,
+                report.is_markdown,
+                report.session_id,
+                report.playtime_seconds,
+            ],
+            |row| row.get(0),
+        );
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let report_id = result.unwrap();
+
+        for attachment in report.attachments {
+            let data = general_purpose::STANDARD.decode(attachment.data);
+            if let Err(e) = data {
+                return Err(AppError::new(&e.to_string()));
+            }
+
+            Self::insert_attachment(connection, report_id, &attachment.file_name, &data.unwrap())?;
+        }
+
+        Self::insert_report_metadata(connection, report_id, &report.metadata)?;
+
+        Ok(())
+    }
+    /// Removes the oldest reports (with lowest IDs) so that only
+    /// the specified number of reports is left (attachments, comments and
+    /// other related rows are removed by cascade).
+    ///
+    /// ## Return
+    /// IDs of removed reports.
+    fn remove_oldest_reports(
+        connection: &Connection,
+        keep_count: u64,
+    ) -> Result<Vec<u64>, AppError> {
+        let report_count: Result<u64> = connection.query_row(
+            &format!("SELECT COUNT(*) FROM {}", REPORT_TABLE_NAME),
+            params![],
+            |row| row.get(0),
+        );
+        if let Err(e) = report_count {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let report_count = report_count.unwrap();
+        if report_count <= keep_count {
+            return Ok(Vec::new());
+        }
+        let remove_count = report_count - keep_count;
+
+        let stmt = connection.prepare(&format!(
+            "SELECT id FROM {} ORDER BY id LIMIT ?1",
+            REPORT_TABLE_NAME
+        ));
+        if let Err(e) = stmt {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let mut stmt = stmt.unwrap();
+
+        let report_ids = stmt.query_map(params![remove_count], |row| row.get(0));
+        if let Err(e) = report_ids {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let report_ids: Result<Vec<u64>> = report_ids.unwrap().collect();
+        if let Err(e) = report_ids {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        if let Err(e) = connection.execute(
+            &format!(
+                "DELETE FROM {0} WHERE id IN (SELECT id FROM {0} ORDER BY id LIMIT ?1)",
+                REPORT_TABLE_NAME
+            ),
+            params![remove_count],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(report_ids.unwrap())
+    }
+    /// Runs a query that returns pairs of a text and a count.
+    ///
+    /// ## Arguments
+    /// * `connection`: database connection to use.
+    /// * `query`: query to run.
+    /// * `parameters`: values of query parameters (`?1`, `?2` and so on).
+    fn query_report_counts(
+        connection: &Connection,
+        query: &str,
+        parameters: &[&dyn ToSql],
+    ) -> Result<Vec<(String, u64)>, AppError> {
+        let stmt = connection.prepare(query);
+        if let Err(e) = stmt {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let mut stmt = stmt.unwrap();
+
+        let result = stmt.query_map(parameters, |row| Ok((row.get(0)?, row.get(1)?)));
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let counts: Result<Vec<(String, u64)>> = result.unwrap().collect();
+        if let Err(e) = counts {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(counts.unwrap())
+    }
+    /// Inserts metadata entries of a report into the database.
+    ///
+    /// ## Arguments
+    /// * `connection`: database connection (or transaction) to use.
+    /// * `report_id`: ID of the report that this metadata belongs to.
+    /// * `metadata`: key/value pairs to insert.
+    fn insert_report_metadata(
+        connection: &Connection,
+        report_id: u64,
+        metadata: &BTreeMap<String, String>,
+    ) -> Result<(), AppError> {
+        for (key, value) in metadata {
+            if let Err(e) = connection.execute(
+                &format!(
+                    "INSERT INTO {} (key, value, fk_report_id) VALUES (?1, ?2, ?3)",
+                    REPORT_METADATA_TABLE_NAME
+                ),
+                params![key, value, report_id],
+            ) {
+                return Err(AppError::new(&e.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+    /// Returns metadata entries of a report.
+    ///
+    /// ## Arguments
+    /// * `connection`: database connection (or transaction) to use.
+    /// * `report_id`: ID of the report to get metadata of.
+    fn query_report_metadata(
+        connection: &Connection,
+        report_id: u64,
+    ) -> Result<BTreeMap<String, String>, AppError> {
+        let stmt = connection.prepare(&format!(
+            "SELECT key, value FROM {} WHERE fk_report_id = ?1",
+            REPORT_METADATA_TABLE_NAME
+        ));
+        if let Err(e) = stmt {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let mut stmt = stmt.unwrap();
+
+        let entries = stmt.query_map(params![report_id], |row| Ok((row.get(0)?, row.get(1)?)));
+        if let Err(e) = entries {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let metadata: Result<BTreeMap<String, String>> = entries.unwrap().collect();
+        if let Err(e) = metadata {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(metadata.unwrap())
+    }
+    /// Compresses and inserts a new attachment into the database
+    /// (a thumbnail is generated for images).
+    ///
+    /// ## Arguments
+    /// * `connection`: database connection (or transaction) to use.
+    /// * `report_id`: ID of the report that this attachment belongs to.
+    /// * `file_name`: name of the attachment file.
+    /// * `data`: uncompressed attachment data.
+    fn insert_attachment(
+        connection: &Connection,
+        report_id: u64,
+        file_name: &str,
+        data: &[u8],
+    ) -> Result<(), AppError> {
+        let mime_type = get_mime_type_from_file_name(file_name);
+
+        // Compress data (original size is stored in `size_in_bytes`).
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        if let Err(e) = encoder.write_all(data) {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let compressed_data = encoder.finish();
+        if let Err(e) = compressed_data {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let compressed_data = compressed_data.unwrap();
+
+        let thumbnail = if mime_type.starts_with("image/") {
+            Self::generate_thumbnail(data)
+        } else {
+            None
+        };
+
+        // Using cached statement since attachments are usually inserted one after another.
+        let stmt = connection.prepare_cached(&format!(
+            "INSERT INTO {} 
+                (
+                    file_name,
+                    data, 
+                    size_in_bytes,
+                    mime_type,
+                    is_compressed,
+                    fk_report_id,
+                    thumbnail,
+                    is_expired
+                ) 
+                VALUES 
+                (?1, ?2, ?3, ?4, ?5, ?6, ?7, 0)",
+            ATTACHMENT_TABLE_NAME
+        ));
+        if let Err(e) = stmt {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let mut stmt = stmt.unwrap();
+
+        let result = stmt.execute(params![
+            file_name,
+            compressed_data,
+            data.len(),
+            mime_type,
+            true,
+            report_id,
+            thumbnail
+        ]);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Generates a small PNG preview of the image.
+    ///
+    /// ## Return
+    /// `None` if the data is not a supported image (reports can contain anything
+    /// so this is not considered an error).
+    fn generate_thumbnail(data: &[u8]) -> Option<Vec<u8>> {
+        let image = image::load_from_memory(data).ok()?;
+
+        let mut thumbnail = Vec::new();
+        image
+            .thumbnail(ATTACHMENT_THUMBNAIL_SIZE, ATTACHMENT_THUMBNAIL_SIZE)
+            .write_to(
+                &mut std::io::Cursor::new(&mut thumbnail),
+                image::ImageOutputFormat::Png,
+            )
+            .ok()?;
+
+        Some(thumbnail)
+    }
+    /// Decompresses attachment data that was compressed in `insert_attachment`.
+    /// Returns hash(salt + backup code), the backup code is normalized
+    /// so that the user can type it without the separator and in any case.
+    fn hash_otp_backup_code(salt: &str, backup_code: &str) -> Vec<u8> {
+        let backup_code: String = backup_code
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+
+        let mut hasher = Sha512::new();
+        hasher.update(salt.as_bytes());
+        hasher.update(backup_code.as_bytes());
+        hasher.finalize().to_vec()
+    }
+    /// Removes session tokens of all users that can no longer be used.
+    fn remove_expired_session_tokens(&self) -> Result<(), AppError> {
+        if let Err(e) = self.connection.execute(
+            &format!(
+                "DELETE FROM {} WHERE expiration_time <= ?1",
+                SESSION_TOKEN_TABLE_NAME
+            ),
+            params![Local::now().timestamp()],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Returns hash of the session token (tokens are random so no salt is needed).
+    fn hash_session_token(session_token: &str) -> Vec<u8> {
+        let mut hasher = Sha512::new();
+        hasher.update(session_token.as_bytes());
+        hasher.finalize().to_vec()
+    }
+    /// Returns a hash of the report's name and text that is used to find duplicate reports.
+    ///
+    /// ## Remarks
+    /// Letter case and whitespace differences are ignored.
+    fn get_report_content_hash(report_name: &str, report_text: &str) -> String {
+        let normalize = |text: &str| {
+            text.split_whitespace()
+                .collect::<Vec<&str>>()
+                .join(" ")
+                .to_lowercase()
+        };
+
+        let mut hasher = Sha512::new();
+        hasher.update(normalize(report_name));
+        hasher.update([0]); // separate name and text so that they can't be mixed
+        hasher.update(normalize(report_text));
+
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+    /// Returns SQL condition that is `true` for rows of the `report` table that were not
+    /// read by the user with the username passed as the first parameter of the statement.
+    fn get_unread_report_condition() -> String {
+        format!(
+            "{}.id NOT IN (SELECT fk_report_id FROM {} \
+            WHERE fk_user_id = (SELECT id FROM {} WHERE username = ?1))",
+            REPORT_TABLE_NAME, REPORT_READ_TABLE_NAME, USER_TABLE_NAME
+        )
+    }
+    fn decompress_attachment_data(data: &[u8]) -> Result<Vec<u8>, AppError> {
+        let mut decompressed_data: Vec<u8> = Vec::new();
+        if let Err(e) = GzDecoder::new(data).read_to_end(&mut decompressed_data) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(decompressed_data)
+    }
+    /// Converts priority value stored in the database to `ReportPriority`.
+    fn priority_from_database_value(value: i32) -> Result<ReportPriority, AppError> {
+        match ReportPriority::from_value(value) {
+            Some(priority) => Ok(priority),
+            None => Err(AppError::new(&format!(
+                "database returned invalid report priority \"{}\"",
+                value
+            ))),
+        }
+    }
+    /// Enabling foreign keys protects us from violating foreign key constraints
+    /// and also enables ON DELETE CASCADE logic.
+    fn enable_foreign_keys(connection: &mut Connection) -> Result<(), AppError> {
+        let result = connection.execute("PRAGMA foreign_keys=on;", []);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Enabling WAL (write-ahead logging) allows readers and a writer
+    /// to access the database concurrently.
+    ///
+    /// ## Remarks
+    /// In WAL mode SQLite creates `-wal` and `-shm` files next to the database file.
+    fn enable_wal_mode(connection: &mut Connection) -> Result<(), AppError> {
+        let result: Result<String> =
+            connection.query_row("PRAGMA journal_mode=WAL;", [], |row| row.get(0));
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let journal_mode = result.unwrap();
+
+        if journal_mode.to_lowercase() != "wal" {
+            return Err(AppError::new(&format!(
+                "failed to enable WAL mode, current journal mode is \"{}\"",
+                journal_mode
+            )));
+        }
+
+        Ok(())
+    }
+    /// Creates the `report` table if it was not found in the database.
+    fn create_report_table_if_not_found(connection: &mut Connection) -> Result<(), AppError> {
+        // Check if table exists.
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT name FROM sqlite_master WHERE type='table' AND name='{}'",
+                REPORT_TABLE_NAME
+            ))
+            .unwrap();
+        let result = stmt.query([]);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let mut rows = result.unwrap();
+        let row = rows.next().unwrap();
+
+        // Create this table.
+        // 'attachments' below is a string of IDs from 'attachment' table
+        // separated by spaces
+        let table_structure = format!(
+            "CREATE TABLE {}(
+                    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+                    report_name     TEXT NOT NULL,
+                    report_text     TEXT NOT NULL,
+                    sender_name     TEXT NOT NULL,
+                    sender_email    TEXT NOT NULL,
+                    game_name       TEXT NOT NULL,
+                    game_version    TEXT NOT NULL,
+                    os_info         TEXT NOT NULL,
+                    date_created_at TEXT NOT NULL,
+                    time_created_at TEXT NOT NULL,
+                    priority        INTEGER NOT NULL,
+                    attachments     TEXT,
+                    content_hash    TEXT NOT NULL DEFAULT '',
+                    occurrence_count INTEGER NOT NULL DEFAULT 1,
+                    last_seen_date  TEXT NOT NULL DEFAULT '',
+                    last_seen_time  TEXT NOT NULL DEFAULT '',
+                    reporter_net_protocol INTEGER NOT NULL DEFAULT 0,
+                    reporter_version TEXT NOT NULL DEFAULT '',
+                    is_markdown     INTEGER NOT NULL DEFAULT 0,
+                    session_id      TEXT NOT NULL DEFAULT '',
+                    playtime_seconds INTEGER NOT NULL DEFAULT 0
+                )",
+            REPORT_TABLE_NAME
+        );
+
+        // Calculate table structure hash.
+        let mut hasher = Sha512::new();
+        hasher.update(&table_structure);
+        let table_hash = hasher.finalize().to_vec();
+
+        if table_hash != REPORT_TABLE_HASH {
+            panic!("\"report\" table was changed and now is incompatible with old versions, \
+                        to fix this panic, follow these steps:\n\
+                        1. increment 'SUPPORTED_DATABASE_VERSION' constant\n\
+                        2. handle old database version in 'handle_old_database_version()' function\n\
+                        3. recalculate new table hash (sha512) and put it into 'REPORT_TABLE_HASH' constant.");
+        }
+
+        if row.is_some() {
+            return Ok(());
+        }
+
+        // Create table.
+        let result = connection.execute(&table_structure, []);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Creates the `version` table if it was not found in the database.
+    fn create_version_table_if_not_found(connection: &mut Connection) -> Result<(), AppError> {
+        // Check if table exists.
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT name FROM sqlite_master WHERE type='table' AND name='{}'",
+                VERSION_TABLE_NAME
+            ))
+            .unwrap();
+        let result = stmt.query([]);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let mut rows = result.unwrap();
+        let row = rows.next().unwrap();
+
+        if row.is_some() {
+            return Ok(());
+        }
+
+        let table_structure = format!(
+            "CREATE TABLE {}(
+                    version INTEGER   
+                )",
+            VERSION_TABLE_NAME
+        );
+
+        // Create table.
+        let result = connection.execute(&table_structure, []);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        // Insert database version.
+        if let Err(e) = connection.execute(
+            // password = hash(salt + hash(password))
+            &format!("INSERT INTO {} (version) VALUES (?1)", VERSION_TABLE_NAME),
+            params![SUPPORTED_DATABASE_VERSION],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        println!("INFO: database version is {}", SUPPORTED_DATABASE_VERSION);
+
+        Ok(())
+    }
+    /// Creates the `user` table if it was not found in the database.
+    fn create_user_table_if_not_found(connection: &mut Connection) -> Result<(), AppError> {
+        // Check if table exists.
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT name FROM sqlite_master WHERE type='table' AND name='{}'",
+                USER_TABLE_NAME
+            ))
+            .unwrap();
+        let result = stmt.query([]);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let mut rows = result.unwrap();
+        let row = rows.next().unwrap();
+
+        // Create this table.
+        // password = hash(salt + hash(password))
+        // need_change_password is '1' if the user
+        // just registered (or the password has expired), thus we need to ask him of a new password,
+        // password_changed_at is a UNIX timestamp (in seconds),
+        // email_verified is '1' if the user set a new password using the password sent to the email.
+        let table_structure = format!(
+            "CREATE TABLE {}(
+                    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
+                    username             TEXT NOT NULL UNIQUE,
+                    salt                 TEXT NOT NULL,
+                    password             TEXT NOT NULL,
+                    need_change_password INTEGER NOT NULL,
+                    need_setup_otp       INTEGER NOT NULL,
+                    otp_secret_key       TEXT NOT NULL,
+                    last_login_date      TEXT NOT NULL,
+                    last_login_time      TEXT NOT NULL,
+                    last_login_ip        TEXT NOT NULL,
+                    date_registered      TEXT NOT NULL,
+                    time_registered      TEXT NOT NULL,
+                    otp_algorithm        TEXT NOT NULL,
+                    otp_digits           INTEGER NOT NULL,
+                    otp_period           INTEGER NOT NULL,
+                    role                 INTEGER NOT NULL,
+                    password_changed_at  INTEGER NOT NULL,
+                    email                TEXT NOT NULL,
+                    email_verified       INTEGER NOT NULL
+                )",
+            USER_TABLE_NAME
+        );
+
+        // Calculate table structure hash.
+        let mut hasher = Sha512::new();
+        hasher.update(&table_structure);
+        let table_hash = hasher.finalize().to_vec();
+
+        if table_hash != USER_TABLE_HASH {
+            panic!("\"user\" table was changed and now is incompatible with old versions, \
+                        to fix this panic, follow these steps:\n\
+                        1. increment 'SUPPORTED_DATABASE_VERSION' constant\n\
+                        2. handle old database version in 'handle_old_database_version()' function\n\
+                        3. recalculate new table hash (sha512) and put it into 'USER_TABLE_HASH' constant.");
+        }
+
+        if row.is_some() {
+            return Ok(());
+        }
+
+        let result = connection.execute(&table_structure, []);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Creates the `attachment` table if it was not found in the database.
+    fn create_attachment_table_if_not_found(connection: &mut Connection) -> Result<(), AppError> {
+        // Check if table exists.
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT name FROM sqlite_master WHERE type='table' AND name='{}'",
+                ATTACHMENT_TABLE_NAME
+            ))
+            .unwrap();
+        let result = stmt.query([]);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let mut rows = result.unwrap();
+        let row = rows.next().unwrap();
+
+        // Create this table.
+        let table_structure = format!(
+            "CREATE TABLE {}(
+                    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
+                    file_name            TEXT NOT NULL,
+                    data                 BLOB NOT NULL,
+                    size_in_bytes        INTEGER NUL NULL,
+                    mime_type            TEXT NOT NULL,
+                    is_compressed        INTEGER NOT NULL,
+                    fk_report_id         INTEGER NOT NULL,
+                    thumbnail            BLOB,
+                    is_expired           INTEGER NOT NULL,
+                    FOREIGN KEY (fk_report_id) REFERENCES report (id) ON DELETE CASCADE
+                )",
+            ATTACHMENT_TABLE_NAME
+        );
+
+        // Calculate table structure hash.
+        let mut hasher = Sha512::new();
+        hasher.update(&table_structure);
+        let table_hash = hasher.finalize().to_vec();
+
+        if table_hash != ATTACHMENT_TABLE_HASH {
+            panic!("\"attachment\" table was changed and now is incompatible with old versions, \
+                        to fix this panic, follow these steps:\n\
+                        1. increment 'SUPPORTED_DATABASE_VERSION' constant\n\
+                        2. handle old database version in 'handle_old_database_version()' function\n\
+                        3. recalculate new table hash (sha512) and put it into 'ATTACHMENT_TABLE_HASH' constant.");
+        }
+
+        if row.is_some() {
+            return Ok(());
+        }
+
+        let result = connection.execute(&table_structure, []);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    fn create_otp_backup_code_table_if_not_found(
+        connection: &mut Connection,
+    ) -> Result<(), AppError> {
+        // Check if table exists.
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT name FROM sqlite_master WHERE type='table' AND name='{}'",
+                OTP_BACKUP_CODE_TABLE_NAME
+            ))
+            .unwrap();
+        let result = stmt.query([]);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let mut rows = result.unwrap();
+        let row = rows.next().unwrap();
+
+        // Create this table.
+        // code_hash = hash(salt + code)
+        let table_structure = format!(
+            "CREATE TABLE {}(
+                    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
+                    code_hash            BLOB NOT NULL,
+                    is_used              INTEGER NOT NULL,
+                    fk_user_id           INTEGER NOT NULL,
+                    FOREIGN KEY (fk_user_id) REFERENCES user (id) ON DELETE CASCADE
+                )",
+            OTP_BACKUP_CODE_TABLE_NAME
+        );
+
+        // Calculate table structure hash.
+        let mut hasher = Sha512::new();
+        hasher.update(&table_structure);
+        let table_hash = hasher.finalize().to_vec();
+
+        if table_hash != OTP_BACKUP_CODE_TABLE_HASH {
+            panic!("\"otp_backup_code\" table was changed and now is incompatible with old versions, \
+                        to fix this panic, follow these steps:\n\
+                        1. increment 'SUPPORTED_DATABASE_VERSION' constant\n\
+                        2. handle old database version in 'handle_old_database_version()' function\n\
+                        3. recalculate new table hash (sha512) and put it into 'OTP_BACKUP_CODE_TABLE_HASH' constant.");
+        }
+
+        if row.is_some() {
+            return Ok(());
+        }
+
+        let result = connection.execute(&table_structure, []);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    fn create_audit_log_table_if_not_found(connection: &mut Connection) -> Result<(), AppError> {
+        // Check if table exists.
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT name FROM sqlite_master WHERE type='table' AND name='{}'",
+                AUDIT_LOG_TABLE_NAME
+            ))
+            .unwrap();
+        let result = stmt.query([]);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        let mut rows = result.unwrap();
+        let row = rows.next().unwrap();
+
+        // Create this table.
+        // Entries don't reference users or reports so that they are kept
+        // after users/reports are removed.
+        let table_structure = format!(
+            "CREATE TABLE {}(
+                    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
+                    actor                TEXT NOT NULL,
+                    action               TEXT NOT NULL,
+                    target               TEXT NOT NULL,
+                    date_created_at      TEXT NOT NULL,
+                    time_created_at      TEXT NOT NULL
+                )",
+            AUDIT_LOG_TABLE_NAME
+        );
+
+        // Calculate table structure hash.
+        let mut hasher = Sha512::new();
+        hasher.update(&table_structure);
+        let table_hash = hasher.finalize().to_vec();
+
+        if table_hash != AUDIT_LOG_TABLE_HASH {
+            panic!("\"audit_log\" table was changed and now is incompatible with old versions, \
+                        to fix this panic, follow these steps:\n\
+                        1. increment 'SUPPORTED_DATABASE_VERSION' constant\n\
+                        2. handle old database version in 'handle_old_database_version()' function\n\
+                        3. recalculate new table hash (sha512) and put it into 'AUDIT_LOG_TABLE_HASH' constant.");
+        }
+
+        if row.is_some() {
+            return Ok(());
+        }
+
+        let result = connection.execute(&table_structure, []);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Creates the `report_read` table if it was not found in the database.
+    fn create_report_read_table_if_not_found(connection: &mut Connection) -> Result<(), AppError> {
+        // Check if table exists.
+        let mut stmt = connection
             .prepare(&format!(
-                "SELECT otp_secret_key FROM {} WHERE username='{}'",
-                USER_TABLE_NAME, username
+                "SELECT name FROM sqlite_master WHERE type='table' AND name='{}'",
+                REPORT_READ_TABLE_NAME
             ))
             .unwrap();
         let result = stmt.query([]);
@@ -999,115 +3695,54 @@ impl DatabaseManager {
         }
 
         let mut rows = result.unwrap();
+        let row = rows.next().unwrap();
 
-        let row = rows.next();
-        if let Err(e) = row {
-            return Err(AppError::new(&e.to_string()));
-        }
-        let row = row.unwrap();
-        if row.is_none() {
-            return Err(AppError::new(&format!(
-                "database returned None for username {}",
-                username
-            )));
-        }
-        let row = row.unwrap();
-        let otp_secret = row.get::<usize, String>(0);
-        if let Err(e) = otp_secret {
-            return Err(AppError::new(&e.to_string()));
-        }
-        let otp_secret = otp_secret.unwrap();
-
-        Ok(otp_secret)
-    }
-    /// Sets new password for user.
-    ///
-    /// Returns `Ok(true)` if user don't need to change password (error), `Ok(false)`
-    /// if changed successfully.
-    ///
-    /// On failure returns `AppError`.
-    pub fn update_user_password(
-        &self,
-        username: &str,
-        mut new_password: Vec<u8>,
-    ) -> Result<bool, AppError> {
-        // Check if user needs to change his password.
-        let result = self.is_user_needs_to_change_password(username);
-        if let Err(app_error) = result {
-            return Err(app_error);
-        }
-        let need_change_password = result.unwrap();
-        if !need_change_password {
-            return Ok(true);
-        }
-
-        let result = self.get_user_password_and_salt(username);
-        if let Err(app_error) = result {
-            return Err(app_error);
-        }
-        let (_current_password, salt) = result.unwrap();
+        // Create this table.
+        // Each row means that the user has read (opened) the report.
+        let table_structure = format!(
+            "CREATE TABLE {}(
+                    fk_user_id           INTEGER NOT NULL,
+                    fk_report_id         INTEGER NOT NULL,
+                    PRIMARY KEY (fk_user_id, fk_report_id),
+                    FOREIGN KEY (fk_user_id) REFERENCES user (id) ON DELETE CASCADE,
+                    FOREIGN KEY (fk_report_id) REFERENCES report (id) ON DELETE CASCADE
+                )",
+            REPORT_READ_TABLE_NAME
+        );
 
-        // Salt + 'password hash' hash.
-        let mut value: Vec<u8> = Vec::from(salt.as_bytes());
-        value.append(&mut new_password);
+        // Calculate table structure hash.
         let mut hasher = Sha512::new();
-        hasher.update(value.as_slice());
-        let password = hasher.finalize().to_vec();
+        hasher.update(&table_structure);
+        let table_hash = hasher.finalize().to_vec();
 
-        // Update password and 'need_to_change_password' in database.
-        let result = self.connection.execute(
-            &format!(
-                "UPDATE {} SET password = ?1, need_change_password = 0 WHERE username = '{}'",
-                USER_TABLE_NAME, username
-            ),
-            [password],
-        );
-        if let Err(e) = result {
-            return Err(AppError::new(&e.to_string()));
+        if table_hash != REPORT_READ_TABLE_HASH {
+            panic!("\"report_read\" table was changed and now is incompatible with old versions, \
+                        to fix this panic, follow these steps:\n\
+                        1. increment 'SUPPORTED_DATABASE_VERSION' constant\n\
+                        2. handle old database version in 'handle_old_database_version()' function\n\
+                        3. recalculate new table hash (sha512) and put it into 'REPORT_READ_TABLE_HASH' constant.");
         }
 
-        Ok(false)
-    }
-    /// Marks that user have setup OTP and no longer needs OTP QR code.
-    pub fn set_user_finished_otp_setup(&self, username: &str) -> Result<(), AppError> {
-        // Check if user needs to setup OTP.
-        let result = self.is_user_needs_setup_otp(username);
-        if let Err(app_error) = result {
-            return Err(app_error);
-        }
-        let need_change_password = result.unwrap();
-        if !need_change_password {
-            return Err(AppError::new(&format!(
-                "user \"{}\" already setup OTP \
-                    but we requested to finish OTP setup",
-                username
-            )));
+        if row.is_some() {
+            return Ok(());
         }
 
-        // Update 'need_setup_otp' in database.
-        let result = self.connection.execute(
-            &format!(
-                "UPDATE {} SET need_setup_otp = 0 WHERE username = '{}'",
-                USER_TABLE_NAME, username
-            ),
-            [],
-        );
+        let result = connection.execute(&table_structure, []);
         if let Err(e) = result {
             return Err(AppError::new(&e.to_string()));
         }
 
         Ok(())
     }
-    /// Check if a given user exists in the database.
-    ///
-    /// Returns `Ok(true)` if the user exists, `Ok(false)` if not.
-    /// On failure returns `AppError`.
-    fn is_user_exists(&self, username: &str) -> Result<bool, AppError> {
-        let mut stmt = self
-            .connection
+    /// Creates the `report_comment` table if it was not found in the database.
+    fn create_report_comment_table_if_not_found(
+        connection: &mut Connection,
+    ) -> Result<(), AppError> {
+        // Check if table exists.
+        let mut stmt = connection
             .prepare(&format!(
-                "SELECT id FROM {} WHERE username='{}'",
-                USER_TABLE_NAME, username
+                "SELECT name FROM sqlite_master WHERE type='table' AND name='{}'",
+                REPORT_COMMENT_TABLE_NAME
             ))
             .unwrap();
         let result = stmt.query([]);
@@ -1116,35 +3751,56 @@ impl DatabaseManager {
         }
 
         let mut rows = result.unwrap();
+        let row = rows.next().unwrap();
 
-        let row = rows.next();
-        if let Err(e) = row {
-            return Err(AppError::new(&e.to_string()));
+        // Create this table.
+        // Author is stored as a username so that comments are kept after the user is removed.
+        let table_structure = format!(
+            "CREATE TABLE {}(
+                    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
+                    author               TEXT NOT NULL,
+                    text                 TEXT NOT NULL,
+                    date_created_at      TEXT NOT NULL,
+                    time_created_at      TEXT NOT NULL,
+                    fk_report_id         INTEGER NOT NULL,
+                    FOREIGN KEY (fk_report_id) REFERENCES report (id) ON DELETE CASCADE
+                )",
+            REPORT_COMMENT_TABLE_NAME
+        );
+
+        // Calculate table structure hash.
+        let mut hasher = Sha512::new();
+        hasher.update(&table_structure);
+        let table_hash = hasher.finalize().to_vec();
+
+        if table_hash != REPORT_COMMENT_TABLE_HASH {
+            panic!("\"report_comment\" table was changed and now is incompatible with old versions, \
+                        to fix this panic, follow these steps:\n\
+                        1. increment 'SUPPORTED_DATABASE_VERSION' constant\n\
+                        2. handle old database version in 'handle_old_database_version()' function\n\
+                        3. recalculate new table hash (sha512) and put it into 'REPORT_COMMENT_TABLE_HASH' constant.");
         }
-        let row = row.unwrap();
-        if row.is_none() {
-            return Ok(false);
+
+        if row.is_some() {
+            return Ok(());
         }
 
-        Ok(true)
-    }
-    /// Enabling foreign keys protects us from violating foreign key constraints
-    /// and also enables ON DELETE CASCADE logic.
-    fn enable_foreign_keys(connection: &mut Connection) -> Result<(), AppError> {
-        let result = connection.execute("PRAGMA foreign_keys=on;", []);
+        let result = connection.execute(&table_structure, []);
         if let Err(e) = result {
             return Err(AppError::new(&e.to_string()));
         }
 
         Ok(())
     }
-    /// Creates the `report` table if it was not found in the database.
-    fn create_report_table_if_not_found(connection: &mut Connection) -> Result<(), AppError> {
+    /// Creates the `webhook_delivery` table if it was not found in the database.
+    fn create_webhook_delivery_table_if_not_found(
+        connection: &mut Connection,
+    ) -> Result<(), AppError> {
         // Check if table exists.
         let mut stmt = connection
             .prepare(&format!(
                 "SELECT name FROM sqlite_master WHERE type='table' AND name='{}'",
-                REPORT_TABLE_NAME
+                WEBHOOK_DELIVERY_TABLE_NAME
             ))
             .unwrap();
         let result = stmt.query([]);
@@ -1156,23 +3812,19 @@ impl DatabaseManager {
         let row = rows.next().unwrap();
 
         // Create this table.
-        // 'attachments' below is a string of IDs from 'attachment' table
-        // separated by spaces
+        // Each row is a report that was not sent to a webhook yet,
+        // `next_attempt_time` is a UNIX timestamp (in seconds).
         let table_structure = format!(
             "CREATE TABLE {}(
-                    id              INTEGER PRIMARY KEY AUTOINCREMENT,
-                    report_name     TEXT NOT NULL,
-                    report_text     TEXT NOT NULL,
-                    sender_name     TEXT NOT NULL,
-                    sender_email    TEXT NOT NULL,
-                    game_name       TEXT NOT NULL,
-                    game_version    TEXT NOT NULL,
-                    os_info         TEXT NOT NULL,
-                    date_created_at TEXT NOT NULL,
-                    time_created_at TEXT NOT NULL,
-                    attachments     TEXT    
+                    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
+                    url                  TEXT NOT NULL,
+                    payload              TEXT NOT NULL,
+                    attempt_count        INTEGER NOT NULL,
+                    next_attempt_time    INTEGER NOT NULL,
+                    fk_report_id         INTEGER NOT NULL,
+                    FOREIGN KEY (fk_report_id) REFERENCES report (id) ON DELETE CASCADE
                 )",
-            REPORT_TABLE_NAME
+            WEBHOOK_DELIVERY_TABLE_NAME
         );
 
         // Calculate table structure hash.
@@ -1180,19 +3832,18 @@ impl DatabaseManager {
         hasher.update(&table_structure);
         let table_hash = hasher.finalize().to_vec();
 
-        if table_hash != REPORT_TABLE_HASH {
-            panic!("\"report\" table was changed and now is incompatible with old versions, \
+        if table_hash != WEBHOOK_DELIVERY_TABLE_HASH {
+            panic!("\"webhook_delivery\" table was changed and now is incompatible with old versions, \
                         to fix this panic, follow these steps:\n\
                         1. increment 'SUPPORTED_DATABASE_VERSION' constant\n\
                         2. handle old database version in 'handle_old_database_version()' function\n\
-                        3. recalculate new table hash (sha512) and put it into 'REPORT_TABLE_HASH' constant.");
+                        3. recalculate new table hash (sha512) and put it into 'WEBHOOK_DELIVERY_TABLE_HASH' constant.");
         }
 
         if row.is_some() {
             return Ok(());
         }
 
-        // Create table.
         let result = connection.execute(&table_structure, []);
         if let Err(e) = result {
             return Err(AppError::new(&e.to_string()));
@@ -1200,13 +3851,15 @@ impl DatabaseManager {
 
         Ok(())
     }
-    /// Creates the `version` table if it was not found in the database.
-    fn create_version_table_if_not_found(connection: &mut Connection) -> Result<(), AppError> {
+    /// Creates the `report_metadata` table if it was not found in the database.
+    fn create_report_metadata_table_if_not_found(
+        connection: &mut Connection,
+    ) -> Result<(), AppError> {
         // Check if table exists.
         let mut stmt = connection
             .prepare(&format!(
                 "SELECT name FROM sqlite_master WHERE type='table' AND name='{}'",
-                VERSION_TABLE_NAME
+                REPORT_METADATA_TABLE_NAME
             ))
             .unwrap();
         let result = stmt.query([]);
@@ -1217,43 +3870,52 @@ impl DatabaseManager {
         let mut rows = result.unwrap();
         let row = rows.next().unwrap();
 
-        if row.is_some() {
-            return Ok(());
-        }
-
+        // Create this table.
+        // Each row is a custom key/value pair that the game attached to the report.
         let table_structure = format!(
             "CREATE TABLE {}(
-                    version INTEGER   
+                    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
+                    key                  TEXT NOT NULL,
+                    value                TEXT NOT NULL,
+                    fk_report_id         INTEGER NOT NULL,
+                    FOREIGN KEY (fk_report_id) REFERENCES report (id) ON DELETE CASCADE
                 )",
-            VERSION_TABLE_NAME
+            REPORT_METADATA_TABLE_NAME
         );
 
-        // Create table.
-        let result = connection.execute(&table_structure, []);
-        if let Err(e) = result {
-            return Err(AppError::new(&e.to_string()));
+        // Calculate table structure hash.
+        let mut hasher = Sha512::new();
+        hasher.update(&table_structure);
+        let table_hash = hasher.finalize().to_vec();
+
+        if table_hash != REPORT_METADATA_TABLE_HASH {
+            panic!("\"report_metadata\" table was changed and now is incompatible with old versions, \
+                        to fix this panic, follow these steps:\n\
+                        1. increment 'SUPPORTED_DATABASE_VERSION' constant\n\
+                        2. handle old database version in 'handle_old_database_version()' function\n\
+                        3. recalculate new table hash (sha512) and put it into 'REPORT_METADATA_TABLE_HASH' constant.");
         }
 
-        // Insert database version.
-        if let Err(e) = connection.execute(
-            // password = hash(salt + hash(password))
-            &format!("INSERT INTO {} (version) VALUES (?1)", VERSION_TABLE_NAME),
-            params![SUPPORTED_DATABASE_VERSION],
-        ) {
-            return Err(AppError::new(&e.to_string()));
+        if row.is_some() {
+            return Ok(());
         }
 
-        println!("INFO: database version is {}", SUPPORTED_DATABASE_VERSION);
+        let result = connection.execute(&table_structure, []);
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
 
         Ok(())
     }
-    /// Creates the `user` table if it was not found in the database.
-    fn create_user_table_if_not_found(connection: &mut Connection) -> Result<(), AppError> {
+    /// Creates the `session_token` table if it was not found in the database.
+    fn create_session_token_table_if_not_found(
+        connection: &mut Connection,
+    ) -> Result<(), AppError> {
         // Check if table exists.
         let mut stmt = connection
             .prepare(&format!(
                 "SELECT name FROM sqlite_master WHERE type='table' AND name='{}'",
-                USER_TABLE_NAME
+                SESSION_TOKEN_TABLE_NAME
             ))
             .unwrap();
         let result = stmt.query([]);
@@ -1265,26 +3927,17 @@ impl DatabaseManager {
         let row = rows.next().unwrap();
 
         // Create this table.
-        // password = hash(salt + hash(password))
-        // need_change_password is '1' if the user
-        // just registered, thus we need to ask him of a new password.
+        // Each row is a token that can be used to resume a client session,
+        // token_hash = hash(token), `expiration_time` is a UNIX timestamp (in seconds).
         let table_structure = format!(
             "CREATE TABLE {}(
                     id                   INTEGER PRIMARY KEY AUTOINCREMENT,
-                    username             TEXT NOT NULL UNIQUE,
-                    salt                 TEXT NOT NULL,
-                    password             TEXT NOT NULL,
-                    need_change_password INTEGER NOT NULL,
-                    need_setup_otp       INTEGER NOT NULL,
-                    otp_secret_key       TEXT NOT NULL,
-                    is_admin             INTEGER NOT NULL,
-                    last_login_date      TEXT NOT NULL,
-                    last_login_time      TEXT NOT NULL,
-                    last_login_ip        TEXT NOT NULL,
-                    date_registered      TEXT NOT NULL,
-                    time_registered      TEXT NOT NULL
+                    token_hash           BLOB NOT NULL UNIQUE,
+                    expiration_time      INTEGER NOT NULL,
+                    fk_user_id           INTEGER NOT NULL,
+                    FOREIGN KEY (fk_user_id) REFERENCES user (id) ON DELETE CASCADE
                 )",
-            USER_TABLE_NAME
+            SESSION_TOKEN_TABLE_NAME
         );
 
         // Calculate table structure hash.
@@ -1292,12 +3945,12 @@ impl DatabaseManager {
         hasher.update(&table_structure);
         let table_hash = hasher.finalize().to_vec();
 
-        if table_hash != USER_TABLE_HASH {
-            panic!("\"user\" table was changed and now is incompatible with old versions, \
+        if table_hash != SESSION_TOKEN_TABLE_HASH {
+            panic!("\"session_token\" table was changed and now is incompatible with old versions, \
                         to fix this panic, follow these steps:\n\
                         1. increment 'SUPPORTED_DATABASE_VERSION' constant\n\
                         2. handle old database version in 'handle_old_database_version()' function\n\
-                        3. recalculate new table hash (sha512) and put it into 'USER_TABLE_HASH' constant.");
+                        3. recalculate new table hash (sha512) and put it into 'SESSION_TOKEN_TABLE_HASH' constant.");
         }
 
         if row.is_some() {
@@ -1311,13 +3964,13 @@ impl DatabaseManager {
 
         Ok(())
     }
-    /// Creates the `attachment` table if it was not found in the database.
-    fn create_attachment_table_if_not_found(connection: &mut Connection) -> Result<(), AppError> {
+    /// Creates the `failed_login` table if it was not found in the database.
+    fn create_failed_login_table_if_not_found(connection: &mut Connection) -> Result<(), AppError> {
         // Check if table exists.
         let mut stmt = connection
             .prepare(&format!(
                 "SELECT name FROM sqlite_master WHERE type='table' AND name='{}'",
-                ATTACHMENT_TABLE_NAME
+                FAILED_LOGIN_TABLE_NAME
             ))
             .unwrap();
         let result = stmt.query([]);
@@ -1328,17 +3981,18 @@ impl DatabaseManager {
         let mut rows = result.unwrap();
         let row = rows.next().unwrap();
 
-        // Create this table.
-        let table_structure = format!(
-            "CREATE TABLE {}(
-                    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
-                    file_name            TEXT NOT NULL,
-                    data                 BLOB NOT NULL,
-                    size_in_bytes        INTEGER NUL NULL,
-                    fk_report_id         INTEGER NOT NULL,
-                    FOREIGN KEY (fk_report_id) REFERENCES report (id) ON DELETE CASCADE
+        // Create this table.
+        // Records don't reference users since the username might not exist.
+        let table_structure = format!(
+            "CREATE TABLE {}(
+                    id                   INTEGER PRIMARY KEY AUTOINCREMENT,
+                    username             TEXT NOT NULL,
+                    ip                   TEXT NOT NULL,
+                    reason               TEXT NOT NULL,
+                    date_created_at      TEXT NOT NULL,
+                    time_created_at      TEXT NOT NULL
                 )",
-            ATTACHMENT_TABLE_NAME
+            FAILED_LOGIN_TABLE_NAME
         );
 
         // Calculate table structure hash.
@@ -1346,12 +4000,12 @@ impl DatabaseManager {
         hasher.update(&table_structure);
         let table_hash = hasher.finalize().to_vec();
 
-        if table_hash != ATTACHMENT_TABLE_HASH {
-            panic!("\"attachment\" table was changed and now is incompatible with old versions, \
+        if table_hash != FAILED_LOGIN_TABLE_HASH {
+            panic!("\"failed_login\" table was changed and now is incompatible with old versions, \
                         to fix this panic, follow these steps:\n\
                         1. increment 'SUPPORTED_DATABASE_VERSION' constant\n\
                         2. handle old database version in 'handle_old_database_version()' function\n\
-                        3. recalculate new table hash (sha512) and put it into 'ATTACHMENT_TABLE_HASH' constant.");
+                        3. recalculate new table hash (sha512) and put it into 'FAILED_LOGIN_TABLE_HASH' constant.");
         }
 
         if row.is_some() {
@@ -1365,6 +4019,51 @@ impl DatabaseManager {
 
         Ok(())
     }
+    /// Creates indexes for commonly queried columns if they were not found in the database.
+    ///
+    /// ## Remarks
+    /// Indexes are created separately from tables so that table structure hashes
+    /// are not changed.
+    fn create_indexes_if_not_found(connection: &mut Connection) -> Result<(), AppError> {
+        let indexes = [
+            ("idx_report_game_name", REPORT_TABLE_NAME, "game_name"),
+            ("idx_report_content_hash", REPORT_TABLE_NAME, "content_hash"),
+            (
+                "idx_report_date_created_at",
+                REPORT_TABLE_NAME,
+                "date_created_at",
+            ),
+            (
+                "idx_attachment_fk_report_id",
+                ATTACHMENT_TABLE_NAME,
+                "fk_report_id",
+            ),
+            (
+                "idx_report_comment_fk_report_id",
+                REPORT_COMMENT_TABLE_NAME,
+                "fk_report_id",
+            ),
+            (
+                "idx_report_metadata_fk_report_id",
+                REPORT_METADATA_TABLE_NAME,
+                "fk_report_id",
+            ),
+        ];
+
+        for (index_name, table_name, column_name) in indexes {
+            if let Err(e) = connection.execute(
+                &format!(
+                    "CREATE INDEX IF NOT EXISTS {} ON {}({})",
+                    index_name, table_name, column_name
+                ),
+                params![],
+            ) {
+                return Err(AppError::new(&e.to_string()));
+            }
+        }
+
+        Ok(())
+    }
     /// Looks if the existing database is not supported by this database manager.
     /// If the existing database is not supported, will upgrade existing database
     /// to the currently supported version.
@@ -1416,6 +4115,71 @@ impl DatabaseManager {
             }
         }
 
+        if version <= 1 {
+            // Upgrade to version 2.
+            DatabaseManager::upgrade_database_to_version_2(connection)?;
+        }
+
+        if version <= 2 {
+            // Upgrade to version 3.
+            DatabaseManager::upgrade_database_to_version_3(connection)?;
+        }
+
+        if version <= 3 {
+            // Upgrade to version 4.
+            DatabaseManager::upgrade_database_to_version_4(connection)?;
+        }
+
+        if version <= 4 {
+            // Upgrade to version 5.
+            DatabaseManager::upgrade_database_to_version_5(connection)?;
+        }
+
+        if version <= 5 {
+            // Upgrade to version 6.
+            DatabaseManager::upgrade_database_to_version_6(connection)?;
+        }
+
+        if version <= 6 {
+            // Upgrade to version 7.
+            DatabaseManager::upgrade_database_to_version_7(connection)?;
+        }
+
+        if version <= 7 {
+            // Upgrade to version 8.
+            DatabaseManager::upgrade_database_to_version_8(connection)?;
+        }
+
+        if version <= 8 {
+            // Upgrade to version 9.
+            DatabaseManager::upgrade_database_to_version_9(connection)?;
+        }
+
+        if version <= 9 {
+            // Upgrade to version 10.
+            DatabaseManager::upgrade_database_to_version_10(connection)?;
+        }
+
+        if version <= 10 {
+            // Upgrade to version 11.
+            DatabaseManager::upgrade_database_to_version_11(connection)?;
+        }
+
+        if version <= 11 {
+            // Upgrade to version 12.
+            DatabaseManager::upgrade_database_to_version_12(connection)?;
+        }
+
+        if version <= 12 {
+            // Upgrade to version 13.
+            DatabaseManager::upgrade_database_to_version_13(connection)?;
+        }
+
+        if version <= 13 {
+            // Upgrade to version 14.
+            DatabaseManager::upgrade_database_to_version_14(connection)?;
+        }
+
         // Handle old version here.
         // Upgrade old database to the new format here.
         //
@@ -1456,6 +4220,368 @@ impl DatabaseManager {
 
         Ok(())
     }
+    /// Upgrades the database from version `1` to version `2`.
+    ///
+    /// Adds `mime_type` column to the `attachment` table and fills it
+    /// for already existing attachments.
+    fn upgrade_database_to_version_2(connection: &mut Connection) -> Result<(), AppError> {
+        if let Err(e) = connection.execute(
+            &format!(
+                "ALTER TABLE {} ADD COLUMN mime_type TEXT NOT NULL DEFAULT 'application/octet-stream'",
+                ATTACHMENT_TABLE_NAME
+            ),
+            params![],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        // Get file names of existing attachments.
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT id, file_name FROM {}",
+                ATTACHMENT_TABLE_NAME
+            ))
+            .unwrap();
+        let result = stmt.query_map([], |row| {
+            Ok((row.get::<usize, u64>(0)?, row.get::<usize, String>(1)?))
+        });
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let result: Result<Vec<(u64, String)>> = result.unwrap().collect();
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let attachments = result.unwrap();
+        drop(stmt);
+
+        // Set MIME type of existing attachments.
+        for (attachment_id, file_name) in attachments {
+            if let Err(e) = connection.execute(
+                &format!(
+                    "UPDATE {} SET mime_type = ?1 WHERE id = ?2",
+                    ATTACHMENT_TABLE_NAME
+                ),
+                params![get_mime_type_from_file_name(&file_name), attachment_id],
+            ) {
+                return Err(AppError::new(&e.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+    /// Upgrades the database from version `2` to version `3`.
+    ///
+    /// Adds `is_compressed` column to the `attachment` table, already existing
+    /// attachments are marked as not compressed.
+    fn upgrade_database_to_version_3(connection: &mut Connection) -> Result<(), AppError> {
+        if let Err(e) = connection.execute(
+            &format!(
+                "ALTER TABLE {} ADD COLUMN is_compressed INTEGER NOT NULL DEFAULT 0",
+                ATTACHMENT_TABLE_NAME
+            ),
+            params![],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Upgrades the database from version `3` to version `4`.
+    ///
+    /// Adds `priority` column to the `report` table, already existing
+    /// reports receive normal priority.
+    fn upgrade_database_to_version_4(connection: &mut Connection) -> Result<(), AppError> {
+        if let Err(e) = connection.execute(
+            &format!(
+                "ALTER TABLE {} ADD COLUMN priority INTEGER NOT NULL DEFAULT {}",
+                REPORT_TABLE_NAME,
+                ReportPriority::Normal.value()
+            ),
+            params![],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Upgrades the database from version `4` to version `5`.
+    ///
+    /// Adds OTP parameter columns to the `user` table, already existing
+    /// users receive parameters that were used before so that their
+    /// authenticator apps continue to work.
+    fn upgrade_database_to_version_5(connection: &mut Connection) -> Result<(), AppError> {
+        let otp_parameters = OtpParameters::default();
+
+        for (column, column_type, default_value) in [
+            (
+                "otp_algorithm",
+                "TEXT",
+                format!("'{}'", otp_parameters.algorithm),
+            ),
+            ("otp_digits", "INTEGER", otp_parameters.digits.to_string()),
+            (
+                "otp_period",
+                "INTEGER",
+                otp_parameters.period_in_sec.to_string(),
+            ),
+        ] {
+            if let Err(e) = connection.execute(
+                &format!(
+                    "ALTER TABLE {} ADD COLUMN {} {} NOT NULL DEFAULT {}",
+                    USER_TABLE_NAME, column, column_type, default_value
+                ),
+                params![],
+            ) {
+                return Err(AppError::new(&e.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Upgrades the database from version `5` to version `6`.
+    ///
+    /// Replaces `is_admin` column of the `user` table with `role` column,
+    /// admins receive admin role and other users receive viewer role.
+    fn upgrade_database_to_version_6(connection: &mut Connection) -> Result<(), AppError> {
+        let statements = [
+            format!(
+                "ALTER TABLE {} ADD COLUMN role INTEGER NOT NULL DEFAULT {}",
+                USER_TABLE_NAME,
+                UserRole::Viewer.value()
+            ),
+            format!(
+                "UPDATE {} SET role = {} WHERE is_admin = 1",
+                USER_TABLE_NAME,
+                UserRole::Admin.value()
+            ),
+            format!("ALTER TABLE {} DROP COLUMN is_admin", USER_TABLE_NAME),
+        ];
+
+        for statement in statements {
+            if let Err(e) = connection.execute(&statement, params![]) {
+                return Err(AppError::new(&e.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Upgrades the database from version `6` to version `7`.
+    ///
+    /// Adds columns used to merge duplicate reports to the `report` table,
+    /// content hash is calculated for already existing reports (already existing
+    /// duplicates are not merged).
+    fn upgrade_database_to_version_7(connection: &mut Connection) -> Result<(), AppError> {
+        let statements = [
+            format!(
+                "ALTER TABLE {} ADD COLUMN content_hash TEXT NOT NULL DEFAULT ''",
+                REPORT_TABLE_NAME
+            ),
+            format!(
+                "ALTER TABLE {} ADD COLUMN occurrence_count INTEGER NOT NULL DEFAULT 1",
+                REPORT_TABLE_NAME
+            ),
+            format!(
+                "ALTER TABLE {} ADD COLUMN last_seen_date TEXT NOT NULL DEFAULT ''",
+                REPORT_TABLE_NAME
+            ),
+            format!(
+                "ALTER TABLE {} ADD COLUMN last_seen_time TEXT NOT NULL DEFAULT ''",
+                REPORT_TABLE_NAME
+            ),
+            format!(
+                "UPDATE {} SET last_seen_date = date_created_at, last_seen_time = time_created_at",
+                REPORT_TABLE_NAME
+            ),
+        ];
+
+        for statement in statements {
+            if let Err(e) = connection.execute(&statement, params![]) {
+                return Err(AppError::new(&e.to_string()));
+            }
+        }
+
+        // Get name and text of existing reports.
+        let mut stmt = connection
+            .prepare(&format!(
+                "SELECT id, report_name, report_text FROM {}",
+                REPORT_TABLE_NAME
+            ))
+            .unwrap();
+        let result = stmt.query_map([], |row| {
+            Ok((
+                row.get::<usize, u64>(0)?,
+                row.get::<usize, String>(1)?,
+                row.get::<usize, String>(2)?,
+            ))
+        });
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let result: Result<Vec<(u64, String, String)>> = result.unwrap().collect();
+        if let Err(e) = result {
+            return Err(AppError::new(&e.to_string()));
+        }
+        let reports = result.unwrap();
+        drop(stmt);
+
+        // Set content hash of existing reports.
+        for (report_id, report_name, report_text) in reports {
+            if let Err(e) = connection.execute(
+                &format!(
+                    "UPDATE {} SET content_hash = ?1 WHERE id = ?2",
+                    REPORT_TABLE_NAME
+                ),
+                params![
+                    Self::get_report_content_hash(&report_name, &report_text),
+                    report_id
+                ],
+            ) {
+                return Err(AppError::new(&e.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+    /// Adds `thumbnail` column to the `attachment` table, already existing
+    /// attachments are left without thumbnails.
+    fn upgrade_database_to_version_8(connection: &mut Connection) -> Result<(), AppError> {
+        if let Err(e) = connection.execute(
+            &format!(
+                "ALTER TABLE {} ADD COLUMN thumbnail BLOB",
+                ATTACHMENT_TABLE_NAME
+            ),
+            params![],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Adds columns with reporter versions to the `report` table
+    /// (unknown for already existing reports).
+    fn upgrade_database_to_version_9(connection: &mut Connection) -> Result<(), AppError> {
+        let statements = [
+            format!(
+                "ALTER TABLE {} ADD COLUMN reporter_net_protocol INTEGER NOT NULL DEFAULT 0",
+                REPORT_TABLE_NAME
+            ),
+            format!(
+                "ALTER TABLE {} ADD COLUMN reporter_version TEXT NOT NULL DEFAULT ''",
+                REPORT_TABLE_NAME
+            ),
+        ];
+
+        for statement in statements {
+            if let Err(e) = connection.execute(&statement, params![]) {
+                return Err(AppError::new(&e.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+    /// Adds `password_changed_at` column to the `user` table, passwords of already
+    /// existing users are considered to be changed during the upgrade.
+    fn upgrade_database_to_version_10(connection: &mut Connection) -> Result<(), AppError> {
+        let statements = [
+            format!(
+                "ALTER TABLE {} ADD COLUMN password_changed_at INTEGER NOT NULL DEFAULT 0",
+                USER_TABLE_NAME
+            ),
+            format!(
+                "UPDATE {} SET password_changed_at = {}",
+                USER_TABLE_NAME,
+                Local::now().timestamp()
+            ),
+        ];
+
+        for statement in statements {
+            if let Err(e) = connection.execute(&statement, params![]) {
+                return Err(AppError::new(&e.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+    /// Adds `email` and `email_verified` columns to the `user` table
+    /// (already existing users don't have an email).
+    fn upgrade_database_to_version_11(connection: &mut Connection) -> Result<(), AppError> {
+        let statements = [
+            format!(
+                "ALTER TABLE {} ADD COLUMN email TEXT NOT NULL DEFAULT ''",
+                USER_TABLE_NAME
+            ),
+            format!(
+                "ALTER TABLE {} ADD COLUMN email_verified INTEGER NOT NULL DEFAULT 0",
+                USER_TABLE_NAME
+            ),
+        ];
+
+        for statement in statements {
+            if let Err(e) = connection.execute(&statement, params![]) {
+                return Err(AppError::new(&e.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds `is_expired` column to the `attachment` table, already existing
+    /// attachments are not expired.
+    fn upgrade_database_to_version_12(connection: &mut Connection) -> Result<(), AppError> {
+        if let Err(e) = connection.execute(
+            &format!(
+                "ALTER TABLE {} ADD COLUMN is_expired INTEGER NOT NULL DEFAULT 0",
+                ATTACHMENT_TABLE_NAME
+            ),
+            params![],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Adds `is_markdown` column to the `report` table (already existing reports
+    /// are plain text).
+    fn upgrade_database_to_version_13(connection: &mut Connection) -> Result<(), AppError> {
+        if let Err(e) = connection.execute(
+            &format!(
+                "ALTER TABLE {} ADD COLUMN is_markdown INTEGER NOT NULL DEFAULT 0",
+                REPORT_TABLE_NAME
+            ),
+            params![],
+        ) {
+            return Err(AppError::new(&e.to_string()));
+        }
+
+        Ok(())
+    }
+    /// Adds columns with game session info to the `report` table
+    /// (unknown for already existing reports).
+    fn upgrade_database_to_version_14(connection: &mut Connection) -> Result<(), AppError> {
+        let statements = [
+            format!(
+                "ALTER TABLE {} ADD COLUMN session_id TEXT NOT NULL DEFAULT ''",
+                REPORT_TABLE_NAME
+            ),
+            format!(
+                "ALTER TABLE {} ADD COLUMN playtime_seconds INTEGER NOT NULL DEFAULT 0",
+                REPORT_TABLE_NAME
+            ),
+        ];
+
+        for statement in statements {
+            if let Err(e) = connection.execute(&statement, params![]) {
+                return Err(AppError::new(&e.to_string()));
+            }
+        }
+
+        Ok(())
+    }
 
     pub fn get_database_location() -> PathBuf {
         #[cfg(any(windows, unix))]
@@ -1474,4 +4600,23 @@ impl DatabaseManager {
             compile_error!("DatabaseManager is not implemented for this OS.");
         }
     }
+    /// Returns the size (in bytes) that the database takes on the disk: the database file
+    /// and the `-wal` and `-shm` files that SQLite uses in WAL mode
+    /// (recently written data is stored in the `-wal` file until a checkpoint).
+    ///
+    /// ## Remarks
+    /// Files that don't exist (yet) are not counted.
+    pub fn get_database_files_size() -> u64 {
+        let database_location = Self::get_database_location();
+
+        ["", "-wal", "-shm"]
+            .iter()
+            .filter_map(|suffix| {
+                let mut path = database_location.clone().into_os_string();
+                path.push(suffix);
+                std::fs::metadata(path).ok()
+            })
+            .map(|metadata| metadata.len())
+            .sum()
+    }
 }
@@ -4,19 +4,47 @@ use std::fmt::Display;
 // External.
 use backtrace::Backtrace;
 
+/// Describes what kind of problem an `AppError` represents
+/// (use this instead of looking for words in the error message).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppErrorKind {
+    /// The server name could not be resolved to an IP address.
+    AddressNotResolved,
+    /// The remote side refused the connection (nothing is listening on the port).
+    ConnectionRefused,
+    /// The remote side closed (or reset) the connection.
+    ConnectionClosed,
+    /// The remote side did not respond in time.
+    Timeout,
+    /// The remote side sent something unexpected (corrupted, too big or unknown message).
+    Protocol,
+    /// Any other error.
+    Internal,
+}
+
 #[derive(Debug)]
 pub struct AppError {
+    kind: AppErrorKind,
     message: String,
     backtrace: Backtrace,
 }
 
 impl AppError {
+    /// Creates a new error of the `Internal` kind.
     pub fn new(message: &str) -> Self {
+        Self::with_kind(AppErrorKind::Internal, message)
+    }
+    /// Creates a new error of the specified kind.
+    pub fn with_kind(kind: AppErrorKind, message: &str) -> Self {
         Self {
+            kind,
             message: String::from(message),
             backtrace: Backtrace::new(),
         }
     }
+    pub fn get_kind(&self) -> AppErrorKind {
+        self.kind
+    }
     pub fn get_message(&self) -> String {
         self.message.clone()
     }
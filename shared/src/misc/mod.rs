@@ -1,4 +1,6 @@
 #[cfg(feature = "database")]
 pub mod db_manager;
 pub mod error;
+pub mod password_policy;
 pub mod report;
+pub mod user;
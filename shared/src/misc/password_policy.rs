@@ -0,0 +1,126 @@
+// External.
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+
+/// Passwords that are rejected if `reject_common_passwords` is enabled
+/// (compared without case).
+const COMMON_PASSWORDS: [&str; 40] = [
+    "123456",
+    "123456789",
+    "12345678",
+    "1234567890",
+    "12345",
+    "1234567",
+    "111111",
+    "000000",
+    "123123",
+    "654321",
+    "666666",
+    "121212",
+    "112233",
+    "password",
+    "password1",
+    "password123",
+    "passw0rd",
+    "qwerty",
+    "qwerty123",
+    "qwertyuiop",
+    "1q2w3e4r",
+    "1qaz2wsx",
+    "asdfghjkl",
+    "abc123",
+    "abcd1234",
+    "iloveyou",
+    "admin",
+    "admin123",
+    "administrator",
+    "root",
+    "letmein",
+    "welcome",
+    "welcome1",
+    "monkey",
+    "dragon",
+    "football",
+    "sunshine",
+    "princess",
+    "master",
+    "changeme",
+];
+
+/// Requirements for new passwords.
+///
+/// ## Remarks
+/// The server only receives hashes of the passwords so the length and the characters
+/// are checked by the client, the server only checks that the password is not common.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordPolicy {
+    /// Minimum number of characters in the password.
+    pub min_length: usize,
+    /// Whether the password should contain letters and digits.
+    pub require_letters_and_digits: bool,
+    /// Whether the password should not be one of the commonly used passwords.
+    pub reject_common_passwords: bool,
+}
+
+impl PasswordPolicy {
+    /// Checks that the password satisfies the policy.
+    ///
+    /// ## Return
+    /// `None` if the password is fine, otherwise a message that describes the problem.
+    pub fn check(&self, password: &str) -> Option<String> {
+        if password.chars().count() < self.min_length {
+            return Some(format!(
+                "The password should contain at least {} characters.",
+                self.min_length
+            ));
+        }
+
+        if self.require_letters_and_digits
+            && !(password.chars().any(|c| c.is_alphabetic())
+                && password.chars().any(|c| c.is_numeric()))
+        {
+            return Some(String::from(
+                "The password should contain both letters and digits.",
+            ));
+        }
+
+        if self.reject_common_passwords
+            && COMMON_PASSWORDS.contains(&password.to_lowercase().as_str())
+        {
+            return Some(String::from(
+                "This password is too common, please, use another one.",
+            ));
+        }
+
+        None
+    }
+    /// Tells if the password hash (SHA-512 of the password, as sent by the client)
+    /// is a hash of one of the common passwords.
+    ///
+    /// ## Remarks
+    /// Since hashes can't be compared without case only lowercase variants are checked.
+    pub fn is_common_password_hash(password_hash: &[u8]) -> bool {
+        COMMON_PASSWORDS.iter().any(|password| {
+            let mut hasher = Sha512::new();
+            hasher.update(password.as_bytes());
+            hasher.finalize().as_slice() == password_hash
+        })
+    }
+    /// Returns a human-readable description of the policy.
+    pub fn describe(&self) -> String {
+        let mut description = format!(
+            "The password should contain at least {} characters",
+            self.min_length
+        );
+
+        if self.require_letters_and_digits {
+            description += ", both letters and digits";
+        }
+
+        if self.reject_common_passwords {
+            description += " and should not be a commonly used password";
+        }
+
+        description + "."
+    }
+}
@@ -1,4 +1,5 @@
 // Std.
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
 // External.
@@ -12,6 +13,47 @@ pub struct ReportSummary {
     pub game: String,
     pub date: String,
     pub time: String,
+    pub priority: ReportPriority,
+    /// How many times this report was received (duplicate reports are merged).
+    pub occurrence_count: u64,
+    /// Whether the user that requested reports did not read (open) this report yet.
+    pub is_unread: bool,
+}
+
+/// Describes how important a report is.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default, EnumString, Display)]
+pub enum ReportPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Critical,
+}
+
+impl ReportPriority {
+    /// Returns a value that is used to store the priority in the database
+    /// (higher value means higher priority).
+    pub fn value(&self) -> i32 {
+        match &self {
+            ReportPriority::Low => 0,
+            ReportPriority::Normal => 1,
+            ReportPriority::High => 2,
+            ReportPriority::Critical => 3,
+        }
+    }
+    /// Converts a value from `value()` back to priority.
+    ///
+    /// ## Return
+    /// `None` if the value is not a valid priority.
+    pub fn from_value(value: i32) -> Option<ReportPriority> {
+        match value {
+            0 => Some(ReportPriority::Low),
+            1 => Some(ReportPriority::Normal),
+            2 => Some(ReportPriority::High),
+            3 => Some(ReportPriority::Critical),
+            _ => None,
+        }
+    }
 }
 
 /// Represents a report that the reporter sends.
@@ -24,6 +66,17 @@ pub struct GameReport {
     pub game_name: String,
     pub game_version: String,
     pub client_os_info: os_info::Info,
+    pub priority: ReportPriority,
+    /// Custom key/value pairs set by the game (see `REPORT_METADATA_MAX_ENTRY_COUNT`).
+    pub metadata: BTreeMap<String, String>,
+    /// Version of the reporter that sent the report.
+    pub reporter_version: String,
+    /// Whether the report text is written in Markdown (otherwise it's plain text).
+    pub is_markdown: bool,
+    /// ID of the game session the report was sent from (empty if not specified).
+    pub session_id: String,
+    /// How long the player played before sending the report (`0` if not specified).
+    pub playtime_seconds: u64,
     // if adding new stuff here
     // also add its limit to the ReportLimits enum (in reporter and server)
     // and update the NETWORK_PROTOCOL_VERSION
@@ -32,6 +85,7 @@ pub struct GameReport {
 
 /// Represents a report that we store in the database and send
 /// to clients.
+#[derive(Serialize)]
 pub struct ReportData {
     pub id: u64,
     pub title: String,
@@ -43,7 +97,48 @@ pub struct ReportData {
     pub sender_name: String,
     pub sender_email: String,
     pub os_info: String,
+    pub priority: ReportPriority,
     pub attachments: Vec<ReportAttachmentSummary>,
+    pub metadata: BTreeMap<String, String>,
+    /// Network protocol version of the reporter that sent the report (`0` if unknown).
+    pub reporter_net_protocol: u16,
+    /// Version of the reporter that sent the report (empty if unknown).
+    pub reporter_version: String,
+    /// Whether the report text is written in Markdown (otherwise it's plain text).
+    pub is_markdown: bool,
+    /// ID of the game session the report was sent from (empty if not specified).
+    pub session_id: String,
+    /// How long the player played before sending the report (`0` if not specified).
+    pub playtime_seconds: u64,
+}
+
+/// Maximum amount of metadata entries (key/value pairs) allowed in a report.
+pub const REPORT_METADATA_MAX_ENTRY_COUNT: usize = 20;
+
+/// Maximum playtime (in seconds) allowed in a report (the database stores signed 64-bit integers).
+pub const REPORT_MAX_PLAYTIME_SECONDS: u64 = i64::MAX as u64;
+
+/// Maximum amount of __characters__ allowed in a report comment.
+pub const REPORT_COMMENT_MAX_LENGTH: usize = 2048;
+
+/// Amount of received reports grouped by game and by day.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ReportStats {
+    /// Game name and the number of reports (all time), games with more reports go first.
+    pub per_game: Vec<(String, u64)>,
+    /// Date (YYYY-MM-DD) and the number of reports received that day,
+    /// contains all days of the requested period (oldest first).
+    pub per_day: Vec<(String, u64)>,
+}
+
+/// A comment that a user left on a report.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ReportComment {
+    pub id: u64,
+    pub author: String,
+    pub text: String,
+    pub date: String,
+    pub time: String,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -51,6 +146,13 @@ pub struct ReportAttachmentSummary {
     pub id: usize,
     pub file_name: String,
     pub size_in_bytes: usize,
+    pub mime_type: String,
+    /// Small PNG preview of the image (`None` if the attachment is not an image
+    /// or was saved before thumbnails were introduced).
+    pub thumbnail: Option<Vec<u8>>,
+    /// Whether the data of the attachment was removed because it was too old
+    /// (such attachments can't be downloaded).
+    pub is_expired: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -59,7 +161,31 @@ pub struct ReportAttachment {
     pub data: Vec<u8>,
 }
 
-#[derive(Debug, EnumString, Display)]
+/// Determines attachment's MIME type using its file extension.
+///
+/// ## Return
+/// `application/octet-stream` if the extension is unknown.
+pub fn get_mime_type_from_file_name(file_name: &str) -> &'static str {
+    let extension = match file_name.rsplit_once('.') {
+        Some((_, extension)) => extension.to_lowercase(),
+        None => return "application/octet-stream",
+    };
+
+    match extension.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "bmp" => "image/bmp",
+        "webp" => "image/webp",
+        "txt" | "log" | "cfg" | "ini" => "text/plain",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, EnumString, Display)]
 pub enum ReportLimits {
     ReportName,
     ReportText,
@@ -67,6 +193,9 @@ pub enum ReportLimits {
     SenderEmail,
     GameName,
     GameVersion,
+    MetadataKey,
+    MetadataValue,
+    SessionId,
 }
 impl ReportLimits {
     /// Returns the maximum amount of __characters__ allowed for the field.
@@ -78,11 +207,32 @@ impl ReportLimits {
             ReportLimits::SenderEmail => 50,
             ReportLimits::GameName => 50,
             ReportLimits::GameVersion => 50,
+            ReportLimits::MetadataKey => 50, // limits of each metadata entry
+            ReportLimits::MetadataValue => 500,
+            ReportLimits::SessionId => 100,
             // if adding new fields, update is_input_valid() in lib.rs (in reporter)
             // also update get_field_limit()
             // also update/add get_field_limit() calls in 'example'
         }
     }
+    /// Returns the minimum amount of __characters__ required for the field by default.
+    ///
+    /// ## Remarks
+    /// The minimum length of the report text can be changed in the reporter
+    /// (`set_min_report_text_length`) and in the server config (`min_report_text_length`).
+    pub fn default_min_length(&self) -> usize {
+        match *self {
+            ReportLimits::ReportName
+            | ReportLimits::ReportText
+            | ReportLimits::SenderName
+            | ReportLimits::SenderEmail
+            | ReportLimits::GameName
+            | ReportLimits::GameVersion
+            | ReportLimits::MetadataKey
+            | ReportLimits::MetadataValue
+            | ReportLimits::SessionId => 0,
+        }
+    }
     pub fn from_string(name: &str) -> Option<ReportLimits> {
         let result = ReportLimits::from_str(name);
         if let Err(_) = result {
@@ -94,15 +244,34 @@ impl ReportLimits {
 }
 
 /// Values that the reporter returns into the game engine.
+///
+/// Games receive the number from `value` so existing numbers should never change
+/// (new entries get new numbers).
 #[derive(PartialEq, Debug, Clone)]
 pub enum ReportResult {
     Ok,
     ServerNotSet,
+    /// A report field is too long or the report text is too short (checked by both
+    /// the reporter and the server), `get_last_error` returns the name of the field.
     InvalidInput,
     CouldNotConnect,
     AttachmentDoesNotExist,
     AttachmentTooBig,
+    /// Any other error (see `get_last_error` for the description).
     Other(String),
+    /// The server does not accept reports from this game version.
+    VersionRejected,
+    /// The server rejected the report (see `get_last_error` for the reason).
+    ServerRejected,
+    /// The server did not accept the proof of work.
+    InvalidProofOfWork,
+    /// The same sender sent a report recently (see `get_last_error` for details).
+    RateLimited,
+    /// The server failed to save the report (try again later).
+    ServerError,
+    /// The connection to the server was lost or timed out while sending the report
+    /// (try again later).
+    ConnectionLost,
     // make sure to handle new entries in the 'example' project
 }
 
@@ -116,6 +285,12 @@ impl ReportResult {
             ReportResult::AttachmentDoesNotExist => 4,
             ReportResult::AttachmentTooBig => 5,
             ReportResult::Other(_) => 6,
+            ReportResult::VersionRejected => 7,
+            ReportResult::ServerRejected => 8,
+            ReportResult::InvalidProofOfWork => 9,
+            ReportResult::RateLimited => 10,
+            ReportResult::ServerError => 11,
+            ReportResult::ConnectionLost => 12,
         }
     }
 }
@@ -0,0 +1,47 @@
+// External.
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+/// Describes what a user can do using the client application.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default, EnumString, Display)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum UserRole {
+    /// Can only view reports.
+    #[default]
+    Viewer,
+    /// Can also change reports (for example, priority) but can't delete them.
+    Triager,
+    /// Can do everything.
+    Admin,
+}
+
+impl UserRole {
+    /// Returns a value that is used to store the role in the database.
+    pub fn value(&self) -> i32 {
+        match &self {
+            UserRole::Viewer => 0,
+            UserRole::Triager => 1,
+            UserRole::Admin => 2,
+        }
+    }
+    /// Converts a value from `value()` back to role.
+    ///
+    /// ## Return
+    /// `None` if the value is not a valid role.
+    pub fn from_value(value: i32) -> Option<UserRole> {
+        match value {
+            0 => Some(UserRole::Viewer),
+            1 => Some(UserRole::Triager),
+            2 => Some(UserRole::Admin),
+            _ => None,
+        }
+    }
+    /// Whether users with this role can change reports (for example, priority).
+    pub fn can_change_reports(&self) -> bool {
+        *self == UserRole::Triager || *self == UserRole::Admin
+    }
+    /// Whether users with this role can delete reports.
+    pub fn can_delete_reports(&self) -> bool {
+        *self == UserRole::Admin
+    }
+}
@@ -1,12 +1,21 @@
+// Std.
+use std::collections::BTreeMap;
+
 // External.
 use serde::{Deserialize, Serialize};
+use strum_macros::IntoStaticStr;
 
 // Custom.
-use crate::misc::report::{ReportAttachmentSummary, ReportSummary};
+use crate::misc::password_policy::PasswordPolicy;
+use crate::misc::report::{
+    ReportAttachmentSummary, ReportComment, ReportPriority, ReportStats, ReportSummary,
+};
+use crate::misc::user::UserRole;
 
 /// Client's request to the server.
 /// If made changes, change protocol version.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum ClientRequest {
     Login {
         client_net_protocol: u16,
@@ -23,6 +32,8 @@ pub enum ClientRequest {
     QueryReportsSummary {
         page: u64,
         amount: u64,
+        sort_by_priority: bool,
+        unread_only: bool,
     },
     QueryReport {
         report_id: u64,
@@ -33,16 +44,56 @@ pub enum ClientRequest {
     DeleteReport {
         report_id: u64,
     },
+    SetReportPriority {
+        report_id: u64,
+        priority: ReportPriority,
+    },
+    QueryReportComments {
+        report_id: u64,
+    },
+    AddReportComment {
+        report_id: u64,
+        text: String,
+    },
+    /// Asks for the server's network protocol version and application version
+    /// (can be sent without logging in, the server closes the connection after answering).
+    ///
+    /// Keep the position of this entry so that older/newer clients can still
+    /// detect a protocol mismatch.
+    QueryServerInfo,
+    /// Asks to restore a session that was closed recently (for example, due to inactivity
+    /// or network issues) without asking the user for password and OTP again.
+    ///
+    /// The server answers with `ClientAnswer::LoginAnswer` (that contains a new token).
+    ResumeSession {
+        client_net_protocol: u16,
+        session_token: String,
+    },
+    /// Tells the server that the user logged out so that the session token of this
+    /// session can no longer be used (the server closes the connection).
+    Logout,
+    /// Sent periodically while the client is open so that the server does not
+    /// disconnect it due to inactivity (the server does not answer).
+    KeepAlive,
+    /// Asks for the number of reports per game and per day for the specified
+    /// number of last days.
+    QueryStats {
+        day_count: u64,
+    },
 }
 
 /// Server's answer to the client.
 /// If made changes, change protocol version.
 #[derive(Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)] // answers are short-lived, boxing would only complicate matching
 pub enum ClientAnswer {
     LoginAnswer {
         is_ok: bool,
-        is_admin: bool,
+        is_admin: bool, // kept so that old clients can read "wrong protocol" answer, use `role`
         fail_reason: Option<ClientLoginFailReason>,
+        otp_backup_codes: Vec<String>, // not empty only once OTP setup is finished
+        role: UserRole,
+        session_token: String, // not empty only if logged in, used in `ClientRequest::ResumeSession`
     },
     ReportsSummary {
         reports: Vec<ReportSummary>,
@@ -61,7 +112,14 @@ pub enum ClientAnswer {
         sender_name: String,
         sender_email: String,
         os_info: String,
+        priority: ReportPriority,
         attachments: Vec<ReportAttachmentSummary>,
+        metadata: BTreeMap<String, String>,
+        reporter_net_protocol: u16,
+        reporter_version: String,
+        is_markdown: bool,
+        session_id: String,
+        playtime_seconds: u64,
     },
     Attachment {
         is_found: bool,
@@ -70,6 +128,23 @@ pub enum ClientAnswer {
     DeleteReportResult {
         is_found_and_removed: bool,
     },
+    SetReportPriorityResult {
+        is_found_and_changed: bool,
+    },
+    ReportComments {
+        comments: Vec<ReportComment>,
+    },
+    AddReportCommentResult {
+        is_found_and_added: bool,
+    },
+    /// Answer to `ClientRequest::QueryServerInfo` (keep the position of this entry).
+    ServerInfo {
+        net_protocol: u16,
+        server_version: String,
+    },
+    Stats {
+        stats: ReportStats,
+    },
 }
 
 /// If made changes, change protocol version.
@@ -89,7 +164,10 @@ pub enum ClientLoginFailResult {
 pub enum ClientLoginFailReason {
     WrongProtocol { server_protocol: u16 },
     WrongCredentials { result: ClientLoginFailResult },
-    NeedFirstPassword, // user just registered, we are waiting for a new password to set
+    NeedFirstPassword { password_policy: PasswordPolicy }, // waiting for a new password to set
+    WeakPassword { reason: String }, // new password does not satisfy the password policy
     NeedOTP,
     SetupOTP { qr_code: String },
+    SessionExpired, // session token is wrong or expired, need to login using password and OTP
+    ServerBusy,     // maximum number of concurrent client connections was reached, try again later
 }
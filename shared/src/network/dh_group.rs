@@ -0,0 +1,88 @@
+// External.
+use num_bigint::BigUint;
+use strum_macros::{Display, EnumString};
+
+/// Prime of the 2048-bit group from https://www.rfc-editor.org/rfc/rfc5114#section-2.1
+const RFC5114_2048_P: &[u8] = b"B10B8F96A080E01DDE92DE5EAE5D54EC52C99FBCFB06A3C69A6A9DCA52D23B616073E28675A23D189838EF1E2EE652C0\
+    13ECB4AEA906112324975C3CD49B83BFACCBDD7D90C4BD7098488E9C219A73724EFFD6FAE5644738FAA31A4FF55BCCC0\
+    A151AF5F0DC8B4BD45BF37DF365C1A65E68CFDA76D4DA708DF1FB2BC2E4A4371";
+/// Generator of the 2048-bit group from https://www.rfc-editor.org/rfc/rfc5114#section-2.1
+const RFC5114_2048_G: &[u8] = b"A4D1CBD5C3FD34126765A442EFB99905F8104DD258AC507FD6406CFF14266D31266FEA1E5C41564B777E690F5504F213\
+    160217B4B01B886A5E91547F9E2749F4D7FBD7D3B9A92EE1909D0D2263F80A76A6A24C087A091F531DBF0A0169B6A28A\
+    D662A4D18E73AFA32D779D5918D08BC8858F4DCEF97C2A24855E6EEB22B3B2E5";
+
+/// Prime of "ffdhe3072" group from https://www.rfc-editor.org/rfc/rfc7919#appendix-A.2
+const FFDHE3072_P: &[u8] = b"FFFFFFFFFFFFFFFFADF85458A2BB4A9AAFDC5620273D3CF1D8B9C583CE2D3695A9E13641146433FBCC939DCE249B3EF9\
+    7D2FE363630C75D8F681B202AEC4617AD3DF1ED5D5FD65612433F51F5F066ED0856365553DED1AF3B557135E7F57C935\
+    984F0C70E0E68B77E2A689DAF3EFE8721DF158A136ADE73530ACCA4F483A797ABC0AB182B324FB61D108A94BB2C8E3FB\
+    B96ADAB760D7F4681D4F42A3DE394DF4AE56EDE76372BB190B07A7C8EE0A6D709E02FCE1CDF7E2ECC03404CD28342F61\
+    9172FE9CE98583FF8E4F1232EEF28183C3FE3B1B4C6FAD733BB5FCBC2EC22005C58EF1837D1683B2C6F34A26C1B2EFFA\
+    886B4238611FCFDCDE355B3B6519035BBC34F4DEF99C023861B46FC9D6E6C9077AD91D2691F7F7EE598CB0FAC186D91C\
+    AEFE130985139270B4130C93BC437944F4FD4452E2D74DD364F2E21E71F54BFF5CAE82AB9C9DF69EE86D2BC522363A0D\
+    ABC521979B0DEADA1DBF9A42D5C4484E0ABCD06BFA53DDEF3C1B20EE3FD59D7C25E41D2B66C62E37FFFFFFFFFFFFFFFF";
+/// Prime of "ffdhe4096" group from https://www.rfc-editor.org/rfc/rfc7919#appendix-A.3
+const FFDHE4096_P: &[u8] = b"FFFFFFFFFFFFFFFFADF85458A2BB4A9AAFDC5620273D3CF1D8B9C583CE2D3695A9E13641146433FBCC939DCE249B3EF9\
+    7D2FE363630C75D8F681B202AEC4617AD3DF1ED5D5FD65612433F51F5F066ED0856365553DED1AF3B557135E7F57C935\
+    984F0C70E0E68B77E2A689DAF3EFE8721DF158A136ADE73530ACCA4F483A797ABC0AB182B324FB61D108A94BB2C8E3FB\
+    B96ADAB760D7F4681D4F42A3DE394DF4AE56EDE76372BB190B07A7C8EE0A6D709E02FCE1CDF7E2ECC03404CD28342F61\
+    9172FE9CE98583FF8E4F1232EEF28183C3FE3B1B4C6FAD733BB5FCBC2EC22005C58EF1837D1683B2C6F34A26C1B2EFFA\
+    886B4238611FCFDCDE355B3B6519035BBC34F4DEF99C023861B46FC9D6E6C9077AD91D2691F7F7EE598CB0FAC186D91C\
+    AEFE130985139270B4130C93BC437944F4FD4452E2D74DD364F2E21E71F54BFF5CAE82AB9C9DF69EE86D2BC522363A0D\
+    ABC521979B0DEADA1DBF9A42D5C4484E0ABCD06BFA53DDEF3C1B20EE3FD59D7C25E41D2B669E1EF16E6F52C3164DF4FB\
+    7930E9E4E58857B6AC7D5F42D69F6D187763CF1D5503400487F55BA57E31CC7A7135C886EFB4318AED6A1E012D9E6832\
+    A907600A918130C46DC778F971AD0038092999A333CB8B7A1A1DB93D7140003C2A4ECEA9F98D0ACC0A8291CDCEC97DCF\
+    8EC9B55A7F88A46B4DB5A851F44182E1C68A007E5E655F6AFFFFFFFFFFFFFFFF";
+/// Generator of all "ffdhe" groups.
+const FFDHE_G: &[u8] = b"2";
+
+/// Diffie-Hellman group that is used to establish a secure connection.
+///
+/// ## Remarks
+/// The side that starts establishing a secure connection (the server) picks the group
+/// and the other side only accepts the groups listed here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumString, Display)]
+pub enum DhGroup {
+    /// 2048-bit group from RFC 5114.
+    #[default]
+    #[strum(serialize = "rfc5114_2048")]
+    Rfc5114_2048,
+    /// 3072-bit group from RFC 7919.
+    #[strum(serialize = "ffdhe3072")]
+    Ffdhe3072,
+    /// 4096-bit group from RFC 7919.
+    #[strum(serialize = "ffdhe4096")]
+    Ffdhe4096,
+}
+
+impl DhGroup {
+    /// All supported groups.
+    pub const ALL: [DhGroup; 3] = [
+        DhGroup::Rfc5114_2048,
+        DhGroup::Ffdhe3072,
+        DhGroup::Ffdhe4096,
+    ];
+
+    /// Returns the prime modulus `p` and the generator `g` of the group.
+    pub fn get_parameters(&self) -> (BigUint, BigUint) {
+        let (p, g) = match self {
+            DhGroup::Rfc5114_2048 => (RFC5114_2048_P, RFC5114_2048_G),
+            DhGroup::Ffdhe3072 => (FFDHE3072_P, FFDHE_G),
+            DhGroup::Ffdhe4096 => (FFDHE4096_P, FFDHE_G),
+        };
+
+        (
+            BigUint::parse_bytes(p, 16).unwrap(),
+            BigUint::parse_bytes(g, 16).unwrap(),
+        )
+    }
+    /// Looks for a supported group with the specified parameters.
+    ///
+    /// ## Return
+    /// `None` if the parameters don't belong to any supported group.
+    pub fn from_parameters(p: &BigUint, g: &BigUint) -> Option<DhGroup> {
+        DhGroup::ALL.into_iter().find(|group| {
+            let (group_p, group_g) = group.get_parameters();
+            group_p == *p && group_g == *g
+        })
+    }
+}
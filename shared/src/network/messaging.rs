@@ -5,13 +5,16 @@ use std::thread;
 use std::time::Duration;
 
 // Custom.
+use super::dh_group::DhGroup;
 use super::net_params::*;
+use super::server_identity::*;
 use crate::misc::error::*;
 
 // External.
 use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, BlockEncryptMut, KeyIvInit};
 use aes::Aes256;
 use cmac::{Cmac, Mac};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
 use num_bigint::{BigUint, RandomBits};
 use rand::{Rng, RngCore};
 use serde::Serialize;
@@ -21,17 +24,49 @@ type Aes256CbcDec = cbc::Decryptor<aes::Aes256>;
 
 // ---------- if changed one change other --------------
 type MessageLenType = u32; // maximum message size is 4 GB, but the server has its own limit
-const MAX_MESSAGE_LEN: usize = std::u32::MAX as usize;
+pub const MAX_MESSAGE_LEN: usize = std::u32::MAX as usize;
 // ---------------------------------------------------------
 
 /// If total message size exceeds this value it will be split into smaller
 /// chunks and send in chunks.
 const MAX_MESSAGE_SIZE_UNTIL_SPLITING_IN_BYTES: usize = 8192;
 
+/// Messages smaller than this value are not compressed
+/// since compression will not save much space.
+const MIN_MESSAGE_SIZE_TO_COMPRESS_IN_BYTES: usize = 512;
+
+// ---------- if changed, change protocol version --------------
+/// First byte of every message that tells if the message is compressed or not.
+const MESSAGE_FLAG_UNCOMPRESSED: u8 = 0;
+const MESSAGE_FLAG_COMPRESSED: u8 = 1;
+// ---------------------------------------------------------
+
 const CONNECTION_RESET_DESC_MESSAGE: &str =
     "the connection was unexpectedly closed by the remote entity, \
                         this might mean that the remote entity found something wrong in \
                         your message (too big, corrupted, etc.)";
+/// Connection that messages are sent over (a TCP socket or, for example, an HTTP session).
+///
+/// ## Remarks
+/// Reading and writing should not block, when no data is available (yet)
+/// `std::io::ErrorKind::WouldBlock` should be returned.
+pub trait MessageStream: Read + Write {
+    /// Returns a string that describes the remote entity (used in error messages).
+    fn get_peer_name(&self) -> std::io::Result<String>;
+}
+
+impl MessageStream for TcpStream {
+    fn get_peer_name(&self) -> std::io::Result<String> {
+        self.peer_addr().map(|addr| addr.to_string())
+    }
+}
+
+impl<S: MessageStream + ?Sized> MessageStream for Box<S> {
+    fn get_peer_name(&self) -> std::io::Result<String> {
+        (**self).get_peer_name()
+    }
+}
+
 enum IoResult {
     Ok(usize),
     Fin,
@@ -44,17 +79,19 @@ enum IoResult {
 ///
 /// Generates a secret key that will be used to encrypt network messages.
 ///
+/// ## Arguments
+/// * `socket`: socket of the remote.
+/// * `dh_group`: Diffie-Hellman group to use (the remote rejects unknown groups).
+/// * `server_identity`: key pair that is used to sign p, g and A values
+///   (the remote can check that it's connected to the real server).
+///
 /// Returns `Ok(Vec<u8>)` with the secret key if no errors occurred.
-pub fn start_establishing_secure_connection(socket: &mut TcpStream) -> Result<Vec<u8>, AppError> {
-    // taken from https://www.rfc-editor.org/rfc/rfc5114#section-2.1
-    let p = BigUint::parse_bytes(
-            b"B10B8F96A080E01DDE92DE5EAE5D54EC52C99FBCFB06A3C69A6A9DCA52D23B616073E28675A23D189838EF1E2EE652C013ECB4AEA906112324975C3CD49B83BFACCBDD7D90C4BD7098488E9C219A73724EFFD6FAE5644738FAA31A4FF55BCCC0A151AF5F0DC8B4BD45BF37DF365C1A65E68CFDA76D4DA708DF1FB2BC2E4A4371",
-            16
-        ).unwrap();
-    let g = BigUint::parse_bytes(
-            b"A4D1CBD5C3FD34126765A442EFB99905F8104DD258AC507FD6406CFF14266D31266FEA1E5C41564B777E690F5504F213160217B4B01B886A5E91547F9E2749F4D7FBD7D3B9A92EE1909D0D2263F80A76A6A24C087A091F531DBF0A0169B6A28AD662A4D18E73AFA32D779D5918D08BC8858F4DCEF97C2A24855E6EEB22B3B2E5",
-            16
-        ).unwrap();
+pub fn start_establishing_secure_connection<S: MessageStream + ?Sized>(
+    socket: &mut S,
+    dh_group: DhGroup,
+    server_identity: &ServerIdentity,
+) -> Result<Vec<u8>, AppError> {
+    let (p, g) = dh_group.get_parameters();
 
     // Send 2 values: p (BigUint), g (BigUint) values.
     let p_buf = bincode::serialize(&p);
@@ -70,6 +107,9 @@ pub fn start_establishing_secure_connection(socket: &mut TcpStream) -> Result<Ve
     }
     let mut g_buf = g_buf.unwrap();
 
+    // Data to sign (p, g and A values).
+    let mut signed_data = [p_buf.as_slice(), g_buf.as_slice()].concat();
+
     let p_len = p_buf.len() as u64;
     let mut p_len = bincode::serialize(&p_len).unwrap();
 
@@ -85,14 +125,17 @@ pub fn start_establishing_secure_connection(socket: &mut TcpStream) -> Result<Ve
     // Send p and g values.
     match write_to_socket(socket, &mut pg_send_buf) {
         IoResult::Fin => {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
         IoResult::Err(app_error) => {
             return Err(app_error);
         }
         IoResult::Ok(_) => {}
         IoResult::Timeout => {
-            return Err(AppError::new("write timeout"));
+            return Err(AppError::with_kind(AppErrorKind::Timeout, "write timeout"));
         }
     }
 
@@ -109,6 +152,7 @@ pub fn start_establishing_secure_connection(socket: &mut TcpStream) -> Result<Ve
         return Err(AppError::new(&e.to_string()));
     }
     let mut a_open_buf = a_open_buf.unwrap();
+    signed_data.extend_from_slice(&a_open_buf);
 
     // Send open key 'A'.
     let a_open_len = a_open_buf.len() as u64;
@@ -120,14 +164,36 @@ pub fn start_establishing_secure_connection(socket: &mut TcpStream) -> Result<Ve
     a_open_len_buf.append(&mut a_open_buf);
     match write_to_socket(socket, &mut a_open_len_buf) {
         IoResult::Fin => {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
+        }
+        IoResult::Err(app_error) => {
+            return Err(app_error);
+        }
+        IoResult::Ok(_) => {}
+        IoResult::Timeout => {
+            return Err(AppError::with_kind(AppErrorKind::Timeout, "write timeout"));
+        }
+    }
+
+    // Send public key of the server and the signature of p, g and A values.
+    let mut identity_buf = Vec::from(server_identity.get_public_key().to_bytes());
+    identity_buf.extend_from_slice(&server_identity.sign(&signed_data));
+    match write_to_socket(socket, &mut identity_buf) {
+        IoResult::Fin => {
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
         IoResult::Err(app_error) => {
             return Err(app_error);
         }
         IoResult::Ok(_) => {}
         IoResult::Timeout => {
-            return Err(AppError::new("write timeout"));
+            return Err(AppError::with_kind(AppErrorKind::Timeout, "write timeout"));
         }
     }
 
@@ -135,14 +201,17 @@ pub fn start_establishing_secure_connection(socket: &mut TcpStream) -> Result<Ve
     let mut b_open_len_buf = vec![0u8; std::mem::size_of::<u64>()];
     match read_from_socket_fill_buf(socket, &mut b_open_len_buf, None) {
         IoResult::Fin => {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
         IoResult::Err(app_error) => {
             return Err(app_error);
         }
         IoResult::Ok(_) => {}
         IoResult::Timeout => {
-            return Err(AppError::new("read timeout"));
+            return Err(AppError::with_kind(AppErrorKind::Timeout, "read timeout"));
         }
     }
 
@@ -156,14 +225,17 @@ pub fn start_establishing_secure_connection(socket: &mut TcpStream) -> Result<Ve
 
     match read_from_socket_fill_buf(socket, &mut b_open_buf, None) {
         IoResult::Fin => {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
         IoResult::Err(app_error) => {
             return Err(app_error);
         }
         IoResult::Ok(_) => {}
         IoResult::Timeout => {
-            return Err(AppError::new("read timeout"));
+            return Err(AppError::with_kind(AppErrorKind::Timeout, "read timeout"));
         }
     }
 
@@ -199,8 +271,16 @@ pub fn start_establishing_secure_connection(socket: &mut TcpStream) -> Result<Ve
 ///
 /// Generates a secret key that will be used to encrypt network messages.
 ///
+/// ## Arguments
+/// * `socket`: socket of the remote.
+/// * `trusted_server_key`: if specified, the connection is refused if the server
+///   uses another key (the signature of the server is always checked).
+///
 /// Returns `Ok(Vec<u8>)` with the secret key if no errors occurred.
-pub fn accept_secure_connection_establishment(socket: &mut TcpStream) -> Result<Vec<u8>, AppError> {
+pub fn accept_secure_connection_establishment<S: MessageStream + ?Sized>(
+    socket: &mut S,
+    trusted_server_key: Option<&ServerPublicKey>,
+) -> Result<Vec<u8>, AppError> {
     // Generate secret key 'b'.
     let mut rng = rand::thread_rng();
     let b: BigUint = rng.sample(RandomBits::new(A_B_BITS));
@@ -210,14 +290,17 @@ pub fn accept_secure_connection_establishment(socket: &mut TcpStream) -> Result<
     let mut p_len_buf = vec![0u8; std::mem::size_of::<u64>()];
     match read_from_socket_fill_buf(socket, &mut p_len_buf, None) {
         IoResult::Fin => {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
         IoResult::Err(app_error) => {
             return Err(app_error);
         }
         IoResult::Ok(_) => {}
         IoResult::Timeout => {
-            return Err(AppError::new("read timeout"));
+            return Err(AppError::with_kind(AppErrorKind::Timeout, "read timeout"));
         }
     }
     let p_len = bincode::deserialize::<u64>(&p_len_buf);
@@ -230,16 +313,20 @@ pub fn accept_secure_connection_establishment(socket: &mut TcpStream) -> Result<
     let mut p_buf = vec![0u8; p_len as usize];
     match read_from_socket_fill_buf(socket, &mut p_buf, None) {
         IoResult::Fin => {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
         IoResult::Err(app_error) => {
             return Err(app_error);
         }
         IoResult::Ok(_) => {}
         IoResult::Timeout => {
-            return Err(AppError::new("read timeout"));
+            return Err(AppError::with_kind(AppErrorKind::Timeout, "read timeout"));
         }
     }
+    let mut signed_data = p_buf.clone();
     let p_buf = bincode::deserialize::<BigUint>(&p_buf);
     if let Err(e) = p_buf {
         return Err(AppError::new(&e.to_string()));
@@ -250,14 +337,17 @@ pub fn accept_secure_connection_establishment(socket: &mut TcpStream) -> Result<
     let mut g_len_buf = vec![0u8; std::mem::size_of::<u64>()];
     match read_from_socket_fill_buf(socket, &mut g_len_buf, None) {
         IoResult::Fin => {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
         IoResult::Err(app_error) => {
             return Err(app_error);
         }
         IoResult::Ok(_) => {}
         IoResult::Timeout => {
-            return Err(AppError::new("read timeout"));
+            return Err(AppError::with_kind(AppErrorKind::Timeout, "read timeout"));
         }
     }
     let g_len = bincode::deserialize::<u64>(&g_len_buf);
@@ -270,22 +360,34 @@ pub fn accept_secure_connection_establishment(socket: &mut TcpStream) -> Result<
     let mut g_buf = vec![0u8; g_len as usize];
     match read_from_socket_fill_buf(socket, &mut g_buf, None) {
         IoResult::Fin => {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
         IoResult::Err(app_error) => {
             return Err(app_error);
         }
         IoResult::Ok(_) => {}
         IoResult::Timeout => {
-            return Err(AppError::new("read timeout"));
+            return Err(AppError::with_kind(AppErrorKind::Timeout, "read timeout"));
         }
     }
+    signed_data.extend_from_slice(&g_buf);
     let g_buf = bincode::deserialize::<BigUint>(&g_buf);
     if let Err(e) = g_buf {
         return Err(AppError::new(&e.to_string()));
     }
     let g = g_buf.unwrap();
 
+    // Only use known groups (don't trust parameters that might be weak).
+    if DhGroup::from_parameters(&p, &g).is_none() {
+        return Err(AppError::new(
+            "the remote entity uses unsupported Diffie-Hellman parameters \
+            (it probably uses a different network protocol)",
+        ));
+    }
+
     // Calculate the open key B.
     let b_open = g.modpow(&b, &p);
 
@@ -293,14 +395,17 @@ pub fn accept_secure_connection_establishment(socket: &mut TcpStream) -> Result<
     let mut a_open_len_buf = vec![0u8; std::mem::size_of::<u64>()];
     match read_from_socket_fill_buf(socket, &mut a_open_len_buf, None) {
         IoResult::Fin => {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
         IoResult::Err(app_error) => {
             return Err(app_error);
         }
         IoResult::Ok(_) => {}
         IoResult::Timeout => {
-            return Err(AppError::new("read timeout"));
+            return Err(AppError::with_kind(AppErrorKind::Timeout, "read timeout"));
         }
     }
 
@@ -314,14 +419,17 @@ pub fn accept_secure_connection_establishment(socket: &mut TcpStream) -> Result<
     let mut a_open_buf = vec![0u8; a_open_len as usize];
     match read_from_socket_fill_buf(socket, &mut a_open_buf, None) {
         IoResult::Fin => {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
         IoResult::Err(app_error) => {
             return Err(app_error);
         }
         IoResult::Ok(_) => {}
         IoResult::Timeout => {
-            return Err(AppError::new("read timeout"));
+            return Err(AppError::with_kind(AppErrorKind::Timeout, "read timeout"));
         }
     }
 
@@ -330,6 +438,46 @@ pub fn accept_secure_connection_establishment(socket: &mut TcpStream) -> Result<
         return Err(AppError::new(&e.to_string()));
     }
     let a_open_big = a_open_big.unwrap();
+    signed_data.extend_from_slice(&a_open_buf);
+
+    // Receive public key of the server and the signature of p, g and A values.
+    let mut identity_buf = vec![0u8; SERVER_PUBLIC_KEY_SIZE + SERVER_SIGNATURE_SIZE];
+    match read_from_socket_fill_buf(socket, &mut identity_buf, None) {
+        IoResult::Fin => {
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
+        }
+        IoResult::Err(app_error) => {
+            return Err(app_error);
+        }
+        IoResult::Ok(_) => {}
+        IoResult::Timeout => {
+            return Err(AppError::with_kind(AppErrorKind::Timeout, "read timeout"));
+        }
+    }
+    let (public_key_buf, signature_buf) = identity_buf.split_at(SERVER_PUBLIC_KEY_SIZE);
+    let server_key = ServerPublicKey::from_bytes(public_key_buf.try_into().unwrap());
+    if server_key.is_none() {
+        return Err(AppError::new("received invalid server public key"));
+    }
+    let server_key = server_key.unwrap();
+    if !server_key.verify(&signed_data, signature_buf.try_into().unwrap()) {
+        return Err(AppError::new(
+            "the signature of the server is invalid, refusing to connect",
+        ));
+    }
+    if let Some(trusted_server_key) = trusted_server_key {
+        if server_key != *trusted_server_key {
+            return Err(AppError::new(&format!(
+                "the server's public key ({}) does not match the trusted server key ({}), \
+                someone might be intercepting the connection (or the server key was changed), \
+                refusing to connect",
+                server_key, trusted_server_key
+            )));
+        }
+    }
 
     // Prepare to send open key B.
     let mut b_open_buf = bincode::serialize(&b_open).unwrap();
@@ -344,14 +492,17 @@ pub fn accept_secure_connection_establishment(socket: &mut TcpStream) -> Result<
     b_open_len_buf.append(&mut b_open_buf);
     match write_to_socket(socket, &mut b_open_len_buf) {
         IoResult::Fin => {
-            return Err(AppError::new("unexpected FIN received"));
+            return Err(AppError::with_kind(
+                AppErrorKind::ConnectionClosed,
+                "unexpected FIN received",
+            ));
         }
         IoResult::Err(app_error) => {
             return Err(app_error);
         }
         IoResult::Ok(_) => {}
         IoResult::Timeout => {
-            return Err(AppError::new("write timeout"));
+            return Err(AppError::with_kind(AppErrorKind::Timeout, "write timeout"));
         }
     }
 
@@ -384,12 +535,13 @@ pub fn accept_secure_connection_establishment(socket: &mut TcpStream) -> Result<
 /// - `message`: message to send.
 ///
 /// Returns `None` if successful, `Some` otherwise.
-pub fn send_message<T>(
-    socket: &mut TcpStream,
+pub fn send_message<S, T>(
+    socket: &mut S,
     secret_key: &[u8; SECRET_KEY_SIZE],
     message: T,
 ) -> Option<AppError>
 where
+    S: MessageStream + ?Sized,
     T: Serialize,
 {
     if secret_key.is_empty() {
@@ -399,7 +551,7 @@ where
     }
 
     // Get socket remote address.
-    let peer_addr = socket.peer_addr();
+    let peer_addr = socket.get_peer_name();
     if let Err(e) = peer_addr {
         return Some(AppError::new(&format!(
             "failed to get socket peer address (error: {})",
@@ -409,7 +561,10 @@ where
     let socket_addr = peer_addr.unwrap();
 
     // Serialize.
-    let mut binary_message = bincode::serialize(&message).unwrap();
+    let binary_message = bincode::serialize(&message).unwrap();
+
+    // Compress (if needed).
+    let mut binary_message = compress_message(binary_message);
 
     // CMAC.
     let mut mac = Cmac::<Aes256>::new_from_slice(secret_key).unwrap();
@@ -457,15 +612,18 @@ where
         // Send.
         match write_to_socket(socket, &mut send_buffer) {
             IoResult::Fin => {
-                return Some(AppError::new("unexpected FIN received"));
+                return Some(AppError::with_kind(
+                    AppErrorKind::ConnectionClosed,
+                    "unexpected FIN received",
+                ));
             }
             IoResult::Err(app_error) => return Some(app_error),
             IoResult::Ok(_) => {}
             IoResult::Timeout => {
-                return Some(AppError::new(&format!(
-                    "write timeout (socket: {})",
-                    socket_addr
-                )));
+                return Some(AppError::with_kind(
+                    AppErrorKind::Timeout,
+                    &format!("write timeout (socket: {})", socket_addr),
+                ));
             }
         }
     } else {
@@ -477,15 +635,18 @@ where
             // Send.
             match write_to_socket(socket, chunk) {
                 IoResult::Fin => {
-                    return Some(AppError::new("unexpected FIN received"));
+                    return Some(AppError::with_kind(
+                        AppErrorKind::ConnectionClosed,
+                        "unexpected FIN received",
+                    ));
                 }
                 IoResult::Err(app_error) => return Some(app_error),
                 IoResult::Ok(_) => {}
                 IoResult::Timeout => {
-                    return Some(AppError::new(&format!(
-                        "write timeout (socket: {})",
-                        socket_addr
-                    )));
+                    return Some(AppError::with_kind(
+                        AppErrorKind::Timeout,
+                        &format!("write timeout (socket: {})", socket_addr),
+                    ));
                 }
             }
         }
@@ -509,12 +670,37 @@ where
 /// Empty `Ok` array if received FIN from remote connection (connection is being closed).
 /// If custom timeout was specified and reached a timeout will return `Ok` with zero length vector,
 /// otherwise if custom timeout was not specified will return `AppError`.
-pub fn receive_message(
-    socket: &mut TcpStream,
+pub fn receive_message<S: MessageStream + ?Sized>(
+    socket: &mut S,
     secret_key: &[u8; SECRET_KEY_SIZE],
     timeout_in_ms: Option<u64>,
     max_allowed_message_size_in_bytes: usize,
     is_fin: &mut bool,
+) -> Result<Vec<u8>, AppError> {
+    receive_message_with_progress(
+        socket,
+        secret_key,
+        timeout_in_ms,
+        max_allowed_message_size_in_bytes,
+        is_fin,
+        &mut |_, _| {},
+    )
+}
+
+/// Same as `receive_message` but also reports how much of the message was received.
+///
+/// ## Arguments
+/// - `on_progress`: called after each received chunk of the message with
+///   the amount of received bytes and the total size of the message (in bytes).
+///
+/// See `receive_message` for other arguments and return value.
+pub fn receive_message_with_progress<S: MessageStream + ?Sized>(
+    socket: &mut S,
+    secret_key: &[u8; SECRET_KEY_SIZE],
+    timeout_in_ms: Option<u64>,
+    max_allowed_message_size_in_bytes: usize,
+    is_fin: &mut bool,
+    on_progress: &mut dyn FnMut(usize, usize),
 ) -> Result<Vec<u8>, AppError> {
     if secret_key.is_empty() {
         return Err(AppError::new(
@@ -523,7 +709,7 @@ pub fn receive_message(
     }
 
     // Get socket remote address.
-    let peer_addr = socket.peer_addr();
+    let peer_addr = socket.get_peer_name();
     if let Err(e) = peer_addr {
         return Err(AppError::new(&format!(
             "failed to get socket peer address (error: {})",
@@ -545,17 +731,23 @@ pub fn receive_message(
         IoResult::Err(app_error) => return Err(app_error),
         IoResult::Ok(byte_count) => {
             if byte_count != message_size_buf.len() {
-                return Err(AppError::new(&format!(
-                    "not all data received (got: {}, expected: {}) (socket: {})",
-                    byte_count,
-                    message_size_buf.len(),
-                    socket_addr
-                )));
+                return Err(AppError::with_kind(
+                    AppErrorKind::Protocol,
+                    &format!(
+                        "not all data received (got: {}, expected: {}) (socket: {})",
+                        byte_count,
+                        message_size_buf.len(),
+                        socket_addr
+                    ),
+                ));
             }
 
             let res = bincode::deserialize::<MessageLenType>(&message_size_buf);
             if let Err(e) = res {
-                return Err(AppError::new(&format!("{:?} (socket: {})", e, socket_addr)));
+                return Err(AppError::with_kind(
+                    AppErrorKind::Protocol,
+                    &format!("{:?} (socket: {})", e, socket_addr),
+                ));
             }
 
             _next_message_size = res.unwrap() as usize;
@@ -564,20 +756,23 @@ pub fn receive_message(
             if timeout_in_ms.is_some() {
                 return Ok(Vec::new());
             } else {
-                return Err(AppError::new(&format!(
-                    "read timeout (socket: {})",
-                    socket_addr
-                )));
+                return Err(AppError::with_kind(
+                    AppErrorKind::Timeout,
+                    &format!("read timeout (socket: {})", socket_addr),
+                ));
             }
         }
     }
 
     // Check message size.
     if _next_message_size > max_allowed_message_size_in_bytes {
-        return Err(AppError::new(&format!(
-            "incoming message is too big to receive ({} > {} bytes) (socket: {})",
-            _next_message_size, max_allowed_message_size_in_bytes, socket_addr
-        )));
+        return Err(AppError::with_kind(
+            AppErrorKind::Protocol,
+            &format!(
+                "incoming message is too big to receive ({} > {} bytes) (socket: {})",
+                _next_message_size, max_allowed_message_size_in_bytes, socket_addr
+            ),
+        ));
     }
 
     // Receive encrypted message.
@@ -589,18 +784,18 @@ pub fn receive_message(
         match read_from_socket_fill_buf(socket, &mut encrypted_message, None) {
             IoResult::Fin => {
                 *is_fin = true;
-                return Err(AppError::new(&format!(
-                    "unexpected FIN received (socket: {})",
-                    socket_addr
-                )));
+                return Err(AppError::with_kind(
+                    AppErrorKind::ConnectionClosed,
+                    &format!("unexpected FIN received (socket: {})", socket_addr),
+                ));
             }
             IoResult::Err(app_error) => return Err(app_error),
             IoResult::Ok(_) => {}
             IoResult::Timeout => {
-                return Err(AppError::new(&format!(
-                    "read timeout (socket: {})",
-                    socket_addr
-                )));
+                return Err(AppError::with_kind(
+                    AppErrorKind::Timeout,
+                    &format!("read timeout (socket: {})", socket_addr),
+                ));
             }
         };
     } else {
@@ -619,33 +814,38 @@ pub fn receive_message(
             match read_from_socket_fill_buf(socket, &mut _chunk, None) {
                 IoResult::Fin => {
                     *is_fin = true;
-                    return Err(AppError::new(&format!(
-                        "unexpected FIN received (socket: {})",
-                        socket_addr
-                    )));
+                    return Err(AppError::with_kind(
+                        AppErrorKind::ConnectionClosed,
+                        &format!("unexpected FIN received (socket: {})", socket_addr),
+                    ));
                 }
                 IoResult::Err(app_error) => return Err(app_error),
                 IoResult::Ok(_) => {}
                 IoResult::Timeout => {
-                    return Err(AppError::new(&format!(
-                        "read timeout (socket: {})",
-                        socket_addr
-                    )));
+                    return Err(AppError::with_kind(
+                        AppErrorKind::Timeout,
+                        &format!("read timeout (socket: {})", socket_addr),
+                    ));
                 }
             };
 
             bytes_left_to_receive -= _chunk.len();
             encrypted_message.append(&mut _chunk);
+
+            on_progress(encrypted_message.len(), _next_message_size);
         }
     }
 
     // Get IV.
     if encrypted_message.len() < IV_LENGTH {
-        return Err(AppError::new(&format!(
-            "unexpected message length ({}) (socket: {})",
-            encrypted_message.len(),
-            socket_addr
-        )));
+        return Err(AppError::with_kind(
+            AppErrorKind::Protocol,
+            &format!(
+                "unexpected message length ({}) (socket: {})",
+                encrypted_message.len(),
+                socket_addr
+            ),
+        ));
     }
     let iv = encrypted_message[..IV_LENGTH].to_vec();
     encrypted_message = encrypted_message[IV_LENGTH..].to_vec();
@@ -653,7 +853,10 @@ pub fn receive_message(
     // Convert IV.
     let iv = iv.try_into();
     if iv.is_err() {
-        return Err(AppError::new("failed to convert iv to generic array"));
+        return Err(AppError::with_kind(
+            AppErrorKind::Protocol,
+            "failed to convert iv to generic array",
+        ));
     }
     let iv: [u8; IV_LENGTH] = iv.unwrap();
 
@@ -661,7 +864,10 @@ pub fn receive_message(
     let decrypted_message = Aes256CbcDec::new(secret_key.into(), &iv.into())
         .decrypt_padded_vec_mut::<Pkcs7>(&encrypted_message);
     if let Err(e) = decrypted_message {
-        return Err(AppError::new(&format!("{:?} (socket: {})", e, socket_addr)));
+        return Err(AppError::with_kind(
+            AppErrorKind::Protocol,
+            &format!("{:?} (socket: {})", e, socket_addr),
+        ));
     }
     let mut decrypted_message = decrypted_message.unwrap();
 
@@ -675,16 +881,107 @@ pub fn receive_message(
     // Convert tag.
     let tag = tag.try_into();
     if tag.is_err() {
-        return Err(AppError::new("failed to convert cmac tag to generic array"));
+        return Err(AppError::with_kind(
+            AppErrorKind::Protocol,
+            "failed to convert cmac tag to generic array",
+        ));
     }
     let tag: [u8; CMAC_TAG_LENGTH] = tag.unwrap();
 
     // Check that tag is correct.
     if let Err(e) = mac.verify(&tag.into()) {
-        return Err(AppError::new(&format!("{:?} (socket: {})", e, socket_addr)));
+        return Err(AppError::with_kind(
+            AppErrorKind::Protocol,
+            &format!("{:?} (socket: {})", e, socket_addr),
+        ));
+    }
+
+    // Decompress (if needed).
+    let message = decompress_message(decrypted_message, max_allowed_message_size_in_bytes);
+    if let Err(app_error) = message {
+        return Err(AppError::with_kind(
+            app_error.get_kind(),
+            &format!("{} (socket: {})", app_error.get_message(), socket_addr),
+        ));
     }
 
-    Ok(decrypted_message)
+    Ok(message.unwrap())
+}
+
+/// Compresses the serialized message if it's big enough and compression
+/// makes it smaller.
+///
+/// ## Return
+/// Message with a flag byte in the beginning that tells if the message
+/// is compressed or not (see `decompress_message`).
+fn compress_message(binary_message: Vec<u8>) -> Vec<u8> {
+    if binary_message.len() >= MIN_MESSAGE_SIZE_TO_COMPRESS_IN_BYTES {
+        let mut encoder =
+            DeflateEncoder::new(vec![MESSAGE_FLAG_COMPRESSED], Compression::default());
+        if encoder.write_all(&binary_message).is_ok() {
+            if let Ok(compressed_message) = encoder.finish() {
+                if compressed_message.len() < binary_message.len() + 1 {
+                    return compressed_message;
+                }
+            }
+        }
+        // Send uncompressed on failure.
+    }
+
+    let mut message = Vec::with_capacity(binary_message.len() + 1);
+    message.push(MESSAGE_FLAG_UNCOMPRESSED);
+    message.extend_from_slice(&binary_message);
+    message
+}
+
+/// Reads the flag byte (see `compress_message`) and decompresses
+/// the message if it was compressed.
+///
+/// ## Arguments
+/// - `message`: received message (with the flag byte).
+/// - `max_allowed_message_size_in_bytes`: maximum size of the decompressed message,
+///   protects from messages that decompress into a huge amount of data.
+fn decompress_message(
+    mut message: Vec<u8>,
+    max_allowed_message_size_in_bytes: usize,
+) -> Result<Vec<u8>, AppError> {
+    if message.is_empty() {
+        return Err(AppError::with_kind(
+            AppErrorKind::Protocol,
+            "received message is empty",
+        ));
+    }
+
+    match message[0] {
+        MESSAGE_FLAG_UNCOMPRESSED => {
+            message.remove(0);
+            Ok(message)
+        }
+        MESSAGE_FLAG_COMPRESSED => {
+            let mut decompressed_message: Vec<u8> = Vec::new();
+            let mut decoder = DeflateDecoder::new(&message[1..])
+                .take(max_allowed_message_size_in_bytes as u64 + 1);
+            if let Err(e) = decoder.read_to_end(&mut decompressed_message) {
+                return Err(AppError::with_kind(AppErrorKind::Protocol, &e.to_string()));
+            }
+
+            if decompressed_message.len() > max_allowed_message_size_in_bytes {
+                return Err(AppError::with_kind(
+                    AppErrorKind::Protocol,
+                    &format!(
+                        "decompressed message is too big (more than {} bytes)",
+                        max_allowed_message_size_in_bytes
+                    ),
+                ));
+            }
+
+            Ok(decompressed_message)
+        }
+        flag => Err(AppError::with_kind(
+            AppErrorKind::Protocol,
+            &format!("received message has unknown flag {}", flag),
+        )),
+    }
 }
 
 /// Writes the specified buffer to the socket with a timeout.
@@ -694,7 +991,7 @@ pub fn receive_message(
 /// ## Arguments:
 /// - `socket`: socket to write this data to.
 /// - `buf`: buffer to write to the socket.
-fn write_to_socket(socket: &mut TcpStream, buf: &mut [u8]) -> IoResult {
+fn write_to_socket<S: MessageStream + ?Sized>(socket: &mut S, buf: &mut [u8]) -> IoResult {
     if buf.is_empty() {
         return IoResult::Err(AppError::new("the specified buffer has zero length"));
     }
@@ -730,8 +1027,16 @@ fn write_to_socket(socket: &mut TcpStream, buf: &mut [u8]) -> IoResult {
                 continue;
             }
             Err(e) => {
-                if e.kind() == std::io::ErrorKind::ConnectionReset {
-                    return IoResult::Err(AppError::new(CONNECTION_RESET_DESC_MESSAGE));
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::ConnectionReset
+                        | std::io::ErrorKind::ConnectionAborted
+                        | std::io::ErrorKind::BrokenPipe
+                ) {
+                    return IoResult::Err(AppError::with_kind(
+                        AppErrorKind::ConnectionClosed,
+                        CONNECTION_RESET_DESC_MESSAGE,
+                    ));
                 }
                 return IoResult::Err(AppError::new(&format!("{}", e,)));
             }
@@ -747,8 +1052,8 @@ fn write_to_socket(socket: &mut TcpStream, buf: &mut [u8]) -> IoResult {
 /// - `buf`: buffer to write read data.
 /// - `timeout_in_ms`: if specified the operation will have a custom timeout,
 /// if not, default timeout of `MAX_WAIT_TIME_IN_READ_WRITE_MS` will be used.
-fn read_from_socket_fill_buf(
-    socket: &mut TcpStream,
+fn read_from_socket_fill_buf<S: MessageStream + ?Sized>(
+    socket: &mut S,
     buf: &mut [u8],
     timeout_in_ms: Option<u64>,
 ) -> IoResult {
@@ -793,8 +1098,16 @@ fn read_from_socket_fill_buf(
                 continue;
             }
             Err(e) => {
-                if e.kind() == std::io::ErrorKind::ConnectionReset {
-                    return IoResult::Err(AppError::new(CONNECTION_RESET_DESC_MESSAGE));
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::ConnectionReset
+                        | std::io::ErrorKind::ConnectionAborted
+                        | std::io::ErrorKind::BrokenPipe
+                ) {
+                    return IoResult::Err(AppError::with_kind(
+                        AppErrorKind::ConnectionClosed,
+                        CONNECTION_RESET_DESC_MESSAGE,
+                    ));
                 }
                 return IoResult::Err(AppError::new(&format!("{}", e,)));
             }
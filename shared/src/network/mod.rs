@@ -1,4 +1,7 @@
 pub mod client_messages;
+pub mod dh_group;
 pub mod messaging;
 pub mod net_params;
+pub mod proof_of_work;
 pub mod reporter_messages;
+pub mod server_identity;
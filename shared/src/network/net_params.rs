@@ -3,7 +3,13 @@ pub const A_B_BITS: u64 = 2048; // if changed, change protocol version
 pub const IV_LENGTH: usize = 16; // if changed, change protocol version
 pub const CMAC_TAG_LENGTH: usize = 16; // if changed, change protocol version
 
+/// Returned by the HTTP transport when a session is created, should be specified
+/// in all following requests of the session.
+pub const HTTP_SESSION_ID_HEADER: &str = "X-Session-Id"; // if changed, change protocol version
+/// Set to `true` by the HTTP transport when the server closed the session.
+pub const HTTP_SESSION_CLOSED_HEADER: &str = "X-Session-Closed"; // if changed, change protocol version
+
 pub const MAX_WAIT_TIME_IN_READ_WRITE_MS: u64 = 120000; // 2 minutes
 pub const WOULD_BLOCK_RETRY_AFTER_MS: u64 = 20;
 
-pub const NETWORK_PROTOCOL_VERSION: u16 = 2;
+pub const NETWORK_PROTOCOL_VERSION: u16 = 32;
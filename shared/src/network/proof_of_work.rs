@@ -0,0 +1,90 @@
+// External.
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Size of the random data in the challenge.
+const CHALLENGE_SIZE_IN_BYTES: usize = 32;
+
+/// Maximum difficulty that can be used (higher values take too long to solve).
+pub const MAX_PROOF_OF_WORK_DIFFICULTY: u8 = 32;
+
+/// Challenge that the reporter needs to solve before sending a report,
+/// makes sending lots of reports expensive.
+///
+/// The solution is a nonce such that SHA-256 hash of the challenge data
+/// followed by the nonce starts with at least `difficulty` zero bits.
+/// If made changes, change protocol version.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ProofOfWorkChallenge {
+    pub data: Vec<u8>,
+    /// Required number of leading zero bits in the hash, `0` if proof of work is disabled.
+    pub difficulty: u8,
+}
+
+impl ProofOfWorkChallenge {
+    /// Creates a new random challenge.
+    ///
+    /// ## Arguments
+    /// * `difficulty`: required number of leading zero bits in the hash
+    ///   (clamped to `MAX_PROOF_OF_WORK_DIFFICULTY`), `0` to disable proof of work.
+    pub fn new(difficulty: u8) -> Self {
+        if difficulty == 0 {
+            return Self::default();
+        }
+
+        let mut data = vec![0u8; CHALLENGE_SIZE_IN_BYTES];
+        rand::thread_rng().fill_bytes(&mut data);
+
+        Self {
+            data,
+            difficulty: difficulty.min(MAX_PROOF_OF_WORK_DIFFICULTY),
+        }
+    }
+    /// Tells if solving this challenge is required.
+    pub fn is_enabled(&self) -> bool {
+        self.difficulty != 0
+    }
+    /// Finds a nonce that solves this challenge.
+    ///
+    /// ## Remarks
+    /// Each difficulty level doubles the average amount of work.
+    pub fn solve(&self) -> u64 {
+        if !self.is_enabled() {
+            return 0;
+        }
+
+        let mut nonce: u64 = 0;
+        while !self.verify(nonce) {
+            nonce += 1;
+        }
+
+        nonce
+    }
+    /// Checks if the specified nonce solves this challenge.
+    pub fn verify(&self, nonce: u64) -> bool {
+        if !self.is_enabled() {
+            return true;
+        }
+
+        let mut hasher = Sha256::new();
+        hasher.update(&self.data);
+        hasher.update(nonce.to_le_bytes());
+        let hash = hasher.finalize();
+
+        Self::count_leading_zero_bits(&hash) >= self.difficulty as u32
+    }
+    /// Returns the number of zero bits in the beginning of the data.
+    fn count_leading_zero_bits(data: &[u8]) -> u32 {
+        let mut count = 0;
+
+        for byte in data {
+            count += byte.leading_zeros();
+            if *byte != 0 {
+                break;
+            }
+        }
+
+        count
+    }
+}
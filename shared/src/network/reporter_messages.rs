@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 // Custom.
 use crate::misc::report::*;
+use crate::network::proof_of_work::ProofOfWorkChallenge;
 
 /// Reporter's request to the server.
 /// If made changes, change protocol version.
@@ -12,21 +13,65 @@ pub enum ReporterRequest {
         reporter_net_protocol: u16,
         game_report: Box<GameReport>,
         attachments: Vec<ReportAttachment>,
+        /// Solution of the challenge received after the connection was established
+        /// (`0` if proof of work is disabled on the server).
+        proof_of_work_nonce: u64,
     },
-    /// Max attachment size (in total) in MB.
-    MaxAttachmentSize {},
+    /// Max attachment size (in total) in MB for reports of the specified game.
+    MaxAttachmentSize { game_name: String },
+    /// Asks for the server's network protocol version and application version.
+    ///
+    /// Keep the position of this entry so that older/newer reporters can still
+    /// detect a protocol mismatch.
+    QueryServerInfo,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub enum ServerAnswer {
     Ok,
     OtherError(String),
+    /// The server does not accept reports from this game version.
+    VersionRejected,
+    /// The server rejected the report (contains a reason that can be shown to the user).
+    Rejected(String),
+    /// The reporter did not solve the proof of work challenge.
+    InvalidProofOfWork,
+    /// The sender sent a report recently, new reports are accepted after a cooldown.
+    RateLimited {
+        retry_after_in_sec: u64,
+    },
+    /// A report field exceeds its limit (contains the name of the field).
+    FieldTooLong {
+        field: String,
+    },
+    /// A report field is shorter than the server requires (contains the name of the field
+    /// and the minimum amount of characters).
+    FieldTooShort {
+        field: String,
+        min_length: usize,
+    },
+    /// The server failed to process the report (details are only logged on the server).
+    InternalError,
 }
 
 /// Server's answer to reporter.
+/// `Challenge` is sent right after a secure connection is established (or `Report` with
+/// `ServerAnswer::RateLimited` if the server is busy, the connection is closed after that).
 /// If made changes, change protocol version.
 #[derive(Serialize, Deserialize, Debug)]
 pub enum ReporterAnswer {
-    Report { result_code: ServerAnswer },
-    MaxAttachmentSize { max_attachments_size_in_mb: usize },
+    Challenge {
+        proof_of_work: ProofOfWorkChallenge,
+    },
+    Report {
+        result_code: ServerAnswer,
+    },
+    MaxAttachmentSize {
+        max_attachments_size_in_mb: usize,
+    },
+    /// Answer to `ReporterRequest::QueryServerInfo` (keep the position of this entry).
+    ServerInfo {
+        net_protocol: u16,
+        server_version: String,
+    },
 }
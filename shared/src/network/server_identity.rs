@@ -0,0 +1,172 @@
+// Std.
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+// External.
+use base64::{engine::general_purpose, Engine as _};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+// Custom.
+use crate::misc::error::AppError;
+
+// ---------- if changed, change protocol version --------------
+pub const SERVER_PUBLIC_KEY_SIZE: usize = ed25519_dalek::PUBLIC_KEY_LENGTH;
+pub const SERVER_SIGNATURE_SIZE: usize = ed25519_dalek::SIGNATURE_LENGTH;
+// ---------------------------------------------------------
+
+/// Long-term key pair of the server, the server signs its part of the key exchange
+/// so that remote entities can make sure that they are talking to the real server.
+pub struct ServerIdentity {
+    signing_key: SigningKey,
+}
+
+/// Public key of the server that clients can pin (trust) to detect
+/// man-in-the-middle attacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerPublicKey {
+    key: VerifyingKey,
+}
+
+impl ServerIdentity {
+    /// Reads the key pair from the specified file, if the file does not exist
+    /// generates a new key pair and saves it to this file.
+    ///
+    /// ## Remarks
+    /// On Unix the new file is only readable by the owner (mode 0600). On Windows the file
+    /// inherits permissions of its directory (by default, the user's data directory that
+    /// is only accessible by the user and administrators).
+    ///
+    /// ## Arguments
+    /// * `path`: path to the file with the private key (base64).
+    ///
+    /// ## Return
+    /// Error if the file exists but could not be read/parsed or if the new key
+    /// could not be saved.
+    pub fn load_or_generate(path: &Path) -> Result<Self, AppError> {
+        if !path.exists() {
+            let identity = Self {
+                signing_key: SigningKey::generate(&mut rand::rngs::OsRng),
+            };
+
+            let encoded_key = general_purpose::STANDARD.encode(identity.signing_key.to_bytes());
+            if let Err(e) = Self::write_private_key_file(path, &encoded_key) {
+                return Err(AppError::new(&format!(
+                    "failed to save the server key to \"{}\" (error: {})",
+                    path.display(),
+                    e
+                )));
+            }
+
+            return Ok(identity);
+        }
+
+        let encoded_key = fs::read_to_string(path);
+        if let Err(e) = encoded_key {
+            return Err(AppError::new(&format!(
+                "failed to read the server key from \"{}\" (error: {})",
+                path.display(),
+                e
+            )));
+        }
+
+        let key_bytes = general_purpose::STANDARD
+            .decode(encoded_key.unwrap().trim())
+            .ok()
+            .and_then(|bytes| <[u8; ed25519_dalek::SECRET_KEY_LENGTH]>::try_from(bytes).ok());
+        match key_bytes {
+            Some(key_bytes) => Ok(Self {
+                signing_key: SigningKey::from_bytes(&key_bytes),
+            }),
+            None => Err(AppError::new(&format!(
+                "the server key in \"{}\" is corrupted (remove the file to generate a new key, \
+                clients that trust the old key will need the new public key)",
+                path.display()
+            ))),
+        }
+    }
+    /// Creates a new file with the private key that other users can't read.
+    fn write_private_key_file(path: &Path, encoded_key: &str) -> std::io::Result<()> {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        let mut file = options.open(path)?;
+        file.write_all(encoded_key.as_bytes())
+    }
+    /// Returns the key that clients should trust.
+    pub fn get_public_key(&self) -> ServerPublicKey {
+        ServerPublicKey {
+            key: self.signing_key.verifying_key(),
+        }
+    }
+    /// Signs the specified data.
+    pub fn sign(&self, data: &[u8]) -> [u8; SERVER_SIGNATURE_SIZE] {
+        self.signing_key.sign(data).to_bytes()
+    }
+}
+
+impl ServerPublicKey {
+    /// Creates a key from bytes that were received from the server.
+    ///
+    /// ## Return
+    /// `None` if the bytes are not a valid key.
+    pub fn from_bytes(bytes: &[u8; SERVER_PUBLIC_KEY_SIZE]) -> Option<Self> {
+        VerifyingKey::from_bytes(bytes).ok().map(|key| Self { key })
+    }
+    /// Parses a key that was exported using `to_string` (surrounding whitespace is ignored).
+    pub fn parse(text: &str) -> Result<Self, AppError> {
+        let key = general_purpose::STANDARD
+            .decode(text.trim())
+            .ok()
+            .and_then(|bytes| <[u8; SERVER_PUBLIC_KEY_SIZE]>::try_from(bytes).ok())
+            .and_then(|bytes| Self::from_bytes(&bytes));
+
+        match key {
+            Some(key) => Ok(key),
+            None => Err(AppError::new("expected a base64-encoded server public key")),
+        }
+    }
+    /// Reads a key from the specified file (see `parse`).
+    pub fn read_from_file(path: &Path) -> Result<Self, AppError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|app_error| {
+                AppError::new(&format!(
+                    "failed to read the server public key from \"{}\" ({})",
+                    path.display(),
+                    app_error.get_message()
+                ))
+            }),
+            Err(e) => Err(AppError::new(&format!(
+                "failed to read the server public key from \"{}\" (error: {})",
+                path.display(),
+                e
+            ))),
+        }
+    }
+    pub fn to_bytes(&self) -> [u8; SERVER_PUBLIC_KEY_SIZE] {
+        self.key.to_bytes()
+    }
+    /// Tells if the signature of the data was created by the server that owns this key.
+    pub fn verify(&self, data: &[u8], signature: &[u8; SERVER_SIGNATURE_SIZE]) -> bool {
+        self.key
+            .verify(data, &Signature::from_bytes(signature))
+            .is_ok()
+    }
+}
+
+impl std::fmt::Display for ServerPublicKey {
+    /// Formats the key as base64 (use `parse` to read it back).
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            general_purpose::STANDARD.encode(self.key.to_bytes())
+        )
+    }
+}